@@ -0,0 +1,24 @@
+const path = require("path");
+
+// Artifact compiled by `make setup`, hardhat-deploy not compiling the contracts itself
+const counter = require(path.join(
+  __dirname,
+  "../../../../lib/kakarot/tests/integration/solidity_contracts/build/Counter.sol/Counter.json",
+));
+
+module.exports = async ({ deployments, getNamedAccounts }) => {
+  const { deploy, execute, read } = deployments;
+  const { deployer } = await getNamedAccounts();
+
+  await deploy("Counter", {
+    from: deployer,
+    contract: { abi: counter.abi, bytecode: counter.bytecode.object },
+    log: true,
+  });
+  await execute("Counter", { from: deployer, log: true }, "inc");
+
+  const count = await read("Counter", "count");
+  if (!count.eq(1)) {
+    throw new Error(`Expected a count of 1 after inc, got ${count}`);
+  }
+};
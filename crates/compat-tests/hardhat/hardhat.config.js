@@ -0,0 +1,14 @@
+require("hardhat-deploy");
+
+// The RPC and the funded account are set by the compatibility test
+module.exports = {
+  networks: {
+    kakarot: {
+      url: process.env.KAKAROT_RPC_URL || "http://127.0.0.1:3030",
+      accounts: process.env.PRIVATE_KEY ? [process.env.PRIVATE_KEY] : [],
+    },
+  },
+  namedAccounts: {
+    deployer: 0,
+  },
+};
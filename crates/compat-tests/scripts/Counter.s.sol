@@ -0,0 +1,22 @@
+// SPDX-License-Identifier: MIT
+pragma solidity >=0.8.0;
+
+import {Script} from "forge-std/Script.sol";
+
+interface ICounter {
+    function inc() external;
+}
+
+/// Deploys a counter and increments it, broadcast by the Foundry compatibility test.
+contract CounterScript is Script {
+    function run() external {
+        bytes memory bytecode = vm.getCode("Counter.sol:Counter");
+        vm.startBroadcast();
+        address counter;
+        assembly {
+            counter := create(0, add(bytecode, 0x20), mload(bytecode))
+        }
+        ICounter(counter).inc();
+        vm.stopBroadcast();
+    }
+}
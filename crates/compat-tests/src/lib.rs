@@ -0,0 +1,5 @@
+//! Compatibility tests of the Kakarot RPC with Ethereum client libraries.
+//!
+//! The tests in `tests/` drive a Kakarot RPC served on top of the test sequencer through the
+//! providers of the client libraries, catching the incompatibilities of the responses that the raw
+//! JSON tests of the RPC miss, e.g. a field a library fails to deserialize.
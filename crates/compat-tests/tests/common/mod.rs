@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use dojo_test_utils::sequencer::TestSequencer;
+use kakarot_rpc::server::KakarotRpcServer;
+use kakarot_rpc_core::client::api::KakarotEthApi;
+use kakarot_rpc_core::client::config::Network;
+use kakarot_rpc_core::client::KakarotClient;
+use kakarot_rpc_core::models::compat::ResponseCompat;
+use starknet::providers::jsonrpc::HttpTransport;
+use starknet::providers::JsonRpcClient;
+
+use crate::utils::deploy_helpers::DeployedKakarot;
+
+/// Serves the RPC on a free port, against the Kakarot system deployed on the test sequencer.
+pub async fn serve_rpc(
+    starknet_test_sequencer: &TestSequencer,
+    deployed_kakarot: &DeployedKakarot,
+) -> KakarotRpcServer {
+    serve_rpc_with_compat(starknet_test_sequencer, deployed_kakarot, ResponseCompat::default()).await
+}
+
+/// Same as [`serve_rpc`], with the given compatibility preset.
+pub async fn serve_rpc_with_compat(
+    starknet_test_sequencer: &TestSequencer,
+    deployed_kakarot: &DeployedKakarot,
+    response_compat: ResponseCompat,
+) -> KakarotRpcServer {
+    let kakarot_client: Arc<dyn KakarotEthApi<_>> = Arc::new(
+        KakarotClient::builder()
+            .with_network(Network::JsonRpcProvider(starknet_test_sequencer.url()))
+            .with_kakarot_address(deployed_kakarot.kakarot)
+            .with_proxy_account_class_hash(deployed_kakarot.kakarot_proxy)
+            .with_provider(JsonRpcClient::new(HttpTransport::new(starknet_test_sequencer.url())))
+            .build()
+            .unwrap(),
+    );
+    KakarotRpcServer::builder()
+        .with_client(kakarot_client)
+        .with_response_compat(response_compat)
+        .serve("127.0.0.1:0".parse().unwrap())
+        .await
+        .unwrap()
+}
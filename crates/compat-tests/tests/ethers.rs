@@ -0,0 +1,87 @@
+// The test sequencer fixture of the core crate, not all of its helpers being used here
+#[allow(dead_code)]
+#[path = "../../core/tests/utils/mod.rs"]
+mod utils;
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use ethers::contract::ContractFactory;
+    use ethers::middleware::SignerMiddleware;
+    use ethers::providers::{Http, Middleware, Provider, Ws};
+    use ethers::signers::Signer;
+    use ethers::types::{Filter, TransactionReceipt, U256, U64};
+    use futures::StreamExt;
+    use kakarot_rpc_core::client::constants::CHAIN_ID;
+    use starknet::core::types::FieldElement;
+
+    use crate::common::serve_rpc;
+    use crate::utils::constants::EOA_WALLET;
+    use crate::utils::deploy_helpers::{construct_kakarot_test_sequencer, deploy_kakarot_system, get_contract};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_ethers_deploy_send_call_logs_and_subscribe() {
+        // Given
+        let starknet_test_sequencer = construct_kakarot_test_sequencer().await;
+        let funded_amount = FieldElement::from_dec_str("10000000000000000000").unwrap();
+        let deployed_kakarot = deploy_kakarot_system(&starknet_test_sequencer, EOA_WALLET.clone(), funded_amount).await;
+        let server = serve_rpc(&starknet_test_sequencer, &deployed_kakarot).await;
+
+        let provider = Provider::<Http>::try_from(format!("http://{}", server.local_addr()))
+            .unwrap()
+            .interval(Duration::from_millis(100));
+        let wallet = EOA_WALLET.clone().with_chain_id(CHAIN_ID);
+        let client = Arc::new(SignerMiddleware::new(provider.clone(), wallet));
+        let ws_provider = Provider::<Ws>::connect(format!("ws://{}", server.local_addr())).await.unwrap();
+
+        // When
+        let chain_id = provider.get_chainid().await.unwrap();
+
+        // Deploy, with the gas and fees filled by ethers
+        let (counter_abi, counter_bytecode) = get_contract("Counter");
+        let counter = ContractFactory::new(counter_abi, counter_bytecode, client.clone())
+            .deploy(())
+            .unwrap()
+            .send()
+            .await
+            .unwrap();
+        let counter_code = provider.get_code(counter.address(), None).await.unwrap();
+
+        // Send and call
+        let mut new_heads = ws_provider.subscribe_blocks().await.unwrap();
+        let inc = counter.method::<_, ()>("inc", ()).unwrap();
+        let inc_receipt: TransactionReceipt = inc.send().await.unwrap().await.unwrap().unwrap();
+        let count: U256 = counter.method::<_, U256>("count", ()).unwrap().call().await.unwrap();
+
+        // Subscribe
+        let new_head = tokio::time::timeout(Duration::from_secs(30), new_heads.next()).await.unwrap().unwrap();
+
+        // Query logs
+        let (plain_opcodes_abi, plain_opcodes_bytecode) = get_contract("PlainOpcodes");
+        let plain_opcodes = ContractFactory::new(plain_opcodes_abi, plain_opcodes_bytecode, client.clone())
+            .deploy((counter.address(),))
+            .unwrap()
+            .send()
+            .await
+            .unwrap();
+        let log_call = plain_opcodes.method::<_, ()>("opcodeLog0", ()).unwrap();
+        let log_receipt: TransactionReceipt = log_call.send().await.unwrap().await.unwrap().unwrap();
+        let logs = provider
+            .get_logs(&Filter::new().address(plain_opcodes.address()).from_block(log_receipt.block_number.unwrap()))
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(U256::from(CHAIN_ID), chain_id);
+        assert!(!counter_code.is_empty());
+        assert_eq!(Some(U64::from(1)), inc_receipt.status);
+        assert_eq!(U256::from(1), count);
+        assert!(new_head.number.is_some() && new_head.hash.is_some());
+        assert!(logs.iter().any(|log| log.transaction_hash == Some(log_receipt.transaction_hash)));
+        server.handle().stop().unwrap();
+    }
+}
@@ -0,0 +1,95 @@
+// The test sequencer fixture of the core crate, not all of its helpers being used here
+#[allow(dead_code)]
+#[path = "../../core/tests/utils/mod.rs"]
+mod utils;
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use ethers::contract::Contract;
+    use ethers::providers::{Http, Middleware, Provider};
+    use ethers::signers::Signer;
+    use ethers::types::{Address, U256};
+    use foundry_config::utils::find_project_root_path;
+    use kakarot_rpc_core::client::constants::CHAIN_ID;
+    use serde_json::Value;
+    use starknet::core::types::FieldElement;
+    use tokio::process::Command;
+
+    use crate::common::serve_rpc;
+    use crate::utils::constants::{EOA_PRIVATE_KEY, EOA_WALLET};
+    use crate::utils::deploy_helpers::{construct_kakarot_test_sequencer, deploy_kakarot_system, get_contract};
+
+    /// Runs `forge` in the project root against the RPC, signing with the funded EOA, and returns
+    /// its standard output.
+    async fn forge(root: &Path, rpc_url: &str, args: &[&str]) -> String {
+        let output = Command::new("forge")
+            .current_dir(root)
+            .args(args)
+            .args(["--root", root.to_str().unwrap(), "--rpc-url", rpc_url, "--private-key", EOA_PRIVATE_KEY])
+            .output()
+            .await
+            .expect("forge not found, install Foundry to run the compatibility tests");
+        assert!(
+            output.status.success(),
+            "forge {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_forge_create_and_script_broadcast() {
+        // Given
+        let starknet_test_sequencer = construct_kakarot_test_sequencer().await;
+        let funded_amount = FieldElement::from_dec_str("10000000000000000000").unwrap();
+        let deployed_kakarot = deploy_kakarot_system(&starknet_test_sequencer, EOA_WALLET.clone(), funded_amount).await;
+        let server = serve_rpc(&starknet_test_sequencer, &deployed_kakarot).await;
+
+        let rpc_url = format!("http://{}", server.local_addr());
+        let provider = Arc::new(Provider::<Http>::try_from(rpc_url.as_str()).unwrap());
+        let root = find_project_root_path().unwrap();
+        let (counter_abi, _) = get_contract("Counter");
+
+        // When
+        // forge create waits for the receipt of the deployment
+        let created = forge(&root, &rpc_url, &["create", "Counter", "--json"]).await;
+        let created: Value = serde_json::from_str(created.lines().last().unwrap()).unwrap();
+        let created_counter: Address = serde_json::from_value(created["deployedTo"].clone()).unwrap();
+        let created_code = provider.get_code(created_counter, None).await.unwrap();
+
+        // forge script simulates against the state of the RPC, then broadcasts one transaction at
+        // a time
+        let nonce_before_script = provider.get_transaction_count(EOA_WALLET.address(), None).await.unwrap();
+        forge(
+            &root,
+            &rpc_url,
+            &["script", "crates/compat-tests/scripts/Counter.s.sol:CounterScript", "--broadcast", "--slow"],
+        )
+        .await;
+        let nonce_after_script = provider.get_transaction_count(EOA_WALLET.address(), None).await.unwrap();
+
+        // The broadcast is recorded under the chain id returned by the RPC
+        let broadcast = root.join(format!("broadcast/Counter.s.sol/{CHAIN_ID}/run-latest.json"));
+        let broadcast: Value = serde_json::from_str(&std::fs::read_to_string(broadcast).unwrap()).unwrap();
+        let script_counter: Address =
+            serde_json::from_value(broadcast["transactions"][0]["contractAddress"].clone()).unwrap();
+        let count: U256 = Contract::new(script_counter, counter_abi, provider.clone())
+            .method::<_, U256>("count", ())
+            .unwrap()
+            .call()
+            .await
+            .unwrap();
+
+        // Then
+        assert!(!created_code.is_empty());
+        assert_eq!(nonce_before_script + 2, nonce_after_script);
+        assert_eq!(U256::from(1), count);
+        server.handle().stop().unwrap();
+    }
+}
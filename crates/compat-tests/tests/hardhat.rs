@@ -0,0 +1,60 @@
+// The test sequencer fixture of the core crate, not all of its helpers being used here
+#[allow(dead_code)]
+#[path = "../../core/tests/utils/mod.rs"]
+mod utils;
+
+// Only the RPC served with a compatibility preset is used here
+#[allow(dead_code)]
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use ethers::providers::{Http, Middleware, Provider};
+    use ethers::signers::Signer;
+    use ethers::types::{Block, H256, U256};
+    use kakarot_rpc_core::models::compat::ResponseCompat;
+    use starknet::core::types::FieldElement;
+    use tokio::process::Command;
+
+    use crate::common::serve_rpc_with_compat;
+    use crate::utils::constants::{EOA_PRIVATE_KEY, EOA_WALLET};
+    use crate::utils::deploy_helpers::{construct_kakarot_test_sequencer, deploy_kakarot_system};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_hardhat_deploy_with_hardhat_compat() {
+        // Given
+        let starknet_test_sequencer = construct_kakarot_test_sequencer().await;
+        let funded_amount = FieldElement::from_dec_str("10000000000000000000").unwrap();
+        let deployed_kakarot = deploy_kakarot_system(&starknet_test_sequencer, EOA_WALLET.clone(), funded_amount).await;
+        let server =
+            serve_rpc_with_compat(&starknet_test_sequencer, &deployed_kakarot, ResponseCompat::hardhat()).await;
+
+        let rpc_url = format!("http://{}", server.local_addr());
+        let provider = Provider::<Http>::try_from(rpc_url.as_str()).unwrap();
+        let hardhat_project = concat!(env!("CARGO_MANIFEST_DIR"), "/hardhat");
+
+        // When
+        // The deploy script deploys a counter, increments it and checks its count
+        let output = Command::new("npx")
+            .current_dir(hardhat_project)
+            .args(["hardhat", "deploy", "--network", "kakarot", "--no-compile"])
+            .env("KAKAROT_RPC_URL", &rpc_url)
+            .env("PRIVATE_KEY", format!("0x{EOA_PRIVATE_KEY}"))
+            .output()
+            .await
+            .expect("npx not found, install Node.js to run the compatibility tests");
+        let nonce = provider.get_transaction_count(EOA_WALLET.address(), None).await.unwrap();
+        // Hardhat plugins send the booleans as strings
+        let block: Block<H256> = provider.request("eth_getBlockByNumber", ("latest", "false")).await.unwrap();
+
+        // Then
+        assert!(
+            output.status.success(),
+            "hardhat deploy failed, run `npm install` in {hardhat_project}: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_eq!(U256::from(2), nonce);
+        assert!(block.number.is_some());
+        server.handle().stop().unwrap();
+    }
+}
@@ -0,0 +1,147 @@
+// The test sequencer fixture of the core crate, not all of its helpers being used here
+#[allow(dead_code)]
+#[path = "../../core/tests/utils/mod.rs"]
+mod utils;
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use ethers::providers::{Http, Middleware, Provider};
+    use ethers::signers::Signer;
+    use ethers::types::transaction::eip2718::TypedTransaction;
+    use ethers::types::{Address, Eip1559TransactionRequest, U256};
+    use kakarot_rpc_core::client::constants::CHAIN_ID;
+    use kakarot_rpc_core::models::compat::canonical_quantity;
+    use serde_json::{json, Value};
+    use starknet::core::types::FieldElement;
+
+    use crate::common::serve_rpc;
+    use crate::utils::constants::EOA_WALLET;
+    use crate::utils::deploy_helpers::{construct_kakarot_test_sequencer, deploy_kakarot_system};
+
+    /// Requests MetaMask makes to load an account and send a transfer, in order.
+    const METAMASK_CALLS: [&str; 10] = [
+        "eth_chainId",
+        "net_version",
+        "eth_blockNumber",
+        "eth_getBlockByNumber",
+        "eth_getBalance",
+        "eth_getTransactionCount",
+        "eth_feeHistory",
+        "eth_estimateGas",
+        "eth_sendRawTransaction",
+        "eth_getTransactionReceipt",
+    ];
+
+    /// Headless EIP-1193 provider: sends the requests of a wallet and returns the raw JSON
+    /// responses, whose shapes are checked field by field instead of being parsed into types.
+    struct Eip1193Provider {
+        provider: Provider<Http>,
+        calls: Vec<&'static str>,
+    }
+
+    impl Eip1193Provider {
+        async fn request(&mut self, method: &'static str, params: Value) -> Value {
+            if self.calls.last() != Some(&method) {
+                self.calls.push(method);
+            }
+            self.provider.request(method, params).await.unwrap_or_else(|err| panic!("{method} failed: {err}"))
+        }
+    }
+
+    /// Asserts that a value is a quantity encoded as Geth does, and returns it.
+    fn quantity(value: &Value, field: &str) -> U256 {
+        let quantity = value.as_str().unwrap_or_else(|| panic!("{field} is not a string: {value}"));
+        assert_eq!(Some(quantity.to_string()), canonical_quantity(quantity), "{field} is not a canonical quantity");
+        U256::from_str_radix(&quantity[2..], 16).unwrap()
+    }
+
+    /// Asserts that a value is hex data of `len` bytes.
+    fn assert_data(value: &Value, len: usize, field: &str) {
+        let data = value.as_str().unwrap_or_else(|| panic!("{field} is not a string: {value}"));
+        let bytes = data.strip_prefix("0x").map(hex::decode);
+        assert!(matches!(bytes, Some(Ok(bytes)) if bytes.len() == len), "{field} is not {len} bytes of data: {data}");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_metamask_startup_and_transfer() {
+        // Given
+        let starknet_test_sequencer = construct_kakarot_test_sequencer().await;
+        let funded_amount = FieldElement::from_dec_str("10000000000000000000").unwrap();
+        let deployed_kakarot = deploy_kakarot_system(&starknet_test_sequencer, EOA_WALLET.clone(), funded_amount).await;
+        let server = serve_rpc(&starknet_test_sequencer, &deployed_kakarot).await;
+
+        let provider = Provider::<Http>::try_from(format!("http://{}", server.local_addr())).unwrap();
+        let mut wallet = Eip1193Provider { provider, calls: Vec::new() };
+        let account = format!("{:#x}", EOA_WALLET.address());
+        let recipient = Address::from_low_u64_be(0xdead);
+
+        // When
+        // Startup: network, latest block and account
+        let chain_id = wallet.request("eth_chainId", json!([])).await;
+        let network_id = wallet.request("net_version", json!([])).await;
+        let block_number = wallet.request("eth_blockNumber", json!([])).await;
+        let block = wallet.request("eth_getBlockByNumber", json!(["latest", false])).await;
+        let balance = wallet.request("eth_getBalance", json!([account, "latest"])).await;
+
+        // Transfer: nonce, fees and gas, then the receipt is polled
+        let nonce = wallet.request("eth_getTransactionCount", json!([account, "pending"])).await;
+        let fee_history = wallet.request("eth_feeHistory", json!(["0x5", "latest", [10, 20, 30]])).await;
+        let transfer = json!({ "from": account, "to": recipient, "value": "0x3e8" });
+        let gas = wallet.request("eth_estimateGas", json!([transfer])).await;
+
+        let base_fee = quantity(&block["baseFeePerGas"], "baseFeePerGas");
+        let priority_fee = quantity(&fee_history["reward"][4][1], "reward");
+        let transaction: TypedTransaction = Eip1559TransactionRequest::new()
+            .from(EOA_WALLET.address())
+            .to(recipient)
+            .value(1000)
+            .nonce(quantity(&nonce, "nonce"))
+            .gas(quantity(&gas, "gas"))
+            .max_fee_per_gas(base_fee * 2 + priority_fee)
+            .max_priority_fee_per_gas(priority_fee)
+            .chain_id(CHAIN_ID)
+            .into();
+        let signature = EOA_WALLET.sign_transaction(&transaction).await.unwrap();
+        let raw_transaction = transaction.rlp_signed(&signature);
+        let hash = wallet.request("eth_sendRawTransaction", json!([raw_transaction])).await;
+
+        let mut receipt = Value::Null;
+        for _ in 0..50 {
+            receipt = wallet.request("eth_getTransactionReceipt", json!([hash])).await;
+            if !receipt.is_null() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+
+        // Then
+        assert_eq!(METAMASK_CALLS.to_vec(), wallet.calls);
+
+        assert_eq!(U256::from(CHAIN_ID), quantity(&chain_id, "chainId"));
+        assert_eq!(json!(CHAIN_ID.to_string()), network_id);
+        let block_number = quantity(&block_number, "blockNumber");
+        assert!(quantity(&block["number"], "number") >= block_number);
+        assert_data(&block["hash"], 32, "hash");
+        assert!(block["transactions"].as_array().unwrap().iter().all(|hash| hash.is_string()));
+        assert!(quantity(&balance, "balance") > U256::zero());
+
+        assert_eq!(6, fee_history["baseFeePerGas"].as_array().unwrap().len());
+        assert_eq!(5, fee_history["gasUsedRatio"].as_array().unwrap().len());
+        let rewards = fee_history["reward"].as_array().unwrap();
+        assert!(rewards.len() == 5 && rewards.iter().all(|reward| reward.as_array().unwrap().len() == 3));
+        quantity(&fee_history["oldestBlock"], "oldestBlock");
+        assert!(quantity(&gas, "gas") >= U256::from(21_000));
+
+        assert_data(&hash, 32, "transactionHash");
+        assert_eq!(hash, receipt["transactionHash"]);
+        assert_eq!(U256::one(), quantity(&receipt["status"], "status"));
+        quantity(&receipt["blockNumber"], "blockNumber");
+        quantity(&receipt["effectiveGasPrice"], "effectiveGasPrice");
+        assert!(receipt["logs"].is_array());
+        server.handle().stop().unwrap();
+    }
+}
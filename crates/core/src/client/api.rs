@@ -2,15 +2,36 @@ use async_trait::async_trait;
 use eyre::Result;
 use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, H256, U128, U256, U64};
 use reth_rpc_types::{
-    BlockTransactions, CallRequest, FeeHistory, Index, RichBlock, SyncStatus, Transaction as EtherTransaction,
+    BlockTransactions, CallRequest, FeeHistory, Index, Log, RichBlock, SyncStatus, Transaction as EtherTransaction,
     TransactionReceipt,
 };
 use starknet::core::types::{BlockId as StarknetBlockId, BroadcastedInvokeTransactionV1, FieldElement};
 use starknet::providers::sequencer::models::TransactionSimulationInfo;
 use starknet::providers::Provider;
 
+use super::audit::AuditEntry;
+use super::breaker::BreakerStats;
+use super::calls::CallCacheStats;
+use super::coalesce::CoalescingStats;
+use super::creations::ContractCreator;
+use super::devnet::DevnetClient;
 use super::errors::EthApiError;
+use super::lanes::LaneStats;
+use super::liveness::LivenessStatus;
+use super::roots::BlockRoots;
+use super::stats::ClientStats;
+use super::tracking::{RelayStats, TrackedTransaction};
+use super::upstream::UpstreamStats;
+use crate::models::account::AccountType;
 use crate::models::balance::TokenBalances;
+use crate::models::bundle::CallBundle;
+use crate::models::capabilities::Capabilities;
+use crate::models::chain_config::ChainConfig;
+use crate::models::deployment::DeploymentReport;
+use crate::models::fee::StarknetFeeEstimate;
+use crate::models::filter::LogFilter;
+use crate::models::message::L1Message;
+use crate::models::state_diff::AccountStateDiff;
 use crate::models::transaction::StarknetTransactions;
 
 #[async_trait]
@@ -21,6 +42,18 @@ pub trait KakarotEthApi<P: Provider + Send + Sync>: KakarotStarknetApi<P> + Send
 
     async fn get_code(&self, ethereum_address: Address, block_id: BlockId) -> Result<Bytes, EthApiError<P::Error>>;
 
+    async fn account_type(
+        &self,
+        ethereum_address: Address,
+        block_id: BlockId,
+    ) -> Result<AccountType, EthApiError<P::Error>>;
+
+    async fn state_diff(
+        &self,
+        block_id: BlockId,
+        slots: Vec<U256>,
+    ) -> Result<Vec<AccountStateDiff>, EthApiError<P::Error>>;
+
     async fn call(&self, to: Address, calldata: Bytes, block_id: BlockId) -> Result<Bytes, EthApiError<P::Error>>;
 
     async fn transaction_by_block_id_and_index(
@@ -37,6 +70,8 @@ pub trait KakarotEthApi<P: Provider + Send + Sync>: KakarotStarknetApi<P> + Send
 
     async fn transaction_receipt(&self, hash: H256) -> Result<Option<TransactionReceipt>, EthApiError<P::Error>>;
 
+    async fn block_receipts(&self, block_id: BlockId) -> Result<Vec<TransactionReceipt>, EthApiError<P::Error>>;
+
     async fn nonce(&self, ethereum_address: Address, block_id: BlockId) -> Result<U256, EthApiError<P::Error>>;
 
     async fn balance(&self, ethereum_address: Address, block_id: BlockId) -> Result<U256, EthApiError<P::Error>>;
@@ -56,12 +91,92 @@ pub trait KakarotEthApi<P: Provider + Send + Sync>: KakarotStarknetApi<P> + Send
 
     async fn send_transaction(&self, bytes: Bytes) -> Result<H256, EthApiError<P::Error>>;
 
+    async fn send_private_transaction(&self, bytes: Bytes) -> Result<H256, EthApiError<P::Error>>;
+
+    async fn transaction_status(&self, hash: H256) -> Result<Option<TrackedTransaction>, EthApiError<P::Error>>;
+
+    async fn l1_messages(&self, hash: H256) -> Result<Option<Vec<L1Message>>, EthApiError<P::Error>>;
+
+    async fn get_logs(&self, filter: LogFilter) -> Result<Vec<Log>, EthApiError<P::Error>>;
+
+    fn relay_stats(&self) -> RelayStats;
+
+    fn audit_log(&self, from: u64, limit: usize) -> Result<Option<Vec<AuditEntry>>, EthApiError<P::Error>>;
+
+    async fn liveness(&self) -> Result<LivenessStatus, EthApiError<P::Error>>;
+
+    fn capabilities(&self) -> Capabilities;
+
+    async fn chain_config(&self) -> ChainConfig;
+
+    fn lane_stats(&self) -> LaneStats;
+
+    fn upstream_stats(&self) -> Vec<UpstreamStats>;
+
+    fn coalescing_stats(&self) -> CoalescingStats;
+
+    fn shed_if_overloaded(&self) -> Result<(), EthApiError<P::Error>>;
+
+    fn breaker_stats(&self) -> BreakerStats;
+
+    fn call_cache_stats(&self) -> CallCacheStats;
+
+    fn stats(&self) -> ClientStats;
+
+    fn devnet(&self) -> Result<&DevnetClient, EthApiError<P::Error>>;
+
+    async fn set_balance(&self, address: Address, balance: U256) -> Result<(), EthApiError<P::Error>>;
+
+    async fn set_code(&self, address: Address, code: Bytes) -> Result<(), EthApiError<P::Error>>;
+
+    async fn set_storage_at(&self, address: Address, index: U256, value: U256) -> Result<(), EthApiError<P::Error>>;
+
+    fn impersonate_account(&self, address: Address, impersonate: bool) -> Result<(), EthApiError<P::Error>>;
+
+    fn impersonated_accounts(&self) -> Vec<Address>;
+
+    async fn send_impersonated_transaction(&self, request: CallRequest) -> Result<H256, EthApiError<P::Error>>;
+
+    async fn snapshot(&self) -> Result<U64, EthApiError<P::Error>>;
+
+    async fn revert(&self, id: U64) -> Result<bool, EthApiError<P::Error>>;
+
+    async fn estimate_starknet_fee(&self, bytes: Bytes) -> Result<StarknetFeeEstimate, EthApiError<P::Error>>;
+
+    async fn call_bundle(
+        &self,
+        transactions: Vec<Bytes>,
+        block_id: BlockId,
+    ) -> Result<CallBundle, EthApiError<P::Error>>;
+
+    async fn block_by_timestamp(
+        &self,
+        timestamp: u64,
+        hydrated: bool,
+    ) -> Result<Option<RichBlock>, EthApiError<P::Error>>;
+
+    async fn transaction_by_sender_and_nonce(
+        &self,
+        sender: Address,
+        nonce: U256,
+    ) -> Result<Option<H256>, EthApiError<P::Error>>;
+
+    fn contract_creator(&self, address: Address) -> Option<ContractCreator>;
+
+    async fn index_contract_creations(&self, from_block: u64, to_block: u64) -> Result<u64, EthApiError<P::Error>>;
+
     async fn get_transaction_count_by_block(&self, block_id: BlockId) -> Result<U64, EthApiError<P::Error>>;
 
     fn base_fee_per_gas(&self) -> U256;
 
     fn max_priority_fee_per_gas(&self) -> U128;
 
+    fn block_gas_limit(&self) -> U256;
+
+    async fn block_timestamp(&self, timestamp: u64, block_number: Option<u64>) -> U256;
+
+    async fn block_roots(&self, block_hash: H256) -> Option<BlockRoots>;
+
     async fn fee_history(
         &self,
         block_count: U256,
@@ -120,4 +235,6 @@ pub trait KakarotStarknetApi<P: Provider + Send + Sync>: Send + Sync {
         block_number: u64,
         skip_validate: bool,
     ) -> Result<TransactionSimulationInfo, EthApiError<P::Error>>;
+
+    async fn validate_deployment(&self) -> Result<DeploymentReport, EthApiError<P::Error>>;
 }
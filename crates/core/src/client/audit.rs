@@ -0,0 +1,121 @@
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use reth_primitives::{Address, H256, U256};
+use serde::{Deserialize, Serialize};
+
+use super::store::{KakarotStore, StoreError, AUDIT_NAMESPACE};
+
+/// Maximum number of audit log entries returned at once by `admin_auditLog`.
+pub const MAX_AUDIT_ENTRIES: usize = 1000;
+
+/// What an audit log entry records about a relayed transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "camelCase")]
+pub enum AuditEvent {
+    /// The transaction was submitted to Starknet on behalf of its sender.
+    #[serde(rename_all = "camelCase")]
+    Relayed { sender: Address, nonce: u64, to: Option<Address>, value: U256, private: bool },
+    /// The transaction was accepted on Starknet, the relayer paying `actual_fee` for it.
+    #[serde(rename_all = "camelCase")]
+    FeePaid { actual_fee: U256 },
+}
+
+/// An entry of the audit log, timestamped in milliseconds since the unix epoch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub sequence: u64,
+    pub timestamp: u64,
+    pub eth_hash: H256,
+    pub starknet_hash: H256,
+    #[serde(flatten)]
+    pub event: AuditEvent,
+}
+
+/// Append-only log of the transactions relayed on behalf of users and of the fees the relayer
+/// paid for them, kept in the store of the RPC for accounting and abuse investigations. Entries
+/// are keyed by their big endian sequence number, so that the store returns them in order.
+pub struct AuditLog {
+    store: Arc<dyn KakarotStore>,
+    /// Sequence number of the next entry, locked while appending so that sequences are unique.
+    next_sequence: Mutex<u64>,
+}
+
+impl AuditLog {
+    /// Opens the audit log of a store, appending after the entries written before a restart.
+    pub fn new(store: Arc<dyn KakarotStore>) -> Self {
+        let next_sequence = match store.entries(AUDIT_NAMESPACE) {
+            Ok(entries) => entries
+                .last()
+                .and_then(|(key, _)| <[u8; 8]>::try_from(key.as_slice()).ok())
+                .map_or(0, |key| u64::from_be_bytes(key) + 1),
+            Err(err) => {
+                tracing::warn!("Failed to read the audit log: {err}");
+                0
+            }
+        };
+        Self { store, next_sequence: Mutex::new(next_sequence) }
+    }
+
+    /// Appends an entry. Storage failures are logged and do not fail the relay.
+    pub fn append(&self, eth_hash: H256, starknet_hash: H256, event: AuditEvent) {
+        let mut next_sequence = self.next_sequence.lock().expect("audit log lock poisoned");
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or_default();
+        let entry = AuditEntry { sequence: *next_sequence, timestamp, eth_hash, starknet_hash, event };
+        let result = serde_json::to_vec(&entry).map_err(|err| err.to_string()).and_then(|value| {
+            self.store.put(AUDIT_NAMESPACE, &entry.sequence.to_be_bytes(), &value).map_err(|err| err.to_string())
+        });
+        match result {
+            Ok(()) => *next_sequence += 1,
+            Err(err) => tracing::warn!("Failed to append transaction {eth_hash:#x} to the audit log: {err}"),
+        }
+    }
+
+    /// Returns up to `limit` entries, starting at the sequence number `from`.
+    pub fn entries(&self, from: u64, limit: usize) -> Result<Vec<AuditEntry>, StoreError> {
+        Ok(self
+            .store
+            .entries(AUDIT_NAMESPACE)?
+            .into_iter()
+            .filter(|(key, _)| <[u8; 8]>::try_from(key.as_slice()).map_or(false, |key| u64::from_be_bytes(key) >= from))
+            .filter_map(|(_, value)| serde_json::from_slice(&value).ok())
+            .take(limit)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::store::InMemoryStore;
+
+    #[test]
+    fn test_audit_log_appends_after_restart() {
+        // Given
+        let store: Arc<dyn KakarotStore> = Arc::new(InMemoryStore::default());
+        let (eth_hash, starknet_hash) = (H256::from_low_u64_be(1), H256::from_low_u64_be(2));
+        let relayed = AuditEvent::Relayed {
+            sender: Address::from_low_u64_be(3),
+            nonce: 0,
+            to: None,
+            value: U256::from(10),
+            private: false,
+        };
+        AuditLog::new(store.clone()).append(eth_hash, starknet_hash, relayed.clone());
+
+        // When
+        let audit_log = AuditLog::new(store);
+        audit_log.append(eth_hash, starknet_hash, AuditEvent::FeePaid { actual_fee: U256::from(5) });
+
+        // Then
+        let entries = audit_log.entries(0, MAX_AUDIT_ENTRIES).unwrap();
+        assert_eq!(vec![0, 1], entries.iter().map(|entry| entry.sequence).collect::<Vec<_>>());
+        assert_eq!(relayed, entries[0].event);
+        assert_eq!(1, audit_log.entries(1, MAX_AUDIT_ENTRIES).unwrap()[0].sequence);
+        assert!(audit_log.entries(0, 0).unwrap().is_empty());
+        let json = serde_json::to_value(&entries[1]).unwrap();
+        assert_eq!("feePaid", json["event"]);
+        assert_eq!("0x5", json["actualFee"]);
+    }
+}
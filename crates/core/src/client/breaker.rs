@@ -0,0 +1,161 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::upstream::UpstreamStats;
+
+/// Default error rate of the Starknet provider above which the expensive requests are shed.
+pub const DEFAULT_BREAKER_MAX_ERROR_RATE: f64 = 0.5;
+/// Default latency of the Starknet provider above which the expensive requests are shed.
+pub const DEFAULT_BREAKER_MAX_LATENCY: Duration = Duration::from_secs(5);
+/// Default time the expensive requests are shed before the health of the provider is checked
+/// again.
+pub const DEFAULT_BREAKER_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// Thresholds of the circuit breaker.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BreakerConfig {
+    pub max_error_rate: f64,
+    pub max_latency: Duration,
+    pub cooldown: Duration,
+}
+
+impl Default for BreakerConfig {
+    fn default() -> Self {
+        Self {
+            max_error_rate: DEFAULT_BREAKER_MAX_ERROR_RATE,
+            max_latency: DEFAULT_BREAKER_MAX_LATENCY,
+            cooldown: DEFAULT_BREAKER_COOLDOWN,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BreakerState {
+    /// Every request is served.
+    Closed,
+    /// The expensive requests are shed.
+    Open,
+}
+
+/// Expensive request shed while the Starknet provider is unhealthy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("node overloaded, retry later")]
+pub struct Overloaded;
+
+/// State of the circuit breaker and health of the Starknet provider it is based on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BreakerStats {
+    pub state: BreakerState,
+    /// Error rate of the endpoint the requests are routed to.
+    pub error_rate: f64,
+    /// Latency of the endpoint the requests are routed to.
+    pub latency_ms: f64,
+    /// Number of times the breaker opened.
+    pub trips: u64,
+    /// Number of requests shed.
+    pub shed: u64,
+}
+
+/// Sheds the expensive requests, e.g. `eth_getLogs` or bundle simulations, while the Starknet
+/// provider is unhealthy, so that the cheap reads and the transaction submissions are still served.
+///
+/// The health of the provider is the moving error rate and latency of its best endpoint, kept up
+/// to date by the requests still served. Once open, the breaker stays open for the cooldown, then
+/// closes as soon as the provider is healthy again.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    config: BreakerConfig,
+    /// Time the breaker opened, `None` while closed.
+    opened_at: Mutex<Option<Instant>>,
+    trips: AtomicU64,
+    shed: AtomicU64,
+}
+
+/// Returns the error rate and the latency in milliseconds of the best endpoint, the one the
+/// requests are routed to. Endpoints never used are ignored.
+fn health(upstream: &[UpstreamStats]) -> (f64, f64) {
+    upstream
+        .iter()
+        .filter(|stats| stats.requests > 0)
+        .min_by(|a, b| a.score().total_cmp(&b.score()))
+        .map_or((0., 0.), |stats| (stats.error_rate, stats.latency_ms))
+}
+
+impl CircuitBreaker {
+    pub fn new(config: BreakerConfig) -> Self {
+        Self { config, opened_at: Mutex::new(None), trips: AtomicU64::new(0), shed: AtomicU64::new(0) }
+    }
+
+    fn is_healthy(&self, upstream: &[UpstreamStats]) -> bool {
+        let (error_rate, latency_ms) = health(upstream);
+        error_rate <= self.config.max_error_rate && latency_ms <= self.config.max_latency.as_secs_f64() * 1000.
+    }
+
+    /// Checks whether an expensive request can be served, given the stats of the Starknet
+    /// endpoints at `now`.
+    pub fn check(&self, upstream: &[UpstreamStats], now: Instant) -> Result<(), Overloaded> {
+        let mut opened_at = self.opened_at.lock().expect("circuit breaker lock poisoned");
+        let cooling_down = opened_at.map_or(false, |opened_at| now < opened_at + self.config.cooldown);
+        if !cooling_down {
+            if self.is_healthy(upstream) {
+                *opened_at = None;
+                return Ok(());
+            }
+            if opened_at.is_none() {
+                self.trips.fetch_add(1, Ordering::Relaxed);
+                let (error_rate, latency_ms) = health(upstream);
+                tracing::warn!(
+                    "Starknet provider unhealthy, error rate {error_rate:.2} and latency {latency_ms:.0}ms: shedding \
+                     expensive requests"
+                );
+            }
+            *opened_at = Some(now);
+        }
+        self.shed.fetch_add(1, Ordering::Relaxed);
+        Err(Overloaded)
+    }
+
+    pub fn stats(&self, upstream: &[UpstreamStats]) -> BreakerStats {
+        let (error_rate, latency_ms) = health(upstream);
+        let open = self.opened_at.lock().expect("circuit breaker lock poisoned").is_some();
+        BreakerStats {
+            state: if open { BreakerState::Open } else { BreakerState::Closed },
+            error_rate,
+            latency_ms,
+            trips: self.trips.load(Ordering::Relaxed),
+            shed: self.shed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circuit_breaker_sheds_while_unhealthy() {
+        // Given
+        let config = BreakerConfig { cooldown: Duration::from_secs(10), ..Default::default() };
+        let breaker = CircuitBreaker::new(config);
+        let endpoint =
+            |error_rate: f64| UpstreamStats { error_rate, latency_ms: 100., requests: 10, ..Default::default() };
+        let (healthy, failing) = ([endpoint(0.1)], [endpoint(0.8)]);
+        let now = Instant::now();
+
+        // Then
+        assert_eq!(Ok(()), breaker.check(&healthy, now));
+        assert_eq!(Err(Overloaded), breaker.check(&failing, now));
+        // Shed for the cooldown, even once the provider recovered
+        assert_eq!(Err(Overloaded), breaker.check(&healthy, now + Duration::from_secs(5)));
+        assert_eq!(BreakerState::Open, breaker.stats(&healthy).state);
+        assert_eq!(Ok(()), breaker.check(&healthy, now + Duration::from_secs(11)));
+        let stats = breaker.stats(&healthy);
+        assert_eq!((BreakerState::Closed, 1, 2), (stats.state, stats.trips, stats.shed));
+    }
+}
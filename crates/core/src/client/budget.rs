@@ -0,0 +1,83 @@
+use serde::Serialize;
+use thiserror::Error;
+
+/// Default maximum number of Starknet calls made to serve a single request.
+pub const DEFAULT_MAX_UPSTREAM_CALLS: u64 = 1_000;
+/// Default maximum size in bytes of the Starknet responses fetched to serve a single request.
+pub const DEFAULT_MAX_UPSTREAM_RESPONSE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Limits of the Starknet calls made to serve a single request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BudgetConfig {
+    pub max_upstream_calls: u64,
+    pub max_upstream_response_bytes: u64,
+}
+
+impl Default for BudgetConfig {
+    fn default() -> Self {
+        Self {
+            max_upstream_calls: DEFAULT_MAX_UPSTREAM_CALLS,
+            max_upstream_response_bytes: DEFAULT_MAX_UPSTREAM_RESPONSE_BYTES,
+        }
+    }
+}
+
+/// Request aborted because serving it would exceed the budget of Starknet calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum BudgetExceeded {
+    #[error("query too complex: more than {0} Starknet calls, narrow the request")]
+    UpstreamCalls(u64),
+    #[error("query too complex: more than {0} bytes of Starknet responses, narrow the request")]
+    UpstreamResponseBytes(u64),
+}
+
+/// Starknet calls left to serve a request, created for each request by the methods fanning out
+/// to many calls, e.g. `eth_getLogs` paginating through the Starknet events.
+#[derive(Debug)]
+pub struct RequestBudget {
+    config: BudgetConfig,
+    calls: u64,
+    response_bytes: u64,
+}
+
+impl RequestBudget {
+    pub fn new(config: BudgetConfig) -> Self {
+        Self { config, calls: 0, response_bytes: 0 }
+    }
+
+    /// Charges `count` Starknet calls, before making them.
+    pub fn charge_calls(&mut self, count: u64) -> Result<(), BudgetExceeded> {
+        self.calls = self.calls.saturating_add(count);
+        if self.calls > self.config.max_upstream_calls {
+            return Err(BudgetExceeded::UpstreamCalls(self.config.max_upstream_calls));
+        }
+        Ok(())
+    }
+
+    /// Charges the size of a Starknet response, measured as its JSON serialization.
+    pub fn charge_response<T: Serialize>(&mut self, response: &T) -> Result<(), BudgetExceeded> {
+        let size = serde_json::to_vec(response).map(|bytes| bytes.len() as u64).unwrap_or_default();
+        self.response_bytes = self.response_bytes.saturating_add(size);
+        if self.response_bytes > self.config.max_upstream_response_bytes {
+            return Err(BudgetExceeded::UpstreamResponseBytes(self.config.max_upstream_response_bytes));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_budget() {
+        // Given
+        let mut budget = RequestBudget::new(BudgetConfig { max_upstream_calls: 2, max_upstream_response_bytes: 8 });
+
+        // Then
+        assert_eq!(Ok(()), budget.charge_calls(2));
+        assert_eq!(Err(BudgetExceeded::UpstreamCalls(2)), budget.charge_calls(1));
+        assert_eq!(Ok(()), budget.charge_response(&"abcd"));
+        assert_eq!(Err(BudgetExceeded::UpstreamResponseBytes(8)), budget.charge_response(&"abcd"));
+    }
+}
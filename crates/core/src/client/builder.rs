@@ -0,0 +1,171 @@
+use std::sync::Arc;
+
+use starknet::core::types::FieldElement;
+use starknet::providers::Provider;
+
+use super::config::{FeeRetryPolicy, GasPriceConfig, Network, OperatingMode, StarknetConfig};
+use super::errors::ConfigError;
+use super::lanes::LaneConfig;
+use super::store::KakarotStore;
+use super::KakarotClient;
+
+/// Builder of a [`KakarotClient`], started by [`KakarotClient::builder`]. The options not set keep
+/// the defaults of [`StarknetConfig::new`] for the network, so that new options do not break the
+/// code building a client.
+///
+/// Example :
+/// ```ignore
+///   let client = KakarotClient::builder()
+///       .with_network(Network::Katana)
+///       .with_kakarot_address(kakarot_address)
+///       .with_proxy_account_class_hash(proxy_account_class_hash)
+///       .with_provider(starknet_provider)
+///       .with_call_cache_capacity(0)
+///       .build()?;
+/// ```
+pub struct KakarotClientBuilder<P> {
+    provider: Option<P>,
+    starknet_config: Option<StarknetConfig>,
+    network: Option<Network>,
+    kakarot_address: Option<FieldElement>,
+    proxy_account_class_hash: Option<FieldElement>,
+    fee_retry_policy: Option<FeeRetryPolicy>,
+    gas_price_config: Option<GasPriceConfig>,
+    operating_mode: Option<OperatingMode>,
+    lane_config: Option<LaneConfig>,
+    store: Option<Arc<dyn KakarotStore>>,
+    call_cache_capacity: Option<usize>,
+}
+
+impl<P> Default for KakarotClientBuilder<P> {
+    fn default() -> Self {
+        Self {
+            provider: None,
+            starknet_config: None,
+            network: None,
+            kakarot_address: None,
+            proxy_account_class_hash: None,
+            fee_retry_policy: None,
+            gas_price_config: None,
+            operating_mode: None,
+            lane_config: None,
+            store: None,
+            call_cache_capacity: None,
+        }
+    }
+}
+
+impl<P: Provider + Send + Sync + 'static> KakarotClientBuilder<P> {
+    /// Sets the Starknet provider the client reads from and submits transactions to.
+    pub fn with_provider(mut self, provider: P) -> Self {
+        self.provider = Some(provider);
+        self
+    }
+
+    /// Starts from a complete configuration, e.g. read from the environment with
+    /// [`StarknetConfig::from_env`]. The options set on the builder take precedence.
+    pub fn with_starknet_config(mut self, starknet_config: StarknetConfig) -> Self {
+        self.starknet_config = Some(starknet_config);
+        self
+    }
+
+    /// Sets the Starknet network, from which the chain id, the fee retry policy, the deployment
+    /// addresses and the native token default.
+    pub fn with_network(mut self, network: Network) -> Self {
+        self.network = Some(network);
+        self
+    }
+
+    pub fn with_kakarot_address(mut self, kakarot_address: FieldElement) -> Self {
+        self.kakarot_address = Some(kakarot_address);
+        self
+    }
+
+    pub fn with_proxy_account_class_hash(mut self, proxy_account_class_hash: FieldElement) -> Self {
+        self.proxy_account_class_hash = Some(proxy_account_class_hash);
+        self
+    }
+
+    /// Sets the resubmission policy of the transactions rejected for an insufficient max fee.
+    pub fn with_fee_retry_policy(mut self, fee_retry_policy: FeeRetryPolicy) -> Self {
+        self.fee_retry_policy = Some(fee_retry_policy);
+        self
+    }
+
+    /// Sets the smoothing and bounds of the gas price reported by `eth_gasPrice`.
+    pub fn with_gas_price_config(mut self, gas_price_config: GasPriceConfig) -> Self {
+        self.gas_price_config = Some(gas_price_config);
+        self
+    }
+
+    pub fn with_operating_mode(mut self, operating_mode: OperatingMode) -> Self {
+        self.operating_mode = Some(operating_mode);
+        self
+    }
+
+    /// Sets the concurrency and queue limits of the heavy read and transaction submission lanes.
+    pub fn with_lane_config(mut self, lane_config: LaneConfig) -> Self {
+        self.lane_config = Some(lane_config);
+        self
+    }
+
+    /// Sets the persistent store of the relayed transactions and of the indexes.
+    pub fn with_store(mut self, store: Arc<dyn KakarotStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Sets the maximum number of `eth_call` results cached, zero disabling the cache.
+    pub fn with_call_cache_capacity(mut self, call_cache_capacity: usize) -> Self {
+        self.call_cache_capacity = Some(call_cache_capacity);
+        self
+    }
+
+    /// Builds the client.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the provider is not set, or if the network, the Kakarot address or the
+    /// proxy account class hash is set neither on the builder nor by a Starknet configuration.
+    pub fn build(self) -> Result<KakarotClient<P>, ConfigError> {
+        let missing = |option: &str| ConfigError::MissingClientOption(option.into());
+        let provider = self.provider.ok_or_else(|| missing("provider"))?;
+
+        let mut config = match self.starknet_config {
+            Some(mut config) => {
+                if let Some(network) = self.network {
+                    config.network = network;
+                }
+                config.kakarot_address = self.kakarot_address.unwrap_or(config.kakarot_address);
+                config.proxy_account_class_hash =
+                    self.proxy_account_class_hash.unwrap_or(config.proxy_account_class_hash);
+                config
+            }
+            None => StarknetConfig::new(
+                self.network.ok_or_else(|| missing("network"))?,
+                self.kakarot_address.ok_or_else(|| missing("kakarot address"))?,
+                self.proxy_account_class_hash.ok_or_else(|| missing("proxy account class hash"))?,
+            ),
+        };
+        if let Some(fee_retry_policy) = self.fee_retry_policy {
+            config.fee_retry_policy = fee_retry_policy;
+        }
+        if let Some(gas_price_config) = self.gas_price_config {
+            config.gas_price_config = gas_price_config;
+        }
+        if let Some(operating_mode) = self.operating_mode {
+            config.operating_mode = operating_mode;
+        }
+        if let Some(lane_config) = self.lane_config {
+            config.lane_config = lane_config;
+        }
+        if let Some(store) = self.store {
+            config.store = Some(store);
+        }
+        if let Some(call_cache_capacity) = self.call_cache_capacity {
+            config.call_cache_capacity = call_cache_capacity;
+        }
+
+        Ok(KakarotClient::from_config(config, provider))
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use reth_primitives::{Address, Bytes, H256};
+use serde::{Deserialize, Serialize};
+
+/// Number of `eth_call` results kept in cache.
+pub const DEFAULT_CALL_CACHE_CAPACITY: usize = 4096;
+
+/// Block an `eth_call` is executed against, as far as its result can be cached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CallBlock {
+    /// The latest block, the head being at the given number. Its results are dropped once a newer
+    /// head is seen.
+    Latest(u64),
+    /// A block given by its number, whose state does not change once accepted.
+    Number(u64),
+    /// A block given by its hash.
+    Hash(H256),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CallKey {
+    block: CallBlock,
+    to: Address,
+    data: Bytes,
+}
+
+#[derive(Default)]
+struct CallCacheState {
+    results: HashMap<CallKey, Bytes>,
+    /// Keys from the least to the most recently used, used to evict results.
+    order: VecDeque<CallKey>,
+    /// Highest head the latest block results were cached at.
+    head: u64,
+}
+
+impl CallCacheState {
+    fn touch(&mut self, key: &CallKey) {
+        self.order.retain(|cached| cached != key);
+        self.order.push_back(key.clone());
+    }
+}
+
+/// Counters of the `eth_call` result cache, returned by `admin_callCacheStats`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallCacheStats {
+    pub entries: usize,
+    pub capacity: usize,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// Cache of the successful `eth_call` results by block, target and calldata, so that the view
+/// functions polled by dashboards are executed once per block. The results of `latest` calls are
+/// dropped on the next head, the other results are kept until the least recently used are evicted.
+/// A capacity of zero disables the cache.
+pub struct CallCache {
+    capacity: usize,
+    state: Mutex<CallCacheState>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl Default for CallCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CALL_CACHE_CAPACITY)
+    }
+}
+
+impl CallCache {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, state: Mutex::default(), hits: AtomicU64::new(0), misses: AtomicU64::new(0) }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Returns the cached result of a call.
+    pub fn get(&self, block: CallBlock, to: Address, data: &Bytes) -> Option<Bytes> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let key = CallKey { block, to, data: data.clone() };
+        let mut state = self.state.lock().expect("call cache lock poisoned");
+        let Some(result) = state.results.get(&key).cloned() else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return None;
+        };
+        state.touch(&key);
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        Some(result)
+    }
+
+    /// Caches the result of a successful call. Caching a `latest` result at a newer head drops the
+    /// results of the previous heads, a result at an older head is not cached.
+    pub fn insert(&self, block: CallBlock, to: Address, data: Bytes, result: Bytes) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut state = self.state.lock().expect("call cache lock poisoned");
+        if let CallBlock::Latest(head) = block {
+            if head < state.head {
+                return;
+            }
+            if head > state.head {
+                state.head = head;
+                state.results.retain(|key, _| !matches!(key.block, CallBlock::Latest(cached) if cached < head));
+                let CallCacheState { results, order, .. } = &mut *state;
+                order.retain(|key| results.contains_key(key));
+            }
+        }
+
+        let key = CallKey { block, to, data };
+        state.results.insert(key.clone(), result);
+        state.touch(&key);
+        while state.order.len() > self.capacity {
+            if let Some(evicted) = state.order.pop_front() {
+                state.results.remove(&evicted);
+            }
+        }
+    }
+
+    pub fn stats(&self) -> CallCacheStats {
+        let entries = self.state.lock().expect("call cache lock poisoned").results.len();
+        CallCacheStats {
+            entries,
+            capacity: self.capacity,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_call_cache_drops_latest_results_on_new_head() {
+        // Given
+        let cache = CallCache::new(2);
+        let (to, data, result) = (Address::from_low_u64_be(1), Bytes::from(vec![1]), Bytes::from(vec![2]));
+        cache.insert(CallBlock::Latest(10), to, data.clone(), result.clone());
+        cache.insert(CallBlock::Number(9), to, data.clone(), result.clone());
+
+        // When
+        cache.insert(CallBlock::Latest(11), to, data.clone(), result.clone());
+        cache.insert(CallBlock::Latest(10), to, data.clone(), result.clone());
+
+        // Then
+        assert_eq!(None, cache.get(CallBlock::Latest(10), to, &data));
+        assert_eq!(Some(result.clone()), cache.get(CallBlock::Latest(11), to, &data));
+        assert_eq!(Some(result), cache.get(CallBlock::Number(9), to, &data));
+        let stats = cache.stats();
+        assert_eq!((2, 2, 1), (stats.entries, stats.hits, stats.misses));
+    }
+
+    #[test]
+    fn test_call_cache_evicts_least_recently_used_results() {
+        // Given
+        let cache = CallCache::new(2);
+        let to = Address::from_low_u64_be(1);
+        let data = |byte: u8| Bytes::from(vec![byte]);
+        cache.insert(CallBlock::Number(1), to, data(1), data(1));
+        cache.insert(CallBlock::Number(1), to, data(2), data(2));
+
+        // When
+        cache.get(CallBlock::Number(1), to, &data(1));
+        cache.insert(CallBlock::Hash(H256::zero()), to, data(3), data(3));
+
+        // Then
+        assert_eq!(Some(data(1)), cache.get(CallBlock::Number(1), to, &data(1)));
+        assert_eq!(None, cache.get(CallBlock::Number(1), to, &data(2)));
+        assert!(CallCache::new(0).get(CallBlock::Number(1), to, &data(1)).is_none());
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+/// Counters of the coalesced Starknet reads.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoalescingStats {
+    /// Reads sent to the Starknet provider.
+    pub requests: u64,
+    /// Reads served by an identical read in flight, without calling the Starknet provider.
+    pub coalesced: u64,
+}
+
+/// Counters of a [`RequestCoalescer`], shared with the admin methods.
+#[derive(Debug, Default)]
+pub struct CoalescingCounters {
+    requests: AtomicU64,
+    coalesced: AtomicU64,
+}
+
+impl CoalescingCounters {
+    pub fn stats(&self) -> CoalescingStats {
+        CoalescingStats {
+            requests: self.requests.load(Ordering::Relaxed),
+            coalesced: self.coalesced.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Coalesces identical concurrent requests: the first one, the leader, is sent while the others
+/// wait for its response.
+#[derive(Debug)]
+pub struct RequestCoalescer<T> {
+    in_flight: Mutex<HashMap<String, broadcast::Sender<T>>>,
+    counters: Arc<CoalescingCounters>,
+}
+
+impl<T> Default for RequestCoalescer<T> {
+    fn default() -> Self {
+        Self { in_flight: Mutex::default(), counters: Arc::default() }
+    }
+}
+
+/// Role of a request in the coalescer.
+pub enum Flight<'a, T: Clone> {
+    /// The request has to be sent, its response completing the identical requests.
+    Leader(Leader<'a, T>),
+    /// An identical request is in flight. The receiver is closed without a response if the
+    /// leader fails or is cancelled, the request then has to be sent.
+    Follower(broadcast::Receiver<T>),
+}
+
+/// An identical request in flight, released on drop.
+pub struct Leader<'a, T: Clone> {
+    coalescer: &'a RequestCoalescer<T>,
+    key: String,
+    completed: bool,
+}
+
+impl<T: Clone> Leader<'_, T> {
+    /// Shares the response of the request with the requests waiting for it.
+    pub fn complete(mut self, response: T) {
+        if let Some(sender) = self.coalescer.remove(&self.key) {
+            // No receiver if no identical request came in meanwhile
+            let _ = sender.send(response);
+        }
+        self.completed = true;
+    }
+}
+
+impl<T: Clone> Drop for Leader<'_, T> {
+    fn drop(&mut self) {
+        // The key may already belong to the next flight once completed
+        if !self.completed {
+            self.coalescer.remove(&self.key);
+        }
+    }
+}
+
+impl<T: Clone> RequestCoalescer<T> {
+    pub fn counters(&self) -> Arc<CoalescingCounters> {
+        self.counters.clone()
+    }
+
+    /// Joins the flight of the requests identified by `key`.
+    pub fn join(&self, key: String) -> Flight<'_, T> {
+        let mut in_flight = self.in_flight.lock().expect("coalescer lock poisoned");
+        if let Some(sender) = in_flight.get(&key) {
+            self.counters.coalesced.fetch_add(1, Ordering::Relaxed);
+            return Flight::Follower(sender.subscribe());
+        }
+        self.counters.requests.fetch_add(1, Ordering::Relaxed);
+        in_flight.insert(key.clone(), broadcast::channel(1).0);
+        Flight::Leader(Leader { coalescer: self, key, completed: false })
+    }
+
+    fn remove(&self, key: &str) -> Option<broadcast::Sender<T>> {
+        self.in_flight.lock().expect("coalescer lock poisoned").remove(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_coalescer_shares_the_leader_response() {
+        // Given
+        let coalescer = RequestCoalescer::<u64>::default();
+        let Flight::Leader(leader) = coalescer.join("blockNumber".into()) else { panic!("expected a leader") };
+        let Flight::Follower(mut follower) = coalescer.join("blockNumber".into()) else {
+            panic!("expected a follower")
+        };
+
+        // When
+        leader.complete(42);
+
+        // Then
+        assert_eq!(42, follower.recv().await.unwrap());
+        assert!(matches!(coalescer.join("blockNumber".into()), Flight::Leader(_)));
+        assert_eq!(CoalescingStats { requests: 2, coalesced: 1 }, coalescer.counters().stats());
+    }
+
+    #[tokio::test]
+    async fn test_coalescer_releases_followers_of_a_failed_leader() {
+        // Given
+        let coalescer = RequestCoalescer::<u64>::default();
+        let leader = coalescer.join("call".into());
+        let Flight::Follower(mut follower) = coalescer.join("call".into()) else { panic!("expected a follower") };
+
+        // When
+        drop(leader);
+
+        // Then
+        assert!(follower.recv().await.is_err());
+    }
+}
@@ -0,0 +1,517 @@
+use std::fmt;
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use reqwest::Client;
+use serde_json::{json, Value};
+use starknet::core::types::{BroadcastedInvokeTransactionV1, FieldElement};
+use thiserror::Error;
+use url::Url;
+
+/// Time allowed to connect to a Starknet JSON-RPC endpoint outside of the provider.
+pub const COMPAT_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Time allowed for a request to a Starknet JSON-RPC endpoint outside of the provider, bounding
+/// the requests sent on the path of an RPC call.
+pub const COMPAT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Code of the JSON-RPC error returned for an unknown method.
+const METHOD_NOT_FOUND: i64 = -32601;
+/// Codes of the Starknet errors returned for a simulated transaction failing, from 0.4 and 0.6.
+const CONTRACT_ERROR: i64 = 40;
+const TRANSACTION_EXECUTION_ERROR: i64 = 41;
+
+lazy_static! {
+    /// HTTP client shared by the requests sent outside of the provider, so that they reuse their
+    /// connections.
+    static ref HTTP_CLIENT: Client = Client::builder()
+        .connect_timeout(COMPAT_CONNECT_TIMEOUT)
+        .timeout(COMPAT_REQUEST_TIMEOUT)
+        .build()
+        .expect("the compat HTTP client should build with timeouts only");
+}
+
+/// Error of a request sent to a Starknet JSON-RPC endpoint outside of the provider.
+#[derive(Debug, Error)]
+pub enum CompatError {
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error(transparent)]
+    Serialization(#[from] serde_json::Error),
+    /// Error returned by the endpoint.
+    #[error("JSON-RPC error {code}: {message}")]
+    Rpc { code: i64, message: String },
+    #[error("unexpected {method} result: {result}")]
+    UnexpectedResult { method: &'static str, result: Value },
+    /// Simulated transaction reverted, with the reason given by the endpoint.
+    #[error("transaction reverted: {0}")]
+    Reverted(String),
+}
+
+/// Version of the Starknet JSON-RPC specification implemented by a provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SpecVersion {
+    V0_4,
+    V0_5,
+    /// 0.6 and later.
+    V0_6,
+}
+
+impl fmt::Display for SpecVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::V0_4 => write!(f, "0.4"),
+            Self::V0_5 => write!(f, "0.5"),
+            Self::V0_6 => write!(f, "0.6+"),
+        }
+    }
+}
+
+impl SpecVersion {
+    /// Parses the version returned by `starknet_specVersion`, e.g. `0.5.1`. Returns `None` for
+    /// versions outside of 0.x, whose request shapes are unknown.
+    pub fn parse(version: &str) -> Option<Self> {
+        let mut parts = version.trim_start_matches('v').split('.');
+        let major: u64 = parts.next()?.parse().ok()?;
+        let minor: u64 = parts.next()?.parse().ok()?;
+        match (major, minor) {
+            (0, 0..=4) => Some(Self::V0_4),
+            (0, 5) => Some(Self::V0_5),
+            (0, _) => Some(Self::V0_6),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the transaction simulation method, renamed in 0.5.
+    pub fn simulate_method(&self) -> &'static str {
+        match self {
+            Self::V0_4 => "starknet_simulateTransaction",
+            _ => "starknet_simulateTransactions",
+        }
+    }
+
+    /// Returns the JSON-RPC request simulating an invoke transaction on top of a block.
+    pub fn simulate_request(
+        &self,
+        request: &BroadcastedInvokeTransactionV1,
+        block_number: u64,
+        skip_validate: bool,
+    ) -> Result<Value, serde_json::Error> {
+        self.simulate_sequence_request(std::slice::from_ref(request), block_number, skip_validate)
+    }
+
+    /// Returns the JSON-RPC request simulating a sequence of invoke transactions on top of a
+    /// block, each transaction being simulated on the state left by the previous ones.
+    pub fn simulate_sequence_request(
+        &self,
+        requests: &[BroadcastedInvokeTransactionV1],
+        block_number: u64,
+        skip_validate: bool,
+    ) -> Result<Value, serde_json::Error> {
+        let transactions = requests.iter().map(invoke_transaction).collect::<Result<Vec<_>, _>>()?;
+
+        let mut simulation_flags = vec![];
+        if skip_validate {
+            simulation_flags.push("SKIP_VALIDATE");
+        }
+        // Estimations should not fail because the sender cannot pay the fee, 0.6 lets the
+        // provider skip the fee transfer
+        if *self >= Self::V0_6 {
+            simulation_flags.push("SKIP_FEE_CHARGE");
+        }
+
+        // Positional params, as the name of the transactions param changed in 0.5
+        Ok(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": self.simulate_method(),
+            "params": [{ "block_number": block_number }, transactions, simulation_flags],
+        }))
+    }
+}
+
+/// Serializes an invoke transaction as a broadcasted transaction of the JSON-RPC specification.
+fn invoke_transaction(request: &BroadcastedInvokeTransactionV1) -> Result<Value, serde_json::Error> {
+    let mut transaction = serde_json::to_value(request)?;
+    transaction["type"] = "INVOKE".into();
+    transaction["version"] = "0x1".into();
+    Ok(transaction)
+}
+
+/// Fee estimation of a simulated transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimulatedFee {
+    pub gas_consumed: u64,
+    pub gas_price: u64,
+    pub overall_fee: u64,
+}
+
+/// Result of a transaction simulated in a sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulatedTransaction {
+    pub fee: SimulatedFee,
+    /// Values returned by the `__execute__` entrypoint of the sender.
+    pub result: Vec<FieldElement>,
+    /// Reason of the revert of the transaction, reported from 0.5.
+    pub revert_reason: Option<String>,
+}
+
+/// Price of the L1 gas of a block in both fee units, reported from 0.6.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct L1GasPrice {
+    pub price_in_wei: u128,
+    pub price_in_fri: u128,
+}
+
+/// Reads the price of the L1 gas of the latest block in WEI and FRI. Only providers implementing
+/// 0.6 and later report the price in FRI.
+pub async fn latest_l1_gas_price(url: Url) -> Result<L1GasPrice, CompatError> {
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "starknet_getBlockWithTxHashes",
+        "params": ["latest"],
+    });
+    let result = post_request(url, &request).await?;
+    parse_l1_gas_price(&result).ok_or(CompatError::UnexpectedResult { method: "starknet_getBlockWithTxHashes", result })
+}
+
+/// Reads the L1 gas price of a block header.
+fn parse_l1_gas_price(block: &Value) -> Option<L1GasPrice> {
+    let l1_gas_price = &block["l1_gas_price"];
+    let price = |name: &str| u128::from_str_radix(l1_gas_price[name].as_str()?.trim_start_matches("0x"), 16).ok();
+    Some(L1GasPrice { price_in_wei: price("price_in_wei")?, price_in_fri: price("price_in_fri")? })
+}
+
+/// Detects the version of the specification implemented by a Starknet JSON-RPC provider.
+/// `starknet_specVersion` was introduced in 0.5, providers answering it with a method not found
+/// error are assumed to implement 0.4.
+///
+/// # Errors
+///
+/// Will return `Err` if the provider could not be reached, returned another error or a version
+/// which is not supported.
+pub async fn probe_spec_version(url: Url) -> Result<SpecVersion, CompatError> {
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": "starknet_specVersion", "params": [] });
+    let result = match post_request(url, &request).await {
+        Ok(result) => result,
+        Err(CompatError::Rpc { code: METHOD_NOT_FOUND, .. }) => return Ok(SpecVersion::V0_4),
+        Err(err) => return Err(err),
+    };
+
+    result
+        .as_str()
+        .and_then(SpecVersion::parse)
+        .ok_or(CompatError::UnexpectedResult { method: "starknet_specVersion", result })
+}
+
+/// Simulates an invoke transaction through the JSON-RPC provider, using the request shape of its
+/// specification version.
+pub async fn simulate_invoke(
+    url: Url,
+    spec_version: SpecVersion,
+    request: &BroadcastedInvokeTransactionV1,
+    block_number: u64,
+    skip_validate: bool,
+) -> Result<SimulatedFee, CompatError> {
+    let request = spec_version.simulate_request(request, block_number, skip_validate)?;
+    let result = post_request(url, &request).await?;
+    parse_simulated_fee(&result).ok_or(CompatError::UnexpectedResult { method: spec_version.simulate_method(), result })
+}
+
+/// Simulates a sequence of invoke transactions through the JSON-RPC provider, each transaction
+/// seeing the state changes of the previous ones.
+pub async fn simulate_invoke_sequence(
+    url: Url,
+    spec_version: SpecVersion,
+    requests: &[BroadcastedInvokeTransactionV1],
+    block_number: u64,
+    skip_validate: bool,
+) -> Result<Vec<SimulatedTransaction>, CompatError> {
+    let request = spec_version.simulate_sequence_request(requests, block_number, skip_validate)?;
+    let result = post_request(url, &request).await?;
+    parse_simulated_transactions(&result)
+        .ok_or(CompatError::UnexpectedResult { method: spec_version.simulate_method(), result })
+}
+
+/// Simulates an invoke transaction through the JSON-RPC provider and returns the gas it consumed.
+/// Fails with [`CompatError::Reverted`] if the transaction reverts, whether the provider reports
+/// the revert in the trace, from 0.5, or fails the simulation with an execution error.
+pub async fn simulate_gas_usage(
+    url: Url,
+    spec_version: SpecVersion,
+    request: &BroadcastedInvokeTransactionV1,
+    block_number: u64,
+) -> Result<u64, CompatError> {
+    let simulated =
+        match simulate_invoke_sequence(url, spec_version, std::slice::from_ref(request), block_number, true).await {
+            Ok(simulated) => simulated,
+            Err(CompatError::Rpc { code: CONTRACT_ERROR | TRANSACTION_EXECUTION_ERROR, message }) => {
+                return Err(CompatError::Reverted(message));
+            }
+            Err(err) => return Err(err),
+        };
+    match simulated.into_iter().next() {
+        Some(SimulatedTransaction { revert_reason: Some(revert_reason), .. }) => {
+            Err(CompatError::Reverted(revert_reason))
+        }
+        Some(simulated) => Ok(simulated.fee.gas_consumed),
+        None => Err(CompatError::UnexpectedResult { method: spec_version.simulate_method(), result: json!([]) }),
+    }
+}
+
+/// Submits an invoke transaction to a Starknet JSON-RPC endpoint other than the provider,
+/// returning its transaction hash.
+pub async fn add_invoke(url: Url, request: &BroadcastedInvokeTransactionV1) -> Result<FieldElement, CompatError> {
+    let transaction = invoke_transaction(request)?;
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "starknet_addInvokeTransaction",
+        "params": [transaction],
+    });
+    let result = post_request(url, &request).await?;
+    match result["transaction_hash"].as_str().and_then(|hash| FieldElement::from_hex_be(hash).ok()) {
+        Some(transaction_hash) => Ok(transaction_hash),
+        None => Err(CompatError::UnexpectedResult { method: "starknet_addInvokeTransaction", result }),
+    }
+}
+
+/// Posts a JSON-RPC request, returning its result.
+async fn post_request(url: Url, request: &Value) -> Result<Value, CompatError> {
+    let mut response: Value = HTTP_CLIENT.post(url).json(request).send().await?.json().await?;
+
+    if let Some(error) = response.get("error") {
+        return Err(CompatError::Rpc {
+            code: error["code"].as_i64().unwrap_or_default(),
+            message: error["message"].as_str().unwrap_or_default().to_string(),
+        });
+    }
+    Ok(response["result"].take())
+}
+
+/// Reads the fee estimation of the first simulated transaction.
+fn parse_simulated_fee(result: &Value) -> Option<SimulatedFee> {
+    parse_fee_estimation(&result.as_array()?.first()?["fee_estimation"])
+}
+
+/// Reads the fee estimation of a simulated transaction. The fee estimation has the same shape in
+/// all versions, 0.6 only adding its unit.
+fn parse_fee_estimation(fee_estimation: &Value) -> Option<SimulatedFee> {
+    let field = |name: &str| u64::from_str_radix(fee_estimation[name].as_str()?.trim_start_matches("0x"), 16).ok();
+    Some(SimulatedFee {
+        gas_consumed: field("gas_consumed")?,
+        gas_price: field("gas_price")?,
+        overall_fee: field("overall_fee")?,
+    })
+}
+
+/// Reads the simulated transactions. The invocation of `__execute__` is named
+/// `function_invocation` in 0.4 and `execute_invocation` from 0.5, where it holds the revert
+/// reason of a reverted transaction instead.
+fn parse_simulated_transactions(result: &Value) -> Option<Vec<SimulatedTransaction>> {
+    result
+        .as_array()?
+        .iter()
+        .map(|simulated| {
+            let fee = parse_fee_estimation(&simulated["fee_estimation"])?;
+            let trace = &simulated["transaction_trace"];
+            let invocation = if trace["execute_invocation"].is_null() {
+                &trace["function_invocation"]
+            } else {
+                &trace["execute_invocation"]
+            };
+            let revert_reason = invocation["revert_reason"].as_str().map(String::from);
+            let result = match invocation["result"].as_array() {
+                Some(result) => result
+                    .iter()
+                    .map(|felt| FieldElement::from_hex_be(felt.as_str()?).ok())
+                    .collect::<Option<Vec<_>>>()?,
+                None => vec![],
+            };
+            Some(SimulatedTransaction { fee, result, revert_reason })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::body_partial_json;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    #[test]
+    fn test_spec_version_parse() {
+        assert_eq!(Some(SpecVersion::V0_4), SpecVersion::parse("0.4.0"));
+        assert_eq!(Some(SpecVersion::V0_5), SpecVersion::parse("0.5.1"));
+        assert_eq!(Some(SpecVersion::V0_6), SpecVersion::parse("0.6.0"));
+        assert_eq!(Some(SpecVersion::V0_6), SpecVersion::parse("0.7.1"));
+        assert_eq!(None, SpecVersion::parse("1.0.0"));
+        assert_eq!(None, SpecVersion::parse("latest"));
+    }
+
+    #[test]
+    fn test_simulate_request_shape() {
+        // Given
+        let request = BroadcastedInvokeTransactionV1 {
+            max_fee: FieldElement::ZERO,
+            signature: vec![],
+            nonce: FieldElement::ONE,
+            sender_address: FieldElement::ONE,
+            calldata: vec![],
+        };
+
+        // When
+        let v0_4 = SpecVersion::V0_4.simulate_request(&request, 10, true).unwrap();
+        let v0_6 = SpecVersion::V0_6.simulate_request(&request, 10, false).unwrap();
+
+        // Then
+        assert_eq!("starknet_simulateTransaction", v0_4["method"]);
+        assert_eq!(json!(["SKIP_VALIDATE"]), v0_4["params"][2]);
+        assert_eq!("starknet_simulateTransactions", v0_6["method"]);
+        assert_eq!(json!(["SKIP_FEE_CHARGE"]), v0_6["params"][2]);
+        assert_eq!("0x1", v0_6["params"][1][0]["version"]);
+        assert_eq!(10, v0_6["params"][0]["block_number"]);
+    }
+
+    #[test]
+    fn test_parse_simulated_fee() {
+        // Given
+        let result = json!([{
+            "transaction_trace": {},
+            "fee_estimation": { "gas_consumed": "0xa", "gas_price": "0x2", "overall_fee": "0x14", "unit": "WEI" }
+        }]);
+
+        // When
+        let fee = parse_simulated_fee(&result).unwrap();
+
+        // Then
+        assert_eq!(SimulatedFee { gas_consumed: 10, gas_price: 2, overall_fee: 20 }, fee);
+    }
+
+    #[test]
+    fn test_parse_l1_gas_price() {
+        // Given
+        let block = json!({
+            "block_number": 10,
+            "l1_gas_price": { "price_in_wei": "0x3b9aca00", "price_in_fri": "0x174876e800" }
+        });
+
+        // When
+        let l1_gas_price = parse_l1_gas_price(&block);
+
+        // Then
+        assert_eq!(Some(L1GasPrice { price_in_wei: 1_000_000_000, price_in_fri: 100_000_000_000 }), l1_gas_price);
+        assert_eq!(None, parse_l1_gas_price(&json!({ "block_number": 10, "gas_price": "0x1" })));
+    }
+
+    #[test]
+    fn test_parse_simulated_transactions() {
+        // Given
+        let fee_estimation = json!({ "gas_consumed": "0xa", "gas_price": "0x2", "overall_fee": "0x14" });
+        let result = json!([
+            {
+                "transaction_trace": { "execute_invocation": { "result": ["0x1", "0x2a"] } },
+                "fee_estimation": fee_estimation
+            },
+            {
+                "transaction_trace": { "execute_invocation": { "revert_reason": "Kakarot: reverted" } },
+                "fee_estimation": fee_estimation
+            }
+        ]);
+
+        // When
+        let simulated = parse_simulated_transactions(&result).unwrap();
+
+        // Then
+        let fee = SimulatedFee { gas_consumed: 10, gas_price: 2, overall_fee: 20 };
+        assert_eq!(
+            vec![
+                SimulatedTransaction {
+                    fee,
+                    result: vec![FieldElement::ONE, FieldElement::from(42u8)],
+                    revert_reason: None
+                },
+                SimulatedTransaction { fee, result: vec![], revert_reason: Some("Kakarot: reverted".into()) },
+            ],
+            simulated
+        );
+    }
+
+    /// Starts a Starknet endpoint answering `starknet_specVersion` with `response`.
+    async fn spec_version_server(response: Value) -> MockServer {
+        let mock_server = MockServer::start().await;
+        Mock::given(body_partial_json(json!({ "method": "starknet_specVersion" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(response))
+            .mount(&mock_server)
+            .await;
+        mock_server
+    }
+
+    #[tokio::test]
+    async fn test_simulate_gas_usage() {
+        // Given
+        let request = BroadcastedInvokeTransactionV1 {
+            max_fee: FieldElement::ZERO,
+            signature: vec![],
+            nonce: FieldElement::ONE,
+            sender_address: FieldElement::ONE,
+            calldata: vec![],
+        };
+        let fee_estimation = json!({ "gas_consumed": "0xa", "gas_price": "0x2", "overall_fee": "0x14" });
+        let succeeded =
+            json!([{ "transaction_trace": { "execute_invocation": {} }, "fee_estimation": fee_estimation }]);
+        let reverted = json!([{
+            "transaction_trace": { "execute_invocation": { "revert_reason": "Kakarot: out of gas" } },
+            "fee_estimation": fee_estimation
+        }]);
+        let responses = [
+            json!({ "jsonrpc": "2.0", "id": 1, "result": succeeded }),
+            json!({ "jsonrpc": "2.0", "id": 1, "result": reverted }),
+            json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": 40, "message": "Contract error" } }),
+            json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": -32603, "message": "Internal error" } }),
+        ];
+
+        // When
+        let mut simulations = vec![];
+        for response in responses {
+            let mock_server = MockServer::start().await;
+            Mock::given(body_partial_json(json!({ "method": "starknet_simulateTransactions" })))
+                .respond_with(ResponseTemplate::new(200).set_body_json(response))
+                .mount(&mock_server)
+                .await;
+            let url = Url::parse(&mock_server.uri()).unwrap();
+            simulations.push(simulate_gas_usage(url, SpecVersion::V0_5, &request, 10).await);
+        }
+
+        // Then
+        assert_eq!(10, *simulations[0].as_ref().unwrap());
+        assert!(matches!(&simulations[1], Err(CompatError::Reverted(reason)) if reason == "Kakarot: out of gas"));
+        assert!(matches!(&simulations[2], Err(CompatError::Reverted(reason)) if reason == "Contract error"));
+        assert!(matches!(simulations[3], Err(CompatError::Rpc { code: -32603, .. })));
+    }
+
+    #[tokio::test]
+    async fn test_probe_spec_version() {
+        // Given
+        let responses = [
+            json!({ "jsonrpc": "2.0", "id": 1, "result": "0.5.1" }),
+            json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": -32601, "message": "Method not found" } }),
+            json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": -32603, "message": "Internal error" } }),
+            json!({ "jsonrpc": "2.0", "id": 1, "result": "1.0.0" }),
+        ];
+
+        // When
+        let mut probes = vec![];
+        for response in responses {
+            let mock_server = spec_version_server(response).await;
+            probes.push(probe_spec_version(Url::parse(&mock_server.uri()).unwrap()).await);
+        }
+
+        // Then
+        assert_eq!(SpecVersion::V0_5, *probes[0].as_ref().unwrap());
+        assert_eq!(SpecVersion::V0_4, *probes[1].as_ref().unwrap());
+        assert!(matches!(probes[2], Err(CompatError::Rpc { code: -32603, .. })));
+        assert!(matches!(probes[3], Err(CompatError::UnexpectedResult { method: "starknet_specVersion", .. })));
+    }
+}
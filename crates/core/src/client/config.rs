@@ -1,16 +1,72 @@
+use std::cmp::Ordering;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
 use eyre::Result;
+use rand::Rng;
+use reth_primitives::U256;
+use serde::{Deserialize, Serialize};
+use starknet::accounts::Call as StarknetCall;
+use starknet::core::chain_id;
 use starknet::core::types::FieldElement;
+use starknet::core::utils::cairo_short_string_to_felt;
 use starknet::providers::jsonrpc::{HttpTransport, JsonRpcTransport};
 use starknet::providers::{JsonRpcClient, SequencerGatewayProvider};
 use url::Url;
 
-use super::constants::{KATANA_RPC_URL, MADARA_RPC_URL};
-use super::errors::ConfigError;
+use super::breaker::BreakerConfig;
+use super::budget::BudgetConfig;
+use super::calls::DEFAULT_CALL_CACHE_CAPACITY;
+use super::coalesce::CoalescingCounters;
+use super::constants::{
+    ACCOUNT_ADDRESS, CHAIN_ID, COUNTER_ADDRESS_MAINNET, COUNTER_ADDRESS_TESTNET1, COUNTER_ADDRESS_TESTNET2,
+    DEFAULT_BLOCK_CONVERSION_CONCURRENCY, DEFAULT_BLOCK_GAS_LIMIT, DEFAULT_FIXED_GAS_ESTIMATE, DEFAULT_MAX_LOGS,
+    DEFAULT_MAX_RAW_TRANSACTION_SIZE, DEFAULT_SENDER_NONCE_SCAN_BLOCKS, EVENTS_CHUNK_SIZE, INC_SELECTOR,
+    KATANA_RPC_URL, MADARA_RPC_URL, STARKNET_NATIVE_TOKEN,
+};
+use super::devnet::DevModeConfig;
+use super::errors::{ChainIdError, ConfigError};
+use super::estimate::{DEFAULT_EXECUTION_TIMEOUT, DEFAULT_RPC_GAS_CAP};
+use super::lanes::LaneConfig;
+use super::liveness::LivenessConfig;
+use super::manifest::DeploymentManifest;
+use super::policy::RelayPolicy;
+use super::sequencer::DEFAULT_NONCE_SEQUENCING_TIMEOUT;
+use super::store::{KakarotStore, StoreConfig, StoreError};
+use super::tip::DEFAULT_BLOCK_NUMBER_TTL;
+use super::tracking::{DEFAULT_READ_YOUR_WRITES_WINDOW, DEFAULT_TRANSACTION_TTL};
+use super::upstream::{UpstreamConfig, UpstreamScores};
+use crate::models::call::Call;
+
+/// Default number of past blocks served in light mode.
+pub const DEFAULT_LIGHT_HISTORY_BLOCKS: u64 = 128;
 
 fn get_env_var(name: &str) -> Result<String, ConfigError> {
     std::env::var(name).map_err(|_| ConfigError::EnvironmentVariableMissing(name.into()))
 }
 
+/// Reads an optional hex encoded `FieldElement` from the environment.
+fn get_optional_felt_env_var(name: &str) -> Result<Option<FieldElement>, ConfigError> {
+    match std::env::var(name) {
+        Ok(value) => FieldElement::from_hex_be(&value).map(Some).map_err(|_| {
+            ConfigError::EnvironmentVariableSetWrong(format!("{name} should be provided as a hex string, got {value}"))
+        }),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Reads and parses an optional environment variable.
+fn get_optional_env_var<T: FromStr>(name: &str) -> Result<Option<T>, ConfigError> {
+    match std::env::var(name) {
+        Ok(value) => value
+            .parse()
+            .map(Some)
+            .map_err(|_| ConfigError::EnvironmentVariableSetWrong(format!("{name} could not be parsed, got {value}"))),
+        Err(_) => Ok(None),
+    }
+}
+
 #[derive(Default, Clone, Debug)]
 pub enum Network {
     #[default]
@@ -46,6 +102,355 @@ impl Network {
             _ => Err(ConfigError::InvalidNetwork(format!("Network {:?} is not supported for provider url", self))),
         }
     }
+
+    /// Returns the Starknet chain id the network is expected to report, if it is known.
+    pub fn starknet_chain_id(&self) -> Option<FieldElement> {
+        match self {
+            Network::MainnetGateway => Some(chain_id::MAINNET),
+            Network::Goerli1Gateway => Some(chain_id::TESTNET),
+            Network::Goerli2Gateway => Some(chain_id::TESTNET2),
+            Network::Katana => cairo_short_string_to_felt("KATANA").ok(),
+            Network::Madara | Network::Sharingan | Network::JsonRpcProvider(_) => None,
+        }
+    }
+
+    /// Returns the token backing the ETH balances on the network. All known networks use the
+    /// Starknet ETH token.
+    pub fn native_token(&self) -> NativeTokenConfig {
+        NativeTokenConfig::default()
+    }
+}
+
+/// Number of decimals of the ETH balances reported by the RPC.
+const ETH_DECIMALS: u8 = 18;
+
+/// ERC-20 token whose balances are reported as ETH balances, e.g. the fee token of the network.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NativeTokenConfig {
+    pub address: FieldElement,
+    /// Decimals of the token, its balances being scaled to 18 decimals.
+    pub decimals: u8,
+}
+
+impl Default for NativeTokenConfig {
+    fn default() -> Self {
+        // This FieldElement::from_hex_be cannot fail as the value is a constant
+        Self { address: FieldElement::from_hex_be(STARKNET_NATIVE_TOKEN).unwrap(), decimals: ETH_DECIMALS }
+    }
+}
+
+impl NativeTokenConfig {
+    /// Converts a token balance to wei.
+    pub fn to_wei(&self, balance: U256) -> U256 {
+        match self.decimals.cmp(&ETH_DECIMALS) {
+            Ordering::Less => balance.saturating_mul(Self::scale(ETH_DECIMALS - self.decimals)),
+            Ordering::Equal => balance,
+            Ordering::Greater => balance / Self::scale(self.decimals - ETH_DECIMALS),
+        }
+    }
+
+    /// Converts wei to a token balance, rounding down.
+    pub fn from_wei(&self, balance: U256) -> U256 {
+        match self.decimals.cmp(&ETH_DECIMALS) {
+            Ordering::Less => balance / Self::scale(ETH_DECIMALS - self.decimals),
+            Ordering::Equal => balance,
+            Ordering::Greater => balance.saturating_mul(Self::scale(self.decimals - ETH_DECIMALS)),
+        }
+    }
+
+    fn scale(decimals: u8) -> U256 {
+        U256::from(10).saturating_pow(U256::from(decimals))
+    }
+}
+
+/// Policy for resubmitting Starknet invoke transactions rejected because of an insufficient max
+/// fee.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeeRetryPolicy {
+    /// Maximum number of resubmissions after the first attempt.
+    pub max_retries: u32,
+    /// Base delay of the exponential backoff between resubmissions.
+    pub base_delay: Duration,
+    /// Percentage applied to the re-estimated fee to obtain the new max fee.
+    pub fee_multiplier_percent: u64,
+}
+
+impl Default for FeeRetryPolicy {
+    fn default() -> Self {
+        Self { max_retries: 3, base_delay: Duration::from_millis(500), fee_multiplier_percent: 150 }
+    }
+}
+
+impl FeeRetryPolicy {
+    /// Returns the default policy for a network. Devnets have no fee market, so a single quick
+    /// retry is enough.
+    pub fn for_network(network: &Network) -> Self {
+        match network {
+            Network::Katana | Network::Madara | Network::Sharingan => {
+                Self { max_retries: 1, base_delay: Duration::from_millis(100), ..Default::default() }
+            }
+            _ => Self::default(),
+        }
+    }
+
+    /// Overrides the network defaults with the `KAKAROT_FEE_RETRY_*` environment variables.
+    pub fn from_env(network: &Network) -> Result<Self, ConfigError> {
+        let mut policy = Self::for_network(network);
+        if let Some(max_retries) = get_optional_env_var("KAKAROT_FEE_RETRY_MAX_RETRIES")? {
+            policy.max_retries = max_retries;
+        }
+        if let Some(base_delay_ms) = get_optional_env_var("KAKAROT_FEE_RETRY_BASE_DELAY_MS")? {
+            policy.base_delay = Duration::from_millis(base_delay_ms);
+        }
+        if let Some(fee_multiplier_percent) = get_optional_env_var("KAKAROT_FEE_RETRY_MULTIPLIER_PERCENT")? {
+            policy.fee_multiplier_percent = fee_multiplier_percent;
+        }
+        Ok(policy)
+    }
+
+    /// Returns the delay before the given retry (starting at 1).
+    pub fn backoff(&self, retry: u32) -> Duration {
+        jittered_backoff(self.base_delay, retry)
+    }
+}
+
+/// Policy for sending again the idempotent Starknet reads failing with a transient error, e.g. a
+/// rate limit or a connection reset.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReadRetryPolicy {
+    /// Maximum number of retries of a read after the first attempt, zero disabling the retries.
+    pub max_retries: u32,
+    /// Base delay of the exponential backoff between retries.
+    pub base_delay: Duration,
+    /// Time after which a read is not retried anymore, so that a request does not outlive the
+    /// timeout of its client.
+    pub budget: Duration,
+}
+
+impl Default for ReadRetryPolicy {
+    fn default() -> Self {
+        Self { max_retries: 2, base_delay: Duration::from_millis(50), budget: Duration::from_secs(2) }
+    }
+}
+
+impl ReadRetryPolicy {
+    /// Overrides the defaults with the `KAKAROT_READ_RETRY_*` environment variables.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let mut policy = Self::default();
+        if let Some(max_retries) = get_optional_env_var("KAKAROT_READ_RETRY_MAX_RETRIES")? {
+            policy.max_retries = max_retries;
+        }
+        if let Some(base_delay_ms) = get_optional_env_var("KAKAROT_READ_RETRY_BASE_DELAY_MS")? {
+            policy.base_delay = Duration::from_millis(base_delay_ms);
+        }
+        if let Some(budget_ms) = get_optional_env_var("KAKAROT_READ_RETRY_BUDGET_MS")? {
+            policy.budget = Duration::from_millis(budget_ms);
+        }
+        Ok(policy)
+    }
+
+    /// Returns the delay before the given retry (starting at 1).
+    pub fn backoff(&self, retry: u32) -> Duration {
+        jittered_backoff(self.base_delay, retry)
+    }
+}
+
+/// Returns the delay before the given retry (starting at 1): the base delay doubled at each
+/// retry, plus a random jitter of up to one base delay.
+fn jittered_backoff(base_delay: Duration, retry: u32) -> Duration {
+    let exponential = base_delay.saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)));
+    let jitter_ms = base_delay.as_millis() as u64;
+    let jitter = if jitter_ms == 0 { 0 } else { rand::thread_rng().gen_range(0..jitter_ms) };
+    exponential.saturating_add(Duration::from_millis(jitter))
+}
+
+/// Smoothing and bounds of the gas price reported by `eth_gasPrice`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GasPriceConfig {
+    /// Weight in percent of a new Starknet gas price sample in the reported gas price, the rest
+    /// being the previous reported price. 100 disables the smoothing.
+    pub sample_weight_percent: u64,
+    /// Lower bound in wei the samples are clamped to.
+    pub min_gas_price: Option<u128>,
+    /// Upper bound in wei the samples are clamped to.
+    pub max_gas_price: Option<u128>,
+}
+
+impl Default for GasPriceConfig {
+    fn default() -> Self {
+        Self { sample_weight_percent: 20, min_gas_price: None, max_gas_price: None }
+    }
+}
+
+impl GasPriceConfig {
+    /// Returns the default configuration for a network. Devnets report the base fee as gas price,
+    /// which needs no smoothing.
+    pub fn for_network(network: &Network) -> Self {
+        match network {
+            Network::Katana | Network::Madara | Network::Sharingan => {
+                Self { sample_weight_percent: 100, ..Default::default() }
+            }
+            _ => Self::default(),
+        }
+    }
+
+    /// Overrides the network defaults with `KAKAROT_GAS_PRICE_SAMPLE_WEIGHT_PERCENT`,
+    /// `KAKAROT_MIN_GAS_PRICE` and `KAKAROT_MAX_GAS_PRICE`.
+    pub fn from_env(network: &Network) -> Result<Self, ConfigError> {
+        let mut config = Self::for_network(network);
+        if let Some(sample_weight_percent) = get_optional_env_var("KAKAROT_GAS_PRICE_SAMPLE_WEIGHT_PERCENT")? {
+            config.sample_weight_percent = sample_weight_percent;
+        }
+        if let Some(min_gas_price) = get_optional_env_var("KAKAROT_MIN_GAS_PRICE")? {
+            config.min_gas_price = Some(min_gas_price);
+        }
+        if let Some(max_gas_price) = get_optional_env_var("KAKAROT_MAX_GAS_PRICE")? {
+            config.max_gas_price = Some(max_gas_price);
+        }
+        Ok(config)
+    }
+}
+
+/// Starknet contracts of a Kakarot deployment the RPC relies on besides Kakarot itself, resolved
+/// per network so that a single binary can serve different deployments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeploymentAddresses {
+    /// Account sending the counter call whose simulation prices the gas.
+    pub gas_price_account: FieldElement,
+    /// Counter contract called to price the gas, `None` to report the base fee as gas price.
+    pub gas_price_counter: Option<FieldElement>,
+}
+
+impl DeploymentAddresses {
+    /// Returns the addresses of the public networks, devnets reporting the base fee as gas price.
+    pub fn for_network(network: &Network) -> Self {
+        let gas_price_counter = match network {
+            Network::MainnetGateway => Some(*COUNTER_ADDRESS_MAINNET),
+            Network::Goerli1Gateway => Some(*COUNTER_ADDRESS_TESTNET1),
+            Network::Goerli2Gateway => Some(*COUNTER_ADDRESS_TESTNET2),
+            _ => None,
+        };
+        Self { gas_price_account: *ACCOUNT_ADDRESS, gas_price_counter }
+    }
+
+    /// Overrides the network defaults with `KAKAROT_GAS_PRICE_ACCOUNT_ADDRESS` and
+    /// `KAKAROT_GAS_PRICE_COUNTER_ADDRESS`.
+    pub fn from_env(network: &Network) -> Result<Self, ConfigError> {
+        let mut addresses = Self::for_network(network);
+        if let Some(gas_price_account) = get_optional_felt_env_var("KAKAROT_GAS_PRICE_ACCOUNT_ADDRESS")? {
+            addresses.gas_price_account = gas_price_account;
+        }
+        if let Some(gas_price_counter) = get_optional_felt_env_var("KAKAROT_GAS_PRICE_COUNTER_ADDRESS")? {
+            addresses.gas_price_counter = Some(gas_price_counter);
+        }
+        Ok(addresses)
+    }
+
+    /// Returns the call simulated to price the gas, if any.
+    pub fn gas_price_call(&self) -> Option<Call> {
+        let counter = self.gas_price_counter?;
+        Some(StarknetCall { to: counter, selector: *INC_SELECTOR, calldata: vec![] }.into())
+    }
+}
+
+/// Operating mode of the RPC, trading the history and indexes it serves against its resource
+/// usage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OperatingMode {
+    /// No index and bounded history: state and logs are only served for the latest blocks.
+    Light,
+    /// Full history, with the relayed transactions tracked.
+    #[default]
+    Full,
+    /// Same as full mode, accepted for the configurations written when archive mode cached the
+    /// translated traces.
+    Archive,
+}
+
+impl FromStr for OperatingMode {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "light" => Ok(Self::Light),
+            "full" => Ok(Self::Full),
+            "archive" => Ok(Self::Archive),
+            _ => Err(ConfigError::EnvironmentVariableSetWrong(format!(
+                "KAKAROT_MODE should be light, full or archive, got {s}"
+            ))),
+        }
+    }
+}
+
+/// Enforcement of the chain id of relayed transactions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChainIdPolicy {
+    /// Rejects unprotected (pre EIP-155) transactions and transactions signed for another chain,
+    /// as Geth.
+    #[default]
+    Strict,
+    /// Accepts any chain id, for development tools replaying transactions of other chains.
+    Permissive,
+}
+
+impl FromStr for ChainIdPolicy {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "strict" => Ok(Self::Strict),
+            "permissive" => Ok(Self::Permissive),
+            _ => Err(ConfigError::EnvironmentVariableSetWrong(format!(
+                "KAKAROT_CHAIN_ID_POLICY should be strict or permissive, got {s}"
+            ))),
+        }
+    }
+}
+
+impl ChainIdPolicy {
+    /// Checks the chain id of a transaction, `None` for unprotected legacy transactions.
+    pub fn check(&self, chain_id: Option<u64>) -> Result<(), ChainIdError> {
+        match (self, chain_id) {
+            (Self::Permissive, _) => Ok(()),
+            (Self::Strict, None) => Err(ChainIdError::Unprotected),
+            (Self::Strict, Some(chain_id)) if chain_id != CHAIN_ID => Err(ChainIdError::Mismatch(chain_id)),
+            (Self::Strict, Some(_)) => Ok(()),
+        }
+    }
+}
+
+/// Values reported in the headers of the converted blocks, for tools computing throughput or
+/// enforcing gas limits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockHeaderConfig {
+    pub gas_limit: u64,
+    /// Reports timestamps spaced by this number of seconds from the genesis block instead of the
+    /// Starknet timestamps, which devnets can repeat across blocks.
+    pub synthetic_block_interval: Option<u64>,
+    /// Computes the transactions and receipts roots of the converted blocks, reported as zero
+    /// otherwise.
+    pub compute_trie_roots: bool,
+}
+
+impl Default for BlockHeaderConfig {
+    fn default() -> Self {
+        Self { gas_limit: DEFAULT_BLOCK_GAS_LIMIT, synthetic_block_interval: None, compute_trie_roots: false }
+    }
+}
+
+impl BlockHeaderConfig {
+    /// Returns the timestamp reported for a block. Pending blocks, without a number, keep their
+    /// Starknet timestamp.
+    pub fn timestamp(&self, genesis_timestamp: u64, timestamp: u64, block_number: Option<u64>) -> u64 {
+        match (self.synthetic_block_interval, block_number) {
+            (Some(interval), Some(block_number)) => {
+                genesis_timestamp.saturating_add(block_number.saturating_mul(interval))
+            }
+            _ => timestamp,
+        }
+    }
 }
 
 #[derive(Default, Clone)]
@@ -57,11 +462,140 @@ pub struct StarknetConfig {
     pub kakarot_address: FieldElement,
     /// Proxy account class hash.
     pub proxy_account_class_hash: FieldElement,
+    /// Expected class hash of the Kakarot contract, checked by the deployment self-check.
+    pub kakarot_class_hash: Option<FieldElement>,
+    /// Expected Starknet chain id, checked by the deployment self-check. Defaults to the chain id
+    /// of the network when it is known.
+    pub starknet_chain_id: Option<FieldElement>,
+    /// Resubmission policy for transactions rejected because of an insufficient max fee.
+    pub fee_retry_policy: FeeRetryPolicy,
+    /// Retries of the idempotent Starknet reads failing with a transient error.
+    pub read_retry_policy: ReadRetryPolicy,
+    /// Contracts of the deployment besides Kakarot.
+    pub deployment_addresses: DeploymentAddresses,
+    /// Smoothing and bounds of the gas price.
+    pub gas_price_config: GasPriceConfig,
+    /// Concurrency of the heavy read and transaction submission lanes.
+    pub lane_config: LaneConfig,
+    /// Operating mode of the RPC.
+    pub operating_mode: OperatingMode,
+    /// Number of past blocks served in light mode.
+    pub light_history_blocks: u64,
+    /// Maximum size of a raw transaction in bytes.
+    pub max_raw_transaction_size: usize,
+    /// Detection of a stale sequencer.
+    pub liveness_config: LivenessConfig,
+    /// Enforcement of the chain id of relayed transactions.
+    pub chain_id_policy: ChainIdPolicy,
+    /// Addresses and selectors the relayed transactions are checked against.
+    pub relay_policy: RelayPolicy,
+    /// Dev mode, against a local devnet.
+    pub dev_mode: DevModeConfig,
+    /// Values reported in the block headers.
+    pub block_header_config: BlockHeaderConfig,
+    /// Persistent store of the relayed transactions, kept in memory only if `None`.
+    pub store: Option<Arc<dyn KakarotStore>>,
+    /// Token backing the ETH balances.
+    pub native_token: NativeTokenConfig,
+    /// Limits of the Starknet calls made to serve a single request.
+    pub budget_config: BudgetConfig,
+    /// Backup Starknet endpoints of the JSON-RPC provider.
+    pub upstream_config: UpstreamConfig,
+    /// Scores of the Starknet endpoints, set when the provider uses an `UpstreamTransport`.
+    pub upstream_scores: Option<Arc<UpstreamScores>>,
+    /// Counters of the coalesced reads, set when the provider uses an `UpstreamTransport`.
+    pub coalescing_counters: Option<Arc<CoalescingCounters>>,
+    /// Thresholds above which the expensive requests are shed.
+    pub breaker_config: BreakerConfig,
+    /// Time the latest block number is served from cache.
+    pub block_number_ttl: Duration,
+    /// Maximum number of `eth_call` results cached, zero disabling the cache.
+    pub call_cache_capacity: usize,
+    /// Time the transactions relayed for a sender are reflected in its pending nonce.
+    pub read_your_writes_window: Duration,
+    /// Time a relayed transaction has to be accepted on Starknet before being dropped and its
+    /// nonce freed.
+    pub transaction_ttl: Duration,
+    /// Number of Starknet events requested per page by `eth_getLogs`.
+    pub events_page_size: u64,
+    /// Maximum number of logs returned by `eth_getLogs`.
+    pub max_logs: usize,
+    /// Maximum number of transactions, receipts or accounts of a block converted concurrently,
+    /// bounding the Starknet requests a block response keeps in flight.
+    pub block_conversion_concurrency: usize,
+    /// Number of recent blocks scanned for a transaction missing from the sender and nonce index.
+    pub sender_nonce_scan_blocks: u64,
+    /// Time a relayed transaction waits for the transactions of its sender with lower nonces to be
+    /// submitted, zero disabling the sequencing by nonce.
+    pub nonce_sequencing_timeout: Duration,
+    /// Starknet JSON-RPC endpoint the private transactions are submitted to instead of the
+    /// provider, e.g. a sequencer endpoint which does not share its mempool.
+    pub private_relay_url: Option<Url>,
+    /// Gas returned by `eth_estimateGas` on the networks whose transactions are not simulated,
+    /// e.g. Katana or Madara.
+    pub fixed_gas_estimate: u64,
+    /// Maximum gas limit `eth_estimateGas` simulates a transaction with, zero disabling the cap.
+    pub rpc_gas_cap: u64,
+    /// Time after which the simulations of `eth_call` and `eth_estimateGas` are aborted, zero
+    /// disabling the timeout.
+    pub execution_timeout: Duration,
+    /// Block the Starknet network was forked at, when the provider is a fork such as a forked
+    /// Katana. `latest` then resolves to the head of the fork.
+    pub fork_block: Option<u64>,
+    /// Whether the relayed transactions and the fees paid for them are written to the audit log
+    /// of the store.
+    pub audit_log: bool,
 }
 
 impl StarknetConfig {
     pub fn new(network: Network, kakarot_address: FieldElement, proxy_account_class_hash: FieldElement) -> Self {
-        StarknetConfig { network, kakarot_address, proxy_account_class_hash }
+        let starknet_chain_id = network.starknet_chain_id();
+        let fee_retry_policy = FeeRetryPolicy::for_network(&network);
+        let deployment_addresses = DeploymentAddresses::for_network(&network);
+        let gas_price_config = GasPriceConfig::for_network(&network);
+        let native_token = network.native_token();
+        StarknetConfig {
+            network,
+            kakarot_address,
+            proxy_account_class_hash,
+            kakarot_class_hash: None,
+            starknet_chain_id,
+            fee_retry_policy,
+            read_retry_policy: ReadRetryPolicy::default(),
+            deployment_addresses,
+            gas_price_config,
+            lane_config: LaneConfig::default(),
+            operating_mode: OperatingMode::default(),
+            light_history_blocks: DEFAULT_LIGHT_HISTORY_BLOCKS,
+            max_raw_transaction_size: DEFAULT_MAX_RAW_TRANSACTION_SIZE,
+            liveness_config: LivenessConfig::default(),
+            chain_id_policy: ChainIdPolicy::default(),
+            relay_policy: RelayPolicy::default(),
+            dev_mode: DevModeConfig::default(),
+            block_header_config: BlockHeaderConfig::default(),
+            store: None,
+            native_token,
+            budget_config: BudgetConfig::default(),
+            upstream_config: UpstreamConfig::default(),
+            upstream_scores: None,
+            coalescing_counters: None,
+            breaker_config: BreakerConfig::default(),
+            block_number_ttl: DEFAULT_BLOCK_NUMBER_TTL,
+            call_cache_capacity: DEFAULT_CALL_CACHE_CAPACITY,
+            read_your_writes_window: DEFAULT_READ_YOUR_WRITES_WINDOW,
+            transaction_ttl: DEFAULT_TRANSACTION_TTL,
+            events_page_size: EVENTS_CHUNK_SIZE,
+            max_logs: DEFAULT_MAX_LOGS,
+            block_conversion_concurrency: DEFAULT_BLOCK_CONVERSION_CONCURRENCY,
+            sender_nonce_scan_blocks: DEFAULT_SENDER_NONCE_SCAN_BLOCKS,
+            nonce_sequencing_timeout: DEFAULT_NONCE_SEQUENCING_TIMEOUT,
+            private_relay_url: None,
+            fixed_gas_estimate: DEFAULT_FIXED_GAS_ESTIMATE,
+            rpc_gas_cap: DEFAULT_RPC_GAS_CAP,
+            execution_timeout: DEFAULT_EXECUTION_TIMEOUT,
+            fork_block: None,
+            audit_log: false,
+        }
     }
 
     /// Create a new `StarknetConfig` from environment variables.
@@ -69,6 +603,12 @@ impl StarknetConfig {
     /// `STARKNET_NETWORK` environment variable should be set the URL of a JsonRpc
     /// starknet provider, e.g. https://starknet-goerli.g.alchemy.com/v2/some_key.
     pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_env_with_manifest(None)
+    }
+
+    /// Same as [`StarknetConfig::from_env`], the addresses and class hashes missing from the
+    /// environment being taken from the manifest of the deployment.
+    pub fn from_env_with_manifest(manifest: Option<&DeploymentManifest>) -> Result<Self, ConfigError> {
         let network = get_env_var("STARKNET_NETWORK")?;
         let network = match network.to_lowercase().as_str() {
             "katana" => Network::Katana,
@@ -81,21 +621,166 @@ impl StarknetConfig {
             network_url => Network::JsonRpcProvider(Url::parse(network_url)?),
         };
 
-        let kakarot_address = get_env_var("KAKAROT_ADDRESS")?;
-        let kakarot_address = FieldElement::from_hex_be(&kakarot_address).map_err(|_| {
-            ConfigError::EnvironmentVariableSetWrong(format!(
-                "KAKAROT_ADDRESS should be provided as a hex string, got {kakarot_address}"
-            ))
-        })?;
+        let kakarot_address = match (get_optional_felt_env_var("KAKAROT_ADDRESS")?, manifest) {
+            (Some(kakarot_address), _) => kakarot_address,
+            (None, Some(manifest)) => manifest.kakarot_address,
+            (None, None) => return Err(ConfigError::EnvironmentVariableMissing("KAKAROT_ADDRESS".into())),
+        };
+        let proxy_account_class_hash = match (get_optional_felt_env_var("PROXY_ACCOUNT_CLASS_HASH")?, manifest) {
+            (Some(proxy_account_class_hash), _) => proxy_account_class_hash,
+            (None, Some(manifest)) => manifest.proxy_account_class_hash,
+            (None, None) => return Err(ConfigError::EnvironmentVariableMissing("PROXY_ACCOUNT_CLASS_HASH".into())),
+        };
 
-        let proxy_account_class_hash = get_env_var("PROXY_ACCOUNT_CLASS_HASH")?;
-        let proxy_account_class_hash = FieldElement::from_hex_be(&proxy_account_class_hash).map_err(|_| {
-            ConfigError::EnvironmentVariableSetWrong(format!(
-                "PROXY_ACCOUNT_CLASS_HASH should be provided as a hex string, got {proxy_account_class_hash}"
-            ))
-        })?;
+        let mut config = StarknetConfig::new(network, kakarot_address, proxy_account_class_hash);
+        config.kakarot_class_hash = get_optional_felt_env_var("KAKAROT_CLASS_HASH")?
+            .or_else(|| manifest.and_then(|manifest| manifest.kakarot_class_hash));
+        if let Some(starknet_chain_id) = get_optional_felt_env_var("STARKNET_CHAIN_ID")? {
+            config.starknet_chain_id = Some(starknet_chain_id);
+        }
+        config.fee_retry_policy = FeeRetryPolicy::from_env(&config.network)?;
+        config.read_retry_policy = ReadRetryPolicy::from_env()?;
+        config.deployment_addresses = DeploymentAddresses::from_env(&config.network)?;
+        config.gas_price_config = GasPriceConfig::from_env(&config.network)?;
+        if let Some(read_concurrency) = get_optional_env_var("KAKAROT_READ_LANE_CONCURRENCY")? {
+            config.lane_config.read_concurrency = read_concurrency;
+        }
+        if let Some(write_concurrency) = get_optional_env_var("KAKAROT_WRITE_LANE_CONCURRENCY")? {
+            config.lane_config.write_concurrency = write_concurrency;
+        }
+        config.lane_config.max_read_queue = get_optional_env_var("KAKAROT_MAX_READ_LANE_QUEUE")?;
+        config.lane_config.max_write_queue = get_optional_env_var("KAKAROT_MAX_WRITE_LANE_QUEUE")?;
+        if let Some(retry_after) = get_optional_env_var("KAKAROT_SHED_RETRY_AFTER_SECS")? {
+            config.lane_config.retry_after = Duration::from_secs(retry_after);
+        }
+        if let Ok(operating_mode) = std::env::var("KAKAROT_MODE") {
+            config.operating_mode = operating_mode.parse()?;
+        }
+        if let Some(light_history_blocks) = get_optional_env_var("KAKAROT_LIGHT_HISTORY_BLOCKS")? {
+            config.light_history_blocks = light_history_blocks;
+        }
+        if let Some(max_raw_transaction_size) = get_optional_env_var("KAKAROT_MAX_RAW_TRANSACTION_SIZE")? {
+            config.max_raw_transaction_size = max_raw_transaction_size;
+        }
+        if let Some(max_block_age) = get_optional_env_var("KAKAROT_MAX_BLOCK_AGE_SECS")? {
+            config.liveness_config.max_block_age = Duration::from_secs(max_block_age);
+        }
+        if let Some(reject_stale_writes) = get_optional_env_var("KAKAROT_REJECT_STALE_WRITES")? {
+            config.liveness_config.reject_stale_writes = reject_stale_writes;
+        }
+        if let Ok(chain_id_policy) = std::env::var("KAKAROT_CHAIN_ID_POLICY") {
+            config.chain_id_policy = chain_id_policy.parse()?;
+        }
+        config.relay_policy = RelayPolicy::from_env()?;
+        if let Some(dev_mode) = get_optional_env_var("KAKAROT_DEV_MODE")? {
+            config.dev_mode.enabled = dev_mode;
+        }
+        if let Some(receipt_timeout) = get_optional_env_var("KAKAROT_DEV_RECEIPT_TIMEOUT_SECS")? {
+            config.dev_mode.receipt_timeout = Duration::from_secs(receipt_timeout);
+        }
+        if let Some(gas_limit) = get_optional_env_var("KAKAROT_BLOCK_GAS_LIMIT")? {
+            config.block_header_config.gas_limit = gas_limit;
+        }
+        config.block_header_config.synthetic_block_interval =
+            get_optional_env_var("KAKAROT_SYNTHETIC_BLOCK_INTERVAL_SECS")?;
+        if let Some(compute_trie_roots) = get_optional_env_var("KAKAROT_COMPUTE_TRIE_ROOTS")? {
+            config.block_header_config.compute_trie_roots = compute_trie_roots;
+        }
+        if let Ok(store) = std::env::var("KAKAROT_STORE") {
+            let store: StoreConfig =
+                store.parse().map_err(|err: StoreError| ConfigError::EnvironmentVariableSetWrong(err.to_string()))?;
+            config.store = store
+                .open()
+                .map_err(|err| ConfigError::EnvironmentVariableSetWrong(format!("KAKAROT_STORE: {err}")))?;
+        }
+        if let Some(address) = get_optional_felt_env_var("KAKAROT_NATIVE_TOKEN_ADDRESS")? {
+            config.native_token.address = address;
+        }
+        if let Some(decimals) = get_optional_env_var("KAKAROT_NATIVE_TOKEN_DECIMALS")? {
+            config.native_token.decimals = decimals;
+        }
+        if let Some(max_upstream_calls) = get_optional_env_var("KAKAROT_MAX_UPSTREAM_CALLS")? {
+            config.budget_config.max_upstream_calls = max_upstream_calls;
+        }
+        if let Some(max_response_bytes) = get_optional_env_var("KAKAROT_MAX_UPSTREAM_RESPONSE_BYTES")? {
+            config.budget_config.max_upstream_response_bytes = max_response_bytes;
+        }
+        if let Ok(backup_urls) = std::env::var("KAKAROT_STARKNET_BACKUP_URLS") {
+            config.upstream_config.backup_urls = backup_urls
+                .split(',')
+                .map(str::trim)
+                .filter(|url| !url.is_empty())
+                .map(Url::parse)
+                .collect::<Result<_, _>>()?;
+        }
+        if let Some(hedge_delay) = get_optional_env_var("KAKAROT_HEDGE_DELAY_MS")? {
+            config.upstream_config.hedge_delay = Duration::from_millis(hedge_delay);
+        }
+        if let Some(max_error_rate) = get_optional_env_var("KAKAROT_BREAKER_MAX_ERROR_RATE")? {
+            config.breaker_config.max_error_rate = max_error_rate;
+        }
+        if let Some(max_latency) = get_optional_env_var("KAKAROT_BREAKER_MAX_LATENCY_MS")? {
+            config.breaker_config.max_latency = Duration::from_millis(max_latency);
+        }
+        if let Some(cooldown) = get_optional_env_var("KAKAROT_BREAKER_COOLDOWN_SECS")? {
+            config.breaker_config.cooldown = Duration::from_secs(cooldown);
+        }
+        if let Some(block_number_ttl) = get_optional_env_var("KAKAROT_BLOCK_NUMBER_TTL_MS")? {
+            config.block_number_ttl = Duration::from_millis(block_number_ttl);
+        }
+        if let Some(window) = get_optional_env_var("KAKAROT_READ_YOUR_WRITES_WINDOW_SECS")? {
+            config.read_your_writes_window = Duration::from_secs(window);
+        }
+        if let Some(transaction_ttl) = get_optional_env_var("KAKAROT_TRANSACTION_TTL_SECS")? {
+            config.transaction_ttl = Duration::from_secs(transaction_ttl);
+        }
+        if let Some(events_page_size) = get_optional_env_var("KAKAROT_EVENTS_PAGE_SIZE")? {
+            config.events_page_size = events_page_size;
+        }
+        if let Some(max_logs) = get_optional_env_var("KAKAROT_MAX_LOGS")? {
+            config.max_logs = max_logs;
+        }
+        if let Some(concurrency) = get_optional_env_var("KAKAROT_BLOCK_CONVERSION_CONCURRENCY")? {
+            config.block_conversion_concurrency = concurrency;
+        }
+        if let Some(sender_nonce_scan_blocks) = get_optional_env_var("KAKAROT_SENDER_NONCE_SCAN_BLOCKS")? {
+            config.sender_nonce_scan_blocks = sender_nonce_scan_blocks;
+        }
+        if let Some(timeout) = get_optional_env_var("KAKAROT_NONCE_SEQUENCING_TIMEOUT_MS")? {
+            config.nonce_sequencing_timeout = Duration::from_millis(timeout);
+        }
+        if let Some(fixed_gas_estimate) = get_optional_env_var("KAKAROT_FIXED_GAS_ESTIMATE")? {
+            config.fixed_gas_estimate = fixed_gas_estimate;
+        }
+        if let Some(rpc_gas_cap) = get_optional_env_var("KAKAROT_RPC_GAS_CAP")? {
+            config.rpc_gas_cap = rpc_gas_cap;
+        }
+        if let Some(execution_timeout) = get_optional_env_var("KAKAROT_EXECUTION_TIMEOUT_MS")? {
+            config.execution_timeout = Duration::from_millis(execution_timeout);
+        }
+        config.fork_block = get_optional_env_var("KAKAROT_FORK_BLOCK")?;
+        if let Some(audit_log) = get_optional_env_var("KAKAROT_AUDIT_LOG")? {
+            config.audit_log = audit_log;
+        }
+        if let Ok(private_relay_url) = std::env::var("KAKAROT_PRIVATE_RELAY_URL") {
+            config.private_relay_url = Some(Url::parse(&private_relay_url)?);
+        }
+        if let Some(call_cache_capacity) = get_optional_env_var("KAKAROT_CALL_CACHE_CAPACITY")? {
+            config.call_cache_capacity = call_cache_capacity;
+        }
+        if let Some(coalesce_requests) = get_optional_env_var("KAKAROT_COALESCE_REQUESTS")? {
+            config.upstream_config.coalesce_requests = coalesce_requests;
+        }
+        if let Ok(hedged_methods) = std::env::var("KAKAROT_HEDGED_METHODS") {
+            config.upstream_config.hedged_methods = hedged_methods
+                .split(',')
+                .map(str::trim)
+                .filter(|class| !class.is_empty() && *class != "none")
+                .map(|class| class.parse().map_err(ConfigError::EnvironmentVariableSetWrong))
+                .collect::<Result<_, _>>()?;
+        }
 
-        Ok(StarknetConfig::new(network, kakarot_address, proxy_account_class_hash))
+        Ok(config)
     }
 }
 
@@ -161,3 +846,97 @@ impl SequencerGatewayProviderBuilder {
         self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_retry_policy_backoff_is_exponential_with_bounded_jitter() {
+        // Given
+        let policy = FeeRetryPolicy { base_delay: Duration::from_millis(100), ..Default::default() };
+
+        // When
+        let delays: Vec<Duration> = (1..=3).map(|retry| policy.backoff(retry)).collect();
+
+        // Then
+        for (delay, expected) in delays.into_iter().zip([100, 200, 400]) {
+            assert!(delay >= Duration::from_millis(expected));
+            assert!(delay < Duration::from_millis(expected + 100));
+        }
+    }
+
+    #[test]
+    fn test_operating_mode_from_str() {
+        // Given
+        let modes = ["light", "Full", "ARCHIVE", "trace"];
+
+        // When
+        let parsed: Vec<Result<OperatingMode, ConfigError>> = modes.iter().map(|mode| mode.parse()).collect();
+
+        // Then
+        assert_eq!(OperatingMode::Light, *parsed[0].as_ref().unwrap());
+        assert_eq!(OperatingMode::Full, *parsed[1].as_ref().unwrap());
+        assert_eq!(OperatingMode::Archive, *parsed[2].as_ref().unwrap());
+        assert!(matches!(parsed[3], Err(ConfigError::EnvironmentVariableSetWrong(_))));
+    }
+
+    #[test]
+    fn test_chain_id_policy_check() {
+        // Given
+        let strict = ChainIdPolicy::Strict;
+        let permissive: ChainIdPolicy = "permissive".parse().unwrap();
+
+        // Then
+        assert_eq!(Ok(()), strict.check(Some(CHAIN_ID)));
+        assert_eq!(Err(ChainIdError::Unprotected), strict.check(None));
+        assert_eq!(Err(ChainIdError::Mismatch(1)), strict.check(Some(1)));
+        assert_eq!(Ok(()), permissive.check(None));
+        assert_eq!(Ok(()), permissive.check(Some(1)));
+    }
+
+    #[test]
+    fn test_block_header_config_timestamp() {
+        // Given
+        let starknet = BlockHeaderConfig::default();
+        let synthetic = BlockHeaderConfig { synthetic_block_interval: Some(12), ..Default::default() };
+
+        // Then
+        assert_eq!(1_500, starknet.timestamp(1_000, 1_500, Some(10)));
+        assert_eq!(1_120, synthetic.timestamp(1_000, 1_500, Some(10)));
+        assert_eq!(1_500, synthetic.timestamp(1_000, 1_500, None));
+    }
+
+    #[test]
+    fn test_deployment_addresses_for_network() {
+        // When
+        let mainnet = DeploymentAddresses::for_network(&Network::MainnetGateway);
+        let katana = DeploymentAddresses::for_network(&Network::Katana);
+
+        // Then
+        assert_eq!(Some(*COUNTER_ADDRESS_MAINNET), mainnet.gas_price_counter);
+        assert!(mainnet.gas_price_call().is_some());
+        assert_eq!(None, katana.gas_price_call());
+    }
+
+    #[test]
+    fn test_fee_retry_policy_for_devnet() {
+        assert_eq!(1, FeeRetryPolicy::for_network(&Network::Katana).max_retries);
+        assert_eq!(FeeRetryPolicy::default(), FeeRetryPolicy::for_network(&Network::MainnetGateway));
+    }
+
+    #[test]
+    fn test_native_token_decimals_adjustment() {
+        // Given
+        let eth = NativeTokenConfig::default();
+        let six_decimals = NativeTokenConfig { decimals: 6, ..Default::default() };
+        let twenty_decimals = NativeTokenConfig { decimals: 20, ..Default::default() };
+
+        // Then
+        assert_eq!(U256::from(5), eth.to_wei(U256::from(5)));
+        assert_eq!(U256::from(5_000_000_000_000u64), six_decimals.to_wei(U256::from(5)));
+        assert_eq!(U256::from(5), six_decimals.from_wei(U256::from(5_000_000_000_001u64)));
+        assert_eq!(U256::from(5), twenty_decimals.to_wei(U256::from(500)));
+        assert_eq!(U256::from(500), twenty_decimals.from_wei(U256::from(5)));
+    }
+}
@@ -1,11 +1,8 @@
 use lazy_static::lazy_static;
 use reth_primitives::{H256, H64, U128, U256, U8};
-use starknet::accounts::Call as StarknetCall;
 use starknet::core::types::FieldElement;
 use starknet::macros::selector;
 
-use crate::models::call::Call;
-
 /// CHAIN_ID = KKRT (0x4b4b5254) in ASCII
 pub const CHAIN_ID: u64 = 1_263_227_476;
 
@@ -13,6 +10,29 @@ pub const STARKNET_NATIVE_TOKEN: &str = "0x49d36570d4e46f48e99674bd3fcc84644ddd6
 
 pub const EARLIEST_BLOCK_NUMBER: u64 = 0;
 
+/// Default maximum number of transactions, receipts or accounts of a block converted
+/// concurrently.
+pub const DEFAULT_BLOCK_CONVERSION_CONCURRENCY: usize = 16;
+
+/// Maximum number of blocks fetched concurrently to resolve the transaction indexes of the logs.
+pub const LOGS_BLOCKS_BUFFER_SIZE: usize = 16;
+
+/// Default number of Starknet events requested per page by `eth_getLogs`.
+pub const EVENTS_CHUNK_SIZE: u64 = 1000;
+
+/// Default maximum number of logs returned by `eth_getLogs`, as most Ethereum providers.
+pub const DEFAULT_MAX_LOGS: usize = 10_000;
+
+/// Default number of recent blocks scanned for a transaction missing from the sender and nonce
+/// index.
+pub const DEFAULT_SENDER_NONCE_SCAN_BLOCKS: u64 = 128;
+
+/// Default gas returned by `eth_estimateGas` on the networks whose transactions are not simulated.
+pub const DEFAULT_FIXED_GAS_ESTIMATE: u64 = 100_000_000_000_000_000;
+
+/// Default maximum size of a raw transaction in bytes, matching Geth's transaction pool limit.
+pub const DEFAULT_MAX_RAW_TRANSACTION_SIZE: usize = 128 * 1024;
+
 pub const MADARA_RPC_URL: &str = "http://127.0.0.1:9944";
 
 pub const KATANA_RPC_URL: &str = "http://0.0.0.0:5050";
@@ -29,6 +49,7 @@ pub mod selectors {
     pub const COMPUTE_STARKNET_ADDRESS: FieldElement = selector!("compute_starknet_address");
 
     pub const GET_EVM_ADDRESS: FieldElement = selector!("get_evm_address");
+    pub const GET_IMPLEMENTATION: FieldElement = selector!("get_implementation");
 
     pub const BALANCE_OF: FieldElement = selector!("balanceOf");
 
@@ -71,14 +92,17 @@ pub mod error_messages {
 
 // This module contains constants which are being used in place of real data that should be fetched
 // in production.
+/// Default gas limit reported in block headers.
+pub const DEFAULT_BLOCK_GAS_LIMIT: u64 = 1_000_000;
+
 lazy_static! {
-    pub static ref GAS_LIMIT: U256 = U256::from(1_000_000u64);
+    pub static ref GAS_LIMIT: U256 = U256::from(DEFAULT_BLOCK_GAS_LIMIT);
     pub static ref GAS_USED: U256 = U256::from(500_000u64);
     pub static ref CUMULATIVE_GAS_USED: U256 = U256::from(1_000_000u64);
     pub static ref EFFECTIVE_GAS_PRICE: U128 = U128::from(1_000_000u64);
     pub static ref SIZE: Option<U256> = Some(U256::from(1_000_000u64));
     pub static ref MAX_FEE: FieldElement = FieldElement::from(100_000_000_000_000_000u64);
-    pub static ref ESTIMATE_GAS: U256 = U256::from(100_000_000_000_000_000u64);
+    pub static ref ESTIMATE_GAS: U256 = U256::from(DEFAULT_FIXED_GAS_ESTIMATE);
     pub static ref TRANSACTION_TYPE: U8 = U8::from(0);
     pub static ref NONCE: Option<H64> = Some(H64::zero());
     pub static ref MIX_HASH: H256 = H256::zero();
@@ -108,10 +132,4 @@ lazy_static! {
     /// The address of the counter contract used to calculate the gas price on goerli 2
     pub static ref COUNTER_ADDRESS_TESTNET2: FieldElement =
         FieldElement::from_hex_be("0x00e438661a4775fdf10cf132cc50730f40e59f3d040b15e64cd292add25eb01b").unwrap();
-    pub static ref COUNTER_CALL_MAINNET: Call =
-        StarknetCall { to: *COUNTER_ADDRESS_MAINNET, selector: *INC_SELECTOR, calldata: vec![] }.into();
-    pub static ref COUNTER_CALL_TESTNET1: Call =
-        StarknetCall { to: *COUNTER_ADDRESS_TESTNET1, selector: *INC_SELECTOR, calldata: vec![] }.into();
-    pub static ref COUNTER_CALL_TESTNET2: Call =
-        StarknetCall { to: *COUNTER_ADDRESS_TESTNET2, selector: *INC_SELECTOR, calldata: vec![] }.into();
 }
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use reth_primitives::{Address, H256};
+use serde::{Deserialize, Serialize};
+
+use super::store::{KakarotStore, CREATIONS_NAMESPACE};
+
+/// Transaction which deployed a contract and its sender, as returned by `ots_getContractCreator`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractCreator {
+    pub hash: H256,
+    /// Sender of the transaction, also for contracts deployed by another contract.
+    pub creator: Address,
+}
+
+/// Index of the contract creations by contract address, kept in the store of the RPC. Creations
+/// are recorded when the receipt of a deployment is converted and by
+/// `kakarot-rpc index backfill-creations`, which scans the deployment events of past blocks.
+pub struct ContractCreations {
+    store: Arc<dyn KakarotStore>,
+}
+
+impl ContractCreations {
+    pub fn new(store: Arc<dyn KakarotStore>) -> Self {
+        Self { store }
+    }
+
+    /// Returns the creation of a contract, if indexed.
+    pub fn get(&self, contract: Address) -> Option<ContractCreator> {
+        match self.store.get(CREATIONS_NAMESPACE, contract.as_bytes()) {
+            Ok(creation) => serde_json::from_slice(&creation?).ok(),
+            Err(err) => {
+                tracing::warn!("Failed to read the creation of {contract:#x}: {err}");
+                None
+            }
+        }
+    }
+
+    /// Records the creation of a contract. Storage failures are logged, the index being rebuilt by
+    /// a backfill.
+    pub fn record(&self, contract: Address, creation: ContractCreator) {
+        let Ok(value) = serde_json::to_vec(&creation) else { return };
+        if let Err(err) = self.store.put(CREATIONS_NAMESPACE, contract.as_bytes(), &value) {
+            tracing::warn!("Failed to index the creation of {contract:#x}: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::store::InMemoryStore;
+
+    #[test]
+    fn test_contract_creations_record() {
+        // Given
+        let creations = ContractCreations::new(Arc::new(InMemoryStore::default()));
+        let contract = Address::from_low_u64_be(1);
+        let creation = ContractCreator { hash: H256::from_low_u64_be(2), creator: Address::from_low_u64_be(3) };
+
+        // When
+        creations.record(contract, creation);
+
+        // Then
+        assert_eq!(Some(creation), creations.get(contract));
+        assert_eq!(None, creations.get(Address::from_low_u64_be(4)));
+    }
+}
@@ -0,0 +1,251 @@
+use std::iter::once;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use reqwest::Client;
+use reth_primitives::U256;
+use serde_json::{json, Value};
+use starknet::core::types::FieldElement;
+use starknet::core::utils::get_storage_var_address;
+use thiserror::Error;
+use url::Url;
+
+/// Default time to wait for the receipt of a transaction sent in dev mode.
+pub const DEFAULT_DEV_RECEIPT_TIMEOUT: Duration = Duration::from_secs(30);
+/// Interval between two receipt lookups in dev mode.
+pub const DEV_RECEIPT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Number of bytecode bytes packed in each felt of the `bytecode_` storage of a contract account.
+pub const BYTECODE_CHUNK_SIZE: usize = 16;
+
+/// Configuration of the dev mode, in which the RPC runs against a local devnet (Katana) for test
+/// suites written for Hardhat or Anvil.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DevModeConfig {
+    pub enabled: bool,
+    /// Time `eth_sendRawTransaction` waits for the devnet to include a transaction.
+    pub receipt_timeout: Duration,
+}
+
+impl Default for DevModeConfig {
+    fn default() -> Self {
+        Self { enabled: false, receipt_timeout: DEFAULT_DEV_RECEIPT_TIMEOUT }
+    }
+}
+
+/// Error raised by the dev methods.
+#[derive(Debug, Error)]
+pub enum DevnetError {
+    /// Dev mode disabled or not running against a JSON-RPC devnet.
+    #[error("dev methods are only available in dev mode, set KAKAROT_DEV_MODE=true")]
+    Unavailable,
+    /// The devnet failed or does not support the method.
+    #[error("devnet request {method} failed: {message}")]
+    Request { method: String, message: String },
+    /// Transaction sent by an account that is not impersonated, as `eth_sendTransaction` has no
+    /// key to sign it.
+    #[error("unknown account {0}")]
+    UnknownAccount(String),
+    /// The devnet did not include the transaction in time.
+    #[error("transaction {0} not included by the devnet after {1:?}")]
+    ReceiptTimeout(String, Duration),
+}
+
+/// Client of the dev methods of a devnet, proxied by the `evm` namespace.
+#[derive(Debug, Clone)]
+pub struct DevnetClient {
+    url: Url,
+    http: Client,
+}
+
+impl DevnetClient {
+    pub fn new(url: Url) -> Self {
+        Self { url, http: Client::new() }
+    }
+
+    /// Sends a JSON-RPC request to the devnet and returns its result.
+    pub async fn request(&self, method: &str, params: Value) -> Result<Value, DevnetError> {
+        let request_error = |message: String| DevnetError::Request { method: method.into(), message };
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response: Value = self
+            .http
+            .post(self.url.clone())
+            .json(&request)
+            .send()
+            .await
+            .map_err(|err| request_error(err.to_string()))?
+            .json()
+            .await
+            .map_err(|err| request_error(err.to_string()))?;
+
+        rpc_result(response).map_err(request_error)
+    }
+
+    /// Mines a block with the pending transactions.
+    pub async fn generate_block(&self) -> Result<(), DevnetError> {
+        self.request("katana_generateBlock", json!([])).await.map(|_| ())
+    }
+
+    /// Sets the timestamp of the next block.
+    pub async fn set_next_block_timestamp(&self, timestamp: u64) -> Result<(), DevnetError> {
+        self.request("katana_setNextBlockTimestamp", json!([timestamp])).await.map(|_| ())
+    }
+
+    /// Moves the timestamp of the next block forward by `seconds`.
+    pub async fn increase_next_block_timestamp(&self, seconds: u64) -> Result<(), DevnetError> {
+        self.request("katana_increaseNextBlockTimestamp", json!([seconds])).await.map(|_| ())
+    }
+
+    /// Dumps the state of the devnet to a file.
+    pub async fn dump_state(&self, path: &Path) -> Result<(), DevnetError> {
+        self.request("devnet_dump", json!({ "path": path })).await.map(|_| ())
+    }
+
+    /// Restores the state of the devnet from a file written by [`DevnetClient::dump_state`].
+    pub async fn load_state(&self, path: &Path) -> Result<(), DevnetError> {
+        self.request("devnet_load", json!({ "path": path })).await.map(|_| ())
+    }
+
+    /// Writes the storage of a Starknet contract.
+    pub async fn set_storage_at(
+        &self,
+        contract_address: FieldElement,
+        writes: &[(FieldElement, FieldElement)],
+    ) -> Result<(), DevnetError> {
+        for (key, value) in writes {
+            let params = json!([format!("{contract_address:#x}"), format!("{key:#x}"), format!("{value:#x}")]);
+            self.request("katana_setStorageAt", params).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Snapshots of the devnet state taken by `evm_snapshot`, stored as state dumps.
+#[derive(Debug, Default)]
+pub struct Snapshots {
+    next_id: AtomicU64,
+    ids: Mutex<Vec<u64>>,
+}
+
+impl Snapshots {
+    /// Returns the id of a new snapshot, starting at 1 as Ganache.
+    pub fn next_id(&self) -> u64 {
+        self.next_id.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Returns the path of the state dump of a snapshot.
+    pub fn path(id: u64) -> PathBuf {
+        std::env::temp_dir().join(format!("kakarot-snapshot-{}-{id}.json", std::process::id()))
+    }
+
+    pub fn insert(&self, id: u64) {
+        self.ids.lock().expect("snapshots lock poisoned").push(id);
+    }
+
+    pub fn contains(&self, id: u64) -> bool {
+        self.ids.lock().expect("snapshots lock poisoned").contains(&id)
+    }
+
+    /// Removes a reverted snapshot along with the snapshots taken after it, which can no longer
+    /// be reverted to.
+    pub fn revert(&self, id: u64) {
+        let mut ids = self.ids.lock().expect("snapshots lock poisoned");
+        for removed in ids.iter().filter(|snapshot| **snapshot >= id) {
+            let _ = std::fs::remove_file(Self::path(*removed));
+        }
+        ids.retain(|snapshot| *snapshot < id);
+    }
+}
+
+/// Splits a 256-bit value into the low and high 128-bit felts of a Cairo `Uint256`.
+pub fn split_u256(value: U256) -> (FieldElement, FieldElement) {
+    let limbs = value.as_limbs();
+    let low = u128::from(limbs[0]) | (u128::from(limbs[1]) << 64);
+    let high = u128::from(limbs[2]) | (u128::from(limbs[3]) << 64);
+    (FieldElement::from(low), FieldElement::from(high))
+}
+
+/// Returns the Starknet storage key of a Cairo storage variable.
+pub fn storage_var_address(var_name: &str, args: &[FieldElement]) -> FieldElement {
+    get_storage_var_address(var_name, args).expect("storage variable names are ASCII")
+}
+
+/// Returns the storage writes setting a `Uint256` storage variable, its high part being stored
+/// right after its low part.
+pub fn uint256_storage_writes(var_name: &str, args: &[FieldElement], value: U256) -> Vec<(FieldElement, FieldElement)> {
+    let address = storage_var_address(var_name, args);
+    let (low, high) = split_u256(value);
+    vec![(address, low), (address + FieldElement::ONE, high)]
+}
+
+/// Returns the storage writes replacing the bytecode of a Kakarot contract account.
+pub fn bytecode_storage_writes(bytecode: &[u8]) -> Vec<(FieldElement, FieldElement)> {
+    let chunks = bytecode.chunks(BYTECODE_CHUNK_SIZE).enumerate().map(|(index, chunk)| {
+        let chunk = FieldElement::from_byte_slice_be(chunk).expect("bytecode chunks fit in a felt");
+        (storage_var_address("bytecode_", &[FieldElement::from(index)]), chunk)
+    });
+    once((storage_var_address("bytecode_len_", &[]), FieldElement::from(bytecode.len()))).chain(chunks).collect()
+}
+
+/// Extracts the result of a JSON-RPC response, or the message of its error.
+fn rpc_result(mut response: Value) -> Result<Value, String> {
+    if let Some(error) = response.get("error") {
+        return Err(error.get("message").and_then(Value::as_str).map_or_else(|| error.to_string(), String::from));
+    }
+    response.get_mut("result").map(Value::take).ok_or_else(|| format!("unexpected devnet response: {response}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rpc_result() {
+        // Given
+        let success = json!({ "jsonrpc": "2.0", "id": 1, "result": null });
+        let failure = json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": -32601, "message": "Method not found" } });
+        let invalid = json!({ "jsonrpc": "2.0", "id": 1 });
+
+        // Then
+        assert_eq!(Ok(Value::Null), rpc_result(success));
+        assert_eq!(Err("Method not found".to_string()), rpc_result(failure));
+        assert!(rpc_result(invalid).is_err());
+    }
+
+    #[test]
+    fn test_storage_writes() {
+        // Given
+        let value = (U256::from(2) << 128) + U256::from(1);
+        let bytecode = [0xffu8; 20];
+
+        // When
+        let balance_writes = uint256_storage_writes("ERC20_balances", &[FieldElement::ONE], value);
+        let bytecode_writes = bytecode_storage_writes(&bytecode);
+
+        // Then
+        assert_eq!((FieldElement::ONE, FieldElement::TWO), split_u256(value));
+        assert_eq!(balance_writes[0].0 + FieldElement::ONE, balance_writes[1].0);
+        assert_eq!((FieldElement::ONE, FieldElement::TWO), (balance_writes[0].1, balance_writes[1].1));
+        assert_eq!(3, bytecode_writes.len());
+        assert_eq!(FieldElement::from(20u8), bytecode_writes[0].1);
+        assert_eq!(FieldElement::from(0xffffffffu32), bytecode_writes[2].1);
+    }
+
+    #[test]
+    fn test_snapshots_revert() {
+        // Given
+        let snapshots = Snapshots::default();
+        let ids: Vec<u64> = (0..3).map(|_| snapshots.next_id()).collect();
+        ids.iter().for_each(|id| snapshots.insert(*id));
+
+        // When
+        snapshots.revert(2);
+
+        // Then
+        assert_eq!(vec![1, 2, 3], ids);
+        assert!(snapshots.contains(1));
+        assert!(!snapshots.contains(2) && !snapshots.contains(3));
+        assert_eq!(4, snapshots.next_id());
+    }
+}
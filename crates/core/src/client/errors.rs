@@ -1,10 +1,25 @@
-use jsonrpsee::types::error::{INTERNAL_ERROR_CODE, INVALID_PARAMS_CODE, SERVER_IS_BUSY_CODE, UNKNOWN_ERROR_CODE};
+use std::time::Duration;
+
+#[cfg(feature = "rpc")]
+use jsonrpsee::types::error::{
+    INTERNAL_ERROR_CODE, INVALID_PARAMS_CODE, METHOD_NOT_FOUND_CODE, SERVER_IS_BUSY_CODE, UNKNOWN_ERROR_CODE,
+};
+#[cfg(feature = "rpc")]
 use jsonrpsee::types::ErrorObject;
+#[cfg(feature = "rpc")]
 use starknet::core::types::StarknetError;
+use starknet::providers::jsonrpc::{HttpTransportError, JsonRpcClientError, RpcError};
 use starknet::providers::ProviderError;
 use thiserror::Error;
 
-use super::helpers::DataDecodingError;
+use super::breaker::Overloaded;
+use super::budget::BudgetExceeded;
+use super::compat::CompatError;
+use super::devnet::DevnetError;
+use super::helpers::{DataDecodingError, RawTransactionError};
+use super::lanes::LaneFull;
+use super::policy::RelayPolicyViolation;
+use super::tracking::{AlreadyKnown, ReceiveError, ReplacementUnderpriced};
 use crate::models::ConversionError;
 
 /// List of JSON-RPC error codes from reth
@@ -20,6 +35,10 @@ pub enum EthRpcErrorCode {
     ResourceNotFound = -32001,
     /// Failed to send transaction, See also <https://github.com/MetaMask/eth-rpc-errors/blob/main/src/error-constants.ts>
     TransactionRejected = -32003,
+    /// Method not supported by the server, <https://eips.ethereum.org/EIPS/eip-1474>
+    MethodNotSupported = -32004,
+    /// Request exceeds a limit of the server, <https://eips.ethereum.org/EIPS/eip-1474>
+    LimitExceeded = -32005,
 }
 
 // Error that can accure when preparing configuration.
@@ -37,6 +56,47 @@ pub enum ConfigError {
     /// Invalid network error.
     #[error("Invalid network: {0}")]
     InvalidNetwork(String),
+    /// Deployment manifest missing or invalid.
+    #[error("Invalid deployment manifest: {0}")]
+    Manifest(String),
+    /// Option of the client builder missing.
+    #[error("Missing client option: {0}")]
+    MissingClientOption(String),
+}
+
+/// Errors of the version 0.4 of the Starknet JSON-RPC specification rejecting an invoke
+/// transaction. The `StarknetError` of the provider implements 0.3 and returns them as unknown
+/// JSON-RPC errors, so they are recognized by their code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvokeRejection {
+    /// `INVALID_TRANSACTION_NONCE`
+    InvalidTransactionNonce,
+    /// `INSUFFICIENT_MAX_FEE`
+    InsufficientMaxFee,
+    /// `INSUFFICIENT_ACCOUNT_BALANCE`
+    InsufficientAccountBalance,
+}
+
+impl InvokeRejection {
+    pub fn from_code(code: i64) -> Option<Self> {
+        match code {
+            52 => Some(Self::InvalidTransactionNonce),
+            53 => Some(Self::InsufficientMaxFee),
+            54 => Some(Self::InsufficientAccountBalance),
+            _ => None,
+        }
+    }
+}
+
+/// Transaction rejected by the chain id policy, with the messages of Geth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum ChainIdError {
+    /// Legacy transaction signed without a chain id.
+    #[error("only replay-protected (EIP-155) transactions allowed over RPC")]
+    Unprotected,
+    /// Transaction signed for another chain.
+    #[error("invalid chain id for signer: got {0}")]
+    Mismatch(u64),
 }
 
 /// Error that can accure when interacting with the Kakarot ETH API.
@@ -63,17 +123,135 @@ pub enum EthApiError<E: std::error::Error> {
     /// Configuration error.
     #[error(transparent)]
     ConfigError(#[from] ConfigError),
+    /// Raw transaction rejected before decoding it.
+    #[error("invalid raw transaction: {0}")]
+    RawTransactionError(#[from] RawTransactionError),
+    /// Transaction rejected because the latest Starknet block is too old.
+    #[error("Starknet sequencer is stale, latest block is {block_age}s old")]
+    StaleSequencer { block_age: u64 },
+    /// Transaction rejected by the chain id policy.
+    #[error(transparent)]
+    ChainIdError(#[from] ChainIdError),
+    /// Transaction rejected by the relay policy.
+    #[error(transparent)]
+    RelayPolicyViolation(#[from] RelayPolicyViolation),
+    /// Transaction replacing a pending one without bumping its fees enough.
+    #[error(transparent)]
+    ReplacementUnderpriced(#[from] ReplacementUnderpriced),
+    /// Transaction already submitted to the RPC.
+    #[error(transparent)]
+    AlreadyKnown(#[from] AlreadyKnown),
+    /// Transaction failing with the highest gas limit `eth_estimateGas` simulates it with.
+    #[error("gas required exceeds allowance ({cap})")]
+    GasRequiredExceedsAllowance { cap: u64 },
+    /// Simulation aborted after the execution timeout.
+    #[error("execution aborted (timeout = {0:?})")]
+    ExecutionTimeout(Duration),
+    /// Dev method failed.
+    #[error(transparent)]
+    DevnetError(#[from] DevnetError),
+    /// Transaction rejected by the private relay.
+    #[error("Private relay failed: {0}")]
+    PrivateRelayError(#[from] CompatError),
+    /// Block outside of the history served in light mode.
+    #[error("block {block_number} is out of the {history_blocks} latest blocks served in light mode")]
+    HistoryUnavailable { block_number: u64, history_blocks: u64 },
+    /// Logs query matching more logs than returned at once.
+    #[error("query returned more than {limit} logs, retry with the block range [{from_block}, {to_block}]")]
+    TooManyLogs { limit: usize, from_block: u64, to_block: u64 },
+    /// Logs query whose first block alone matches more logs than returned at once, so that no
+    /// block range fits.
+    #[error("block {block_number} alone has more than {limit} logs, narrow the query by address or topics")]
+    TooManyLogsInBlock { limit: usize, block_number: u64 },
+    /// Logs query exceeding the budget of Starknet calls, the blocks up to `to_block` fitting in
+    /// the budget.
+    #[error("{reason}, retry with the block range [{from_block}, {to_block}]")]
+    LogsBudgetExceeded { reason: BudgetExceeded, from_block: u64, to_block: u64 },
+    /// Starknet provider returning the continuation token it was given, which would never end.
+    #[error("Starknet provider returned the same events continuation token twice: {0}")]
+    StalledEventsPagination(String),
+    /// Request exceeding the budget of Starknet calls.
+    #[error(transparent)]
+    BudgetExceeded(#[from] BudgetExceeded),
+    /// Expensive request shed by the circuit breaker.
+    #[error(transparent)]
+    Overloaded(#[from] Overloaded),
+    /// Request shed because the queue of its lane is full.
+    #[error(transparent)]
+    LaneFull(#[from] LaneFull),
     /// Other error.
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
 
+impl<E: std::error::Error> EthApiError<E> {
+    /// Returns true if the error was raised because the max fee of a Starknet transaction was
+    /// lower than its actual fee. A sender unable to pay the fee is not, a new max fee would not
+    /// help.
+    pub fn is_insufficient_fee(&self) -> bool
+    where
+        E: 'static,
+    {
+        self.invoke_rejection() == Some(InvokeRejection::InsufficientMaxFee)
+    }
+
+    /// Returns true if the error was raised because the nonce of a Starknet transaction was not the
+    /// nonce its account expected.
+    pub fn is_invalid_nonce(&self) -> bool
+    where
+        E: 'static,
+    {
+        self.invoke_rejection() == Some(InvokeRejection::InvalidTransactionNonce)
+    }
+
+    /// Returns the reason an invoke transaction was rejected by the JSON-RPC provider or by the
+    /// private relay, if it was rejected for a reason of the specification.
+    pub fn invoke_rejection(&self) -> Option<InvokeRejection>
+    where
+        E: 'static,
+    {
+        let code = match self {
+            Self::RequestError(ProviderError::Other(err)) => {
+                match (err as &dyn std::error::Error).downcast_ref::<JsonRpcClientError<HttpTransportError>>()? {
+                    JsonRpcClientError::RpcError(RpcError::Unknown(err)) => err.code,
+                    _ => return None,
+                }
+            }
+            Self::PrivateRelayError(CompatError::Rpc { code, .. }) => *code,
+            _ => return None,
+        };
+        InvokeRejection::from_code(code)
+    }
+
+    /// Returns true if the error was raised because a simulated transaction failed, e.g. reverted
+    /// or ran out of gas, rather than because the simulation could not be run.
+    pub fn is_execution_failure(&self) -> bool {
+        let message = self.to_string().to_lowercase();
+        ["transaction_failed", "entry_point_failed", "revert", "out of gas"].iter().any(|hint| message.contains(hint))
+    }
+
+    /// Returns true if the error was raised because a simulated transaction ran out of gas.
+    pub fn is_out_of_gas(&self) -> bool {
+        self.to_string().to_lowercase().contains("out of gas")
+    }
+}
+
 impl<T, E: std::error::Error> From<ConversionError<T>> for EthApiError<E> {
     fn from(err: ConversionError<T>) -> Self {
         Self::ConversionError(err.to_string())
     }
 }
 
+impl<E: std::error::Error> From<ReceiveError> for EthApiError<E> {
+    fn from(err: ReceiveError) -> Self {
+        match err {
+            ReceiveError::AlreadyKnown(err) => Self::AlreadyKnown(err),
+            ReceiveError::ReplacementUnderpriced(err) => Self::ReplacementUnderpriced(err),
+        }
+    }
+}
+
+#[cfg(feature = "rpc")]
 impl<E: std::error::Error> From<EthApiError<E>> for ErrorObject<'static> {
     fn from(error: EthApiError<E>) -> Self {
         match error {
@@ -111,11 +289,56 @@ impl<E: std::error::Error> From<EthApiError<E>> for ErrorObject<'static> {
             EthApiError::FeederGatewayError(err) => rpc_err(INTERNAL_ERROR_CODE, err),
             EthApiError::MissingParameterError(err) => rpc_err(INVALID_PARAMS_CODE, err),
             EthApiError::ConfigError(err) => rpc_err(INTERNAL_ERROR_CODE, err.to_string()),
+            EthApiError::RawTransactionError(err) => rpc_err(EthRpcErrorCode::InvalidInput as i32, err.to_string()),
+            err @ EthApiError::StaleSequencer { .. } => rpc_err(SERVER_IS_BUSY_CODE, err.to_string()),
+            EthApiError::ChainIdError(err) => rpc_err(EthRpcErrorCode::InvalidInput as i32, err.to_string()),
+            EthApiError::RelayPolicyViolation(err) => {
+                rpc_err(EthRpcErrorCode::TransactionRejected as i32, err.to_string())
+            }
+            EthApiError::ReplacementUnderpriced(err) => rpc_err(EthRpcErrorCode::InvalidInput as i32, err.to_string()),
+            // The hash of the first submission is returned so that clients can keep tracking it
+            EthApiError::AlreadyKnown(err) => ErrorObject::owned(
+                EthRpcErrorCode::InvalidInput as i32,
+                err.to_string(),
+                Some(serde_json::json!(format!("{:#x}", err.0))),
+            ),
+            err @ EthApiError::GasRequiredExceedsAllowance { .. } => {
+                rpc_err(EthRpcErrorCode::InvalidInput as i32, err.to_string())
+            }
+            err @ EthApiError::ExecutionTimeout(_) => rpc_err(EthRpcErrorCode::InvalidInput as i32, err.to_string()),
+            EthApiError::DevnetError(err @ DevnetError::Unavailable) => rpc_err(METHOD_NOT_FOUND_CODE, err.to_string()),
+            EthApiError::DevnetError(err) => rpc_err(INTERNAL_ERROR_CODE, err.to_string()),
+            err @ EthApiError::PrivateRelayError(_) => {
+                rpc_err(EthRpcErrorCode::TransactionRejected as i32, err.to_string())
+            }
+            err @ EthApiError::HistoryUnavailable { .. } => {
+                rpc_err(EthRpcErrorCode::ResourceNotFound as i32, err.to_string())
+            }
+            // The block range which fits is also set in the data of the error, as on Infura
+            err @ EthApiError::TooManyLogs { from_block, to_block, .. }
+            | err @ EthApiError::LogsBudgetExceeded { from_block, to_block, .. } => ErrorObject::owned(
+                EthRpcErrorCode::LimitExceeded as i32,
+                err.to_string(),
+                Some(serde_json::json!({ "from": format!("{from_block:#x}"), "to": format!("{to_block:#x}") })),
+            ),
+            err @ EthApiError::TooManyLogsInBlock { .. } => {
+                rpc_err(EthRpcErrorCode::LimitExceeded as i32, err.to_string())
+            }
+            err @ EthApiError::StalledEventsPagination(_) => rpc_err(INTERNAL_ERROR_CODE, err.to_string()),
+            EthApiError::BudgetExceeded(err) => rpc_err(EthRpcErrorCode::LimitExceeded as i32, err.to_string()),
+            EthApiError::Overloaded(err) => rpc_err(SERVER_IS_BUSY_CODE, err.to_string()),
+            // The retry hint is also set as the `Retry-After` header of the HTTP responses
+            EthApiError::LaneFull(err) => ErrorObject::owned(
+                EthRpcErrorCode::LimitExceeded as i32,
+                err.to_string(),
+                Some(serde_json::json!({ "retryAfter": err.retry_after.as_secs() })),
+            ),
             EthApiError::Other(err) => rpc_err(INTERNAL_ERROR_CODE, err.to_string()),
         }
     }
 }
 
+#[cfg(feature = "rpc")]
 impl<E: std::error::Error> From<EthApiError<E>> for jsonrpsee::core::Error {
     fn from(err: EthApiError<E>) -> Self {
         jsonrpsee::core::Error::Call(err.into())
@@ -123,6 +346,71 @@ impl<E: std::error::Error> From<EthApiError<E>> for jsonrpsee::core::Error {
 }
 
 /// Constructs a JSON-RPC error object, consisting of `code` and `message`.
+#[cfg(feature = "rpc")]
 pub fn rpc_err(code: i32, msg: impl Into<String>) -> jsonrpsee::types::error::ErrorObject<'static> {
     jsonrpsee::types::error::ErrorObject::owned(code, msg.into(), None::<()>)
 }
+
+#[cfg(all(test, feature = "rpc"))]
+mod tests {
+    use starknet::providers::jsonrpc::JsonRpcError;
+
+    use super::*;
+
+    /// Returns the error of the JSON-RPC provider for an unknown Starknet error code.
+    fn rejected(code: i64, message: &str) -> EthApiError<JsonRpcClientError<HttpTransportError>> {
+        let err = JsonRpcClientError::RpcError(RpcError::Unknown(JsonRpcError { code, message: message.into() }));
+        EthApiError::RequestError(ProviderError::Other(err))
+    }
+
+    #[test]
+    fn test_logs_limit_errors_suggest_block_range() {
+        // Given
+        let too_many_logs =
+            EthApiError::<std::io::Error>::TooManyLogs { limit: 10_000, from_block: 100, to_block: 180 };
+        let budget_exceeded = EthApiError::<std::io::Error>::LogsBudgetExceeded {
+            reason: BudgetExceeded::UpstreamCalls(1000),
+            from_block: 5,
+            to_block: 10,
+        };
+
+        // When
+        let too_many_logs: ErrorObject<'static> = too_many_logs.into();
+        let budget_exceeded: ErrorObject<'static> = budget_exceeded.into();
+
+        // Then
+        assert_eq!(EthRpcErrorCode::LimitExceeded as i32, too_many_logs.code());
+        assert_eq!(r#"{"from":"0x64","to":"0xb4"}"#, too_many_logs.data().unwrap().get());
+        assert_eq!(
+            "query too complex: more than 1000 Starknet calls, narrow the request, retry with the block range [5, 10]",
+            budget_exceeded.message()
+        );
+        assert_eq!(r#"{"from":"0x5","to":"0xa"}"#, budget_exceeded.data().unwrap().get());
+    }
+
+    #[test]
+    fn test_invoke_rejection() {
+        // Given
+        let invalid_nonce = rejected(52, "Invalid transaction nonce");
+        let private_relay_invalid_nonce =
+            EthApiError::<std::io::Error>::from(CompatError::Rpc { code: 52, message: "Invalid nonce".into() });
+        let contract_error = EthApiError::<JsonRpcClientError<HttpTransportError>>::RequestError(
+            ProviderError::StarknetError(StarknetError::ContractError),
+        );
+        let unknown = rejected(63, "An unexpected error occured: invalid nonce");
+
+        // Then
+        assert!(invalid_nonce.is_invalid_nonce());
+        assert!(private_relay_invalid_nonce.is_invalid_nonce());
+        assert!(!contract_error.is_invalid_nonce());
+        assert!(!unknown.is_invalid_nonce());
+        assert_eq!(Some(InvokeRejection::InsufficientAccountBalance), rejected(54, "").invoke_rejection());
+    }
+
+    #[test]
+    fn test_is_insufficient_fee() {
+        assert!(rejected(53, "Max fee is smaller than the minimal transaction cost").is_insufficient_fee());
+        assert!(!rejected(54, "Account balance is smaller than the transaction's max_fee").is_insufficient_fee());
+        assert!(!EthApiError::<std::io::Error>::Other(anyhow::anyhow!("max fee exceeds balance")).is_insufficient_fee());
+    }
+}
@@ -0,0 +1,99 @@
+use std::future::Future;
+use std::time::Duration;
+
+use super::constants::gas::MINIMUM_GAS_FEE;
+
+/// Default maximum gas limit `eth_estimateGas` simulates a transaction with, as on Geth.
+pub const DEFAULT_RPC_GAS_CAP: u64 = 50_000_000;
+/// Default time after which `eth_call` and `eth_estimateGas` are aborted, as on Geth.
+pub const DEFAULT_EXECUTION_TIMEOUT: Duration = Duration::from_secs(5);
+/// Relative error of the gas limit at which the search stops, as on Geth.
+const ESTIMATE_GAS_ERROR_RATIO: f64 = 0.015;
+/// Gas forwarded for free with a value transfer, which the used gas does not account for.
+const CALL_STIPEND: u64 = 2300;
+
+/// Returns the highest gas limit a transaction is estimated with: the gas limit of the request if
+/// it covers the intrinsic gas, else the block gas limit, capped by the RPC gas cap, zero
+/// disabling the cap.
+pub fn gas_limit_cap(requested: Option<u64>, block_gas_limit: u64, gas_cap: u64) -> u64 {
+    let gas_limit = requested.filter(|gas_limit| *gas_limit >= MINIMUM_GAS_FEE).unwrap_or(block_gas_limit);
+    if gas_cap == 0 { gas_limit } else { gas_limit.min(gas_cap) }
+}
+
+/// Searches the lowest gas limit a transaction succeeds with, the way Geth does, given the gas it
+/// used when executed with the gas limit `hi`. The transaction may need more gas than it uses,
+/// e.g. for the 63/64 of the gas forwarded to the calls, so the used gas plus this margin is tried
+/// first, then the range is bisected until the relative error is below 1.5%. `execute` returns
+/// whether the transaction succeeds with a gas limit.
+pub async fn search_gas_limit<E, F, Fut>(used: u64, mut hi: u64, mut execute: F) -> Result<u64, E>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Result<bool, E>>,
+{
+    let mut lo = used.saturating_sub(1);
+    let optimistic = used.saturating_add(CALL_STIPEND).saturating_mul(64) / 63;
+    if optimistic < hi {
+        if execute(optimistic).await? {
+            hi = optimistic;
+        } else {
+            lo = optimistic;
+        }
+    }
+
+    while lo + 1 < hi {
+        if (hi - lo) as f64 / hi as f64 < ESTIMATE_GAS_ERROR_RATIO {
+            break;
+        }
+        // Small gas limits are searched from below, most transactions need little more than they use
+        let mid = (lo + (hi - lo) / 2).min(lo.saturating_mul(2));
+        if execute(mid).await? {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    Ok(hi)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use super::*;
+
+    #[test]
+    fn test_gas_limit_cap() {
+        assert_eq!(1_000_000, gas_limit_cap(None, 1_000_000, DEFAULT_RPC_GAS_CAP));
+        assert_eq!(1_000_000, gas_limit_cap(Some(20_000), 1_000_000, DEFAULT_RPC_GAS_CAP));
+        assert_eq!(100_000, gas_limit_cap(Some(100_000), 1_000_000, DEFAULT_RPC_GAS_CAP));
+        assert_eq!(DEFAULT_RPC_GAS_CAP, gas_limit_cap(Some(u64::MAX), 1_000_000, DEFAULT_RPC_GAS_CAP));
+        assert_eq!(u64::MAX, gas_limit_cap(Some(u64::MAX), 1_000_000, 0));
+    }
+
+    #[tokio::test]
+    async fn test_search_gas_limit() {
+        // Given
+        // A contract checking that it was given 150k gas while using 60k
+        let required = 150_000;
+        let mut executions = 0;
+
+        // When
+        let gas_limit = search_gas_limit(60_000, 1_000_000, |gas_limit| {
+            executions += 1;
+            async move { Ok::<_, Infallible>(gas_limit >= required) }
+        })
+        .await
+        .unwrap();
+        let used = search_gas_limit(60_000, 1_000_000, |gas_limit| async move { Ok::<_, Infallible>(gas_limit >= 60_000) })
+            .await
+            .unwrap();
+
+        // Then
+        assert!(gas_limit >= required);
+        assert!((gas_limit - required) as f64 / (gas_limit as f64) < ESTIMATE_GAS_ERROR_RATIO);
+        assert!(executions < 20);
+        // A transaction needing the gas it uses is searched below the used gas plus the margin
+        assert!((60_000..(60_000 + CALL_STIPEND) * 64 / 63).contains(&used));
+        assert!((used - 60_000) as f64 / (used as f64) < ESTIMATE_GAS_ERROR_RATIO);
+    }
+}
@@ -1,6 +1,10 @@
+use std::future::Future;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
 use eyre::Result;
-use reth_primitives::{Bloom, Bytes, H160};
-use reth_rlp::DecodeError;
+use futures::stream::{self, Stream, StreamExt};
+use reth_primitives::{Bloom, Bytes, TransactionSigned, H160};
+use reth_rlp::{Decodable, DecodeError};
 use reth_rpc_types::TransactionReceipt;
 use starknet::core::types::{
     FieldElement, MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs, ValueOutOfRangeError,
@@ -11,17 +15,31 @@ use super::constants::{CUMULATIVE_GAS_USED, EFFECTIVE_GAS_PRICE, GAS_USED, TRANS
 use crate::client::constants::selectors::ETH_SEND_TRANSACTION;
 use crate::client::errors::EthApiError;
 use crate::models::ConversionError;
+pub use crate::models::DataDecodingError;
 
-#[derive(Debug, Error)]
-pub enum DataDecodingError {
-    #[error("failed to decode signature {0}")]
-    SignatureDecodingError(String),
-    #[error("failed to decode transaction")]
-    TransactionDecodingError(#[from] DecodeError),
-    #[error("{entrypoint} returned invalid array length, expected {expected}, got {actual}")]
-    InvalidReturnArrayLength { entrypoint: String, expected: usize, actual: usize },
+/// Error raised when the raw bytes of an `eth_sendRawTransaction` request are rejected.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RawTransactionError {
+    #[error("empty raw transaction")]
+    Empty,
+    #[error("oversized data: transaction size {size}, limit {limit}")]
+    Oversized { size: usize, limit: usize },
+    #[error("rlp: {0}")]
+    Rlp(DecodeError),
+    #[error("rlp: {0} trailing bytes after the transaction")]
+    TrailingBytes(usize),
+    #[error("rlp: malformed transaction")]
+    Malformed,
+    /// EIP-2718 transaction type Kakarot does not execute, e.g. EIP-4844 blob transactions.
+    #[error("transaction type not supported")]
+    UnsupportedType(u8),
 }
 
+/// EIP-2718 types of the typed transactions executed by Kakarot: EIP-2930 and EIP-1559.
+const SUPPORTED_TRANSACTION_TYPES: [u8; 2] = [0x01, 0x02];
+/// Typed transactions start with their type, legacy transactions with an RLP list header.
+const MAX_TRANSACTION_TYPE: u8 = 0x7f;
+
 #[derive(Debug)]
 struct InvalidFieldElementError;
 
@@ -104,6 +122,39 @@ pub fn create_default_transaction_receipt() -> TransactionReceipt {
     }
 }
 
+/// Decodes the raw bytes of an `eth_sendRawTransaction` request, rejecting empty, oversized and
+/// malformed inputs. Never panics, whatever the input.
+pub fn decode_raw_transaction(bytes: &[u8], max_size: usize) -> Result<TransactionSigned, RawTransactionError> {
+    if bytes.is_empty() {
+        return Err(RawTransactionError::Empty);
+    }
+    if bytes.len() > max_size {
+        return Err(RawTransactionError::Oversized { size: bytes.len(), limit: max_size });
+    }
+
+    // Rejected before decoding, reth failing with a confusing RLP error on unknown types
+    match bytes[0] {
+        transaction_type
+            if transaction_type <= MAX_TRANSACTION_TYPE && !SUPPORTED_TRANSACTION_TYPES.contains(&transaction_type) =>
+        {
+            return Err(RawTransactionError::UnsupportedType(transaction_type));
+        }
+        _ => {}
+    }
+
+    let mut data = bytes;
+    // The decoder is not expected to panic, this guards the server against hostile inputs if it
+    // does
+    let transaction = catch_unwind(AssertUnwindSafe(|| TransactionSigned::decode(&mut data)))
+        .map_err(|_| RawTransactionError::Malformed)?
+        .map_err(RawTransactionError::Rlp)?;
+    if !data.is_empty() {
+        return Err(RawTransactionError::TrailingBytes(data.len()));
+    }
+
+    Ok(transaction)
+}
+
 pub fn bytes_to_felt_vec(bytes: &Bytes) -> Vec<FieldElement> {
     bytes.to_vec().into_iter().map(FieldElement::from).collect()
 }
@@ -123,11 +174,95 @@ pub fn raw_kakarot_calldata(kakarot_address: FieldElement, mut calldata: Vec<Fie
     execute_calldata
 }
 
+/// Maps the items to futures run with at most `concurrency` in flight, yielding their outputs in
+/// the order of the items whatever the order they complete in, e.g. the transactions of a block.
+pub fn buffered_in_order<I, F, Fut>(items: I, concurrency: usize, f: F) -> impl Stream<Item = Fut::Output>
+where
+    I: IntoIterator,
+    F: FnMut(I::Item) -> Fut,
+    Fut: Future,
+{
+    stream::iter(items).map(f).buffered(concurrency.max(1))
+}
+
 #[cfg(test)]
 mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use rand::Rng;
+    use reth_primitives::{Signature, Transaction, TransactionKind, TxEip1559, U256};
 
     use super::*;
 
+    fn raw_transaction() -> Vec<u8> {
+        let transaction = Transaction::Eip1559(TxEip1559 {
+            chain_id: 1_263_227_476,
+            nonce: 1,
+            gas_limit: 21_000,
+            to: TransactionKind::Call(H160::from_low_u64_be(1)),
+            value: 1,
+            input: Bytes::from(vec![1, 2, 3]),
+            ..Default::default()
+        });
+        let signature = Signature { r: U256::from(1), s: U256::from(1), odd_y_parity: false };
+        let mut raw_transaction = vec![];
+        TransactionSigned::from_transaction_and_signature(transaction, signature)
+            .encode_enveloped(&mut raw_transaction);
+        raw_transaction
+    }
+
+    #[test]
+    fn test_decode_raw_transaction_errors() {
+        // Given
+        let raw_transaction = raw_transaction();
+        let mut trailing = raw_transaction.clone();
+        trailing.push(0);
+
+        // When
+        let valid = decode_raw_transaction(&raw_transaction, 1024);
+        let empty = decode_raw_transaction(&[], 1024);
+        let oversized = decode_raw_transaction(&raw_transaction, 10);
+        let trailing = decode_raw_transaction(&trailing, 1024);
+        let truncated = decode_raw_transaction(&raw_transaction[..raw_transaction.len() - 1], 1024);
+        let blob = decode_raw_transaction(&[0x03, 0xc0], 1024);
+
+        // Then
+        assert!(valid.is_ok());
+        assert_eq!(Err(RawTransactionError::Empty), empty);
+        assert_eq!(Err(RawTransactionError::Oversized { size: raw_transaction.len(), limit: 10 }), oversized);
+        assert_eq!(Err(RawTransactionError::TrailingBytes(1)), trailing);
+        assert!(matches!(truncated, Err(RawTransactionError::Rlp(_))));
+        assert_eq!(Err(RawTransactionError::UnsupportedType(3)), blob);
+    }
+
+    #[test]
+    fn test_fuzz_decode_raw_transaction_never_panics() {
+        let mut rng = rand::thread_rng();
+        let raw_transaction = raw_transaction();
+
+        // Random inputs, starting with each transaction type byte or a list header
+        for _ in 0..10_000 {
+            let len = rng.gen_range(1..512);
+            let mut bytes: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+            bytes[0] = [0x01, 0x02, 0xc0, 0xf8, 0xf9, rng.gen()][rng.gen_range(0..6)];
+            let _ = decode_raw_transaction(&bytes, 1024);
+        }
+
+        // Truncations and mutations of a valid transaction
+        for len in 0..raw_transaction.len() {
+            let _ = decode_raw_transaction(&raw_transaction[..len], 1024);
+        }
+        for _ in 0..10_000 {
+            let mut bytes = raw_transaction.clone();
+            for _ in 0..rng.gen_range(1..4) {
+                let index = rng.gen_range(0..bytes.len());
+                bytes[index] = rng.gen();
+            }
+            let _ = decode_raw_transaction(&bytes, 1024);
+        }
+    }
+
     #[test]
     fn test_bytes_to_felt_vec() {
         let bytes = Bytes::from(vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
@@ -164,4 +299,27 @@ mod tests {
             serde_json::from_str(include_str!("../models/test_data/bytecode/eth/counter.json")).unwrap();
         assert_eq!(expected, bytes);
     }
+
+    #[tokio::test]
+    async fn test_buffered_in_order_keeps_the_order_of_the_items() {
+        // Given
+        let (in_flight, max_in_flight) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let convert = |delay_ms: u64| {
+            let (in_flight, max_in_flight) = (&in_flight, &max_in_flight);
+            async move {
+                let running = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(running, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                delay_ms
+            }
+        };
+
+        // When
+        let converted: Vec<u64> = buffered_in_order([40, 10, 30, 20, 0], 2, convert).collect().await;
+
+        // Then
+        assert_eq!(vec![40, 10, 30, 20, 0], converted);
+        assert_eq!(2, max_in_flight.load(Ordering::SeqCst));
+    }
 }
@@ -0,0 +1,228 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// Default maximum number of concurrent heavy read requests.
+pub const DEFAULT_READ_LANE_CONCURRENCY: usize = 64;
+/// Default maximum number of concurrent transaction submissions.
+pub const DEFAULT_WRITE_LANE_CONCURRENCY: usize = 16;
+/// Default time clients are told to wait before retrying a shed request.
+pub const DEFAULT_SHED_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// Configuration of the request lanes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaneConfig {
+    pub read_concurrency: usize,
+    pub write_concurrency: usize,
+    /// Maximum number of requests waiting for a slot of the read lane, the next ones being shed.
+    /// Unbounded if `None`.
+    pub max_read_queue: Option<usize>,
+    /// Maximum number of requests waiting for a slot of the write lane.
+    pub max_write_queue: Option<usize>,
+    /// Time the clients of the shed requests are told to wait before retrying.
+    pub retry_after: Duration,
+}
+
+impl Default for LaneConfig {
+    fn default() -> Self {
+        Self {
+            read_concurrency: DEFAULT_READ_LANE_CONCURRENCY,
+            write_concurrency: DEFAULT_WRITE_LANE_CONCURRENCY,
+            max_read_queue: None,
+            max_write_queue: None,
+            retry_after: DEFAULT_SHED_RETRY_AFTER,
+        }
+    }
+}
+
+/// Request shed because the queue of its lane is full, with the time the client should wait
+/// before retrying.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("server busy, too many requests queued: retry after {}s", .retry_after.as_secs())]
+pub struct LaneFull {
+    pub retry_after: Duration,
+}
+
+/// Queue depth of a lane.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaneDepth {
+    /// Maximum number of concurrent requests.
+    pub capacity: u64,
+    /// Requests being processed.
+    pub in_flight: u64,
+    /// Requests waiting for a slot.
+    pub queued: u64,
+    /// Requests shed because the queue was full.
+    pub shed: u64,
+}
+
+/// Queue depths of the read and write lanes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaneStats {
+    pub read: LaneDepth,
+    pub write: LaneDepth,
+}
+
+/// A bounded number of concurrent requests.
+#[derive(Debug)]
+pub struct Lane {
+    semaphore: Semaphore,
+    capacity: usize,
+    max_queued: Option<usize>,
+    retry_after: Duration,
+    in_flight: AtomicU64,
+    queued: AtomicU64,
+    shed: AtomicU64,
+}
+
+/// A slot of a lane, released on drop.
+pub struct LanePermit<'a> {
+    lane: &'a Lane,
+    _permit: SemaphorePermit<'a>,
+}
+
+/// Decrements the queued requests of a lane on drop, including when the waiting request is
+/// cancelled.
+struct QueuedGuard<'a>(&'a AtomicU64);
+
+impl Drop for QueuedGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl Lane {
+    pub fn new(capacity: usize, max_queued: Option<usize>, retry_after: Duration) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            semaphore: Semaphore::new(capacity),
+            capacity,
+            max_queued,
+            retry_after,
+            in_flight: AtomicU64::new(0),
+            queued: AtomicU64::new(0),
+            shed: AtomicU64::new(0),
+        }
+    }
+
+    /// Waits for a slot of the lane. The request is shed instead if no slot is free and the queue
+    /// is full, so that the latency of the queued requests does not grow without bound.
+    pub async fn acquire(&self) -> Result<LanePermit<'_>, LaneFull> {
+        let queued_before = self.queued.fetch_add(1, Ordering::Relaxed);
+        let queued = QueuedGuard(&self.queued);
+        let permit = match (self.semaphore.try_acquire(), self.max_queued) {
+            (Ok(permit), _) => permit,
+            (Err(_), Some(max_queued)) if queued_before >= max_queued as u64 => {
+                self.shed.fetch_add(1, Ordering::Relaxed);
+                return Err(LaneFull { retry_after: self.retry_after });
+            }
+            // The semaphore is never closed
+            (Err(_), _) => self.semaphore.acquire().await.expect("lane semaphore closed"),
+        };
+        drop(queued);
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+        Ok(LanePermit { lane: self, _permit: permit })
+    }
+
+    pub fn depth(&self) -> LaneDepth {
+        LaneDepth {
+            capacity: self.capacity as u64,
+            in_flight: self.in_flight.load(Ordering::Relaxed),
+            queued: self.queued.load(Ordering::Relaxed),
+            shed: self.shed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Drop for LanePermit<'_> {
+    fn drop(&mut self) {
+        self.lane.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Separate lanes for heavy read requests and transaction submissions, so that submissions are
+/// never queued behind a flood of reads.
+#[derive(Debug)]
+pub struct RequestLanes {
+    read: Lane,
+    write: Lane,
+}
+
+impl Default for RequestLanes {
+    fn default() -> Self {
+        Self::new(&LaneConfig::default())
+    }
+}
+
+impl RequestLanes {
+    pub fn new(config: &LaneConfig) -> Self {
+        Self {
+            read: Lane::new(config.read_concurrency, config.max_read_queue, config.retry_after),
+            write: Lane::new(config.write_concurrency, config.max_write_queue, config.retry_after),
+        }
+    }
+
+    pub async fn read(&self) -> Result<LanePermit<'_>, LaneFull> {
+        self.read.acquire().await
+    }
+
+    pub async fn write(&self) -> Result<LanePermit<'_>, LaneFull> {
+        self.write.acquire().await
+    }
+
+    pub fn stats(&self) -> LaneStats {
+        LaneStats { read: self.read.depth(), write: self.write.depth() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_write_lane_is_not_blocked_by_reads() {
+        // Given
+        let lanes = RequestLanes::new(&LaneConfig { read_concurrency: 1, write_concurrency: 1, ..Default::default() });
+        let _read = lanes.read().await.unwrap();
+
+        // When
+        let queued_read = tokio::time::timeout(std::time::Duration::from_millis(10), lanes.read()).await;
+        let write = tokio::time::timeout(std::time::Duration::from_millis(10), lanes.write()).await;
+
+        // Then
+        assert!(queued_read.is_err());
+        assert!(write.is_ok());
+        let stats = lanes.stats();
+        assert_eq!(LaneDepth { capacity: 1, in_flight: 1, queued: 0, shed: 0 }, stats.read);
+        assert_eq!(LaneDepth { capacity: 1, in_flight: 1, queued: 0, shed: 0 }, stats.write);
+    }
+
+    #[tokio::test]
+    async fn test_lane_sheds_requests_once_its_queue_is_full() {
+        // Given
+        let config = LaneConfig { read_concurrency: 1, max_read_queue: Some(1), ..Default::default() };
+        let lanes = Arc::new(RequestLanes::new(&config));
+        let read = lanes.read().await.unwrap();
+        let queued_lanes = lanes.clone();
+        let queued = tokio::spawn(async move { queued_lanes.read().await.is_ok() });
+        while lanes.stats().read.queued == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        // When
+        let shed = lanes.read().await.err();
+        drop(read);
+
+        // Then
+        assert_eq!(Some(LaneFull { retry_after: DEFAULT_SHED_RETRY_AFTER }), shed);
+        assert!(queued.await.unwrap());
+        assert_eq!(1, lanes.stats().read.shed);
+    }
+}
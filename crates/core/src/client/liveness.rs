@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Default age of the latest Starknet block above which the sequencer is considered stale.
+pub const DEFAULT_MAX_BLOCK_AGE: Duration = Duration::from_secs(600);
+
+/// Configuration of the sequencer liveness detection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LivenessConfig {
+    /// Age of the latest block above which the sequencer is considered stale.
+    pub max_block_age: Duration,
+    /// Rejects transactions while the sequencer is stale, as they would be built against
+    /// outdated `latest` state.
+    pub reject_stale_writes: bool,
+}
+
+impl Default for LivenessConfig {
+    fn default() -> Self {
+        Self { max_block_age: DEFAULT_MAX_BLOCK_AGE, reject_stale_writes: false }
+    }
+}
+
+/// Freshness of the latest Starknet block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LivenessStatus {
+    pub latest_block_number: u64,
+    pub latest_block_timestamp: u64,
+    /// Age of the latest block, in seconds.
+    pub block_age: u64,
+    /// Age above which the sequencer is considered stale, in seconds.
+    pub max_block_age: u64,
+    pub stale: bool,
+}
+
+impl LivenessStatus {
+    /// Computes the liveness of the sequencer given its latest block and the current UNIX
+    /// timestamp.
+    pub fn new(latest_block_number: u64, latest_block_timestamp: u64, now: u64, max_block_age: Duration) -> Self {
+        let block_age = now.saturating_sub(latest_block_timestamp);
+        let max_block_age = max_block_age.as_secs();
+        Self { latest_block_number, latest_block_timestamp, block_age, max_block_age, stale: block_age > max_block_age }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_liveness_status_stale() {
+        // Given
+        let max_block_age = Duration::from_secs(60);
+
+        // When
+        let fresh = LivenessStatus::new(10, 1_000, 1_060, max_block_age);
+        let stale = LivenessStatus::new(10, 1_000, 1_061, max_block_age);
+        let ahead = LivenessStatus::new(10, 1_000, 990, max_block_age);
+
+        // Then
+        assert!(!fresh.stale);
+        assert!(stale.stale);
+        assert_eq!(61, stale.block_age);
+        assert_eq!(0, ahead.block_age);
+    }
+}
@@ -0,0 +1,105 @@
+use std::path::Path;
+
+use serde_json::Value;
+use starknet::core::types::FieldElement;
+
+use super::errors::ConfigError;
+
+/// Files written by the Kakarot deployer, holding the deployed contracts and the declared classes.
+const DEPLOYMENTS_FILE: &str = "deployments.json";
+const DECLARATIONS_FILE: &str = "declarations.json";
+
+/// Addresses and class hashes of a Kakarot deployment, discovered instead of being set one by one
+/// in the environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeploymentManifest {
+    pub kakarot_address: FieldElement,
+    pub proxy_account_class_hash: FieldElement,
+    pub kakarot_class_hash: Option<FieldElement>,
+}
+
+fn felt_field(value: &Value, pointer: &str, source: &str) -> Result<Option<FieldElement>, ConfigError> {
+    let Some(field) = value.pointer(pointer) else { return Ok(None) };
+    let felt = field.as_str().and_then(|field| FieldElement::from_hex_be(field).ok());
+    felt.map(Some).ok_or_else(|| ConfigError::Manifest(format!("{source}: {pointer} is not a hex string, got {field}")))
+}
+
+fn read_json(path: &Path) -> Result<Value, ConfigError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|err| ConfigError::Manifest(format!("failed to read {}: {err}", path.display())))?;
+    serde_json::from_str(&content).map_err(|err| ConfigError::Manifest(format!("{}: {err}", path.display())))
+}
+
+impl DeploymentManifest {
+    /// Reads a manifest from the deployments and declarations of the Kakarot deployer, e.g.
+    /// `{"kakarot": {"address": "0x..."}}` and `{"proxy": "0x..."}`. A published manifest holds
+    /// both in a single document.
+    pub fn from_json(deployments: &Value, declarations: &Value, source: &str) -> Result<Self, ConfigError> {
+        let missing = |field: &str| ConfigError::Manifest(format!("{source}: missing {field}"));
+        Ok(Self {
+            kakarot_address: felt_field(deployments, "/kakarot/address", source)?
+                .ok_or_else(|| missing("kakarot.address"))?,
+            proxy_account_class_hash: felt_field(declarations, "/proxy", source)?.ok_or_else(|| missing("proxy"))?,
+            kakarot_class_hash: felt_field(deployments, "/kakarot/class_hash", source)?,
+        })
+    }
+
+    /// Loads a manifest from a published URL, from a JSON file or from a directory of the Kakarot
+    /// deployer holding `deployments.json` and `declarations.json`.
+    pub async fn load(source: &str) -> Result<Self, ConfigError> {
+        if source.starts_with("http://") || source.starts_with("https://") {
+            let fetch_error = |err: reqwest::Error| ConfigError::Manifest(format!("failed to fetch {source}: {err}"));
+            let body = reqwest::get(source).await.and_then(reqwest::Response::error_for_status).map_err(fetch_error)?;
+            let manifest: Value = serde_json::from_str(&body.text().await.map_err(fetch_error)?)
+                .map_err(|err| ConfigError::Manifest(format!("{source}: {err}")))?;
+            return Self::from_json(&manifest, &manifest, source);
+        }
+
+        let path = Path::new(source);
+        if path.is_dir() {
+            let deployments = read_json(&path.join(DEPLOYMENTS_FILE))?;
+            let declarations = read_json(&path.join(DECLARATIONS_FILE))?;
+            return Self::from_json(&deployments, &declarations, source);
+        }
+        let manifest = read_json(path)?;
+        Self::from_json(&manifest, &manifest, source)
+    }
+
+    /// Discovers the manifest of the deployment when `KAKAROT_ADDRESS` is not set: from
+    /// `KAKAROT_MANIFEST`, or from the working directory if it holds the files of the Kakarot
+    /// deployer, as in docker compose. Returns `None` if the addresses are set in the environment
+    /// or nothing is found.
+    pub async fn discover() -> Result<Option<Self>, ConfigError> {
+        if let Ok(source) = std::env::var("KAKAROT_MANIFEST") {
+            return Self::load(&source).await.map(Some);
+        }
+        if std::env::var("KAKAROT_ADDRESS").is_ok() || !Path::new(DEPLOYMENTS_FILE).is_file() {
+            return Ok(None);
+        }
+        Self::load(".").await.map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_deployment_manifest_from_json() {
+        // Given
+        let deployments = json!({ "kakarot": { "address": "0x1", "tx": "0x2" }, "blockhash_registry": {} });
+        let declarations = json!({ "proxy": "0x3", "contract_account": "0x4" });
+
+        // When
+        let manifest = DeploymentManifest::from_json(&deployments, &declarations, "test").unwrap();
+
+        // Then
+        assert_eq!(FieldElement::from(1u8), manifest.kakarot_address);
+        assert_eq!(FieldElement::from(3u8), manifest.proxy_account_class_hash);
+        assert_eq!(None, manifest.kakarot_class_hash);
+        assert!(DeploymentManifest::from_json(&deployments, &json!({}), "test").is_err());
+        assert!(DeploymentManifest::from_json(&json!({ "kakarot": { "address": 1 } }), &declarations, "test").is_err());
+    }
+}
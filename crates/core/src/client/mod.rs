@@ -1,51 +1,135 @@
 pub mod api;
+pub mod audit;
+pub mod breaker;
+pub mod budget;
+pub mod builder;
+pub mod calls;
+pub mod coalesce;
+pub mod compat;
 pub mod config;
 pub mod constants;
+pub mod creations;
+pub mod devnet;
 pub mod errors;
+pub mod estimate;
 pub mod helpers;
+pub mod lanes;
+pub mod liveness;
+pub mod manifest;
+pub mod nonces;
+pub mod oracle;
+pub mod policy;
+pub mod retry;
+pub mod roots;
+pub mod schema;
+pub mod sequencer;
+pub mod stats;
+pub mod store;
 #[cfg(test)]
 pub mod tests;
+pub mod timestamps;
+pub mod tip;
+pub mod tracking;
+pub mod upstream;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use async_trait::async_trait;
 use eyre::Result;
-use futures::future::join_all;
+use futures::future::{self, join_all};
+use futures::stream::{self, StreamExt, TryStreamExt};
 use helpers::vec_felt_to_bytes;
 use reqwest::Client;
 use reth_primitives::{
-    keccak256, AccessList, Address, BlockId, BlockNumberOrTag, Bloom, Bytes, Signature, Transaction, TransactionKind,
+    keccak256, Address, BlockId, BlockNumberOrTag, Bloom, Bytes, Signature, Transaction, TransactionKind,
     TransactionSigned, TxEip1559, H256, U128, U256, U64, U8,
 };
-use reth_rlp::Decodable;
 use reth_rpc_types::{
-    BlockTransactions, CallRequest, FeeHistory, Index, RichBlock, SyncInfo, SyncStatus,
+    BlockTransactions, CallRequest, FeeHistory, Index, Log, RichBlock, SyncInfo, SyncStatus,
     Transaction as EtherTransaction, TransactionReceipt,
 };
 use starknet::core::types::{
-    BlockId as StarknetBlockId, BlockTag, BroadcastedInvokeTransaction, BroadcastedInvokeTransactionV1, FieldElement,
-    FunctionCall, InvokeTransactionReceipt, MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs,
-    MaybePendingTransactionReceipt, StarknetError, SyncStatusType, Transaction as TransactionType,
+    BlockId as StarknetBlockId, BlockTag, BroadcastedInvokeTransaction, BroadcastedInvokeTransactionV1,
+    BroadcastedTransaction, Event, EventFilter, FieldElement, FunctionCall, InvokeTransactionReceipt,
+    MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs, MaybePendingStateUpdate, MaybePendingTransactionReceipt,
+    PendingTransactionReceipt, StarknetError, StateDiff, SyncStatusType, Transaction as TransactionType,
     TransactionReceipt as StarknetTransactionReceipt, TransactionStatus as StarknetTransactionStatus,
 };
 use starknet::providers::sequencer::models::{FeeEstimate, FeeUnit, TransactionSimulationInfo, TransactionTrace};
 use starknet::providers::{Provider, ProviderError};
+use tokio::sync::OnceCell;
+use url::Url;
 
 use self::api::{KakarotEthApi, KakarotStarknetApi};
-use self::config::{Network, StarknetConfig};
+use self::audit::{AuditEntry, AuditEvent, AuditLog};
+use self::breaker::{BreakerStats, CircuitBreaker};
+use self::budget::{BudgetConfig, RequestBudget};
+use self::builder::KakarotClientBuilder;
+use self::calls::{CallBlock, CallCache, CallCacheStats};
+use self::coalesce::{CoalescingCounters, CoalescingStats};
+use self::compat::{
+    add_invoke, latest_l1_gas_price, probe_spec_version, simulate_gas_usage, simulate_invoke, simulate_invoke_sequence,
+    SpecVersion,
+};
+use self::config::{
+    BlockHeaderConfig, ChainIdPolicy, DeploymentAddresses, FeeRetryPolicy, NativeTokenConfig, Network, OperatingMode,
+    ReadRetryPolicy, StarknetConfig,
+};
 use self::constants::gas::{BASE_FEE_PER_GAS, MAX_PRIORITY_FEE_PER_GAS, MINIMUM_GAS_FEE};
-use self::constants::selectors::{BALANCE_OF, EVM_CONTRACT_DEPLOYED, GET_EVM_ADDRESS};
+use self::constants::selectors::{BALANCE_OF, BYTECODE, EVM_CONTRACT_DEPLOYED, GET_EVM_ADDRESS, GET_IMPLEMENTATION};
 use self::constants::{
-    ACCOUNT_ADDRESS, CHAIN_ID, COUNTER_CALL_MAINNET, COUNTER_CALL_TESTNET1, COUNTER_CALL_TESTNET2, ESTIMATE_GAS,
-    MAX_FEE, STARKNET_NATIVE_TOKEN,
+    CHAIN_ID, EARLIEST_BLOCK_NUMBER, EFFECTIVE_GAS_PRICE, ESTIMATE_GAS, KAKAROT_CLIENT_VERSION,
+    LOGS_BLOCKS_BUFFER_SIZE, MAX_FEE,
+};
+use self::creations::{ContractCreations, ContractCreator};
+use self::devnet::{
+    bytecode_storage_writes, split_u256, uint256_storage_writes, DevModeConfig, DevnetClient, DevnetError, Snapshots,
+    DEV_RECEIPT_POLL_INTERVAL,
 };
 use self::errors::EthApiError;
-use self::helpers::{bytes_to_felt_vec, raw_kakarot_calldata, DataDecodingError};
+use self::estimate::{gas_limit_cap, search_gas_limit};
+use self::helpers::{
+    buffered_in_order, bytes_to_felt_vec, decode_eth_call_return, decode_raw_transaction, raw_kakarot_calldata,
+    DataDecodingError,
+};
+use self::lanes::{LaneStats, RequestLanes};
+use self::liveness::{LivenessConfig, LivenessStatus};
+use self::nonces::SenderNonceIndex;
+use self::oracle::GasPriceOracle;
+use self::policy::RelayPolicy;
+use self::retry::retry_read;
+use self::roots::{consensus_receipt, BlockRoots, BlockRootsCache};
+use self::schema::NAMESPACES;
+use self::sequencer::{NonceSequencer, MAX_NONCE_RECOVERIES};
+use self::stats::ClientStats;
+use self::store::{InMemoryStore, KakarotStore};
+use self::timestamps::{search_block_by_timestamp, BlockTimestampsCache};
+use self::tip::BlockNumberCache;
+use self::tracking::{
+    RelayStats, RelayStatus, TrackedTransaction, TransactionFees, TransactionTracker, DEFAULT_TRACKER_CAPACITY,
+};
+use self::upstream::{UpstreamScores, UpstreamStats};
 use crate::contracts::contract_account::ContractAccount;
 use crate::contracts::kakarot::KakarotContract;
+use crate::models::account::{AccountState, AccountType};
 use crate::models::balance::{TokenBalance, TokenBalances};
 use crate::models::block::{BlockWithTxHashes, BlockWithTxs, EthBlockId};
+use crate::models::bundle::{BundleTransactionResult, CallBundle};
+use crate::models::call::Calls;
+use crate::models::capabilities::Capabilities;
+use crate::models::chain_config::{ChainConfig, FeeToken, GasConstants};
 use crate::models::convertible::{ConvertibleStarknetBlock, ConvertibleStarknetEvent, ConvertibleStarknetTransaction};
+use crate::models::deployment::{DeploymentCheck, DeploymentReport};
 use crate::models::event::StarknetEvent;
+use crate::models::fee::{starknet_fee_to_gas, StarknetFeeEstimate};
 use crate::models::felt::Felt252Wrapper;
+use crate::models::filter::LogFilter;
+use crate::models::message::{l1_to_l2_message_hash, L1Message, MessageDirection, MessageStatus};
+use crate::models::precompile::Precompile;
+use crate::models::state_diff::{is_balance_write, sort_storage_writes, AccountStateDiff};
 use crate::models::transaction::{StarknetTransaction, StarknetTransactions};
 use crate::models::ConversionError;
 
@@ -53,39 +137,810 @@ pub struct KakarotClient<P: Provider + Send + Sync> {
     starknet_provider: P,
     kakarot_contract: KakarotContract<P>,
     network: Network,
+    kakarot_class_hash: Option<FieldElement>,
+    starknet_chain_id: Option<FieldElement>,
+    fee_retry_policy: FeeRetryPolicy,
+    read_retry_policy: ReadRetryPolicy,
+    deployment_addresses: DeploymentAddresses,
+    gas_price_oracle: GasPriceOracle,
+    /// Tracker of the relayed transactions, disabled in light mode.
+    transaction_tracker: Option<TransactionTracker>,
+    request_lanes: RequestLanes,
+    operating_mode: OperatingMode,
+    light_history_blocks: u64,
+    max_raw_transaction_size: usize,
+    liveness_config: LivenessConfig,
+    chain_id_policy: ChainIdPolicy,
+    relay_policy: RelayPolicy,
+    dev_mode: DevModeConfig,
+    /// Client of the dev methods of the devnet, only set in dev mode.
+    devnet: Option<DevnetClient>,
+    /// Accounts whose transactions `eth_sendTransaction` relays without signature in dev mode.
+    impersonated_accounts: RwLock<HashSet<Address>>,
+    /// Types of the accounts by implementation class hash.
+    account_types: RwLock<HashMap<FieldElement, AccountType>>,
+    /// Snapshots of the devnet state taken by `evm_snapshot`.
+    snapshots: Snapshots,
+    block_header_config: BlockHeaderConfig,
+    native_token: NativeTokenConfig,
+    budget_config: BudgetConfig,
+    /// Scores of the Starknet endpoints, when the provider spreads its requests over several.
+    upstream_scores: Option<Arc<UpstreamScores>>,
+    /// Counters of the coalesced Starknet reads, when the provider coalesces them.
+    coalescing_counters: Option<Arc<CoalescingCounters>>,
+    circuit_breaker: CircuitBreaker,
+    block_number_cache: BlockNumberCache,
+    /// Results of the successful `eth_call` by block, target and calldata.
+    call_cache: CallCache,
+    read_your_writes_window: Duration,
+    events_page_size: u64,
+    max_logs: usize,
+    block_conversion_concurrency: usize,
+    /// Endpoint the private transactions are submitted to, the provider if `None`.
+    private_relay_url: Option<Url>,
+    /// Timestamp of the genesis block, fetched once for the synthetic block timestamps.
+    genesis_timestamp: OnceCell<u64>,
+    /// Trie roots of the converted blocks by block hash.
+    block_roots: BlockRootsCache,
+    /// Reported timestamps of the blocks probed by `kakarot_getBlockByTimestamp`.
+    block_timestamps: BlockTimestampsCache,
+    contract_creations: ContractCreations,
+    /// Log of the relayed transactions and of the fees paid for them, if enabled.
+    audit_log: Option<AuditLog>,
+    sender_nonces: SenderNonceIndex,
+    /// Store of the indexes, the persistent store if any.
+    index_store: Arc<dyn KakarotStore>,
+    sender_nonce_scan_blocks: u64,
+    nonce_sequencer: NonceSequencer,
+    /// Gas returned by `eth_estimateGas` on the networks whose transactions are not simulated.
+    fixed_gas_estimate: u64,
+    rpc_gas_cap: u64,
+    execution_timeout: Duration,
+    /// Block the Starknet network was forked at, when the provider is a fork.
+    fork_block: Option<u64>,
+    /// Specification version of the JSON-RPC provider, probed once.
+    spec_version: OnceCell<SpecVersion>,
 }
 
-impl<P: Provider + Send + Sync> KakarotClient<P> {
+impl<P: Provider + Send + Sync + 'static> KakarotClient<P> {
+    /// Returns a builder of the client, the options not set keeping their defaults.
+    pub fn builder() -> KakarotClientBuilder<P> {
+        KakarotClientBuilder::default()
+    }
+
     /// Create a new `KakarotClient`.
+    #[deprecated(note = "use `KakarotClient::builder().with_starknet_config(..).with_provider(..).build()`")]
     pub fn new(starknet_config: StarknetConfig, starknet_provider: P) -> Self {
-        let StarknetConfig { kakarot_address, proxy_account_class_hash, network } = starknet_config;
+        Self::from_config(starknet_config, starknet_provider)
+    }
+
+    /// Creates the client from a complete configuration.
+    fn from_config(starknet_config: StarknetConfig, starknet_provider: P) -> Self {
+        let StarknetConfig {
+            kakarot_address,
+            proxy_account_class_hash,
+            network,
+            kakarot_class_hash,
+            starknet_chain_id,
+            fee_retry_policy,
+            read_retry_policy,
+            deployment_addresses,
+            gas_price_config,
+            lane_config,
+            operating_mode,
+            light_history_blocks,
+            max_raw_transaction_size,
+            liveness_config,
+            chain_id_policy,
+            relay_policy,
+            dev_mode,
+            block_header_config,
+            store,
+            native_token,
+            budget_config,
+            upstream_config: _,
+            upstream_scores,
+            coalescing_counters,
+            breaker_config,
+            block_number_ttl,
+            call_cache_capacity,
+            read_your_writes_window,
+            transaction_ttl,
+            events_page_size,
+            max_logs,
+            block_conversion_concurrency,
+            sender_nonce_scan_blocks,
+            nonce_sequencing_timeout,
+            private_relay_url,
+            fixed_gas_estimate,
+            rpc_gas_cap,
+            execution_timeout,
+            fork_block,
+            audit_log,
+        } = starknet_config;
 
         let kakarot_contract = KakarotContract::new(kakarot_address, proxy_account_class_hash);
+        let devnet = dev_mode.enabled.then(|| network.provider_url().ok().map(DevnetClient::new)).flatten();
+        // Indexes are kept in memory without a persistent store
+        let index_store: Arc<dyn KakarotStore> = store.clone().unwrap_or_else(|| Arc::new(InMemoryStore::default()));
+        let contract_creations = ContractCreations::new(index_store.clone());
+        let sender_nonces = SenderNonceIndex::new(index_store.clone());
+        let audit_log = audit_log.then(|| AuditLog::new(index_store.clone()));
+        // The dev methods rewrite the state of the devnet without producing a block
+        let call_cache = CallCache::new(if dev_mode.enabled { 0 } else { call_cache_capacity });
+
+        Self {
+            starknet_provider,
+            network,
+            kakarot_contract,
+            kakarot_class_hash,
+            starknet_chain_id,
+            fee_retry_policy,
+            read_retry_policy,
+            deployment_addresses,
+            gas_price_oracle: GasPriceOracle::new(gas_price_config),
+            transaction_tracker: (operating_mode != OperatingMode::Light).then(|| match store {
+                Some(store) => {
+                    TransactionTracker::with_store(DEFAULT_TRACKER_CAPACITY, store).with_ttl(transaction_ttl)
+                }
+                None => TransactionTracker::default().with_ttl(transaction_ttl),
+            }),
+            request_lanes: RequestLanes::new(&lane_config),
+            operating_mode,
+            light_history_blocks,
+            max_raw_transaction_size,
+            liveness_config,
+            chain_id_policy,
+            relay_policy,
+            dev_mode,
+            devnet,
+            impersonated_accounts: RwLock::default(),
+            account_types: RwLock::default(),
+            snapshots: Snapshots::default(),
+            block_header_config,
+            native_token,
+            budget_config,
+            upstream_scores,
+            coalescing_counters,
+            circuit_breaker: CircuitBreaker::new(breaker_config),
+            block_number_cache: BlockNumberCache::new(block_number_ttl),
+            call_cache,
+            read_your_writes_window,
+            events_page_size,
+            max_logs,
+            block_conversion_concurrency,
+            private_relay_url,
+            genesis_timestamp: OnceCell::new(),
+            block_roots: BlockRootsCache::default(),
+            block_timestamps: BlockTimestampsCache::default(),
+            contract_creations,
+            audit_log,
+            sender_nonces,
+            index_store,
+            sender_nonce_scan_blocks,
+            nonce_sequencer: NonceSequencer::new(nonce_sequencing_timeout),
+            fixed_gas_estimate,
+            rpc_gas_cap,
+            execution_timeout,
+            fork_block,
+            spec_version: OnceCell::new(),
+        }
+    }
+
+    /// Resolves the `latest` tag to the head of the fork when the provider is a fork, so that every
+    /// method reads the same block, whichever block the fork resolves the tag to.
+    async fn pin_block_id(&self, block_id: StarknetBlockId) -> Result<StarknetBlockId, EthApiError<P::Error>> {
+        match (block_id, self.fork_block) {
+            (StarknetBlockId::Tag(BlockTag::Latest), Some(_)) => {
+                Ok(StarknetBlockId::Number(self.block_number().await?.as_u64()))
+            }
+            (block_id, _) => Ok(block_id),
+        }
+    }
+
+    /// Converts an Ethereum block id to the Starknet block id read, pinned to the head of the fork
+    /// if any.
+    async fn starknet_block_id(&self, block_id: BlockId) -> Result<StarknetBlockId, EthApiError<P::Error>> {
+        self.pin_block_id(EthBlockId::new(block_id).try_into()?).await
+    }
+
+    /// Returns the block the result of a call against `block_id` is cached at, `None` if it is
+    /// not cached, e.g. for the pending block. `latest` calls are cached at the current head.
+    async fn call_block(&self, block_id: BlockId) -> Result<Option<CallBlock>, EthApiError<P::Error>> {
+        if !self.call_cache.is_enabled() {
+            return Ok(None);
+        }
+        Ok(match block_id {
+            BlockId::Hash(hash) => Some(CallBlock::Hash(hash.block_hash)),
+            BlockId::Number(BlockNumberOrTag::Number(number)) => Some(CallBlock::Number(number)),
+            BlockId::Number(BlockNumberOrTag::Earliest) => Some(CallBlock::Number(EARLIEST_BLOCK_NUMBER)),
+            BlockId::Number(BlockNumberOrTag::Pending) => None,
+            BlockId::Number(_) => Some(CallBlock::Latest(self.block_number().await?.as_u64())),
+        })
+    }
+
+    /// Returns the version of the Starknet JSON-RPC specification implemented by the provider,
+    /// probing it until a probe succeeds. Returns `None` for gateway networks or if the probe
+    /// failed, which is logged and retried on the next call.
+    pub async fn spec_version(&self) -> Option<SpecVersion> {
+        let url = self.network.provider_url().ok()?;
+        match self.spec_version.get_or_try_init(|| probe_spec_version(url)).await {
+            Ok(spec_version) => Some(*spec_version),
+            Err(err) => {
+                tracing::warn!("Starknet JSON-RPC spec version probe failed: {err}");
+                None
+            }
+        }
+    }
+
+    /// Records an included transaction in the sender and nonce index.
+    fn record_sender_nonce(&self, transaction: &EtherTransaction) {
+        if let Ok(nonce) = transaction.nonce.try_into() {
+            self.sender_nonces.record(transaction.from, nonce, transaction.hash);
+        }
+    }
+
+    /// Sends an idempotent Starknet read, retrying it on transient failures according to the read
+    /// retry policy.
+    async fn read<T, F, Fut>(&self, read: F) -> Result<T, ProviderError<P::Error>>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, ProviderError<P::Error>>>,
+    {
+        retry_read(&self.read_retry_policy, read).await
+    }
+
+    /// Runs a simulation, aborting it after the execution timeout so that a pathological call does
+    /// not hold a Starknet connection. Dropping the simulation cancels its Starknet requests.
+    async fn with_execution_timeout<T>(
+        &self,
+        simulation: impl Future<Output = Result<T, EthApiError<P::Error>>>,
+    ) -> Result<T, EthApiError<P::Error>> {
+        if self.execution_timeout.is_zero() {
+            return simulation.await;
+        }
+        tokio::time::timeout(self.execution_timeout, simulation)
+            .await
+            .map_err(|_| EthApiError::ExecutionTimeout(self.execution_timeout))?
+    }
+
+    /// Submits a Kakarot transaction to the Starknet provider, re-estimating its fee and
+    /// resubmitting it according to the fee retry policy when it is rejected for an insufficient
+    /// max fee.
+    async fn submit_starknet_transaction_with_fee_retry(
+        &self,
+        mut request: BroadcastedInvokeTransactionV1,
+        private: bool,
+    ) -> Result<H256, EthApiError<P::Error>> {
+        let mut retry = 0;
+        loop {
+            let submitted = match (&self.private_relay_url, private) {
+                (Some(private_relay_url), true) => add_invoke(private_relay_url.clone(), &request)
+                    .await
+                    .map(|hash| H256::from(hash.to_bytes_be()))
+                    .map_err(EthApiError::from),
+                _ => self.submit_starknet_transaction(request.clone()).await,
+            };
+            match submitted {
+                Err(err) if err.is_insufficient_fee() && retry < self.fee_retry_policy.max_retries => {
+                    retry += 1;
+                    tokio::time::sleep(self.fee_retry_policy.backoff(retry)).await;
+                    request.max_fee = self.estimate_max_fee(&request).await?;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Submits the Kakarot transaction of `evm_address` with the given nonce once the transactions
+    /// of the sender with lower nonces were submitted, taking a slot of the write lane for each
+    /// submission only. A transaction rejected for an invalid nonce is resubmitted after
+    /// resynchronizing the nonce of the sender with Starknet, unless its nonce was already used.
+    async fn submit_sequenced_starknet_transaction(
+        &self,
+        request: BroadcastedInvokeTransactionV1,
+        evm_address: Address,
+        nonce: u64,
+        private: bool,
+    ) -> Result<H256, EthApiError<P::Error>> {
+        let sequencer = &self.nonce_sequencer;
+        if sequencer.is_enabled() && !sequencer.is_tracked(evm_address) {
+            // Undeployed accounts have no nonce yet, their first transaction is submitted as is
+            if let Ok(next_nonce) = self.pending_starknet_nonce(request.sender_address).await {
+                sequencer.observe(evm_address, next_nonce);
+            }
+        }
+
+        let mut recoveries = 0;
+        loop {
+            let permit = sequencer.write_slot(&self.request_lanes, evm_address, nonce).await?;
+            let submitted = self.submit_starknet_transaction_with_fee_retry(request.clone(), private).await;
+            drop(permit);
+            match submitted {
+                Ok(starknet_transaction_hash) => {
+                    sequencer.submitted(evm_address, nonce);
+                    return Ok(starknet_transaction_hash);
+                }
+                Err(err) if sequencer.is_enabled() && err.is_invalid_nonce() && recoveries < MAX_NONCE_RECOVERIES => {
+                    recoveries += 1;
+                    let expected_nonce = self.pending_starknet_nonce(request.sender_address).await?;
+                    sequencer.observe(evm_address, expected_nonce);
+                    if nonce < expected_nonce {
+                        return Err(err);
+                    }
+                    tracing::debug!("Resubmitting the transaction of {evm_address:#x} with nonce {nonce}: {err}");
+                    tokio::time::sleep(self.fee_retry_policy.backoff(recoveries)).await;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Returns the nonce a Starknet account expects next, read from the pending state.
+    async fn pending_starknet_nonce(&self, starknet_address: FieldElement) -> Result<u64, EthApiError<P::Error>> {
+        let block_id = StarknetBlockId::Tag(BlockTag::Pending);
+        let nonce = self.read(|| self.starknet_provider.get_nonce(block_id, starknet_address)).await?;
+        Ok(u64::try_from(Felt252Wrapper::from(nonce))?)
+    }
+
+    /// Estimates the fee of a Kakarot transaction on the latest block and applies the fee
+    /// multiplier of the fee retry policy.
+    async fn estimate_max_fee(
+        &self,
+        request: &BroadcastedInvokeTransactionV1,
+    ) -> Result<FieldElement, EthApiError<P::Error>> {
+        let fee_estimate = self.estimate_invoke_fee(request).await?;
+        Ok(Felt252Wrapper::try_from(fee_estimate.max_fee)?.into())
+    }
+
+    /// Estimates the fee of a Kakarot transaction on the latest block.
+    async fn estimate_invoke_fee(
+        &self,
+        request: &BroadcastedInvokeTransactionV1,
+    ) -> Result<StarknetFeeEstimate, EthApiError<P::Error>> {
+        let transaction = BroadcastedTransaction::Invoke(BroadcastedInvokeTransaction::V1(request.clone()));
+        let transactions = [transaction];
+        let fee_estimates = self
+            .read(|| self.starknet_provider.estimate_fee(&transactions, &StarknetBlockId::Tag(BlockTag::Latest)))
+            .await?;
+        let fee_estimate = fee_estimates
+            .first()
+            .ok_or_else(|| EthApiError::Other(anyhow::anyhow!("Kakarot estimate_fee: empty fee estimation")))?;
+
+        let overall_fee = U256::from(fee_estimate.overall_fee);
+        let max_fee =
+            overall_fee.saturating_mul(U256::from(self.fee_retry_policy.fee_multiplier_percent)) / U256::from(100);
+        Ok(StarknetFeeEstimate {
+            gas_consumed: U256::from(fee_estimate.gas_consumed),
+            gas_price: U256::from(fee_estimate.gas_price),
+            overall_fee,
+            max_fee,
+            gas_price_fri: None,
+            overall_fee_fri: None,
+            max_fee_fri: None,
+        })
+    }
+
+    /// Returns true if the transaction is private and not included in a block yet.
+    fn is_withheld(&self, hash: &H256) -> bool {
+        self.transaction_tracker.as_ref().map_or(false, |transaction_tracker| transaction_tracker.is_withheld(hash))
+    }
+
+    /// Fails if the block is older than the history served in light mode.
+    async fn ensure_within_history(&self, starknet_block_id: &StarknetBlockId) -> Result<(), EthApiError<P::Error>> {
+        if self.operating_mode != OperatingMode::Light || matches!(starknet_block_id, StarknetBlockId::Tag(_)) {
+            return Ok(());
+        }
+
+        let block_number = self.map_block_id_to_block_number(starknet_block_id).await?;
+        let latest_block_number = self.read(|| self.starknet_provider.block_number()).await?;
+        if latest_block_number.saturating_sub(block_number) >= self.light_history_blocks {
+            return Err(EthApiError::HistoryUnavailable { block_number, history_blocks: self.light_history_blocks });
+        }
+        Ok(())
+    }
+
+    /// Computes the Starknet address of an EVM address at the given block. Returns `None` if the
+    /// block predates the deployment of Kakarot, as no EVM account can exist yet.
+    async fn historical_starknet_address(
+        &self,
+        ethereum_address: Address,
+        starknet_block_id: &StarknetBlockId,
+    ) -> Result<Option<FieldElement>, EthApiError<P::Error>> {
+        self.ensure_within_history(starknet_block_id).await?;
+        match self.compute_starknet_address(ethereum_address, starknet_block_id).await {
+            Ok(starknet_address) => Ok(Some(starknet_address)),
+            Err(_) if self.is_before_kakarot_deployment(starknet_block_id).await => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Sets the transaction index of the logs, the position of their transaction in its block, and
+    /// sorts them by block, transaction and log index. The blocks of the logs are fetched
+    /// concurrently, at most `LOGS_BLOCKS_BUFFER_SIZE` at once, so that logs spread over many
+    /// blocks do not cost a round trip per block.
+    async fn index_log_transactions(
+        &self,
+        logs: &mut [Log],
+        budget: &mut RequestBudget,
+    ) -> Result<(), EthApiError<P::Error>> {
+        let block_numbers: BTreeSet<u64> = logs
+            .iter()
+            .filter_map(|log| log.block_number)
+            .filter_map(|block_number| u64::try_from(block_number).ok())
+            .collect();
+        budget.charge_calls(block_numbers.len() as u64)?;
+
+        let transaction_indexes: HashMap<H256, U256> = stream::iter(block_numbers)
+            .map(|block_number| async move {
+                let block = self
+                    .read(|| self.starknet_provider.get_block_with_tx_hashes(StarknetBlockId::Number(block_number)))
+                    .await?;
+                let transactions = BlockWithTxHashes::new(block).transactions();
+                Ok::<_, EthApiError<P::Error>>(
+                    transactions
+                        .iter()
+                        .enumerate()
+                        .map(|(index, hash)| (H256::from_slice(&hash.to_bytes_be()), U256::from(index)))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .buffer_unordered(LOGS_BLOCKS_BUFFER_SIZE)
+            .try_collect::<Vec<_>>()
+            .await?
+            .into_iter()
+            .flatten()
+            .collect();
+
+        for log in logs.iter_mut() {
+            log.transaction_index = log.transaction_hash.and_then(|hash| transaction_indexes.get(&hash).copied());
+        }
+        logs.sort_by_key(|log| (log.block_number, log.transaction_index, log.log_index));
+        Ok(())
+    }
+
+    /// Returns the deployment state of the account of an EVM address at the given block.
+    async fn account_state(
+        &self,
+        ethereum_address: Address,
+        starknet_block_id: &StarknetBlockId,
+    ) -> Result<AccountState, EthApiError<P::Error>> {
+        let Some(starknet_address) = self.historical_starknet_address(ethereum_address, starknet_block_id).await?
+        else {
+            return Ok(AccountState::Undeployed);
+        };
+        match self.read(|| self.starknet_provider.get_class_hash_at(starknet_block_id, starknet_address)).await {
+            Ok(class_hash) => Ok(AccountState::Deployed { starknet_address, class_hash }),
+            Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => Ok(AccountState::Undeployed),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Returns the value read from the account of an EVM address, or the default value if the read
+    /// failed because the account is not deployed. The deployment state is only resolved on
+    /// failures, so that reads of deployed accounts cost no additional request.
+    async fn or_undeployed<T: Default>(
+        &self,
+        read: Result<T, EthApiError<P::Error>>,
+        ethereum_address: Address,
+        starknet_block_id: &StarknetBlockId,
+    ) -> Result<T, EthApiError<P::Error>> {
+        match read {
+            Ok(value) => Ok(value),
+            Err(err) => match self.account_state(ethereum_address, starknet_block_id).await? {
+                AccountState::Undeployed => Ok(T::default()),
+                AccountState::Deployed { .. } => Err(err),
+            },
+        }
+    }
+
+    /// Translates the changes of a Starknet contract in a state diff, `None` if the contract is not
+    /// a Kakarot account.
+    async fn account_state_diff(
+        &self,
+        starknet_address: FieldElement,
+        state_diff: &StateDiff,
+        slots: &[U256],
+        block_id: BlockId,
+        starknet_block_id: &StarknetBlockId,
+    ) -> Result<Option<AccountStateDiff>, EthApiError<P::Error>> {
+        let class_hash =
+            self.read(|| self.starknet_provider.get_class_hash_at(starknet_block_id, starknet_address)).await?;
+        if class_hash != self.kakarot_contract.proxy_account_class_hash {
+            return Ok(None);
+        }
+        let address = self.get_evm_address(&starknet_address, starknet_block_id).await?;
+
+        let entries = state_diff
+            .storage_diffs
+            .iter()
+            .find(|diff| diff.address == starknet_address)
+            .map_or(&[][..], |diff| diff.storage_entries.as_slice());
+        let writes = sort_storage_writes(entries, slots);
+        let nonce = state_diff
+            .nonces
+            .iter()
+            .find(|update| update.contract_address == starknet_address)
+            .map(|update| Felt252Wrapper::from(update.nonce).into());
+        let balance_changed = state_diff
+            .storage_diffs
+            .iter()
+            .filter(|diff| diff.address == self.native_token.address)
+            .flat_map(|diff| &diff.storage_entries)
+            .any(|entry| is_balance_write(entry, starknet_address));
+        let deployed = state_diff.deployed_contracts.iter().any(|item| item.address == starknet_address);
+
+        // The values after the block are read rather than decoded from the writes, a `Uint256`
+        // being possibly written one half at a time
+        let balance = if balance_changed { Some(self.balance(address, block_id).await?) } else { None };
+        let code = if writes.code || deployed { Some(self.get_code(address, block_id).await?) } else { None };
+        let mut storage = BTreeMap::new();
+        for slot in writes.slots {
+            let value = self.storage_at(address, slot, block_id).await?;
+            storage.insert(H256::from(slot.to_be_bytes::<32>()), H256::from(value.to_be_bytes::<32>()));
+        }
+
+        Ok(Some(AccountStateDiff { address, nonce, balance, code, storage, starknet_storage: writes.other }))
+    }
+
+    /// Returns the type of the account deployed at a Starknet address with the given class.
+    /// Accounts are proxies, their type is read from the class of their implementation and cached
+    /// by class hash.
+    async fn account_type_at(
+        &self,
+        starknet_address: FieldElement,
+        class_hash: FieldElement,
+        starknet_block_id: &StarknetBlockId,
+    ) -> Result<AccountType, EthApiError<P::Error>> {
+        let class_hash = if class_hash == self.kakarot_contract.proxy_account_class_hash {
+            let request = FunctionCall {
+                contract_address: starknet_address,
+                entry_point_selector: GET_IMPLEMENTATION,
+                calldata: vec![],
+            };
+            let implementation = self.read(|| self.starknet_provider.call(&request, starknet_block_id)).await?;
+            *implementation.first().ok_or_else(|| DataDecodingError::InvalidReturnArrayLength {
+                entrypoint: "get_implementation".into(),
+                expected: 1,
+                actual: 0,
+            })?
+        } else {
+            class_hash
+        };
+
+        if let Some(account_type) = self.account_types.read().expect("account types lock poisoned").get(&class_hash) {
+            return Ok(*account_type);
+        }
+
+        // Only contract accounts hold a bytecode
+        let request =
+            FunctionCall { contract_address: starknet_address, entry_point_selector: BYTECODE, calldata: vec![] };
+        let account_type = match self.read(|| self.starknet_provider.call(&request, starknet_block_id)).await {
+            Ok(_) => AccountType::Contract,
+            Err(ProviderError::StarknetError(StarknetError::ContractError)) => AccountType::Eoa,
+            Err(err) => return Err(err.into()),
+        };
+        self.account_types.write().expect("account types lock poisoned").insert(class_hash, account_type);
+
+        Ok(account_type)
+    }
+
+    /// Returns true if the Kakarot contract is not deployed yet at the given block.
+    async fn is_before_kakarot_deployment(&self, starknet_block_id: &StarknetBlockId) -> bool {
+        matches!(
+            self.read(|| self.starknet_provider.get_class_hash_at(starknet_block_id, self.kakarot_address())).await,
+            Err(ProviderError::StarknetError(StarknetError::ContractNotFound))
+        )
+    }
+
+    /// Decodes raw Ethereum transaction bytes and recovers their signer.
+    fn decode_raw_transaction(&self, bytes: &Bytes) -> Result<(TransactionSigned, Address), EthApiError<P::Error>> {
+        let transaction = decode_raw_transaction(bytes, self.max_raw_transaction_size)?;
+        self.chain_id_policy.check(transaction.chain_id())?;
+
+        let evm_address = transaction.recover_signer().ok_or_else(|| {
+            EthApiError::Other(anyhow::anyhow!("Kakarot send_transaction: signature ecrecover failed"))
+        })?;
+
+        Ok((transaction, evm_address))
+    }
+
+    /// Waits for the devnet to include a Starknet transaction, so that its receipt is available
+    /// when `eth_sendRawTransaction` returns in dev mode.
+    async fn wait_for_receipt(&self, starknet_transaction_hash: H256) -> Result<(), EthApiError<P::Error>> {
+        let transaction_hash: Felt252Wrapper = starknet_transaction_hash.try_into()?;
+        let transaction_hash: FieldElement = transaction_hash.into();
+        let started_at = Instant::now();
+
+        loop {
+            match self.starknet_provider.get_transaction_receipt(transaction_hash).await {
+                Ok(MaybePendingTransactionReceipt::Receipt(_)) => return Ok(()),
+                Ok(MaybePendingTransactionReceipt::PendingReceipt(_))
+                | Err(ProviderError::StarknetError(StarknetError::TransactionHashNotFound)) => {}
+                Err(err) => return Err(err.into()),
+            }
+
+            if started_at.elapsed() >= self.dev_mode.receipt_timeout {
+                return Err(DevnetError::ReceiptTimeout(
+                    format!("{starknet_transaction_hash:#x}"),
+                    self.dev_mode.receipt_timeout,
+                )
+                .into());
+            }
+            tokio::time::sleep(DEV_RECEIPT_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Records the status reported by Starknet for a relayed transaction and, once it is accepted,
+    /// the fee the relayer paid for it in the audit log.
+    fn update_relay_status(&self, starknet_hash: H256, status: StarknetTransactionStatus, actual_fee: FieldElement) {
+        let Some(transaction_tracker) = &self.transaction_tracker else { return };
+        if !transaction_tracker.update_from_starknet(starknet_hash, status) {
+            return;
+        }
+        let (Some(audit_log), Some(tracked)) = (&self.audit_log, transaction_tracker.get(&starknet_hash)) else {
+            return;
+        };
+        let is_accepted =
+            |status: &RelayStatus| matches!(status, RelayStatus::AcceptedOnL2 | RelayStatus::AcceptedOnL1);
+        // The fee is paid once, when the transaction is first accepted
+        if is_accepted(&tracked.status) && tracked.events.iter().filter(|event| is_accepted(&event.status)).count() == 1
+        {
+            let actual_fee = Felt252Wrapper::from(actual_fee).into();
+            audit_log.append(tracked.eth_hash, starknet_hash, AuditEvent::FeePaid { actual_fee });
+        }
+    }
+
+    /// Relays a decoded Ethereum transaction sent by `evm_address` to Kakarot, tracking its
+    /// lifecycle. Private transactions are submitted to the private relay, if any, and withheld
+    /// from the pending responses until included in a block.
+    async fn relay_transaction(
+        &self,
+        bytes: &Bytes,
+        transaction: &TransactionSigned,
+        evm_address: Address,
+        private: bool,
+    ) -> Result<H256, EthApiError<P::Error>> {
+        let eth_hash = transaction.hash();
+        if let Err(violation) = self.relay_policy.check(evm_address, transaction.to(), transaction.input()) {
+            tracing::warn!(target: "kakarot_rpc::relay_policy", "Rejected transaction {eth_hash:#x}: {violation}");
+            return Err(violation.into());
+        }
+
+        if self.liveness_config.reject_stale_writes {
+            let liveness = self.liveness().await?;
+            if liveness.stale {
+                return Err(EthApiError::StaleSequencer { block_age: liveness.block_age });
+            }
+        }
+
+        let transaction_tracker = self.transaction_tracker.as_ref();
+        let mut replaced = None;
+        if let Some(transaction_tracker) = transaction_tracker {
+            let max_fee_per_gas = transaction.max_fee_per_gas();
+            let fees = TransactionFees {
+                max_fee_per_gas,
+                max_priority_fee_per_gas: transaction.max_priority_fee_per_gas().unwrap_or(max_fee_per_gas),
+            };
+            replaced = transaction_tracker.received(eth_hash, evm_address, transaction.nonce(), fees)?;
+            if private {
+                transaction_tracker.set_private(eth_hash);
+            }
+        }
+        let track_rejection = |err: EthApiError<P::Error>| {
+            if let Some(transaction_tracker) = transaction_tracker {
+                transaction_tracker.rejected(eth_hash, err.to_string());
+            }
+            err
+        };
+
+        // Get estimated_fee from Starknet
+        let max_fee = *MAX_FEE;
 
-        Self { starknet_provider, network, kakarot_contract }
+        let request = self
+            .starknet_invoke_request(bytes, evm_address, transaction.nonce(), max_fee)
+            .await
+            .map_err(track_rejection)?;
+
+        let starknet_transaction_hash = self
+            .submit_sequenced_starknet_transaction(request, evm_address, transaction.nonce(), private)
+            .await
+            .map_err(track_rejection)?;
+        if let Some(audit_log) = &self.audit_log {
+            let event = AuditEvent::Relayed {
+                sender: evm_address,
+                nonce: transaction.nonce(),
+                to: transaction.to(),
+                value: U256::from(transaction.value()),
+                private,
+            };
+            audit_log.append(eth_hash, starknet_transaction_hash, event);
+        }
+        if let Some(transaction_tracker) = transaction_tracker {
+            transaction_tracker.sent(eth_hash, starknet_transaction_hash);
+            // Starknet has no way to drop a pending invoke, the replaced transaction is only
+            // superseded once the sequencer accepted its replacement
+            if let Some(replaced) = replaced {
+                transaction_tracker.replaced(replaced, eth_hash);
+            }
+        }
+
+        if self.dev_mode.enabled {
+            self.wait_for_receipt(starknet_transaction_hash).await?;
+        }
+        self.block_number_cache.invalidate().await;
+
+        Ok(starknet_transaction_hash)
+    }
+
+    /// Builds the Starknet invoke transaction relaying the raw Ethereum transaction bytes sent by
+    /// `evm_address` to Kakarot.
+    async fn starknet_invoke_request(
+        &self,
+        bytes: &Bytes,
+        evm_address: Address,
+        nonce: u64,
+        max_fee: FieldElement,
+    ) -> Result<BroadcastedInvokeTransactionV1, EthApiError<P::Error>> {
+        let starknet_block_id = StarknetBlockId::Tag(BlockTag::Latest);
+        let starknet_address = self.compute_starknet_address(evm_address, &starknet_block_id).await?;
+
+        let nonce = FieldElement::from(nonce);
+
+        let calldata = raw_kakarot_calldata(self.kakarot_address(), bytes_to_felt_vec(bytes));
+
+        let signature = vec![];
+
+        Ok(BroadcastedInvokeTransactionV1 { max_fee, signature, nonce, sender_address: starknet_address, calldata })
+    }
+
+    /// Simulates a transaction for `eth_estimateGas` and returns the gas it uses, through the
+    /// JSON-RPC provider if its specification version is given, else through the feeder gateway.
+    /// Unlike [`Self::simulate_transaction`], a failed JSON-RPC simulation is an error rather than
+    /// default values, the gas search relying on each simulation.
+    async fn simulate_gas_usage(
+        &self,
+        request: BroadcastedInvokeTransactionV1,
+        block_number: u64,
+        json_rpc_simulation: Option<(Url, SpecVersion)>,
+    ) -> Result<u64, EthApiError<P::Error>> {
+        let Some((provider_url, spec_version)) = json_rpc_simulation else {
+            return Ok(self.simulate_transaction(request, block_number, true).await?.fee_estimation.gas_usage);
+        };
+        // Reverts are reported as such, so that the search tells them from the other failures
+        simulate_gas_usage(provider_url, spec_version, &request, block_number)
+            .await
+            .map_err(|err| EthApiError::Other(anyhow::anyhow!("Starknet {spec_version} simulation failed: {err}")))
     }
 }
 
 #[async_trait]
-impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
+impl<P: Provider + Send + Sync + 'static> KakarotEthApi<P> for KakarotClient<P> {
     /// Returns the latest block number
     async fn block_number(&self) -> Result<U64, EthApiError<P::Error>> {
-        let block_number = self.starknet_provider.block_number().await?;
+        let block_number =
+            self.block_number_cache.get_or_fetch(|| self.read(|| self.starknet_provider.block_number())).await?;
+        // A fork may number its own blocks from zero, its head is never below the block it forked
+        let block_number = self.fork_block.map_or(block_number, |fork_block| block_number.max(fork_block));
         Ok(block_number.into())
     }
 
     /// Returns the bytecode of a contract given its address and a block id.
     async fn get_code(&self, ethereum_address: Address, block_id: BlockId) -> Result<Bytes, EthApiError<P::Error>> {
-        let starknet_block_id: StarknetBlockId = EthBlockId::new(block_id).try_into()?;
+        let starknet_block_id = self.starknet_block_id(block_id).await?;
 
-        // Convert the hex-encoded string to a FieldElement
-        let ethereum_address: Felt252Wrapper = ethereum_address.into();
-        let ethereum_address = ethereum_address.into();
+        let AccountState::Deployed { starknet_address: starknet_contract_address, class_hash } =
+            self.account_state(ethereum_address, &starknet_block_id).await?
+        else {
+            return Ok(Bytes::default());
+        };
 
-        let starknet_contract_address = self
-            .kakarot_contract
-            .compute_starknet_address(&self.starknet_provider, &ethereum_address, &starknet_block_id)
-            .await?;
+        // Wallets detect smart contract wallets from the code, Kakarot EOAs have none
+        if self.account_type_at(starknet_contract_address, class_hash, &starknet_block_id).await?
+            != AccountType::Contract
+        {
+            return Ok(Bytes::default());
+        }
 
         let contract_account = ContractAccount::new(starknet_contract_address);
         let bytecode = contract_account.bytecode(&self.starknet_provider, &starknet_block_id).await?;
@@ -94,24 +949,98 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
         Ok(bytecode)
     }
 
-    /// Returns the result of executing a call on a ethereum address for a given calldata and block
-    /// without creating a transaction.
-    async fn call(&self, to: Address, calldata: Bytes, block_id: BlockId) -> Result<Bytes, EthApiError<P::Error>> {
-        let starknet_block_id: StarknetBlockId = EthBlockId::new(block_id).try_into()?;
+    /// Returns the type of the account deployed at an Ethereum address at a given block.
+    async fn account_type(
+        &self,
+        ethereum_address: Address,
+        block_id: BlockId,
+    ) -> Result<AccountType, EthApiError<P::Error>> {
+        if Precompile::from_address(ethereum_address).is_some() {
+            return Ok(AccountType::Precompile);
+        }
+        let starknet_block_id = self.starknet_block_id(block_id).await?;
+
+        match self.account_state(ethereum_address, &starknet_block_id).await? {
+            AccountState::Deployed { starknet_address, class_hash } => {
+                self.account_type_at(starknet_address, class_hash, &starknet_block_id).await
+            }
+            AccountState::Undeployed => Ok(AccountType::Undeployed),
+        }
+    }
+
+    /// Returns the changes of the Kakarot accounts in a block, translated from the Starknet state
+    /// diff of the block. Among the storage writes, only the given slots are recognized. Accounts
+    /// whose balance is the only change, e.g. the recipients of transfers, are not reported: their
+    /// balance is stored under a hash of their Starknet address.
+    async fn state_diff(
+        &self,
+        block_id: BlockId,
+        slots: Vec<U256>,
+    ) -> Result<Vec<AccountStateDiff>, EthApiError<P::Error>> {
+        let starknet_block_id = self.starknet_block_id(block_id).await?;
+        let state_diff = match self.read(|| self.starknet_provider.get_state_update(&starknet_block_id)).await? {
+            MaybePendingStateUpdate::Update(update) => update.state_diff,
+            MaybePendingStateUpdate::PendingUpdate(update) => update.state_diff,
+        };
 
-        let to: Felt252Wrapper = to.into();
-        let to = to.into();
+        let touched: BTreeSet<FieldElement> = state_diff
+            .storage_diffs
+            .iter()
+            .map(|diff| diff.address)
+            .chain(state_diff.nonces.iter().map(|update| update.contract_address))
+            .chain(state_diff.deployed_contracts.iter().map(|item| item.address))
+            .filter(|address| *address != self.kakarot_contract.address && *address != self.native_token.address)
+            .collect();
 
-        let calldata = calldata.clone().into_iter().map(FieldElement::from).collect::<Vec<_>>();
+        let account_diffs = buffered_in_order(touched, self.block_conversion_concurrency, |starknet_address| {
+            self.account_state_diff(starknet_address, &state_diff, &slots, block_id, &starknet_block_id)
+        })
+        .try_collect::<Vec<_>>()
+        .await?;
+        Ok(account_diffs.into_iter().flatten().collect())
+    }
 
-        let result = self.kakarot_contract.eth_call(&self.starknet_provider, &to, calldata, &starknet_block_id).await?;
+    /// Returns the result of executing a call on a ethereum address for a given calldata and block
+    /// without creating a transaction.
+    async fn call(&self, to: Address, calldata: Bytes, block_id: BlockId) -> Result<Bytes, EthApiError<P::Error>> {
+        let _permit = self.request_lanes.read().await?;
+        let call_block = self.call_block(block_id).await?;
+        if let Some(result) = call_block.and_then(|block| self.call_cache.get(block, to, &calldata)) {
+            return Ok(result);
+        }
+        let starknet_block_id = self.starknet_block_id(block_id).await?;
+        self.ensure_within_history(&starknet_block_id).await?;
+
+        let to_address: Felt252Wrapper = to.into();
+        let to_address = to_address.into();
+
+        let starknet_calldata = calldata.clone().into_iter().map(FieldElement::from).collect::<Vec<_>>();
+
+        let result = self
+            .with_execution_timeout(async {
+                let result = self
+                    .kakarot_contract
+                    .eth_call(&self.starknet_provider, &to_address, starknet_calldata, &starknet_block_id)
+                    .await;
+                // Precompiles have no Starknet contract, their failures are never those of an undeployed
+                // account
+                if Precompile::from_address(to).is_some() {
+                    result
+                } else {
+                    self.or_undeployed(result, to, &starknet_block_id).await
+                }
+            })
+            .await;
 
-        Ok(result)
+        if let (Some(block), Ok(result)) = (call_block, &result) {
+            self.call_cache.insert(block, to, calldata, result.clone());
+        }
+        result
     }
 
     /// Get the syncing status of the light client
     async fn syncing(&self) -> Result<SyncStatus, EthApiError<P::Error>> {
-        let status = self.starknet_provider.syncing().await?;
+        let status = self.read(|| self.starknet_provider.syncing()).await?;
 
         match status {
             SyncStatusType::NotSyncing => Ok(SyncStatus::None),
@@ -150,8 +1079,8 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
 
     /// Returns the number of transactions in a block given a block id.
     async fn get_transaction_count_by_block(&self, block_id: BlockId) -> Result<U64, EthApiError<P::Error>> {
-        let starknet_block_id: StarknetBlockId = EthBlockId::new(block_id).try_into()?;
-        let starknet_block = self.starknet_provider.get_block_with_txs(starknet_block_id).await?;
+        let starknet_block_id = self.starknet_block_id(block_id).await?;
+        let starknet_block = self.read(|| self.starknet_provider.get_block_with_txs(starknet_block_id)).await?;
 
         let block_transactions = match starknet_block {
             MaybePendingBlockWithTxs::PendingBlock(pending_block_with_txs) => {
@@ -180,14 +1109,16 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
         tx_index: Index,
     ) -> Result<EtherTransaction, EthApiError<P::Error>> {
         let index: u64 = usize::from(tx_index) as u64;
-        let starknet_block_id: StarknetBlockId = EthBlockId::new(block_id).try_into()?;
+        let starknet_block_id = self.starknet_block_id(block_id).await?;
 
-        let starknet_tx: StarknetTransaction =
-            self.starknet_provider.get_transaction_by_block_id_and_index(starknet_block_id, index).await?.into();
+        let starknet_tx: StarknetTransaction = self
+            .read(|| self.starknet_provider.get_transaction_by_block_id_and_index(starknet_block_id, index))
+            .await?
+            .into();
 
         let tx_hash: FieldElement = starknet_tx.transaction_hash()?.into();
 
-        let tx_receipt = self.starknet_provider.get_transaction_receipt(tx_hash).await?;
+        let tx_receipt = self.read(|| self.starknet_provider.get_transaction_receipt(tx_hash)).await?;
         let (block_hash, block_num) = match tx_receipt {
             MaybePendingTransactionReceipt::Receipt(StarknetTransactionReceipt::Invoke(tr)) => {
                 let block_hash: Felt252Wrapper = tr.block_hash.into();
@@ -202,15 +1133,17 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
 
     /// Returns the transaction for a given transaction hash.
     async fn transaction_by_hash(&self, hash: H256) -> Result<Option<EtherTransaction>, EthApiError<P::Error>> {
+        let withheld = self.is_withheld(&hash);
         let hash: Felt252Wrapper = hash.try_into()?;
         let hash: FieldElement = hash.into();
 
-        let transaction: StarknetTransaction = match self.starknet_provider.get_transaction_by_hash(hash).await {
-            Err(_) => return Ok(None),
-            Ok(transaction) => transaction.into(),
-        };
+        let transaction: StarknetTransaction =
+            match self.read(|| self.starknet_provider.get_transaction_by_hash(hash)).await {
+                Err(_) => return Ok(None),
+                Ok(transaction) => transaction.into(),
+            };
 
-        let tx_receipt = match self.starknet_provider.get_transaction_receipt(hash).await {
+        let tx_receipt = match self.read(|| self.starknet_provider.get_transaction_receipt(hash)).await {
             Err(_) => return Ok(None),
             Ok(receipt) => receipt,
         };
@@ -222,7 +1155,14 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
             }
             _ => (None, None), // skip all transactions other than Invoke, covers the pending case
         };
+        // Private transactions are only surfaced once included in a block
+        if block_hash.is_none() && withheld {
+            return Ok(None);
+        }
         let eth_transaction = transaction.to_eth_transaction(self, block_hash, block_num, None).await?;
+        if block_hash.is_some() {
+            self.record_sender_nonce(&eth_transaction);
+        }
         Ok(Some(eth_transaction))
     }
 
@@ -230,8 +1170,9 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
     async fn transaction_receipt(&self, hash: H256) -> Result<Option<TransactionReceipt>, EthApiError<P::Error>> {
         // TODO: Error when trying to transform 32 bytes hash to FieldElement
         let transaction_hash: Felt252Wrapper = hash.try_into()?;
+        let transaction_hash: FieldElement = transaction_hash.into();
         let starknet_tx_receipt =
-            match self.starknet_provider.get_transaction_receipt::<FieldElement>(transaction_hash.into()).await {
+            match self.read(|| self.starknet_provider.get_transaction_receipt(transaction_hash)).await {
                 Err(_) => return Ok(None),
                 Ok(receipt) => receipt,
             };
@@ -244,10 +1185,16 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
                     block_hash,
                     block_number,
                     events,
+                    actual_fee,
                     ..
                 }) => {
+                    // Received but not included yet, receipt pollers such as Foundry keep waiting
+                    // instead of reporting a failure
+                    if status == StarknetTransactionStatus::Pending {
+                        return Ok(None);
+                    }
                     let starknet_tx: StarknetTransaction =
-                        self.starknet_provider.get_transaction_by_hash(transaction_hash).await?.into();
+                        self.read(|| self.starknet_provider.get_transaction_by_hash(transaction_hash)).await?.into();
 
                     let transaction_hash: Felt252Wrapper = transaction_hash.into();
                     let transaction_hash: Option<H256> = Some(transaction_hash.into());
@@ -259,6 +1206,13 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
                     let block_number: Option<U256> = Some(block_number.into());
 
                     let eth_tx = starknet_tx.to_eth_transaction(self, None, None, None).await?;
+                    let transaction_type = U8::from(eth_tx.transaction_type.unwrap_or_default().to::<u8>());
+                    // The Starknet fee is paid at the base fee of the block, as reported by
+                    // `eth_feeHistory`
+                    let effective_gas_price: u128 =
+                        self.base_fee_per_gas().try_into().map_err(ConversionError::<u128>::from)?;
+                    let gas_used =
+                        starknet_fee_to_gas(Felt252Wrapper::from(actual_fee).into(), U256::from(effective_gas_price));
                     let from = eth_tx.from;
                     let to = eth_tx.to;
                     let contract_address = match to {
@@ -284,6 +1238,9 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
                             Some(evm_address.try_into()?)
                         }
                     };
+                    if let (Some(contract), Some(hash)) = (contract_address, transaction_hash) {
+                        self.contract_creations.record(contract, ContractCreator { hash, creator: from });
+                    }
 
                     let status_code = match status {
                         StarknetTransactionStatus::Rejected | StarknetTransactionStatus::Pending => Some(U64::from(0)),
@@ -292,6 +1249,8 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
                         }
                     };
 
+                    self.update_relay_status(transaction_hash.unwrap_or_default(), status, actual_fee);
+
                     let logs = events
                         .into_iter()
                         .map(StarknetEvent::new)
@@ -309,15 +1268,17 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
                         block_number,
                         from,
                         to,
-                        cumulative_gas_used: U256::from(1_000_000), // TODO: Fetch real data
-                        gas_used: Some(U256::from(500_000)),
+                        // Reported as the first transaction of its block, the gas used by the
+                        // transactions before it in the block being unknown
+                        cumulative_gas_used: gas_used,
+                        gas_used: Some(gas_used),
                         contract_address,
                         logs,
                         state_root: None,             // TODO: Fetch real data
                         logs_bloom: Bloom::default(), // TODO: Fetch real data
                         status_code,
-                        effective_gas_price: U128::from(1_000_000), // TODO: Fetch real data
-                        transaction_type: U8::from(0),              // TODO: Fetch real data
+                        effective_gas_price: U128::from(effective_gas_price),
+                        transaction_type,
                     }
                 }
                 // L1Handler, Declare, Deploy and DeployAccount transactions unsupported for now in
@@ -332,48 +1293,85 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
         Ok(Some(res_receipt))
     }
 
-    /// Returns the nonce for a given ethereum address
-    /// if ethereum -> stark mapping doesn't exist in the starknet provider, we translate
-    /// ContractNotFound errors into zeros
+    /// Returns the receipts of the Kakarot transactions of a block, in the order of the block.
+    async fn block_receipts(&self, block_id: BlockId) -> Result<Vec<TransactionReceipt>, EthApiError<P::Error>> {
+        let starknet_block_id = self.starknet_block_id(block_id).await?;
+        let block = self.get_eth_block_from_starknet_block(starknet_block_id, false).await?;
+        let BlockTransactions::Hashes(hashes) = block.inner.transactions else { return Ok(Vec::new()) };
+
+        let receipts =
+            buffered_in_order(hashes, self.block_conversion_concurrency, |hash| self.transaction_receipt(hash))
+                .try_collect::<Vec<_>>()
+                .await?;
+        // The transactions of the pending block have no receipt yet
+        Ok(receipts.into_iter().flatten().collect())
+    }
+
+    /// Returns the nonce for a given ethereum address, zero if its account is not deployed.
     async fn nonce(&self, ethereum_address: Address, block_id: BlockId) -> Result<U256, EthApiError<P::Error>> {
-        let starknet_block_id: StarknetBlockId = EthBlockId::new(block_id).try_into()?;
-        let starknet_address = self.compute_starknet_address(ethereum_address, &starknet_block_id).await?;
+        // The pending nonce reflects the transactions relayed for the sender, even before the
+        // Starknet provider includes them in its pending state
+        let relayed_nonce = match (&block_id, &self.transaction_tracker) {
+            (BlockId::Number(BlockNumberOrTag::Pending), Some(transaction_tracker)) => {
+                transaction_tracker.expire();
+                transaction_tracker.next_pending_nonce(ethereum_address, self.read_your_writes_window).map(U256::from)
+            }
+            _ => None,
+        };
+        let starknet_block_id = self.starknet_block_id(block_id).await?;
 
-        self.starknet_provider
-            .get_nonce(starknet_block_id, starknet_address)
-            .await
-            .map(|nonce| {
-                let nonce: Felt252Wrapper = nonce.into();
-                nonce.into()
-            })
-            .or_else(|err| match err {
-                // Some RPCs return ContractError instead of ContractNotFound
-                ProviderError::StarknetError(StarknetError::ContractNotFound)
-                | ProviderError::StarknetError(StarknetError::ContractError) => Ok(U256::from(0)),
-                _ => Err(EthApiError::from(err)),
-            })
+        let Some(starknet_address) = self.historical_starknet_address(ethereum_address, &starknet_block_id).await?
+        else {
+            return Ok(relayed_nonce.unwrap_or_default());
+        };
+
+        // Some RPCs return ContractError instead of ContractNotFound for undeployed accounts
+        let nonce: Result<U256, _> =
+            match self.read(|| self.starknet_provider.get_nonce(&starknet_block_id, starknet_address)).await {
+                Ok(nonce) => Ok(Felt252Wrapper::from(nonce).into()),
+                Err(err) => Err(EthApiError::from(err)),
+            };
+        let nonce = self.or_undeployed(nonce, ethereum_address, &starknet_block_id).await?;
+        Ok(relayed_nonce.map_or(nonce, |relayed_nonce| nonce.max(relayed_nonce)))
     }
 
-    /// Returns the balance in Starknet's native token of a specific EVM address.
+    /// Returns the balance in Starknet's native token of a specific EVM address. Unlike the other
+    /// reads, the balance of an undeployed account is read as well, as tokens can be sent to its
+    /// Starknet address before the deployment.
     async fn balance(&self, ethereum_address: Address, block_id: BlockId) -> Result<U256, EthApiError<P::Error>> {
-        let starknet_block_id: StarknetBlockId = EthBlockId::new(block_id).try_into()?;
-        let starknet_address = self.compute_starknet_address(ethereum_address, &starknet_block_id).await?;
+        let starknet_block_id = self.starknet_block_id(block_id).await?;
+        let Some(starknet_address) = self.historical_starknet_address(ethereum_address, &starknet_block_id).await?
+        else {
+            return Ok(U256::from(0));
+        };
 
         let request = FunctionCall {
-            // This FieldElement::from_hex_be cannot fail as the value is a constant
-            contract_address: FieldElement::from_hex_be(STARKNET_NATIVE_TOKEN).unwrap(),
+            contract_address: self.native_token.address,
             entry_point_selector: BALANCE_OF,
             calldata: vec![starknet_address],
         };
 
-        let balance = self.starknet_provider.call(request, starknet_block_id).await?;
-
-        let balance: Felt252Wrapper = (*balance.first().ok_or_else(|| {
-            DataDecodingError::InvalidReturnArrayLength { entrypoint: "balance".into(), expected: 1, actual: 0 }
-        })?)
-        .into();
+        // Reads the balance at the requested block, so that historical queries do not report the
+        // latest balance
+        let balance = self.read(|| self.starknet_provider.call(&request, starknet_block_id)).await?;
+
+        // The balance is a Uint256, returned as its low and high 128-bit parts
+        let (low, high) = match balance.as_slice() {
+            [low, high, ..] => (*low, *high),
+            [low] => (*low, FieldElement::ZERO),
+            [] => {
+                return Err(DataDecodingError::InvalidReturnArrayLength {
+                    entrypoint: "balance".into(),
+                    expected: 2,
+                    actual: 0,
+                }
+                .into());
+            }
+        };
+        let low: U256 = Felt252Wrapper::from(low).into();
+        let high: U256 = Felt252Wrapper::from(high).into();
 
-        Ok(balance.into())
+        Ok(self.native_token.to_wei(low + (high << 128)))
     }
 
     /// Returns the storage value at a specific index of a contract given its address and a block
@@ -384,15 +1382,12 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
         index: U256,
         block_id: BlockId,
     ) -> Result<U256, EthApiError<P::Error>> {
-        let starknet_block_id: StarknetBlockId = EthBlockId::new(block_id).try_into()?;
+        let starknet_block_id = self.starknet_block_id(block_id).await?;
 
-        let address: Felt252Wrapper = address.into();
-        let address = address.into();
-
-        let starknet_contract_address = self
-            .kakarot_contract
-            .compute_starknet_address(&self.starknet_provider, &address, &starknet_block_id)
-            .await?;
+        let Some(starknet_contract_address) = self.historical_starknet_address(address, &starknet_block_id).await?
+        else {
+            return Ok(U256::from(0));
+        };
 
         let key_low = index & U256::from(u128::MAX);
         let key_low: Felt252Wrapper = key_low.try_into()?;
@@ -420,6 +1415,8 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
         let addr: Felt252Wrapper = address.into();
         let addr: FieldElement = addr.into();
 
+        RequestBudget::new(self.budget_config).charge_calls(contract_addresses.len() as u64)?;
+
         let handles = contract_addresses.into_iter().map(|token_address| {
             let calldata = vec![entrypoint, addr];
 
@@ -452,33 +1449,657 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
 
     /// Sends raw Ethereum transaction bytes to Kakarot
     async fn send_transaction(&self, bytes: Bytes) -> Result<H256, EthApiError<P::Error>> {
-        let mut data = bytes.as_ref();
+        let (transaction, evm_address) = self.decode_raw_transaction(&bytes)?;
 
-        let transaction = TransactionSigned::decode(&mut data).map_err(DataDecodingError::TransactionDecodingError)?;
+        self.relay_transaction(&bytes, &transaction, evm_address, false).await
+    }
 
-        let evm_address = transaction.recover_signer().ok_or_else(|| {
-            EthApiError::Other(anyhow::anyhow!("Kakarot send_transaction: signature ecrecover failed"))
-        })?;
+    /// Relays a raw Ethereum transaction which is withheld from the pending responses of the RPC
+    /// until included in a block. Private transactions are only known through the transaction
+    /// index, so they are rejected in light mode.
+    async fn send_private_transaction(&self, bytes: Bytes) -> Result<H256, EthApiError<P::Error>> {
+        if self.transaction_tracker.is_none() {
+            return Err(EthApiError::Other(anyhow::anyhow!(
+                "eth_sendPrivateRawTransaction needs the transaction index, which is disabled in light mode"
+            )));
+        }
+        let (transaction, evm_address) = self.decode_raw_transaction(&bytes)?;
 
-        let starknet_block_id = StarknetBlockId::Tag(BlockTag::Latest);
+        self.relay_transaction(&bytes, &transaction, evm_address, true).await
+    }
 
-        let starknet_address = self.compute_starknet_address(evm_address, &starknet_block_id).await?;
+    /// Returns the estimated Starknet fee the relayer would pay to send the raw Ethereum
+    /// transaction bytes to Kakarot, in WEI and, when the provider reports the gas price in both
+    /// units, in FRI.
+    async fn estimate_starknet_fee(&self, bytes: Bytes) -> Result<StarknetFeeEstimate, EthApiError<P::Error>> {
+        let (transaction, evm_address) = self.decode_raw_transaction(&bytes)?;
+        let request =
+            self.starknet_invoke_request(&bytes, evm_address, transaction.nonce(), FieldElement::ZERO).await?;
+        let fee_estimate = self.estimate_invoke_fee(&request).await?;
 
-        let nonce = FieldElement::from(transaction.nonce());
+        let (Ok(provider_url), Some(spec_version)) = (self.network.provider_url(), self.spec_version().await) else {
+            return Ok(fee_estimate);
+        };
+        if spec_version < SpecVersion::V0_6 {
+            return Ok(fee_estimate);
+        }
+        match latest_l1_gas_price(provider_url).await {
+            Ok(l1_gas_price) => Ok(fee_estimate
+                .with_fri_rate(U256::from(l1_gas_price.price_in_wei), U256::from(l1_gas_price.price_in_fri))),
+            Err(err) => {
+                tracing::warn!("Failed to read the gas price in FRI, estimating the fee in WEI only: {err}");
+                Ok(fee_estimate)
+            }
+        }
+    }
 
-        let calldata = raw_kakarot_calldata(self.kakarot_address(), bytes_to_felt_vec(&bytes));
+    /// Simulates an ordered bundle of raw Ethereum transactions on top of a block, each
+    /// transaction seeing the state changes of the previous ones. Requires a Starknet JSON-RPC
+    /// provider supporting simulations, the feeder gateway only simulating one transaction.
+    async fn call_bundle(
+        &self,
+        transactions: Vec<Bytes>,
+        block_id: BlockId,
+    ) -> Result<CallBundle, EthApiError<P::Error>> {
+        let (Ok(provider_url), Some(spec_version)) = (self.network.provider_url(), self.spec_version().await) else {
+            return Err(EthApiError::Other(anyhow::anyhow!(
+                "kakarot_callBundle requires a Starknet JSON-RPC provider supporting simulations"
+            )));
+        };
+        let _permit = self.request_lanes.read().await?;
 
-        // Get estimated_fee from Starknet
-        let max_fee = *MAX_FEE;
+        let starknet_block_id = self.starknet_block_id(block_id).await?;
+        self.ensure_within_history(&starknet_block_id).await?;
+        let block_number = self.map_block_id_to_block_number(&starknet_block_id).await?;
 
-        let signature = vec![];
+        let mut requests = Vec::with_capacity(transactions.len());
+        let mut decoded = Vec::with_capacity(transactions.len());
+        for bytes in &transactions {
+            let (transaction, evm_address) = self.decode_raw_transaction(bytes)?;
+            // The nonces are the ones signed, so that a sender can chain transactions in a bundle
+            requests
+                .push(self.starknet_invoke_request(bytes, evm_address, transaction.nonce(), FieldElement::ZERO).await?);
+            decoded.push((transaction, evm_address));
+        }
 
-        let request =
-            BroadcastedInvokeTransactionV1 { max_fee, signature, nonce, sender_address: starknet_address, calldata };
+        let simulated =
+            simulate_invoke_sequence(provider_url, spec_version, &requests, block_number, true).await.map_err(
+                |err| EthApiError::Other(anyhow::anyhow!("Starknet {spec_version} bundle simulation failed: {err}")),
+            )?;
 
-        let starknet_transaction_hash = self.submit_starknet_transaction(request).await?;
+        let results: Vec<_> = decoded
+            .into_iter()
+            .zip(simulated)
+            .map(|((transaction, evm_address), simulated)| {
+                // `__execute__` of the EOA returns the data returned by Kakarot
+                let return_data =
+                    decode_eth_call_return::<P::Error>(&simulated.result).map(vec_felt_to_bytes).unwrap_or_default();
+                BundleTransactionResult {
+                    tx_hash: transaction.hash(),
+                    from_address: evm_address,
+                    to_address: transaction.to(),
+                    gas_used: U256::from(simulated.fee.gas_consumed),
+                    return_data,
+                    error: simulated.revert_reason,
+                }
+            })
+            .collect();
+        let total_gas_used = results.iter().map(|result| result.gas_used).fold(U256::ZERO, |total, gas| total + gas);
 
-        Ok(starknet_transaction_hash)
+        Ok(CallBundle { state_block_number: block_number.into(), total_gas_used, results })
+    }
+
+    /// Returns the last block whose reported timestamp is at or before `timestamp`, or `None` if
+    /// the genesis block is after it. The timestamps of the blocks probed by the binary search are
+    /// cached.
+    async fn block_by_timestamp(
+        &self,
+        timestamp: u64,
+        hydrated: bool,
+    ) -> Result<Option<RichBlock>, EthApiError<P::Error>> {
+        let head = self.block_number().await?.as_u64();
+        let block_number = search_block_by_timestamp(timestamp, head, |block_number| async move {
+            if let Some(timestamp) = self.block_timestamps.get(block_number) {
+                return Ok(timestamp);
+            }
+            let block = self
+                .read(|| self.starknet_provider.get_block_with_tx_hashes(StarknetBlockId::Number(block_number)))
+                .await?;
+            let timestamp = self.block_timestamp(BlockWithTxHashes::new(block).timestamp(), Some(block_number)).await;
+            let timestamp: u64 = timestamp.try_into().map_err(ConversionError::<u64>::from)?;
+            self.block_timestamps.insert(block_number, timestamp);
+            Ok::<_, EthApiError<P::Error>>(timestamp)
+        })
+        .await?;
+
+        let Some(block_number) = block_number else { return Ok(None) };
+        let block = self.get_eth_block_from_starknet_block(StarknetBlockId::Number(block_number), hydrated).await?;
+        Ok(Some(block))
+    }
+
+    /// Returns the hash of the included transaction of a sender with the given nonce. A
+    /// transaction missing from the index is looked for in the recent blocks, indexing their
+    /// transactions.
+    async fn transaction_by_sender_and_nonce(
+        &self,
+        sender: Address,
+        nonce: U256,
+    ) -> Result<Option<H256>, EthApiError<P::Error>> {
+        let nonce: u64 = nonce.try_into().map_err(ConversionError::<u64>::from)?;
+        if let Some(hash) = self.sender_nonces.get(sender, nonce) {
+            return Ok(Some(hash));
+        }
+        // The transaction is not included yet if the sender did not use the nonce
+        let latest = BlockId::Number(BlockNumberOrTag::Latest);
+        if self.nonce(sender, latest).await? <= U256::from(nonce) {
+            return Ok(None);
+        }
+
+        let mut budget = RequestBudget::new(self.budget_config);
+        let head = self.block_number().await?.as_u64();
+        let oldest = head.saturating_sub(self.sender_nonce_scan_blocks.saturating_sub(1));
+        for block_number in (oldest..=head).rev() {
+            budget.charge_calls(1)?;
+            self.get_eth_block_from_starknet_block(StarknetBlockId::Number(block_number), true).await?;
+            if let Some(hash) = self.sender_nonces.get(sender, nonce) {
+                return Ok(Some(hash));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the transaction which deployed a contract and its sender, if indexed.
+    fn contract_creator(&self, address: Address) -> Option<ContractCreator> {
+        self.contract_creations.get(address)
+    }
+
+    /// Indexes the contracts deployed in the blocks of the range, inclusive, from the deployment
+    /// events of Kakarot. Returns the number of contracts indexed.
+    async fn index_contract_creations(&self, from_block: u64, to_block: u64) -> Result<u64, EthApiError<P::Error>> {
+        let event_filter = EventFilter {
+            from_block: Some(StarknetBlockId::Number(from_block)),
+            to_block: Some(StarknetBlockId::Number(to_block)),
+            address: Some(self.kakarot_address()),
+            keys: None,
+        };
+
+        let mut indexed = 0;
+        let mut continuation_token = None;
+        loop {
+            let page = self
+                .read(|| {
+                    self.starknet_provider.get_events(
+                        event_filter.clone(),
+                        continuation_token.clone(),
+                        self.events_page_size,
+                    )
+                })
+                .await?;
+
+            for event in page.events.iter().filter(|event| event.keys.contains(&EVM_CONTRACT_DEPLOYED)) {
+                let Some(evm_address) = event.data.first() else { continue };
+                let contract: Address = Felt252Wrapper::from(*evm_address).try_into()?;
+                // Contracts deployed by contracts are attributed to the sender of the transaction
+                let transaction: StarknetTransaction =
+                    self.read(|| self.starknet_provider.get_transaction_by_hash(event.transaction_hash)).await?.into();
+                let creator = transaction.to_eth_transaction(self, None, None, None).await?.from;
+                let hash: Felt252Wrapper = event.transaction_hash.into();
+                self.contract_creations.record(contract, ContractCreator { hash: hash.into(), creator });
+                indexed += 1;
+            }
+
+            match page.continuation_token {
+                Some(token) if continuation_token.as_ref() == Some(&token) => {
+                    return Err(EthApiError::StalledEventsPagination(token));
+                }
+                None => break,
+                token => continuation_token = token,
+            }
+        }
+
+        Ok(indexed)
+    }
+
+    /// Returns the lifecycle of a relayed transaction given its Ethereum or Starknet hash,
+    /// refreshing its status from the Starknet provider if it is not final yet. A transaction not
+    /// accepted on Starknet within the transaction TTL is reported as dropped.
+    async fn transaction_status(&self, hash: H256) -> Result<Option<TrackedTransaction>, EthApiError<P::Error>> {
+        let Some(transaction_tracker) = &self.transaction_tracker else {
+            return Ok(None);
+        };
+        let tracked = match transaction_tracker.get(&hash) {
+            Some(tracked) => tracked,
+            None => return Ok(None),
+        };
+
+        if let (Some(starknet_hash), false) = (tracked.starknet_hash, tracked.status.is_final()) {
+            let transaction_hash: Felt252Wrapper = starknet_hash.try_into()?;
+            let transaction_hash: FieldElement = transaction_hash.into();
+            let receipt = self.read(|| self.starknet_provider.get_transaction_receipt(transaction_hash)).await;
+            if let Ok(MaybePendingTransactionReceipt::Receipt(StarknetTransactionReceipt::Invoke(receipt))) = receipt {
+                self.update_relay_status(starknet_hash, receipt.status, receipt.actual_fee);
+            }
+        }
+        transaction_tracker.expire();
+
+        Ok(transaction_tracker.get(&hash))
+    }
+
+    /// Returns the messages between L1 and Starknet of a transaction: the L2 → L1 messages it
+    /// sent, or the L1 → L2 message consumed by an L1 handler transaction. Messages sent by the
+    /// bridge precompiles are sent by the Kakarot contract.
+    async fn l1_messages(&self, hash: H256) -> Result<Option<Vec<L1Message>>, EthApiError<P::Error>> {
+        let transaction_hash: Felt252Wrapper = hash.try_into()?;
+        let transaction_hash: FieldElement = transaction_hash.into();
+        let receipt = match self.read(|| self.starknet_provider.get_transaction_receipt(transaction_hash)).await {
+            Ok(receipt) => receipt,
+            Err(ProviderError::StarknetError(StarknetError::TransactionHashNotFound)) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        let messages = match receipt {
+            MaybePendingTransactionReceipt::Receipt(StarknetTransactionReceipt::Invoke(receipt)) => {
+                L1Message::l2_to_l1(
+                    self.kakarot_address(),
+                    &receipt.messages_sent,
+                    MessageStatus::of_l2_to_l1(receipt.status),
+                )
+            }
+            MaybePendingTransactionReceipt::PendingReceipt(PendingTransactionReceipt::Invoke(receipt)) => {
+                L1Message::l2_to_l1(self.kakarot_address(), &receipt.messages_sent, MessageStatus::Pending)
+            }
+            MaybePendingTransactionReceipt::Receipt(StarknetTransactionReceipt::L1Handler(receipt)) => {
+                let TransactionType::L1Handler(transaction) =
+                    self.read(|| self.starknet_provider.get_transaction_by_hash(transaction_hash)).await?
+                else {
+                    return Ok(Some(vec![]));
+                };
+                // The first calldata element of an L1 handler is the L1 sender of the message
+                let Some((from_address, payload)) = transaction.calldata.split_first() else {
+                    return Ok(Some(vec![]));
+                };
+                vec![L1Message {
+                    direction: MessageDirection::L1ToL2,
+                    hash: l1_to_l2_message_hash(
+                        *from_address,
+                        transaction.contract_address,
+                        transaction.nonce,
+                        transaction.entry_point_selector,
+                        payload,
+                    ),
+                    from_address: *from_address,
+                    to_address: transaction.contract_address,
+                    payload: payload.to_vec(),
+                    nonce: Some(transaction.nonce),
+                    status: MessageStatus::of_l1_to_l2(receipt.status),
+                }]
+            }
+            _ => vec![],
+        };
+
+        Ok(Some(messages))
+    }
+
+    /// Returns the logs emitted by Kakarot contracts matching the filter.
+    async fn get_logs(&self, filter: LogFilter) -> Result<Vec<Log>, EthApiError<P::Error>> {
+        let (from_block, to_block) = match filter.block_hash {
+            Some(block_hash) => {
+                let block_id: StarknetBlockId = EthBlockId::new(BlockId::Hash(block_hash.into())).try_into()?;
+                (block_id, block_id)
+            }
+            None => {
+                let from_block = filter.from_block.unwrap_or(BlockNumberOrTag::Latest);
+                let to_block = filter.to_block.unwrap_or(BlockNumberOrTag::Latest);
+                (
+                    self.starknet_block_id(BlockId::Number(from_block)).await?,
+                    self.starknet_block_id(BlockId::Number(to_block)).await?,
+                )
+            }
+        };
+
+        self.ensure_within_history(&from_block).await?;
+
+        let _permit = self.request_lanes.read().await?;
+
+        let event_filter = EventFilter {
+            from_block: Some(from_block),
+            to_block: Some(to_block),
+            address: Some(self.kakarot_address()),
+            keys: None,
+        };
+
+        let mut budget = RequestBudget::new(self.budget_config);
+        let mut logs = vec![];
+        let mut continuation_token = None;
+        let mut first_block: Option<u64> = None;
+        let mut current_block: Option<(u64, u64)> = None;
+        // Points the queries exceeding the budget to the blocks whose events were all fetched, so
+        // that indexers resume from the next block instead of bisecting the range
+        let narrow = |reason, first_block: Option<u64>, current_block: Option<(u64, u64)>| -> EthApiError<P::Error> {
+            match (first_block, current_block) {
+                (Some(from_block), Some((block_number, _))) if block_number > from_block => {
+                    EthApiError::LogsBudgetExceeded { reason, from_block, to_block: block_number - 1 }
+                }
+                _ => EthApiError::BudgetExceeded(reason),
+            }
+        };
+        loop {
+            budget.charge_calls(1).map_err(|reason| narrow(reason, first_block, current_block))?;
+            let page = self
+                .read(|| {
+                    self.starknet_provider.get_events(
+                        event_filter.clone(),
+                        continuation_token.clone(),
+                        self.events_page_size,
+                    )
+                })
+                .await?;
+            budget.charge_response(&page.events).map_err(|reason| narrow(reason, first_block, current_block))?;
+
+            for emitted_event in page.events {
+                first_block.get_or_insert(emitted_event.block_number);
+                // Log indexes are relative to the block
+                let log_index = match current_block {
+                    Some((block_number, log_index)) if block_number == emitted_event.block_number => log_index + 1,
+                    _ => 0,
+                };
+                current_block = Some((emitted_event.block_number, log_index));
+
+                let block_hash = H256::from_slice(&emitted_event.block_hash.to_bytes_be());
+                let transaction_hash = H256::from_slice(&emitted_event.transaction_hash.to_bytes_be());
+                let event = StarknetEvent::new(Event {
+                    from_address: emitted_event.from_address,
+                    keys: emitted_event.keys,
+                    data: emitted_event.data,
+                });
+
+                let Ok(log) = event.to_eth_log(
+                    self,
+                    Some(block_hash),
+                    Some(U256::from(emitted_event.block_number)),
+                    Some(transaction_hash),
+                    Some(U256::from(log_index)),
+                    None,
+                ) else {
+                    continue;
+                };
+                if !filter.matches(&log) {
+                    continue;
+                }
+                // Fails rather than truncating the logs, pointing to the blocks fully returned
+                if logs.len() == self.max_logs {
+                    let from_block = logs.first().and_then(|log: &Log| log.block_number).unwrap_or_default();
+                    let from_block: u64 = from_block.try_into().map_err(ConversionError::<u64>::from)?;
+                    if emitted_event.block_number <= from_block {
+                        return Err(EthApiError::TooManyLogsInBlock { limit: self.max_logs, block_number: from_block });
+                    }
+                    let to_block = emitted_event.block_number - 1;
+                    return Err(EthApiError::TooManyLogs { limit: self.max_logs, from_block, to_block });
+                }
+                logs.push(log);
+            }
+
+            match page.continuation_token {
+                Some(token) if continuation_token.as_ref() == Some(&token) => {
+                    return Err(EthApiError::StalledEventsPagination(token));
+                }
+                None => break,
+                token => continuation_token = token,
+            }
+        }
+
+        self.index_log_transactions(&mut logs, &mut budget).await?;
+        Ok(logs)
+    }
+
+    /// Returns the counters of the transactions relayed by the RPC.
+    fn relay_stats(&self) -> RelayStats {
+        let Some(transaction_tracker) = &self.transaction_tracker else {
+            return RelayStats::default();
+        };
+        transaction_tracker.expire();
+        transaction_tracker.stats()
+    }
+
+    /// Returns up to `limit` entries of the audit log starting at the sequence number `from`,
+    /// `None` if the audit log is disabled.
+    fn audit_log(&self, from: u64, limit: usize) -> Result<Option<Vec<AuditEntry>>, EthApiError<P::Error>> {
+        let Some(audit_log) = &self.audit_log else { return Ok(None) };
+        let entries = audit_log
+            .entries(from, limit)
+            .map_err(|err| EthApiError::Other(anyhow::anyhow!("Failed to read the audit log: {err}")))?;
+        Ok(Some(entries))
+    }
+
+    /// Returns the age of the latest Starknet block and whether the sequencer is stale.
+    async fn liveness(&self) -> Result<LivenessStatus, EthApiError<P::Error>> {
+        let block = self
+            .read(|| self.starknet_provider.get_block_with_tx_hashes(StarknetBlockId::Tag(BlockTag::Latest)))
+            .await?;
+        let MaybePendingBlockWithTxHashes::Block(block) = block else {
+            return Err(ProviderError::StarknetError(StarknetError::BlockNotFound).into());
+        };
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|now| now.as_secs()).unwrap_or_default();
+        Ok(LivenessStatus::new(block.block_number, block.timestamp, now, self.liveness_config.max_block_age))
+    }
+
+    /// Returns the features served by the RPC, derived from the components it runs rather than
+    /// from its operating mode alone.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            mode: self.operating_mode,
+            history_blocks: (self.operating_mode == OperatingMode::Light).then_some(self.light_history_blocks),
+            transaction_index: self.transaction_tracker.is_some(),
+        }
+    }
+
+    /// Returns the chain configuration the RPC operates under. The Starknet chain id is fetched
+    /// from the provider when not configured.
+    async fn chain_config(&self) -> ChainConfig {
+        let starknet_chain_id = match self.starknet_chain_id {
+            Some(starknet_chain_id) => Some(starknet_chain_id),
+            None => self.read(|| self.starknet_provider.chain_id()).await.ok(),
+        };
+        ChainConfig {
+            chain_id: U64::from(CHAIN_ID),
+            starknet_chain_id,
+            kakarot_address: self.kakarot_contract.address,
+            proxy_account_class_hash: self.kakarot_contract.proxy_account_class_hash,
+            kakarot_class_hash: self.kakarot_class_hash,
+            mode: self.operating_mode,
+            fee_token: FeeToken { address: self.native_token.address, decimals: self.native_token.decimals },
+            gas: GasConstants {
+                base_fee_per_gas: U64::from(BASE_FEE_PER_GAS),
+                max_priority_fee_per_gas: MAX_PRIORITY_FEE_PER_GAS,
+                minimum_gas: U64::from(MINIMUM_GAS_FEE),
+                effective_gas_price: *EFFECTIVE_GAS_PRICE,
+            },
+            client_version: KAKAROT_CLIENT_VERSION.clone(),
+            fork_block: self.fork_block.map(U64::from),
+            namespaces: Vec::new(),
+        }
+    }
+
+    /// Returns the client of the dev methods of the devnet.
+    fn devnet(&self) -> Result<&DevnetClient, EthApiError<P::Error>> {
+        self.devnet.as_ref().ok_or(DevnetError::Unavailable.into())
+    }
+
+    /// Sets the ETH balance of an account, writing the storage of the native token.
+    async fn set_balance(&self, address: Address, balance: U256) -> Result<(), EthApiError<P::Error>> {
+        let devnet = self.devnet()?;
+        let starknet_address = self.compute_starknet_address(address, &StarknetBlockId::Tag(BlockTag::Latest)).await?;
+        let writes = uint256_storage_writes("ERC20_balances", &[starknet_address], self.native_token.from_wei(balance));
+        devnet.set_storage_at(self.native_token.address, &writes).await?;
+        Ok(())
+    }
+
+    /// Replaces the bytecode of a deployed contract account.
+    async fn set_code(&self, address: Address, code: Bytes) -> Result<(), EthApiError<P::Error>> {
+        let devnet = self.devnet()?;
+        let starknet_address = self.compute_starknet_address(address, &StarknetBlockId::Tag(BlockTag::Latest)).await?;
+        devnet.set_storage_at(starknet_address, &bytecode_storage_writes(&code)).await?;
+        Ok(())
+    }
+
+    /// Sets a storage slot of a deployed contract account.
+    async fn set_storage_at(&self, address: Address, index: U256, value: U256) -> Result<(), EthApiError<P::Error>> {
+        let devnet = self.devnet()?;
+        let starknet_address = self.compute_starknet_address(address, &StarknetBlockId::Tag(BlockTag::Latest)).await?;
+        let (key_low, key_high) = split_u256(index);
+        let writes = uint256_storage_writes("storage_", &[key_low, key_high], value);
+        devnet.set_storage_at(starknet_address, &writes).await?;
+        Ok(())
+    }
+
+    /// Starts or stops impersonating an account.
+    fn impersonate_account(&self, address: Address, impersonate: bool) -> Result<(), EthApiError<P::Error>> {
+        self.devnet()?;
+        let mut impersonated_accounts =
+            self.impersonated_accounts.write().expect("impersonated accounts lock poisoned");
+        if impersonate {
+            impersonated_accounts.insert(address);
+        } else {
+            impersonated_accounts.remove(&address);
+        }
+        Ok(())
+    }
+
+    /// Returns the impersonated accounts, sorted.
+    fn impersonated_accounts(&self) -> Vec<Address> {
+        let mut accounts: Vec<Address> =
+            self.impersonated_accounts.read().expect("impersonated accounts lock poisoned").iter().copied().collect();
+        accounts.sort();
+        accounts
+    }
+
+    /// Relays an unsigned transaction sent by an impersonated account. The devnet has to skip the
+    /// validation of the transactions, e.g. Katana with `--disable-validate`, as it checks their
+    /// signatures.
+    async fn send_impersonated_transaction(&self, request: CallRequest) -> Result<H256, EthApiError<P::Error>> {
+        self.devnet()?;
+        let from =
+            request.from.ok_or_else(|| EthApiError::MissingParameterError("from for send_transaction".into()))?;
+        if !self.impersonated_accounts.read().expect("impersonated accounts lock poisoned").contains(&from) {
+            return Err(DevnetError::UnknownAccount(format!("{from:?}")).into());
+        }
+        let _permit = self.request_lanes.write().await?;
+
+        let nonce = match request.nonce {
+            Some(nonce) => nonce,
+            None => self.nonce(from, BlockId::Number(BlockNumberOrTag::Latest)).await?,
+        };
+        let nonce = nonce.try_into().map_err(ConversionError::<u64>::from)?;
+        let gas_limit = request.gas.unwrap_or(U256::ZERO).try_into().map_err(ConversionError::<u64>::from)?;
+        let max_fee_per_gas = request
+            .max_fee_per_gas
+            .unwrap_or_else(|| U256::from(BASE_FEE_PER_GAS))
+            .try_into()
+            .map_err(ConversionError::<u128>::from)?;
+        let max_priority_fee_per_gas = request
+            .max_priority_fee_per_gas
+            .unwrap_or_else(|| U256::from(MAX_PRIORITY_FEE_PER_GAS))
+            .try_into()
+            .map_err(ConversionError::<u128>::from)?;
+        let value = request.value.unwrap_or(U256::ZERO).try_into().map_err(ConversionError::<u128>::from)?;
+
+        let transaction = Transaction::Eip1559(TxEip1559 {
+            chain_id: CHAIN_ID,
+            nonce,
+            gas_limit,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            to: request.to.map_or(TransactionKind::Create, TransactionKind::Call),
+            value,
+            access_list: request.access_list.unwrap_or_default(),
+            input: request.data.unwrap_or_default(),
+        });
+        let transaction = TransactionSigned::from_transaction_and_signature(transaction, Signature::default());
+
+        let mut bytes = vec![];
+        transaction.encode_enveloped(&mut bytes);
+        self.relay_transaction(&bytes.into(), &transaction, from, false).await
+    }
+
+    /// Takes a snapshot of the devnet state and returns its id.
+    async fn snapshot(&self) -> Result<U64, EthApiError<P::Error>> {
+        let devnet = self.devnet()?;
+        let id = self.snapshots.next_id();
+        devnet.dump_state(&Snapshots::path(id)).await?;
+        self.snapshots.insert(id);
+        Ok(id.into())
+    }
+
+    /// Reverts the devnet state to a snapshot, which is consumed along with the snapshots taken
+    /// after it. Returns false if the snapshot is unknown.
+    async fn revert(&self, id: U64) -> Result<bool, EthApiError<P::Error>> {
+        let devnet = self.devnet()?;
+        let id = id.as_u64();
+        if !self.snapshots.contains(id) {
+            return Ok(false);
+        }
+        devnet.load_state(&Snapshots::path(id)).await?;
+        self.snapshots.revert(id);
+        self.block_number_cache.invalidate().await;
+        Ok(true)
+    }
+
+    /// Returns the queue depths of the read and write request lanes.
+    fn lane_stats(&self) -> LaneStats {
+        self.request_lanes.stats()
+    }
+
+    /// Returns the scores of the Starknet endpoints, empty with a single endpoint provider.
+    fn upstream_stats(&self) -> Vec<UpstreamStats> {
+        self.upstream_scores.as_ref().map(|scores| scores.stats()).unwrap_or_default()
+    }
+
+    /// Returns the counters of the coalesced Starknet reads, zero if the provider does not
+    /// coalesce them.
+    fn coalescing_stats(&self) -> CoalescingStats {
+        self.coalescing_counters.as_ref().map(|counters| counters.stats()).unwrap_or_default()
+    }
+
+    /// Fails if an expensive request should be shed because the Starknet provider is unhealthy.
+    /// The breaker never opens with a single endpoint provider, which reports no stats.
+    fn shed_if_overloaded(&self) -> Result<(), EthApiError<P::Error>> {
+        Ok(self.circuit_breaker.check(&self.upstream_stats(), Instant::now())?)
+    }
+
+    /// Returns the state of the circuit breaker of the expensive requests.
+    fn breaker_stats(&self) -> BreakerStats {
+        self.circuit_breaker.stats(&self.upstream_stats())
+    }
+
+    /// Returns the counters of the `eth_call` result cache.
+    fn call_cache_stats(&self) -> CallCacheStats {
+        self.call_cache.stats()
+    }
+
+    /// Returns a snapshot of the caches, indexes and queues of the client. The indexes are
+    /// counted in the store, which reads all their entries with the sled and memory backends.
+    fn stats(&self) -> ClientStats {
+        let indexes = NAMESPACES
+            .into_iter()
+            .filter_map(|namespace| match self.index_store.count(namespace) {
+                Ok(entries) => Some((namespace.to_string(), entries)),
+                Err(err) => {
+                    tracing::warn!("Failed to count the entries of the {namespace} index: {err}");
+                    None
+                }
+            })
+            .collect();
+        ClientStats {
+            call_cache: self.call_cache.stats().into(),
+            block_roots_cache: self.block_roots.stats(),
+            block_timestamps_cache: self.block_timestamps.stats(),
+            indexes,
+            lanes: self.lane_stats(),
+            relay: self.relay_stats(),
+            upstreams: self.upstream_stats(),
+            coalescing: self.coalescing_stats(),
+        }
     }
 
     /// Returns the fixed base_fee_per_gas of Kakarot
@@ -489,6 +2110,83 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
         U256::from(BASE_FEE_PER_GAS)
     }
 
+    /// Returns the gas limit reported in block headers.
+    fn block_gas_limit(&self) -> U256 {
+        U256::from(self.block_header_config.gas_limit)
+    }
+
+    /// Returns the timestamp reported for a block, synthetic if a block interval is configured.
+    async fn block_timestamp(&self, timestamp: u64, block_number: Option<u64>) -> U256 {
+        if self.block_header_config.synthetic_block_interval.is_none() {
+            return U256::from(timestamp);
+        }
+
+        let genesis_timestamp = self
+            .genesis_timestamp
+            .get_or_try_init(|| async {
+                let genesis =
+                    self.read(|| self.starknet_provider.get_block_with_tx_hashes(StarknetBlockId::Number(0))).await?;
+                Ok::<_, ProviderError<P::Error>>(BlockWithTxHashes::new(genesis).timestamp())
+            })
+            .await;
+        match genesis_timestamp {
+            Ok(genesis_timestamp) => {
+                U256::from(self.block_header_config.timestamp(*genesis_timestamp, timestamp, block_number))
+            }
+            Err(_) => U256::from(timestamp),
+        }
+    }
+
+    /// Returns the transactions and receipts roots of an accepted block, if their computation is
+    /// enabled. Roots are computed over the Kakarot transactions of the block and their receipts.
+    async fn block_roots(&self, block_hash: H256) -> Option<BlockRoots> {
+        if !self.block_header_config.compute_trie_roots {
+            return None;
+        }
+        if let Some(roots) = self.block_roots.get(&block_hash) {
+            return Some(roots);
+        }
+
+        let roots = async {
+            let starknet_block_hash: Felt252Wrapper = block_hash.try_into()?;
+            let starknet_block_hash: FieldElement = starknet_block_hash.into();
+            let block = self
+                .read(|| self.starknet_provider.get_block_with_txs(StarknetBlockId::Hash(starknet_block_hash)))
+                .await?;
+            let block = BlockWithTxs::new(block);
+
+            let mut transactions = vec![];
+            let mut receipts = vec![];
+            for transaction in block.transactions() {
+                let transaction: StarknetTransaction = transaction.into();
+                if !transaction.is_kakarot_tx(self).await? {
+                    continue;
+                }
+                let calls: Calls = transaction.calldata()?.try_into()?;
+                let signed_transaction: TransactionSigned = (&calls).try_into()?;
+                let hash: H256 = transaction.transaction_hash()?.into();
+                let receipt = self
+                    .transaction_receipt(hash)
+                    .await?
+                    .ok_or_else(|| EthApiError::Other(anyhow::anyhow!("missing receipt of transaction {hash:#x}")))?;
+                receipts.push(consensus_receipt(&signed_transaction, &receipt));
+                transactions.push(signed_transaction);
+            }
+            Ok::<_, EthApiError<P::Error>>(BlockRoots::compute(&transactions, &receipts))
+        };
+
+        match roots.await {
+            Ok(roots) => {
+                self.block_roots.insert(block_hash, roots);
+                Some(roots)
+            }
+            Err(err) => {
+                tracing::warn!("Failed to compute the trie roots of block {block_hash:#x}: {err}");
+                None
+            }
+        }
+    }
+
     /// Returns the max_priority_fee_per_gas of Kakarot
     fn max_priority_fee_per_gas(&self) -> U128 {
         MAX_PRIORITY_FEE_PER_GAS
@@ -499,7 +2197,7 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
         &self,
         block_count: U256,
         newest_block: BlockNumberOrTag,
-        _reward_percentiles: Option<Vec<f64>>,
+        reward_percentiles: Option<Vec<f64>>,
     ) -> Result<FeeHistory, EthApiError<P::Error>> {
         let block_count_usize =
             usize::try_from(block_count).map_err(|e| ConversionError::<()>::ValueOutOfRange(e.to_string()))?;
@@ -518,26 +2216,35 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
         let newest_block = U256::from(newest_block);
         let oldest_block: U256 = if newest_block >= block_count { newest_block - block_count } else { U256::from(0) };
 
-        // TODO: transition `reward` hardcoded default out of nearing-demo-day hack and seeing how to
-        // properly source/translate this value
-        Ok(FeeHistory { base_fee_per_gas, gas_used_ratio, oldest_block, reward: Some(vec![vec![]]) })
+        // Kakarot has no priority fee: one zero reward per percentile and block, as the EIP-1559 fee
+        // estimators of ethers-rs and Foundry read the reward of each block
+        let reward = reward_percentiles
+            .map(|percentiles| vec![vec![U256::from(MAX_PRIORITY_FEE_PER_GAS); percentiles.len()]; block_count_usize]);
+
+        Ok(FeeHistory { base_fee_per_gas, gas_used_ratio, oldest_block, reward })
     }
 
-    /// Returns the estimated gas for a transaction
+    /// Returns the lowest gas limit a transaction succeeds with, searched as on Geth between the
+    /// gas it uses and the gas limit of the request or of the block, capped by the RPC gas cap.
+    /// Transactions are simulated by the feeder gateway or else by the JSON-RPC provider, the
+    /// providers without simulations returning the fixed gas estimate.
     async fn estimate_gas(&self, request: CallRequest, block_id: BlockId) -> Result<U256, EthApiError<P::Error>> {
-        match self.network {
-            Network::MainnetGateway | Network::Goerli1Gateway | Network::Goerli2Gateway => (),
-            _ => {
-                return Ok(*ESTIMATE_GAS);
-            }
+        let json_rpc_simulation = match self.network {
+            Network::MainnetGateway | Network::Goerli1Gateway | Network::Goerli2Gateway => None,
+            _ => match (self.network.provider_url(), self.spec_version().await) {
+                (Ok(provider_url), Some(spec_version)) => Some((provider_url, spec_version)),
+                _ => return Ok(U256::from(self.fixed_gas_estimate)),
+            },
         };
+        let _permit = self.request_lanes.read().await?;
 
         let chain_id = request.chain_id.unwrap_or(CHAIN_ID.into());
 
         let from = request.from.ok_or_else(|| EthApiError::MissingParameterError("from for estimate_gas".into()))?;
         let nonce = self.nonce(from, block_id).await?.try_into().map_err(ConversionError::<u64>::from)?;
 
-        let gas_limit = request.gas.unwrap_or(U256::ZERO).try_into().map_err(ConversionError::<u64>::from)?;
+        let requested_gas_limit = request.gas.map(|gas| u64::try_from(gas).unwrap_or(u64::MAX));
+        let hi = gas_limit_cap(requested_gas_limit, self.block_header_config.gas_limit, self.rpc_gas_cap);
         let max_fee_per_gas = request
             .max_fee_per_gas
             .unwrap_or_else(|| U256::from(BASE_FEE_PER_GAS))
@@ -554,75 +2261,102 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
         let value = request.value.unwrap_or(U256::ZERO).try_into().map_err(ConversionError::<u128>::from)?;
 
         let data = request.data.unwrap_or_default();
+        let access_list = request.access_list.unwrap_or_default();
 
-        let tx = Transaction::Eip1559(TxEip1559 {
-            chain_id: chain_id.low_u64(),
-            nonce,
-            gas_limit,
-            max_fee_per_gas,
-            max_priority_fee_per_gas,
-            to,
-            value,
-            access_list: AccessList(vec![]),
-            input: data,
-        });
-
-        let starknet_block_id: StarknetBlockId = EthBlockId::new(block_id).try_into()?;
+        let starknet_block_id = self.starknet_block_id(block_id).await?;
         let block_number = self.map_block_id_to_block_number(&starknet_block_id).await?;
 
         let sender_address = self.compute_starknet_address(from, &starknet_block_id).await?;
 
-        let mut data = vec![];
-        tx.encode_with_signature(&Signature::default(), &mut data, false);
-        let data = data.into_iter().map(FieldElement::from).collect();
-        let calldata = raw_kakarot_calldata(self.kakarot_address(), data);
+        let invoke = |gas_limit: u64| {
+            let tx = Transaction::Eip1559(TxEip1559 {
+                chain_id: chain_id.low_u64(),
+                nonce,
+                gas_limit,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                to,
+                value,
+                access_list: access_list.clone(),
+                input: data.clone(),
+            });
 
-        let tx = BroadcastedInvokeTransactionV1 {
-            max_fee: FieldElement::ZERO,
-            signature: vec![],
-            sender_address,
-            nonce: nonce.into(),
-            calldata,
+            let mut data = vec![];
+            tx.encode_with_signature(&Signature::default(), &mut data, false);
+            let data = data.into_iter().map(FieldElement::from).collect();
+            let calldata = raw_kakarot_calldata(self.kakarot_address(), data);
+
+            BroadcastedInvokeTransactionV1 {
+                max_fee: FieldElement::ZERO,
+                signature: vec![],
+                sender_address,
+                nonce: nonce.into(),
+                calldata,
+            }
         };
 
-        let fee_estimate = self.simulate_transaction(tx, block_number, true).await?.fee_estimation;
-        if fee_estimate.gas_usage < MINIMUM_GAS_FEE {
-            return Ok(U256::from(MINIMUM_GAS_FEE));
-        }
-        Ok(U256::from(fee_estimate.gas_usage))
+        let simulate = |request| self.simulate_gas_usage(request, block_number, json_rpc_simulation.clone());
+        self.with_execution_timeout(async {
+            // A transaction failing with the highest gas limit fails with any, reverts are returned as is
+            let used = match simulate(invoke(hi)).await {
+                Ok(gas_usage) => gas_usage.max(MINIMUM_GAS_FEE),
+                Err(err) if err.is_out_of_gas() => return Err(EthApiError::GasRequiredExceedsAllowance { cap: hi }),
+                Err(err) => return Err(err),
+            };
+            if used > hi {
+                return Err(EthApiError::GasRequiredExceedsAllowance { cap: hi });
+            }
+
+            let gas_limit = search_gas_limit(used, hi, |gas_limit| {
+                let simulation = simulate(invoke(gas_limit));
+                async move {
+                    match simulation.await {
+                        Ok(_) => Ok(true),
+                        Err(err) if err.is_execution_failure() => Ok(false),
+                        Err(err) => Err(err),
+                    }
+                }
+            })
+            .await?;
+            Ok(U256::from(gas_limit))
+        })
+        .await
     }
 
     /// Returns the gas price on the network
     async fn gas_price(&self) -> Result<U256, EthApiError<P::Error>> {
-        let call = match self.network {
-            Network::MainnetGateway => COUNTER_CALL_MAINNET.clone(),
-            Network::Goerli1Gateway => COUNTER_CALL_TESTNET1.clone(),
-            Network::Goerli2Gateway => COUNTER_CALL_TESTNET2.clone(),
-            _ => return Ok(self.base_fee_per_gas()),
+        let Some(call) = self.deployment_addresses.gas_price_call() else {
+            return Ok(self.base_fee_per_gas());
         };
 
         let raw_calldata: Vec<FieldElement> = call.into();
 
+        // The gas price is sampled once per block
+        let block_number = self.block_number().await?.as_u64();
+        if let Some(gas_price) = self.gas_price_oracle.price_at(block_number) {
+            return Ok(gas_price);
+        }
+
         let block_id = StarknetBlockId::Tag(BlockTag::Latest);
-        let nonce = self.starknet_provider.get_nonce(block_id, *ACCOUNT_ADDRESS).await?;
+        let sender_address = self.deployment_addresses.gas_price_account;
+        let nonce = self.read(|| self.starknet_provider.get_nonce(block_id, sender_address)).await?;
 
         let tx = BroadcastedInvokeTransactionV1 {
             max_fee: FieldElement::ZERO,
             signature: vec![],
-            sender_address: *ACCOUNT_ADDRESS,
+            sender_address,
             nonce,
             calldata: raw_calldata,
         };
 
-        let block_number = self.block_number().await?.as_u64();
         let fee_estimate = self.simulate_transaction(tx, block_number, true).await?.fee_estimation;
 
-        Ok(U256::from(fee_estimate.gas_price))
+        Ok(self.gas_price_oracle.update(block_number, U256::from(fee_estimate.gas_price)))
     }
 }
 
 #[async_trait]
-impl<P: Provider + Send + Sync> KakarotStarknetApi<P> for KakarotClient<P> {
+impl<P: Provider + Send + Sync + 'static> KakarotStarknetApi<P> for KakarotClient<P> {
     /// Returns the Kakarot contract address.
     fn kakarot_address(&self) -> FieldElement {
         self.kakarot_contract.address
@@ -644,7 +2378,7 @@ impl<P: Provider + Send + Sync> KakarotStarknetApi<P> for KakarotClient<P> {
             StarknetBlockId::Number(n) => Ok(*n),
             StarknetBlockId::Tag(_) => Ok(self.block_number().await?.as_u64()),
             StarknetBlockId::Hash(_) => {
-                let block = self.starknet_provider.get_block_with_tx_hashes(block_id).await?;
+                let block = self.read(|| self.starknet_provider.get_block_with_tx_hashes(block_id)).await?;
                 match block {
                     MaybePendingBlockWithTxHashes::Block(block_with_tx_hashes) => Ok(block_with_tx_hashes.block_number),
                     _ => Err(ProviderError::StarknetError(StarknetError::BlockNotFound).into()),
@@ -666,7 +2400,7 @@ impl<P: Provider + Send + Sync> KakarotStarknetApi<P> for KakarotClient<P> {
             calldata: vec![],
         };
 
-        let evm_address = self.starknet_provider.call(request, starknet_block_id).await?;
+        let evm_address = self.read(|| self.starknet_provider.call(&request, starknet_block_id)).await?;
         let evm_address: Felt252Wrapper = (*evm_address.first().ok_or_else(|| {
             DataDecodingError::InvalidReturnArrayLength { entrypoint: "get_evm_address".into(), expected: 1, actual: 0 }
         })?)
@@ -710,11 +2444,16 @@ impl<P: Provider + Send + Sync> KakarotStarknetApi<P> for KakarotClient<P> {
         block_hash: Option<H256>,
         block_number: Option<U256>,
     ) -> BlockTransactions {
-        let handles = Into::<Vec<TransactionType>>::into(initial_transactions).into_iter().map(|tx| async move {
+        // Convert the transactions as a stream so that at most `block_conversion_concurrency`
+        // conversions, and their Starknet responses, are in flight at once.
+        let transactions = Into::<Vec<TransactionType>>::into(initial_transactions);
+        let transactions_vec = buffered_in_order(transactions, self.block_conversion_concurrency, |tx| async move {
             let tx = Into::<StarknetTransaction>::into(tx);
             tx.to_eth_transaction(self, block_hash, block_number, None).await
-        });
-        let transactions_vec = join_all(handles).await.into_iter().filter_map(|transaction| transaction.ok()).collect();
+        })
+        .filter_map(|transaction| future::ready(transaction.ok()))
+        .collect()
+        .await;
         BlockTransactions::Full(transactions_vec)
     }
 
@@ -724,20 +2463,137 @@ impl<P: Provider + Send + Sync> KakarotStarknetApi<P> for KakarotClient<P> {
         block_id: StarknetBlockId,
         hydrated_tx: bool,
     ) -> Result<RichBlock, EthApiError<P::Error>> {
-        if hydrated_tx {
-            let block = self.starknet_provider.get_block_with_txs(block_id).await?;
-            let starknet_block = BlockWithTxs::new(block);
-            Ok(starknet_block.to_eth_block(self).await)
+        let block_id = self.pin_block_id(block_id).await?;
+        let _permit = self.request_lanes.read().await?;
+        let mut block = if hydrated_tx {
+            let block = self.read(|| self.starknet_provider.get_block_with_txs(block_id)).await?;
+            BlockWithTxs::new(block).to_eth_block(self).await
         } else {
-            let block = self.starknet_provider.get_block_with_tx_hashes(block_id).await?;
-            let starknet_block = BlockWithTxHashes::new(block);
-            Ok(starknet_block.to_eth_block(self).await)
+            let block = self.read(|| self.starknet_provider.get_block_with_tx_hashes(block_id)).await?;
+            BlockWithTxHashes::new(block).to_eth_block(self).await
+        };
+
+        let pending = matches!(block_id, StarknetBlockId::Tag(BlockTag::Pending));
+        if let (BlockTransactions::Full(transactions), false) = (&block.inner.transactions, pending) {
+            for transaction in transactions {
+                self.record_sender_nonce(transaction);
+            }
+        }
+        if pending {
+            match &mut block.inner.transactions {
+                BlockTransactions::Full(transactions) => {
+                    transactions.retain(|transaction| !self.is_withheld(&transaction.hash))
+                }
+                BlockTransactions::Hashes(hashes) => hashes.retain(|hash| !self.is_withheld(hash)),
+                BlockTransactions::Uncle => {}
+            }
+        }
+        Ok(block)
+    }
+
+    /// Checks that the Kakarot deployment the client is configured against exists on the Starknet
+    /// provider and matches the configuration.
+    async fn validate_deployment(&self) -> Result<DeploymentReport, EthApiError<P::Error>> {
+        let block_id = StarknetBlockId::Tag(BlockTag::Latest);
+        let mut checks = vec![];
+
+        let kakarot_address = self.kakarot_address();
+        let kakarot_class_hash = match self.starknet_provider.get_class_hash_at(&block_id, kakarot_address).await {
+            Ok(class_hash) => {
+                checks.push(DeploymentCheck::passed(
+                    "kakarot_deployed",
+                    format!("Kakarot is deployed at {kakarot_address:#x}"),
+                ));
+                Some(class_hash)
+            }
+            Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => {
+                checks.push(DeploymentCheck::failed(
+                    "kakarot_deployed",
+                    format!(
+                        "no contract found at KAKAROT_ADDRESS {kakarot_address:#x}, check that it matches the address \
+                         in your deployments.json"
+                    ),
+                ));
+                None
+            }
+            Err(err) => {
+                checks.push(DeploymentCheck::failed(
+                    "kakarot_deployed",
+                    format!("failed to fetch the class hash of Kakarot at {kakarot_address:#x}: {err}"),
+                ));
+                None
+            }
+        };
+
+        match (self.kakarot_class_hash, kakarot_class_hash) {
+            (Some(expected), Some(actual)) if expected == actual => {
+                checks.push(DeploymentCheck::passed("kakarot_class_hash", format!("Kakarot class hash is {actual:#x}")))
+            }
+            (Some(expected), Some(actual)) => checks.push(DeploymentCheck::failed(
+                "kakarot_class_hash",
+                format!(
+                    "Kakarot class hash is {actual:#x} but KAKAROT_CLASS_HASH is {expected:#x}, the configured \
+                     address might point to another Kakarot version"
+                ),
+            )),
+            (Some(_), None) => checks.push(DeploymentCheck::failed(
+                "kakarot_class_hash",
+                "cannot check the class hash of Kakarot as it is not deployed",
+            )),
+            (None, _) => {
+                checks.push(DeploymentCheck::passed("kakarot_class_hash", "KAKAROT_CLASS_HASH not set, skipped"))
+            }
+        }
+
+        let proxy_account_class_hash = self.proxy_account_class_hash();
+        match self.starknet_provider.get_class(&block_id, proxy_account_class_hash).await {
+            Ok(_) => checks.push(DeploymentCheck::passed(
+                "proxy_account_class_declared",
+                format!("proxy account class {proxy_account_class_hash:#x} is declared"),
+            )),
+            Err(ProviderError::StarknetError(StarknetError::ClassHashNotFound)) => {
+                checks.push(DeploymentCheck::failed(
+                    "proxy_account_class_declared",
+                    format!(
+                        "PROXY_ACCOUNT_CLASS_HASH {proxy_account_class_hash:#x} is not declared, check that it \
+                         matches the proxy class hash in your declarations.json"
+                    ),
+                ))
+            }
+            Err(err) => checks.push(DeploymentCheck::failed(
+                "proxy_account_class_declared",
+                format!("failed to fetch the proxy account class {proxy_account_class_hash:#x}: {err}"),
+            )),
+        }
+
+        match self.starknet_chain_id {
+            Some(expected) => match self.starknet_provider.chain_id().await {
+                Ok(actual) if actual == expected => checks
+                    .push(DeploymentCheck::passed("starknet_chain_id", format!("Starknet chain id is {actual:#x}"))),
+                Ok(actual) => checks.push(DeploymentCheck::failed(
+                    "starknet_chain_id",
+                    format!(
+                        "Starknet provider reports chain id {actual:#x} but {expected:#x} is expected, check \
+                         STARKNET_NETWORK and STARKNET_CHAIN_ID"
+                    ),
+                )),
+                Err(err) => checks.push(DeploymentCheck::failed(
+                    "starknet_chain_id",
+                    format!("failed to fetch the Starknet chain id: {err}"),
+                )),
+            },
+            None => checks.push(DeploymentCheck::passed(
+                "starknet_chain_id",
+                "chain id unknown for the configured network and STARKNET_CHAIN_ID not set, skipped",
+            )),
         }
+
+        Ok(DeploymentReport { checks })
     }
 
-    /// Get the simulation of the BroadcastedInvokeTransactionV1 result
-    /// FIXME 306: make simulate_transaction agnostic of the provider (rn only works for
-    /// a SequencerGatewayProvider on testnets and mainnet)
+    /// Get the simulation of the BroadcastedInvokeTransactionV1 result, through the feeder gateway
+    /// on testnets and mainnet or the JSON-RPC provider if it supports simulations.
+    /// Simulation traces are only returned by the feeder gateway.
     async fn simulate_transaction(
         &self,
         request: BroadcastedInvokeTransactionV1,
@@ -749,9 +2605,31 @@ impl<P: Provider + Send + Sync> KakarotStarknetApi<P> for KakarotClient<P> {
         // build the url for simulate transaction
         let url = self.network.gateway_url();
 
-        // if the url is invalid, return an empty simulation (allows to call simulate_transaction on Kakana,
-        // Madara, etc.)
+        // if the url is invalid, simulate with the JSON-RPC provider, falling back to an empty
+        // simulation (allows to call simulate_transaction on Kakana, Madara, etc.)
         if url.is_err() {
+            if let (Ok(provider_url), Some(spec_version)) = (self.network.provider_url(), self.spec_version().await) {
+                match simulate_invoke(provider_url, spec_version, &request, block_number, skip_validate).await {
+                    Ok(fee) => {
+                        return Ok(TransactionSimulationInfo {
+                            trace: TransactionTrace {
+                                function_invocation: None,
+                                fee_transfer_invocation: None,
+                                validate_invocation: None,
+                                signature: vec![],
+                            },
+                            fee_estimation: FeeEstimate {
+                                gas_usage: fee.gas_consumed,
+                                gas_price: fee.gas_price,
+                                overall_fee: fee.overall_fee,
+                                unit: FeeUnit::Wei,
+                            },
+                        });
+                    }
+                    Err(err) => tracing::warn!("Starknet {spec_version} simulation failed, using defaults: {err}"),
+                }
+            }
+
             let gas_usage = (*ESTIMATE_GAS).try_into().map_err(ConversionError::UintConversionError)?;
             let gas_price: Felt252Wrapper = (*MAX_FEE).into();
             let overall_fee = Felt252Wrapper::from(gas_usage) * gas_price.clone();
@@ -796,18 +2674,20 @@ impl<P: Provider + Send + Sync> KakarotStarknetApi<P> for KakarotClient<P> {
             .await
             .map_err(|e| EthApiError::FeederGatewayError(format!("gateway post error: {:?}", e)))?;
 
+        // the body of an error response holds why the transaction failed, e.g. out of gas
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(EthApiError::FeederGatewayError(format!("http error {status}: {body}")));
+        }
+
         // decode the response to a `TransactionSimulationInfo`
-        let resp: TransactionSimulationInfo = response
-            .error_for_status()
-            .map_err(|e| EthApiError::FeederGatewayError(format!("http error: {:?}", e)))?
-            .json()
-            .await
-            .map_err(|e| {
-                EthApiError::FeederGatewayError(format!(
-                    "error while decoding response body to TransactionSimulationInfo: {:?}",
-                    e
-                ))
-            })?;
+        let resp: TransactionSimulationInfo = response.json().await.map_err(|e| {
+            EthApiError::FeederGatewayError(format!(
+                "error while decoding response body to TransactionSimulationInfo: {:?}",
+                e
+            ))
+        })?;
 
         Ok(resp)
     }
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use reth_primitives::{Address, H256};
+
+use super::store::{KakarotStore, SENDER_NONCES_NAMESPACE};
+
+/// Index of the included transactions by sender and nonce, kept in the store of the RPC. The
+/// transactions of the blocks converted with their transactions are recorded, e.g. by block
+/// explorers and subscriptions, the lookups scanning the recent blocks on a miss.
+pub struct SenderNonceIndex {
+    store: Arc<dyn KakarotStore>,
+}
+
+/// Returns the key of a transaction: the sender followed by the big endian nonce.
+pub fn sender_nonce_key(sender: Address, nonce: u64) -> Vec<u8> {
+    [sender.as_bytes(), &nonce.to_be_bytes()].concat()
+}
+
+impl SenderNonceIndex {
+    pub fn new(store: Arc<dyn KakarotStore>) -> Self {
+        Self { store }
+    }
+
+    /// Returns the hash of the transaction of a sender with the given nonce, if indexed.
+    pub fn get(&self, sender: Address, nonce: u64) -> Option<H256> {
+        match self.store.get(SENDER_NONCES_NAMESPACE, &sender_nonce_key(sender, nonce)) {
+            Ok(hash) => hash.filter(|hash| hash.len() == H256::len_bytes()).map(|hash| H256::from_slice(&hash)),
+            Err(err) => {
+                tracing::warn!("Failed to read the transaction of {sender:#x} with nonce {nonce}: {err}");
+                None
+            }
+        }
+    }
+
+    /// Records an included transaction. Storage failures are logged, the transaction being indexed
+    /// again by the next scan.
+    pub fn record(&self, sender: Address, nonce: u64, hash: H256) {
+        if let Err(err) = self.store.put(SENDER_NONCES_NAMESPACE, &sender_nonce_key(sender, nonce), hash.as_bytes()) {
+            tracing::warn!("Failed to index the transaction {hash:#x}: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::store::InMemoryStore;
+
+    #[test]
+    fn test_sender_nonce_index_record() {
+        // Given
+        let index = SenderNonceIndex::new(Arc::new(InMemoryStore::default()));
+        let (sender, hash) = (Address::from_low_u64_be(1), H256::from_low_u64_be(2));
+
+        // When
+        index.record(sender, 7, hash);
+
+        // Then
+        assert_eq!(Some(hash), index.get(sender, 7));
+        assert_eq!(None, index.get(sender, 8));
+        assert_eq!(None, index.get(Address::from_low_u64_be(3), 7));
+    }
+}
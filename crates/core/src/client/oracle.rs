@@ -0,0 +1,76 @@
+use std::sync::Mutex;
+
+use reth_primitives::U256;
+
+use super::config::GasPriceConfig;
+
+/// Gas price reported by `eth_gasPrice`, smoothed over the blocks. The Starknet gas price is
+/// sampled once per block, clamped to the configured bounds and averaged with the previous price
+/// with an exponential weight, so that one anomalous sample does not make wallets show absurd fees.
+pub struct GasPriceOracle {
+    config: GasPriceConfig,
+    /// Block of the last sample and the gas price reported since.
+    last: Mutex<Option<(u64, U256)>>,
+}
+
+impl GasPriceOracle {
+    pub fn new(config: GasPriceConfig) -> Self {
+        Self { config, last: Mutex::default() }
+    }
+
+    /// Returns the gas price reported at a block, if it was already sampled.
+    pub fn price_at(&self, block_number: u64) -> Option<U256> {
+        match *self.last.lock().expect("gas price oracle lock poisoned") {
+            Some((sampled_at, price)) if sampled_at == block_number => Some(price),
+            _ => None,
+        }
+    }
+
+    /// Records the gas price sampled at a block and returns the reported gas price. Samples of
+    /// blocks older than the last sample do not change the reported price.
+    pub fn update(&self, block_number: u64, sample: U256) -> U256 {
+        let sample = self.clamp(sample);
+        let mut last = self.last.lock().expect("gas price oracle lock poisoned");
+        let price = match *last {
+            Some((sampled_at, price)) if sampled_at >= block_number => return price,
+            Some((_, previous)) => {
+                let weight = U256::from(self.config.sample_weight_percent.clamp(1, 100));
+                (sample * weight + previous * (U256::from(100) - weight)) / U256::from(100)
+            }
+            None => sample,
+        };
+        *last = Some((block_number, price));
+        price
+    }
+
+    fn clamp(&self, sample: U256) -> U256 {
+        let sample = self.config.min_gas_price.map_or(sample, |min| sample.max(U256::from(min)));
+        self.config.max_gas_price.map_or(sample, |max| sample.min(U256::from(max)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gas_price_oracle_smooths_and_clamps_samples() {
+        // Given
+        let config = GasPriceConfig { sample_weight_percent: 25, min_gas_price: Some(10), max_gas_price: Some(1000) };
+        let oracle = GasPriceOracle::new(config);
+
+        // When
+        let first = oracle.update(1, U256::from(100));
+        let spike = oracle.update(2, U256::from(1_000_000));
+        let stale = oracle.update(1, U256::from(5));
+
+        // Then
+        assert_eq!(U256::from(100), first);
+        // The spike is clamped to 1000 before being weighted
+        assert_eq!(U256::from(325), spike);
+        assert_eq!(spike, stale);
+        assert_eq!(Some(spike), oracle.price_at(2));
+        assert_eq!(None, oracle.price_at(3));
+        assert_eq!(U256::from(10), GasPriceOracle::new(config).update(1, U256::ZERO));
+    }
+}
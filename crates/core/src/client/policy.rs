@@ -0,0 +1,123 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use reth_primitives::Address;
+use thiserror::Error;
+
+use super::errors::ConfigError;
+
+/// Transaction rejected by the relay policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum RelayPolicyViolation {
+    #[error("sender {0:#x} is denied by the relay policy")]
+    DeniedSender(Address),
+    #[error("sender {0:#x} is not allowed by the relay policy")]
+    SenderNotAllowed(Address),
+    #[error("recipient {0:#x} is denied by the relay policy")]
+    DeniedRecipient(Address),
+    #[error("recipient {0:#x} is not allowed by the relay policy")]
+    RecipientNotAllowed(Address),
+    #[error("selector 0x{} is denied by the relay policy", hex::encode(.0))]
+    DeniedSelector([u8; 4]),
+}
+
+/// Addresses and selectors the RPC refuses to relay transactions for, e.g. for compliance or to
+/// mitigate abuse. Deny lists take precedence over allow lists, and an empty allow list allows
+/// any address.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RelayPolicy {
+    /// Addresses which can neither send nor receive relayed transactions.
+    pub denied_addresses: HashSet<Address>,
+    /// Only senders relayed, if not empty.
+    pub allowed_senders: HashSet<Address>,
+    /// Only recipients called, if not empty. Deployments have no recipient and are not
+    /// restricted by this list.
+    pub allowed_recipients: HashSet<Address>,
+    /// Function selectors which cannot be called.
+    pub denied_selectors: HashSet<[u8; 4]>,
+}
+
+fn parse_list<T>(var: &str, parse: impl Fn(&str) -> Option<T>) -> Result<Vec<T>, ConfigError> {
+    let Ok(list) = std::env::var(var) else { return Ok(vec![]) };
+    list.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            parse(item).ok_or_else(|| ConfigError::EnvironmentVariableSetWrong(format!("invalid {var} entry: {item}")))
+        })
+        .collect()
+}
+
+fn parse_selector(selector: &str) -> Option<[u8; 4]> {
+    hex::decode(selector.trim_start_matches("0x")).ok()?.try_into().ok()
+}
+
+impl RelayPolicy {
+    /// Reads the comma separated lists of `KAKAROT_RELAY_DENIED_ADDRESSES`,
+    /// `KAKAROT_RELAY_ALLOWED_SENDERS`, `KAKAROT_RELAY_ALLOWED_RECIPIENTS` and
+    /// `KAKAROT_RELAY_DENIED_SELECTORS`.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let address = |address: &str| Address::from_str(address).ok();
+        Ok(Self {
+            denied_addresses: parse_list("KAKAROT_RELAY_DENIED_ADDRESSES", address)?.into_iter().collect(),
+            allowed_senders: parse_list("KAKAROT_RELAY_ALLOWED_SENDERS", address)?.into_iter().collect(),
+            allowed_recipients: parse_list("KAKAROT_RELAY_ALLOWED_RECIPIENTS", address)?.into_iter().collect(),
+            denied_selectors: parse_list("KAKAROT_RELAY_DENIED_SELECTORS", parse_selector)?.into_iter().collect(),
+        })
+    }
+
+    /// Checks a transaction sent by `sender` to `to`, `None` for a deployment, with `input` as
+    /// calldata.
+    pub fn check(&self, sender: Address, to: Option<Address>, input: &[u8]) -> Result<(), RelayPolicyViolation> {
+        if self.denied_addresses.contains(&sender) {
+            return Err(RelayPolicyViolation::DeniedSender(sender));
+        }
+        if !self.allowed_senders.is_empty() && !self.allowed_senders.contains(&sender) {
+            return Err(RelayPolicyViolation::SenderNotAllowed(sender));
+        }
+
+        let Some(to) = to else { return Ok(()) };
+        if self.denied_addresses.contains(&to) {
+            return Err(RelayPolicyViolation::DeniedRecipient(to));
+        }
+        if !self.allowed_recipients.is_empty() && !self.allowed_recipients.contains(&to) {
+            return Err(RelayPolicyViolation::RecipientNotAllowed(to));
+        }
+        match input.get(..4).and_then(|selector| <[u8; 4]>::try_from(selector).ok()) {
+            Some(selector) if self.denied_selectors.contains(&selector) => {
+                Err(RelayPolicyViolation::DeniedSelector(selector))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relay_policy_check() {
+        // Given
+        let (sender, denied, recipient) =
+            (Address::from_low_u64_be(1), Address::from_low_u64_be(2), Address::from_low_u64_be(3));
+        let transfer = parse_selector("0xa9059cbb").unwrap();
+        let policy = RelayPolicy {
+            denied_addresses: [denied].into(),
+            allowed_recipients: [recipient].into(),
+            denied_selectors: [transfer].into(),
+            ..Default::default()
+        };
+
+        // Then
+        assert_eq!(Ok(()), policy.check(sender, Some(recipient), &[0xde, 0xad, 0xbe, 0xef]));
+        assert_eq!(Ok(()), policy.check(sender, None, &transfer));
+        assert_eq!(Err(RelayPolicyViolation::DeniedSender(denied)), policy.check(denied, Some(recipient), &[]));
+        assert_eq!(Err(RelayPolicyViolation::DeniedRecipient(denied)), policy.check(sender, Some(denied), &[]));
+        assert_eq!(Err(RelayPolicyViolation::RecipientNotAllowed(sender)), policy.check(sender, Some(sender), &[]));
+        assert_eq!(
+            Err(RelayPolicyViolation::DeniedSelector(transfer)),
+            policy.check(sender, Some(recipient), &transfer)
+        );
+    }
+}
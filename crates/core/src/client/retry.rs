@@ -0,0 +1,105 @@
+use std::future::Future;
+use std::time::Instant;
+
+use starknet::providers::ProviderError;
+
+use super::config::ReadRetryPolicy;
+
+/// Hints of the provider errors raised by invalid requests, which fail the same way when retried.
+const FATAL_ERROR_HINTS: [&str; 4] = ["invalid", "not found", "unsupported", "deserializ"];
+
+/// Returns true if a Starknet read failing with the error can succeed when sent again: rate
+/// limits and transport failures are transient, the errors defined by the Starknet specification,
+/// e.g. `ContractNotFound`, are answers of the provider and are returned as is.
+pub fn is_retryable<E: std::error::Error>(err: &ProviderError<E>) -> bool {
+    match err {
+        ProviderError::RateLimited => true,
+        ProviderError::Other(err) => {
+            let message = err.to_string().to_lowercase();
+            !FATAL_ERROR_HINTS.iter().any(|hint| message.contains(hint))
+        }
+        ProviderError::StarknetError(_) | ProviderError::ArrayLengthMismatch => false,
+    }
+}
+
+/// Sends an idempotent Starknet read, sending it again after a jittered backoff while it fails
+/// with a retryable error, up to the retries and the time budget of the policy. Transactions must
+/// never be sent through it.
+pub async fn retry_read<T, E, F, Fut>(policy: &ReadRetryPolicy, mut read: F) -> Result<T, ProviderError<E>>
+where
+    E: std::error::Error,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, ProviderError<E>>>,
+{
+    let started_at = Instant::now();
+    let mut retry = 0;
+    loop {
+        match read().await {
+            Err(err) if retry < policy.max_retries && is_retryable(&err) => {
+                retry += 1;
+                let delay = policy.backoff(retry);
+                if started_at.elapsed().saturating_add(delay) > policy.budget {
+                    return Err(err);
+                }
+                tracing::debug!("Retrying Starknet read in {delay:?} after: {err}");
+                tokio::time::sleep(delay).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    use starknet::core::types::StarknetError;
+
+    use super::*;
+
+    type Error = ProviderError<std::io::Error>;
+
+    fn transport_error(message: &str) -> Error {
+        ProviderError::Other(std::io::Error::new(std::io::ErrorKind::Other, message.to_string()))
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(is_retryable(&Error::RateLimited));
+        assert!(is_retryable(&transport_error("connection reset by peer")));
+        assert!(!is_retryable(&transport_error("Invalid params")));
+        assert!(!is_retryable(&Error::StarknetError(StarknetError::ContractNotFound)));
+        assert!(!is_retryable(&Error::ArrayLengthMismatch));
+    }
+
+    #[tokio::test]
+    async fn test_retry_read_retries_transient_errors_only() {
+        // Given
+        let policy = ReadRetryPolicy { max_retries: 2, base_delay: Duration::ZERO, budget: Duration::from_secs(1) };
+        let attempts = AtomicU32::new(0);
+
+        // When
+        let flaky = retry_read(&policy, || async {
+            match attempts.fetch_add(1, Ordering::Relaxed) {
+                0 => Err(Error::RateLimited),
+                _ => Ok(42),
+            }
+        })
+        .await;
+        let fatal: Result<u64, _> =
+            retry_read(&policy, || async { Err(Error::StarknetError(StarknetError::BlockNotFound)) }).await;
+        let down: Result<u64, _> = retry_read(&policy, || async {
+            attempts.fetch_add(1, Ordering::Relaxed);
+            Err(transport_error("connection refused"))
+        })
+        .await;
+
+        // Then
+        assert_eq!(42, flaky.unwrap());
+        assert!(matches!(fatal, Err(ProviderError::StarknetError(StarknetError::BlockNotFound))));
+        assert!(down.is_err());
+        // 2 attempts for the flaky read and 3 for the unavailable provider
+        assert_eq!(5, attempts.load(Ordering::Relaxed));
+    }
+}
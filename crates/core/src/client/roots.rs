@@ -0,0 +1,120 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::RwLock;
+
+use reth_primitives::proofs::{calculate_receipt_root, calculate_transaction_root};
+use reth_primitives::{Log, Receipt, ReceiptWithBloom, TransactionSigned, H256, U64};
+use reth_rpc_types::TransactionReceipt;
+
+use super::stats::{CacheCounters, CacheStats};
+
+/// Number of blocks whose trie roots are kept in cache.
+pub const DEFAULT_BLOCK_ROOTS_CACHE_CAPACITY: usize = 1024;
+
+/// Transactions and receipts trie roots of a converted block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockRoots {
+    pub transactions_root: H256,
+    pub receipts_root: H256,
+}
+
+impl BlockRoots {
+    /// Computes the roots of the Ethereum transactions of a block and their receipts, in the same
+    /// order.
+    pub fn compute(transactions: &[TransactionSigned], receipts: &[ReceiptWithBloom]) -> Self {
+        Self {
+            transactions_root: calculate_transaction_root(transactions.iter()),
+            receipts_root: calculate_receipt_root(receipts.iter()),
+        }
+    }
+}
+
+/// Builds the consensus receipt of a transaction from its RPC receipt.
+pub fn consensus_receipt(transaction: &TransactionSigned, receipt: &TransactionReceipt) -> ReceiptWithBloom {
+    Receipt {
+        tx_type: transaction.tx_type(),
+        success: receipt.status_code == Some(U64::from(1)),
+        cumulative_gas_used: receipt.cumulative_gas_used.try_into().unwrap_or(u64::MAX),
+        logs: receipt
+            .logs
+            .iter()
+            .map(|log| Log { address: log.address, topics: log.topics.clone(), data: log.data.clone() })
+            .collect(),
+    }
+    .with_bloom()
+}
+
+/// Cache of the trie roots by block hash, evicting the oldest blocks first. Roots of accepted
+/// blocks never change.
+#[derive(Debug)]
+pub struct BlockRootsCache {
+    capacity: usize,
+    state: RwLock<(HashMap<H256, BlockRoots>, VecDeque<H256>)>,
+    counters: CacheCounters,
+}
+
+impl Default for BlockRootsCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_BLOCK_ROOTS_CACHE_CAPACITY)
+    }
+}
+
+impl BlockRootsCache {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, state: RwLock::default(), counters: CacheCounters::default() }
+    }
+
+    pub fn get(&self, block_hash: &H256) -> Option<BlockRoots> {
+        self.counters.record(self.state.read().expect("block roots cache lock poisoned").0.get(block_hash).copied())
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.counters.stats(self.state.read().expect("block roots cache lock poisoned").0.len())
+    }
+
+    pub fn insert(&self, block_hash: H256, roots: BlockRoots) {
+        let mut state = self.state.write().expect("block roots cache lock poisoned");
+        let (roots_by_hash, order) = &mut *state;
+        if roots_by_hash.insert(block_hash, roots).is_none() {
+            order.push_back(block_hash);
+        }
+        while order.len() > self.capacity {
+            if let Some(evicted) = order.pop_front() {
+                roots_by_hash.remove(&evicted);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_block_roots_of_empty_block() {
+        // Given
+        let empty_root = H256::from_str("0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421").unwrap();
+
+        // When
+        let roots = BlockRoots::compute(&[], &[]);
+
+        // Then
+        assert_eq!(BlockRoots { transactions_root: empty_root, receipts_root: empty_root }, roots);
+    }
+
+    #[test]
+    fn test_block_roots_cache_eviction() {
+        // Given
+        let cache = BlockRootsCache::new(2);
+        let roots = BlockRoots { transactions_root: H256::zero(), receipts_root: H256::zero() };
+
+        // When
+        (1..=3).for_each(|i| cache.insert(H256::from_low_u64_be(i), roots));
+
+        // Then
+        assert_eq!(None, cache.get(&H256::from_low_u64_be(1)));
+        assert_eq!(Some(roots), cache.get(&H256::from_low_u64_be(3)));
+        assert_eq!(CacheStats::new(2, 1, 1), cache.stats());
+    }
+}
@@ -0,0 +1,209 @@
+use reth_primitives::{Address, H256};
+
+use super::audit::AuditEntry;
+use super::creations::ContractCreator;
+use super::store::{
+    KakarotStore, StoreError, AUDIT_NAMESPACE, CREATIONS_NAMESPACE, SCHEMA_NAMESPACE, SENDER_NONCES_NAMESPACE,
+    TRANSACTIONS_NAMESPACE,
+};
+use super::tracking::TrackedTransaction;
+
+/// Version of the format of the indexes written by this release. Stores written before the
+/// format was versioned are at version 1.
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+const SCHEMA_VERSION_KEY: &[u8] = b"version";
+
+/// Namespaces of the indexes, reported by `index status`.
+pub const NAMESPACES: [&str; 4] =
+    [TRANSACTIONS_NAMESPACE, CREATIONS_NAMESPACE, SENDER_NONCES_NAMESPACE, AUDIT_NAMESPACE];
+
+/// Rewrites the indexes of a store from the previous schema version to `version`.
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    /// Version of the store once migrated.
+    pub version: u32,
+    pub description: &'static str,
+    pub run: fn(&dyn KakarotStore) -> Result<(), StoreError>,
+}
+
+/// Migrations between the schema versions, in order. A release changing the format of an index
+/// bumps [`CURRENT_SCHEMA_VERSION`] and adds the migration rewriting the existing entries, so that
+/// operators do not have to backfill the index from scratch.
+pub const MIGRATIONS: &[Migration] = &[];
+
+/// Returns the schema version of a store.
+pub fn schema_version(store: &dyn KakarotStore) -> Result<u32, StoreError> {
+    let Some(version) = store.get(SCHEMA_NAMESPACE, SCHEMA_VERSION_KEY)? else { return Ok(1) };
+    let version = <[u8; 4]>::try_from(version.as_slice())
+        .map_err(|_| StoreError::Backend(format!("invalid schema version {version:?}")))?;
+    Ok(u32::from_be_bytes(version))
+}
+
+fn set_schema_version(store: &dyn KakarotStore, version: u32) -> Result<(), StoreError> {
+    store.put(SCHEMA_NAMESPACE, SCHEMA_VERSION_KEY, &version.to_be_bytes())
+}
+
+/// Returns the migrations bringing a store at `version` to the current version.
+pub fn pending_migrations(version: u32) -> Vec<&'static Migration> {
+    MIGRATIONS.iter().filter(|migration| migration.version > version).collect()
+}
+
+/// Migrates a store to the current schema version, returning the migrations applied. Fails for
+/// a store written by a newer release, whose format this release cannot read.
+pub fn migrate(store: &dyn KakarotStore) -> Result<Vec<&'static Migration>, StoreError> {
+    migrate_with(store, MIGRATIONS, CURRENT_SCHEMA_VERSION)
+}
+
+fn migrate_with<'a>(
+    store: &dyn KakarotStore,
+    migrations: &'a [Migration],
+    current_version: u32,
+) -> Result<Vec<&'a Migration>, StoreError> {
+    let version = schema_version(store)?;
+    if version > current_version {
+        return Err(StoreError::UnsupportedSchema { found: version, supported: current_version });
+    }
+
+    let pending: Vec<_> = migrations.iter().filter(|migration| migration.version > version).collect();
+    for migration in &pending {
+        tracing::info!("Migrating the store to schema version {}: {}", migration.version, migration.description);
+        (migration.run)(store)?;
+        // Recorded after each migration so that an interrupted upgrade resumes where it stopped
+        set_schema_version(store, migration.version)?;
+    }
+    set_schema_version(store, current_version)?;
+    Ok(pending)
+}
+
+/// Schema version and size of the indexes of a store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoreStatus {
+    pub schema_version: u32,
+    pub pending_migrations: Vec<&'static str>,
+    /// Number of entries per namespace.
+    pub entries: Vec<(&'static str, usize)>,
+}
+
+/// Returns the status of a store, without migrating it.
+pub fn status(store: &dyn KakarotStore) -> Result<StoreStatus, StoreError> {
+    let schema_version = schema_version(store)?;
+    let entries = NAMESPACES
+        .into_iter()
+        .map(|namespace| Ok((namespace, store.count(namespace)?)))
+        .collect::<Result<_, StoreError>>()?;
+    Ok(StoreStatus {
+        schema_version,
+        pending_migrations: pending_migrations(schema_version).iter().map(|migration| migration.description).collect(),
+        entries,
+    })
+}
+
+/// Entries checked by `index verify` and the ones which cannot be read by this release.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerifyReport {
+    pub checked: u64,
+    /// Descriptions of the invalid entries.
+    pub invalid: Vec<String>,
+}
+
+/// Checks that every entry of the indexes can be read by this release.
+pub fn verify(store: &dyn KakarotStore) -> Result<VerifyReport, StoreError> {
+    let mut report = VerifyReport::default();
+
+    for (key, value) in store.entries(TRANSACTIONS_NAMESPACE)? {
+        report.checked += 1;
+        match serde_json::from_slice::<TrackedTransaction>(&value) {
+            Ok(tracked) if key == tracked.eth_hash.as_bytes() => {}
+            Ok(tracked) => report.invalid.push(format!(
+                "{TRANSACTIONS_NAMESPACE}: transaction {:#x} stored under key {}",
+                tracked.eth_hash,
+                hex::encode(&key)
+            )),
+            Err(err) => report.invalid.push(format!("{TRANSACTIONS_NAMESPACE}: {}: {err}", hex::encode(&key))),
+        }
+    }
+
+    for (key, value) in store.entries(CREATIONS_NAMESPACE)? {
+        report.checked += 1;
+        if key.len() != Address::len_bytes() {
+            report.invalid.push(format!("{CREATIONS_NAMESPACE}: invalid contract address {}", hex::encode(&key)));
+        } else if let Err(err) = serde_json::from_slice::<ContractCreator>(&value) {
+            report.invalid.push(format!("{CREATIONS_NAMESPACE}: {}: {err}", hex::encode(&key)));
+        }
+    }
+
+    for (key, value) in store.entries(SENDER_NONCES_NAMESPACE)? {
+        report.checked += 1;
+        if key.len() != Address::len_bytes() + 8 {
+            report.invalid.push(format!("{SENDER_NONCES_NAMESPACE}: invalid sender and nonce {}", hex::encode(&key)));
+        } else if value.len() != H256::len_bytes() {
+            report.invalid.push(format!("{SENDER_NONCES_NAMESPACE}: invalid transaction hash {}", hex::encode(&value)));
+        }
+    }
+
+    for (key, value) in store.entries(AUDIT_NAMESPACE)? {
+        report.checked += 1;
+        match serde_json::from_slice::<AuditEntry>(&value) {
+            Ok(entry) if key == entry.sequence.to_be_bytes() => {}
+            Ok(entry) => report.invalid.push(format!(
+                "{AUDIT_NAMESPACE}: entry {} stored under key {}",
+                entry.sequence,
+                hex::encode(&key)
+            )),
+            Err(err) => report.invalid.push(format!("{AUDIT_NAMESPACE}: {}: {err}", hex::encode(&key))),
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::store::InMemoryStore;
+
+    fn rename_namespace(store: &dyn KakarotStore) -> Result<(), StoreError> {
+        for (key, value) in store.entries("legacy")? {
+            store.put(TRANSACTIONS_NAMESPACE, &key, &value)?;
+            store.delete("legacy", &key)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_with() {
+        // Given
+        let store = InMemoryStore::default();
+        store.put("legacy", b"key", b"value").unwrap();
+        let migrations = [Migration { version: 2, description: "rename legacy", run: rename_namespace }];
+
+        // When
+        let applied = migrate_with(&store, &migrations, 2).unwrap();
+
+        // Then
+        assert_eq!(1, applied.len());
+        assert_eq!(2, schema_version(&store).unwrap());
+        assert_eq!(Some(b"value".to_vec()), store.get(TRANSACTIONS_NAMESPACE, b"key").unwrap());
+        assert!(migrate_with(&store, &migrations, 2).unwrap().is_empty());
+        assert!(matches!(
+            migrate_with(&store, &migrations, 1),
+            Err(StoreError::UnsupportedSchema { found: 2, supported: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_verify() {
+        // Given
+        let store = InMemoryStore::default();
+        store.put(TRANSACTIONS_NAMESPACE, &[1; 32], b"not a transaction").unwrap();
+        store.put(SENDER_NONCES_NAMESPACE, &[2; 28], &[3; 32]).unwrap();
+
+        // When
+        let report = verify(&store).unwrap();
+
+        // Then
+        assert_eq!(2, report.checked);
+        assert_eq!(1, report.invalid.len());
+    }
+}
@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use reth_primitives::Address;
+use tokio::sync::watch;
+
+use super::lanes::{LaneFull, LanePermit, RequestLanes};
+
+/// Default time a transaction waits for the transactions of its sender with lower nonces to be
+/// submitted before being submitted anyway.
+pub const DEFAULT_NONCE_SEQUENCING_TIMEOUT: Duration = Duration::from_secs(5);
+/// Maximum number of resubmissions of a transaction rejected by Starknet for an invalid nonce.
+pub const MAX_NONCE_RECOVERIES: u32 = 2;
+/// Default time after which the nonce of a sender without transactions is forgotten, its next
+/// transaction reading it again from the pending state of its account.
+pub const DEFAULT_SENDER_IDLE_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Orders the submissions of the transactions of each sender by nonce. Starknet rejects an invoke
+/// whose nonce is ahead of the nonce of its account, so a burst of transactions relayed
+/// concurrently for a sender fails with an invalid nonce whenever a transaction overtakes the
+/// previous one. Each transaction instead waits until the transaction with the previous nonce was
+/// submitted, up to a timeout in case that transaction never comes.
+///
+/// The next nonce of each sender is published through a watch channel, so that the waiting
+/// transactions are woken up without polling and without holding a lock.
+pub struct NonceSequencer {
+    senders: RwLock<Senders>,
+    /// Time a transaction waits for its turn, zero disabling the sequencing.
+    timeout: Duration,
+    /// Time after which an idle sender is evicted.
+    idle_timeout: Duration,
+}
+
+/// Next nonce of a sender, with the time it was last updated.
+struct SenderNonce {
+    next: watch::Sender<u64>,
+    updated_at: Instant,
+}
+
+struct Senders {
+    by_address: HashMap<Address, SenderNonce>,
+    pruned_at: Instant,
+}
+
+impl Default for Senders {
+    fn default() -> Self {
+        Self { by_address: HashMap::default(), pruned_at: Instant::now() }
+    }
+}
+
+impl Senders {
+    /// Evicts the senders idle for longer than the timeout, at most once per timeout. Senders
+    /// with transactions waiting for their turn are kept.
+    fn prune(&mut self, idle_timeout: Duration) {
+        if self.pruned_at.elapsed() < idle_timeout {
+            return;
+        }
+        self.by_address
+            .retain(|_, sender| sender.next.receiver_count() > 0 || sender.updated_at.elapsed() < idle_timeout);
+        self.pruned_at = Instant::now();
+    }
+}
+
+impl Default for NonceSequencer {
+    fn default() -> Self {
+        Self::new(DEFAULT_NONCE_SEQUENCING_TIMEOUT)
+    }
+}
+
+impl NonceSequencer {
+    pub fn new(timeout: Duration) -> Self {
+        Self { senders: RwLock::default(), timeout, idle_timeout: DEFAULT_SENDER_IDLE_TIMEOUT }
+    }
+
+    /// Sets the time after which the nonce of an idle sender is forgotten.
+    pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.timeout.is_zero()
+    }
+
+    /// Returns true if the next nonce of the sender is known.
+    pub fn is_tracked(&self, sender: Address) -> bool {
+        self.senders.read().expect("nonce sequencer lock poisoned").by_address.contains_key(&sender)
+    }
+
+    /// Records the nonce Starknet expects next for a sender, e.g. read from the pending state of
+    /// its account. The local nonce never moves backwards.
+    pub fn observe(&self, sender: Address, next_nonce: u64) {
+        let mut senders = self.senders.write().expect("nonce sequencer lock poisoned");
+        match senders.by_address.get_mut(&sender) {
+            Some(sender) => {
+                sender.next.send_modify(|next| *next = (*next).max(next_nonce));
+                sender.updated_at = Instant::now();
+            }
+            None => {
+                senders.prune(self.idle_timeout);
+                let nonce = SenderNonce { next: watch::channel(next_nonce).0, updated_at: Instant::now() };
+                senders.by_address.insert(sender, nonce);
+            }
+        }
+    }
+
+    /// Records the submission of the transaction of a sender with the given nonce, letting the
+    /// transaction with the next nonce go.
+    pub fn submitted(&self, sender: Address, nonce: u64) {
+        self.observe(sender, nonce + 1);
+    }
+
+    /// Waits until the transactions of the sender with lower nonces were submitted, or until the
+    /// timeout. Returns immediately for the senders not tracked yet.
+    pub async fn wait_turn(&self, sender: Address, nonce: u64) {
+        if !self.is_enabled() {
+            return;
+        }
+        let receiver = self
+            .senders
+            .read()
+            .expect("nonce sequencer lock poisoned")
+            .by_address
+            .get(&sender)
+            .map(|sender| sender.next.subscribe());
+        let Some(mut receiver) = receiver else { return };
+
+        let turn = async {
+            while *receiver.borrow() < nonce {
+                if receiver.changed().await.is_err() {
+                    break;
+                }
+            }
+        };
+        if tokio::time::timeout(self.timeout, turn).await.is_err() {
+            tracing::debug!("Submitting the transaction of {sender:#x} with nonce {nonce} before the previous ones");
+        }
+    }
+
+    /// Waits for the turn of the transaction of the sender with the given nonce, then for a slot
+    /// of the write lane. The slot is only taken once the transaction can be submitted, so that a
+    /// sender waiting for a missing nonce never holds a slot the other senders could use.
+    pub async fn write_slot<'a>(
+        &self,
+        lanes: &'a RequestLanes,
+        sender: Address,
+        nonce: u64,
+    ) -> Result<LanePermit<'a>, LaneFull> {
+        self.wait_turn(sender, nonce).await;
+        lanes.write().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::client::lanes::LaneConfig;
+
+    #[tokio::test]
+    async fn test_nonce_sequencer_orders_submissions() {
+        // Given
+        let sequencer = NonceSequencer::new(Duration::from_secs(5));
+        let sender = Address::from_low_u64_be(1);
+        sequencer.observe(sender, 3);
+        let submitted = Mutex::new(vec![]);
+
+        // When
+        let (sequencer, submitted) = (&sequencer, &submitted);
+        let submit = move |nonce: u64| async move {
+            sequencer.wait_turn(sender, nonce).await;
+            submitted.lock().unwrap().push(nonce);
+            sequencer.submitted(sender, nonce);
+        };
+        tokio::join!(submit(5), submit(4), submit(3));
+
+        // Then
+        assert_eq!(vec![3, 4, 5], *submitted.lock().unwrap());
+        assert!(!sequencer.is_tracked(Address::from_low_u64_be(2)));
+    }
+
+    #[tokio::test]
+    async fn test_gapped_sender_does_not_hold_the_write_lane() {
+        // Given
+        let sequencer = NonceSequencer::new(Duration::from_secs(5));
+        let lanes = RequestLanes::new(&LaneConfig { write_concurrency: 1, ..Default::default() });
+        let (gapped_sender, other_sender) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2));
+        sequencer.observe(gapped_sender, 3);
+        sequencer.observe(other_sender, 0);
+
+        // When
+        let gapped = sequencer.write_slot(&lanes, gapped_sender, 5);
+        tokio::pin!(gapped);
+        let permit = tokio::select! {
+            _ = &mut gapped => panic!("the transaction with nonce 5 should wait for nonces 3 and 4"),
+            permit = sequencer.write_slot(&lanes, other_sender, 0) => permit.unwrap(),
+        };
+
+        // Then
+        assert_eq!(1, lanes.stats().write.in_flight);
+        drop(permit);
+        assert_eq!(0, lanes.stats().write.in_flight);
+    }
+
+    #[tokio::test]
+    async fn test_nonce_sequencer_evicts_idle_senders() {
+        // Given
+        let sequencer = NonceSequencer::new(Duration::from_secs(5)).with_idle_timeout(Duration::ZERO);
+        let (idle_sender, new_sender) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2));
+        sequencer.observe(idle_sender, 3);
+
+        // When
+        sequencer.observe(new_sender, 0);
+
+        // Then
+        assert!(!sequencer.is_tracked(idle_sender));
+        assert!(sequencer.is_tracked(new_sender));
+    }
+
+    #[tokio::test]
+    async fn test_nonce_sequencer_times_out_on_gaps() {
+        // Given
+        let sequencer = NonceSequencer::new(Duration::from_millis(10));
+        let sender = Address::from_low_u64_be(1);
+        sequencer.observe(sender, 0);
+
+        // When
+        sequencer.wait_turn(sender, 2).await;
+
+        // Then
+        assert!(sequencer.is_tracked(sender));
+    }
+}
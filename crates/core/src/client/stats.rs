@@ -0,0 +1,95 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use super::calls::CallCacheStats;
+use super::coalesce::CoalescingStats;
+use super::lanes::LaneStats;
+use super::tracking::RelayStats;
+use super::upstream::UpstreamStats;
+
+/// Entries, hits and misses of a cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    pub entries: usize,
+    pub hits: u64,
+    pub misses: u64,
+    /// Share of the lookups served by the cache, `None` before the first lookup.
+    pub hit_rate: Option<f64>,
+}
+
+impl CacheStats {
+    pub fn new(entries: usize, hits: u64, misses: u64) -> Self {
+        let lookups = hits + misses;
+        let hit_rate = (lookups > 0).then(|| hits as f64 / lookups as f64);
+        Self { entries, hits, misses, hit_rate }
+    }
+}
+
+impl From<CallCacheStats> for CacheStats {
+    fn from(stats: CallCacheStats) -> Self {
+        Self::new(stats.entries, stats.hits, stats.misses)
+    }
+}
+
+/// Hits and misses of a cache, counted on lookup.
+#[derive(Debug, Default)]
+pub struct CacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl CacheCounters {
+    /// Counts the result of a lookup and returns it.
+    pub fn record<T>(&self, cached: Option<T>) -> Option<T> {
+        let counter = if cached.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        cached
+    }
+
+    pub fn stats(&self, entries: usize) -> CacheStats {
+        CacheStats::new(entries, self.hits.load(Ordering::Relaxed), self.misses.load(Ordering::Relaxed))
+    }
+}
+
+/// Snapshot of the caches, indexes and queues of the client, returned by `admin_stats` and
+/// dumped to the logs on `SIGUSR1` to debug a deployment without a metrics stack.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientStats {
+    pub call_cache: CacheStats,
+    pub block_roots_cache: CacheStats,
+    pub block_timestamps_cache: CacheStats,
+    /// Number of entries of the indexes of the store, by namespace.
+    pub indexes: BTreeMap<String, usize>,
+    /// Requests in flight and queued in the read and write lanes.
+    pub lanes: LaneStats,
+    pub relay: RelayStats,
+    /// Latencies of the Starknet endpoints, empty with a single endpoint provider.
+    pub upstreams: Vec<UpstreamStats>,
+    pub coalescing: CoalescingStats,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_counters() {
+        // Given
+        let counters = CacheCounters::default();
+
+        // When
+        let before = counters.stats(0);
+        counters.record(Some(1));
+        counters.record(Some(2));
+        counters.record(None::<u64>);
+        counters.record(Some(3));
+
+        // Then
+        assert_eq!(None, before.hit_rate);
+        assert_eq!(CacheStats { entries: 3, hits: 3, misses: 1, hit_rate: Some(0.75) }, counters.stats(3));
+    }
+}
@@ -0,0 +1,305 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+use thiserror::Error;
+
+use super::schema::migrate;
+
+/// Namespace of the transactions recorded by the transaction tracker.
+pub const TRANSACTIONS_NAMESPACE: &str = "transactions";
+/// Namespace of the contract creations by contract address.
+pub const CREATIONS_NAMESPACE: &str = "creations";
+/// Namespace of the included transactions by sender and nonce.
+pub const SENDER_NONCES_NAMESPACE: &str = "sender_nonces";
+/// Namespace of the audit log of the relayed transactions, by sequence number.
+pub const AUDIT_NAMESPACE: &str = "audit";
+/// Namespace of the schema version of the store.
+pub const SCHEMA_NAMESPACE: &str = "schema";
+
+/// Error raised by a storage backend.
+#[derive(Debug, Error)]
+pub enum StoreError {
+    #[error("storage backend error: {0}")]
+    Backend(String),
+    #[error("{0} storage backend not available, build with the `{0}` feature")]
+    Unavailable(&'static str),
+    #[error("invalid store {0}, expected memory, sled:<path> or sqlite:<path>")]
+    InvalidConfig(String),
+    #[error("store schema version {found} is newer than the version {supported} of this release")]
+    UnsupportedSchema { found: u32, supported: u32 },
+}
+
+/// Key-value storage of the indexes maintained by the RPC, such as the relayed transactions.
+/// Entries are grouped by namespace, each index using its own namespace.
+///
+/// Backends other than the in memory one are enabled by their feature, so that deployments only
+/// build the databases they use. External databases can be plugged in by implementing this
+/// trait.
+pub trait KakarotStore: Send + Sync {
+    fn get(&self, namespace: &str, key: &[u8]) -> Result<Option<Vec<u8>>, StoreError>;
+
+    fn put(&self, namespace: &str, key: &[u8], value: &[u8]) -> Result<(), StoreError>;
+
+    fn delete(&self, namespace: &str, key: &[u8]) -> Result<(), StoreError>;
+
+    /// Returns the entries of a namespace, ordered by key.
+    fn entries(&self, namespace: &str) -> Result<Vec<(Vec<u8>, Vec<u8>)>, StoreError>;
+
+    /// Returns the number of entries of a namespace, reading them all unless the backend counts
+    /// them itself.
+    fn count(&self, namespace: &str) -> Result<usize, StoreError> {
+        Ok(self.entries(namespace)?.len())
+    }
+}
+
+/// Store keeping its entries in memory, lost on restart.
+#[derive(Debug, Default)]
+pub struct InMemoryStore {
+    namespaces: RwLock<HashMap<String, BTreeMap<Vec<u8>, Vec<u8>>>>,
+}
+
+impl KakarotStore for InMemoryStore {
+    fn get(&self, namespace: &str, key: &[u8]) -> Result<Option<Vec<u8>>, StoreError> {
+        let namespaces = self.namespaces.read().expect("store lock poisoned");
+        Ok(namespaces.get(namespace).and_then(|entries| entries.get(key)).cloned())
+    }
+
+    fn put(&self, namespace: &str, key: &[u8], value: &[u8]) -> Result<(), StoreError> {
+        let mut namespaces = self.namespaces.write().expect("store lock poisoned");
+        namespaces.entry(namespace.to_string()).or_default().insert(key.to_vec(), value.to_vec());
+        Ok(())
+    }
+
+    fn delete(&self, namespace: &str, key: &[u8]) -> Result<(), StoreError> {
+        let mut namespaces = self.namespaces.write().expect("store lock poisoned");
+        if let Some(entries) = namespaces.get_mut(namespace) {
+            entries.remove(key);
+        }
+        Ok(())
+    }
+
+    fn entries(&self, namespace: &str) -> Result<Vec<(Vec<u8>, Vec<u8>)>, StoreError> {
+        let namespaces = self.namespaces.read().expect("store lock poisoned");
+        Ok(namespaces
+            .get(namespace)
+            .map(|entries| entries.iter().map(|(key, value)| (key.clone(), value.clone())).collect())
+            .unwrap_or_default())
+    }
+
+    fn count(&self, namespace: &str) -> Result<usize, StoreError> {
+        let namespaces = self.namespaces.read().expect("store lock poisoned");
+        Ok(namespaces.get(namespace).map_or(0, BTreeMap::len))
+    }
+}
+
+/// Store backed by an embedded [sled](https://github.com/spacejam/sled) database, one tree per
+/// namespace.
+#[cfg(feature = "sled")]
+pub struct SledStore {
+    db: sled::Db,
+}
+
+#[cfg(feature = "sled")]
+impl SledStore {
+    pub fn open(path: &std::path::Path) -> Result<Self, StoreError> {
+        Ok(Self { db: sled::open(path).map_err(|err| StoreError::Backend(err.to_string()))? })
+    }
+
+    fn tree(&self, namespace: &str) -> Result<sled::Tree, StoreError> {
+        self.db.open_tree(namespace).map_err(|err| StoreError::Backend(err.to_string()))
+    }
+}
+
+#[cfg(feature = "sled")]
+impl KakarotStore for SledStore {
+    fn get(&self, namespace: &str, key: &[u8]) -> Result<Option<Vec<u8>>, StoreError> {
+        let value = self.tree(namespace)?.get(key).map_err(|err| StoreError::Backend(err.to_string()))?;
+        Ok(value.map(|value| value.to_vec()))
+    }
+
+    fn put(&self, namespace: &str, key: &[u8], value: &[u8]) -> Result<(), StoreError> {
+        self.tree(namespace)?.insert(key, value).map_err(|err| StoreError::Backend(err.to_string()))?;
+        Ok(())
+    }
+
+    fn delete(&self, namespace: &str, key: &[u8]) -> Result<(), StoreError> {
+        self.tree(namespace)?.remove(key).map_err(|err| StoreError::Backend(err.to_string()))?;
+        Ok(())
+    }
+
+    fn entries(&self, namespace: &str) -> Result<Vec<(Vec<u8>, Vec<u8>)>, StoreError> {
+        self.tree(namespace)?
+            .iter()
+            .map(|entry| {
+                entry
+                    .map(|(key, value)| (key.to_vec(), value.to_vec()))
+                    .map_err(|err| StoreError::Backend(err.to_string()))
+            })
+            .collect()
+    }
+
+    fn count(&self, namespace: &str) -> Result<usize, StoreError> {
+        Ok(self.tree(namespace)?.len())
+    }
+}
+
+/// Store backed by a SQLite database, all namespaces sharing a single table.
+#[cfg(feature = "sqlite")]
+pub struct SqliteStore {
+    connection: std::sync::Mutex<rusqlite::Connection>,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteStore {
+    pub fn open(path: &std::path::Path) -> Result<Self, StoreError> {
+        let connection = rusqlite::Connection::open(path).map_err(|err| StoreError::Backend(err.to_string()))?;
+        connection
+            .execute(
+                "CREATE TABLE IF NOT EXISTS entries (namespace TEXT NOT NULL, key BLOB NOT NULL, value BLOB NOT NULL, \
+                 PRIMARY KEY (namespace, key))",
+                [],
+            )
+            .map_err(|err| StoreError::Backend(err.to_string()))?;
+        Ok(Self { connection: std::sync::Mutex::new(connection) })
+    }
+
+    fn connection(&self) -> std::sync::MutexGuard<'_, rusqlite::Connection> {
+        self.connection.lock().expect("sqlite connection lock poisoned")
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl KakarotStore for SqliteStore {
+    fn get(&self, namespace: &str, key: &[u8]) -> Result<Option<Vec<u8>>, StoreError> {
+        use rusqlite::OptionalExtension;
+
+        self.connection()
+            .query_row("SELECT value FROM entries WHERE namespace = ?1 AND key = ?2", (namespace, key), |row| {
+                row.get(0)
+            })
+            .optional()
+            .map_err(|err| StoreError::Backend(err.to_string()))
+    }
+
+    fn put(&self, namespace: &str, key: &[u8], value: &[u8]) -> Result<(), StoreError> {
+        self.connection()
+            .execute(
+                "INSERT OR REPLACE INTO entries (namespace, key, value) VALUES (?1, ?2, ?3)",
+                (namespace, key, value),
+            )
+            .map_err(|err| StoreError::Backend(err.to_string()))?;
+        Ok(())
+    }
+
+    fn delete(&self, namespace: &str, key: &[u8]) -> Result<(), StoreError> {
+        self.connection()
+            .execute("DELETE FROM entries WHERE namespace = ?1 AND key = ?2", (namespace, key))
+            .map_err(|err| StoreError::Backend(err.to_string()))?;
+        Ok(())
+    }
+
+    fn entries(&self, namespace: &str) -> Result<Vec<(Vec<u8>, Vec<u8>)>, StoreError> {
+        let connection = self.connection();
+        let mut statement = connection
+            .prepare("SELECT key, value FROM entries WHERE namespace = ?1 ORDER BY key")
+            .map_err(|err| StoreError::Backend(err.to_string()))?;
+        let entries = statement
+            .query_map([namespace], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|err| StoreError::Backend(err.to_string()))?
+            .collect::<Result<_, _>>()
+            .map_err(|err| StoreError::Backend(err.to_string()))?;
+        Ok(entries)
+    }
+
+    fn count(&self, namespace: &str) -> Result<usize, StoreError> {
+        self.connection()
+            .query_row("SELECT COUNT(*) FROM entries WHERE namespace = ?1", [namespace], |row| row.get::<_, i64>(0))
+            .map(|count| count as usize)
+            .map_err(|err| StoreError::Backend(err.to_string()))
+    }
+}
+
+/// Storage backend of the indexes, `memory` by default.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum StoreConfig {
+    #[default]
+    Memory,
+    Sled(PathBuf),
+    Sqlite(PathBuf),
+}
+
+impl FromStr for StoreConfig {
+    type Err = StoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "memory" => Ok(Self::Memory),
+            Some(("sled", path)) if !path.is_empty() => Ok(Self::Sled(path.into())),
+            Some(("sqlite", path)) if !path.is_empty() => Ok(Self::Sqlite(path.into())),
+            _ => Err(StoreError::InvalidConfig(s.to_string())),
+        }
+    }
+}
+
+impl StoreConfig {
+    /// Opens the store and migrates it to the current schema version, `None` for the in memory
+    /// backend as the indexes already keep their entries in memory.
+    pub fn open(&self) -> Result<Option<Arc<dyn KakarotStore>>, StoreError> {
+        let store = self.open_unmigrated()?;
+        if let Some(store) = &store {
+            migrate(store.as_ref())?;
+        }
+        Ok(store)
+    }
+
+    /// Opens the store as is, e.g. to report its status before migrating it.
+    pub fn open_unmigrated(&self) -> Result<Option<Arc<dyn KakarotStore>>, StoreError> {
+        match self {
+            Self::Memory => Ok(None),
+            #[cfg(feature = "sled")]
+            Self::Sled(path) => Ok(Some(Arc::new(SledStore::open(path)?))),
+            #[cfg(not(feature = "sled"))]
+            Self::Sled(_) => Err(StoreError::Unavailable("sled")),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(path) => Ok(Some(Arc::new(SqliteStore::open(path)?))),
+            #[cfg(not(feature = "sqlite"))]
+            Self::Sqlite(_) => Err(StoreError::Unavailable("sqlite")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_config_from_str() {
+        assert_eq!(StoreConfig::Memory, "memory".parse().unwrap());
+        assert_eq!(StoreConfig::Sled("/tmp/kakarot".into()), "sled:/tmp/kakarot".parse().unwrap());
+        assert_eq!(StoreConfig::Sqlite("kakarot.db".into()), "sqlite:kakarot.db".parse().unwrap());
+        assert!("postgres://localhost".parse::<StoreConfig>().is_err());
+        assert!("sled:".parse::<StoreConfig>().is_err());
+    }
+
+    #[test]
+    fn test_in_memory_store() {
+        // Given
+        let store = InMemoryStore::default();
+
+        // When
+        store.put("a", b"2", b"two").unwrap();
+        store.put("a", b"1", b"one").unwrap();
+        store.put("b", b"1", b"other").unwrap();
+        store.delete("a", b"2").unwrap();
+
+        // Then
+        assert_eq!(Some(b"one".to_vec()), store.get("a", b"1").unwrap());
+        assert_eq!(None, store.get("a", b"2").unwrap());
+        assert_eq!(vec![(b"1".to_vec(), b"other".to_vec())], store.entries("b").unwrap());
+        assert!(store.entries("c").unwrap().is_empty());
+        assert_eq!(1, store.count("a").unwrap());
+        assert_eq!(0, store.count("c").unwrap());
+    }
+}
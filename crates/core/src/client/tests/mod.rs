@@ -1,25 +1,40 @@
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 
 use dojo_test_utils::rpc::MockJsonRpcTransport;
-use reth_primitives::{BlockId, BlockNumberOrTag, Bytes, H256, U256, U64};
+use reth_primitives::{
+    BlockId, BlockNumberOrTag, Bytes, Signature, Transaction, TransactionKind, TransactionSigned, TxEip1559, H256,
+    U256, U64,
+};
 use reth_rpc_types::CallRequest;
-use starknet::core::types::{BlockId as StarknetBlockId, BlockTag, BroadcastedInvokeTransactionV1};
-use starknet::providers::jsonrpc::JsonRpcMethod;
+use serde_json::{json, Value};
+use starknet::core::types::{BlockId as StarknetBlockId, BlockTag, BroadcastedInvokeTransactionV1, StarknetError};
+use starknet::providers::jsonrpc::{HttpTransport, JsonRpcMethod};
 use starknet::providers::sequencer::models::BlockId as SequencerBlockId;
-use starknet::providers::{JsonRpcClient, SequencerGatewayProvider};
+use starknet::providers::{JsonRpcClient, ProviderError, SequencerGatewayProvider};
 use starknet_crypto::FieldElement;
+use url::Url;
+use wiremock::matchers::{body_partial_json, method};
+use wiremock::{Mock, MockServer, ResponseTemplate};
 
-use super::config::{Network, SequencerGatewayProviderBuilder};
+use super::config::{
+    FeeRetryPolicy, Network, OperatingMode, SequencerGatewayProviderBuilder, DEFAULT_LIGHT_HISTORY_BLOCKS,
+};
 use crate::client::api::{KakarotEthApi, KakarotStarknetApi};
+use crate::client::compat::SpecVersion;
 use crate::client::config::StarknetConfig;
 use crate::client::constants::{CHAIN_ID, COUNTER_ADDRESS_TESTNET1, INC_SELECTOR};
+use crate::client::errors::{ConfigError, EthApiError};
 use crate::client::KakarotClient;
+use crate::contracts::contract_account::ContractAccount;
 use crate::mock::constants::{
     ABDEL_ETHEREUM_ADDRESS, ABDEL_STARKNET_ADDRESS, ABDEL_STARKNET_ADDRESS_HEX, ACCOUNT_ADDRESS, ACCOUNT_ADDRESS_EVM,
     COUNTER_ADDRESS_EVM, INC_DATA, KAKAROT_ADDRESS, KAKAROT_TESTNET_ADDRESS, PROXY_ACCOUNT_CLASS_HASH,
     PROXY_ACCOUNT_CLASS_HASH_HEX,
 };
 use crate::mock::mock_starknet::{fixtures, mock_starknet_provider, AvailableFixtures, StarknetRpcFixture};
+use crate::models::filter::LogFilter;
 use crate::wrap_kakarot;
 
 pub fn init_testnet_client() -> KakarotClient<SequencerGatewayProvider> {
@@ -27,7 +42,7 @@ pub fn init_testnet_client() -> KakarotClient<SequencerGatewayProvider> {
     let config = StarknetConfig::new(Network::Goerli1Gateway, kakarot_address, Default::default());
 
     let provider = SequencerGatewayProviderBuilder::new(&Network::Goerli1Gateway).build();
-    KakarotClient::new(config, provider)
+    KakarotClient::builder().with_starknet_config(config).with_provider(provider).build().unwrap()
 }
 
 pub fn init_mock_client(
@@ -36,7 +51,7 @@ pub fn init_mock_client(
     let config = StarknetConfig::new(Network::Katana, *KAKAROT_ADDRESS, *PROXY_ACCOUNT_CLASS_HASH);
     let starknet_provider = mock_starknet_provider(fixtures);
 
-    KakarotClient::new(config, starknet_provider)
+    KakarotClient::builder().with_starknet_config(config).with_provider(starknet_provider).build().unwrap()
 }
 
 #[tokio::test]
@@ -52,6 +67,76 @@ async fn test_block_number() {
     assert_eq!(U64::from(19640), block_number);
 }
 
+#[tokio::test]
+async fn test_fork_pins_latest_to_fork_head() {
+    // Given
+    let fixtures = fixtures(vec![wrap_kakarot!(JsonRpcMethod::BlockNumber)]);
+    let mut config = StarknetConfig::new(Network::Katana, *KAKAROT_ADDRESS, *PROXY_ACCOUNT_CLASS_HASH);
+    config.fork_block = Some(20000);
+    let client = KakarotClient::builder()
+        .with_starknet_config(config)
+        .with_provider(mock_starknet_provider(Some(fixtures)))
+        .build()
+        .unwrap();
+
+    // When
+    let block_number = client.block_number().await.unwrap();
+    let latest = client.starknet_block_id(BlockId::Number(BlockNumberOrTag::Latest)).await.unwrap();
+    let pending = client.starknet_block_id(BlockId::Number(BlockNumberOrTag::Pending)).await.unwrap();
+
+    // Then
+    // The fork numbers its blocks from 19640, below the block it forked
+    assert_eq!(U64::from(20000), block_number);
+    assert_eq!(StarknetBlockId::Number(20000), latest);
+    assert_eq!(StarknetBlockId::Tag(BlockTag::Pending), pending);
+}
+
+#[test]
+fn test_client_builder_overrides_network_defaults() {
+    // Given
+    let builder = || {
+        KakarotClient::builder()
+            .with_network(Network::Katana)
+            .with_kakarot_address(*KAKAROT_ADDRESS)
+            .with_proxy_account_class_hash(*PROXY_ACCOUNT_CLASS_HASH)
+    };
+
+    // When
+    let client = builder().with_provider(mock_starknet_provider(None)).with_call_cache_capacity(0).build().unwrap();
+    let missing = builder().build();
+
+    // Then
+    assert_eq!(*KAKAROT_ADDRESS, client.kakarot_address());
+    assert!(!client.call_cache.is_enabled());
+    assert!(matches!(missing, Err(ConfigError::MissingClientOption(option)) if option == "provider"));
+}
+
+#[test]
+fn test_capabilities_follow_the_components_of_the_client() {
+    // Given
+    let client = |mode| {
+        KakarotClient::builder()
+            .with_network(Network::Katana)
+            .with_kakarot_address(*KAKAROT_ADDRESS)
+            .with_proxy_account_class_hash(*PROXY_ACCOUNT_CLASS_HASH)
+            .with_operating_mode(mode)
+            .with_provider(mock_starknet_provider(None))
+            .build()
+            .unwrap()
+    };
+
+    // When
+    let light = client(OperatingMode::Light).capabilities();
+    let archive = client(OperatingMode::Archive).capabilities();
+
+    // Then
+    assert_eq!(Some(DEFAULT_LIGHT_HISTORY_BLOCKS), light.history_blocks);
+    assert!(!light.transaction_index);
+    assert_eq!(OperatingMode::Archive, archive.mode);
+    assert_eq!(None, archive.history_blocks);
+    assert!(archive.transaction_index);
+}
+
 #[tokio::test]
 async fn test_nonce() {
     // Given
@@ -89,13 +174,13 @@ async fn test_fee_history() {
     let count = 10;
     let block_count = U256::from(count);
     let newest_block = BlockNumberOrTag::Latest;
-    let fee_history = client.fee_history(block_count, newest_block, None).await.unwrap();
+    let fee_history = client.fee_history(block_count, newest_block, Some(vec![25., 75.])).await.unwrap();
 
     // Then
     assert_eq!(vec![U256::from(1); count + 1], fee_history.base_fee_per_gas);
     assert_eq!(vec![0.9; count], fee_history.gas_used_ratio);
     assert_eq!(U256::from(19630), fee_history.oldest_block);
-    assert_eq!((Some(vec![vec![]])), fee_history.reward);
+    assert_eq!(Some(vec![vec![U256::ZERO; 2]; count]), fee_history.reward);
 }
 
 #[tokio::test]
@@ -212,3 +297,231 @@ async fn test_gas_price() {
     // Then
     assert!(gas_price > U256::from(0));
 }
+
+/// Mounts a Starknet JSON-RPC method answering `response`, at most `times` times if set.
+async fn mount_starknet_method(mock_server: &MockServer, name: &str, mut response: Value, times: Option<u64>) {
+    response["jsonrpc"] = "2.0".into();
+    response["id"] = 1.into();
+    let mock = Mock::given(method("POST"))
+        .and(body_partial_json(json!({ "method": name })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(response));
+    match times {
+        Some(times) => mock.up_to_n_times(times).expect(times).mount(mock_server).await,
+        None => mock.mount(mock_server).await,
+    }
+}
+
+#[tokio::test]
+async fn test_submission_is_retried_with_a_new_max_fee_after_an_insufficient_max_fee() {
+    // Given
+    let mock_server = MockServer::start().await;
+    let insufficient_max_fee =
+        json!({ "error": { "code": 53, "message": "Max fee is smaller than the minimal transaction cost" } });
+    mount_starknet_method(&mock_server, "starknet_addInvokeTransaction", insufficient_max_fee, Some(1)).await;
+    let accepted = json!({ "result": { "transaction_hash": "0x1234" } });
+    mount_starknet_method(&mock_server, "starknet_addInvokeTransaction", accepted, Some(1)).await;
+    mount_starknet_method(&mock_server, "starknet_getNonce", json!({ "result": "0x0" }), None).await;
+    let fee_estimate = json!({ "result": [{ "gas_consumed": "0x64", "gas_price": "0xa", "overall_fee": "0x3e8" }] });
+    mount_starknet_method(&mock_server, "starknet_estimateFee", fee_estimate, None).await;
+
+    let url = Url::parse(&mock_server.uri()).unwrap();
+    let mut config =
+        StarknetConfig::new(Network::JsonRpcProvider(url.clone()), *KAKAROT_ADDRESS, *PROXY_ACCOUNT_CLASS_HASH);
+    config.fee_retry_policy =
+        FeeRetryPolicy { max_retries: 1, base_delay: Duration::ZERO, fee_multiplier_percent: 150 };
+    let client = KakarotClient::builder()
+        .with_starknet_config(config)
+        .with_provider(JsonRpcClient::new(HttpTransport::new(url)))
+        .build()
+        .unwrap();
+    let request = BroadcastedInvokeTransactionV1 {
+        sender_address: FieldElement::ONE,
+        calldata: vec![],
+        max_fee: FieldElement::ONE,
+        nonce: FieldElement::ZERO,
+        signature: vec![],
+    };
+
+    // When
+    let hash = client.submit_sequenced_starknet_transaction(request, *ABDEL_ETHEREUM_ADDRESS, 0, false).await.unwrap();
+
+    // Then
+    assert_eq!(H256::from_low_u64_be(0x1234), hash);
+    let submissions: Vec<Value> = mock_server
+        .received_requests()
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(|request| serde_json::from_slice::<Value>(&request.body).ok())
+        .filter(|body| body["method"] == "starknet_addInvokeTransaction")
+        .collect();
+    assert_eq!(2, submissions.len());
+    // 150% of the re-estimated fee of 1000
+    assert!(submissions[1].to_string().contains(r#""max_fee":"0x5dc""#));
+}
+
+#[tokio::test]
+async fn test_concurrent_submissions_of_a_transaction_are_relayed_once() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mount_starknet_method(&mock_server, "starknet_call", json!({ "result": [ABDEL_STARKNET_ADDRESS_HEX] }), None).await;
+    mount_starknet_method(&mock_server, "starknet_getNonce", json!({ "result": "0x0" }), None).await;
+    let accepted = json!({ "result": { "transaction_hash": "0x1234" } });
+    mount_starknet_method(&mock_server, "starknet_addInvokeTransaction", accepted, None).await;
+
+    let url = Url::parse(&mock_server.uri()).unwrap();
+    let config =
+        StarknetConfig::new(Network::JsonRpcProvider(url.clone()), *KAKAROT_ADDRESS, *PROXY_ACCOUNT_CLASS_HASH);
+    let client = Arc::new(
+        KakarotClient::builder()
+            .with_starknet_config(config)
+            .with_provider(JsonRpcClient::new(HttpTransport::new(url)))
+            .build()
+            .unwrap(),
+    );
+    let transaction = TransactionSigned::from_transaction_and_signature(
+        Transaction::Eip1559(TxEip1559 {
+            chain_id: CHAIN_ID,
+            nonce: 0,
+            gas_limit: 21_000,
+            max_fee_per_gas: 10,
+            max_priority_fee_per_gas: 1,
+            to: TransactionKind::Call(*COUNTER_ADDRESS_EVM),
+            value: 0,
+            access_list: Default::default(),
+            input: Bytes::from_str(INC_DATA).unwrap(),
+        }),
+        Signature::default(),
+    );
+    let mut bytes = vec![];
+    transaction.encode_enveloped(&mut bytes);
+    let bytes = Bytes::from(bytes);
+
+    // When
+    let submissions = (0..2).map(|_| {
+        let (client, bytes, transaction) = (client.clone(), bytes.clone(), transaction.clone());
+        tokio::spawn(
+            async move { client.relay_transaction(&bytes, &transaction, *ABDEL_ETHEREUM_ADDRESS, false).await },
+        )
+    });
+    let results: Vec<_> = futures::future::join_all(submissions).await.into_iter().map(Result::unwrap).collect();
+
+    // Then
+    assert_eq!(1, results.iter().filter(|result| result.is_ok()).count());
+    assert_eq!(1, results.iter().filter(|result| matches!(result, Err(EthApiError::AlreadyKnown(_)))).count());
+    let relays = mock_server
+        .received_requests()
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(|request| serde_json::from_slice::<Value>(&request.body).ok())
+        .filter(|body| body["method"] == "starknet_addInvokeTransaction")
+        .count();
+    assert_eq!(1, relays);
+}
+
+#[tokio::test]
+async fn test_failed_spec_version_probe_is_not_cached() {
+    // Given
+    let mock_server = MockServer::start().await;
+    let internal_error = json!({ "error": { "code": -32603, "message": "Internal error" } });
+    mount_starknet_method(&mock_server, "starknet_specVersion", internal_error, Some(1)).await;
+    mount_starknet_method(&mock_server, "starknet_specVersion", json!({ "result": "0.6.0" }), Some(1)).await;
+
+    let url = Url::parse(&mock_server.uri()).unwrap();
+    let config =
+        StarknetConfig::new(Network::JsonRpcProvider(url.clone()), *KAKAROT_ADDRESS, *PROXY_ACCOUNT_CLASS_HASH);
+    let client = KakarotClient::builder()
+        .with_starknet_config(config)
+        .with_provider(JsonRpcClient::new(HttpTransport::new(url)))
+        .build()
+        .unwrap();
+
+    // When
+    let failed = client.spec_version().await;
+    let probed = client.spec_version().await;
+    let cached = client.spec_version().await;
+
+    // Then
+    assert_eq!(None, failed);
+    assert_eq!(Some(SpecVersion::V0_6), probed);
+    assert_eq!(Some(SpecVersion::V0_6), cached);
+}
+
+#[tokio::test]
+async fn test_storage_is_empty_only_before_the_deployment() {
+    // Given
+    let (not_deployed, failing) = (MockServer::start().await, MockServer::start().await);
+    let contract_not_found = json!({ "error": { "code": 20, "message": "Contract not found" } });
+    mount_starknet_method(&not_deployed, "starknet_call", contract_not_found, None).await;
+    let contract_error = json!({ "error": { "code": 40, "message": "Contract error" } });
+    mount_starknet_method(&failing, "starknet_call", contract_error, None).await;
+    let provider =
+        |mock_server: &MockServer| JsonRpcClient::new(HttpTransport::new(Url::parse(&mock_server.uri()).unwrap()));
+    let contract_account = ContractAccount::new(FieldElement::ONE);
+    let block_id = StarknetBlockId::Tag(BlockTag::Latest);
+
+    // When
+    let empty =
+        contract_account.storage(&provider(&not_deployed), &FieldElement::ZERO, &FieldElement::ZERO, &block_id).await;
+    let failed =
+        contract_account.storage(&provider(&failing), &FieldElement::ZERO, &FieldElement::ZERO, &block_id).await;
+
+    // Then
+    assert_eq!(U256::ZERO, empty.unwrap());
+    assert!(matches!(
+        failed,
+        Err(EthApiError::RequestError(ProviderError::StarknetError(StarknetError::ContractError)))
+    ));
+}
+
+/// Starts a Starknet endpoint returning a single page with one Kakarot event per block number,
+/// and a client returning at most 2 logs.
+async fn events_client(block_numbers: &[u64]) -> (MockServer, KakarotClient<JsonRpcClient<HttpTransport>>) {
+    let mock_server = MockServer::start().await;
+    let events: Vec<Value> = block_numbers
+        .iter()
+        .map(|block_number| {
+            json!({
+                "from_address": format!("{:#x}", *KAKAROT_ADDRESS),
+                "keys": ["0x1"],
+                "data": [],
+                "block_hash": format!("{block_number:#x}"),
+                "block_number": block_number,
+                "transaction_hash": "0x1234",
+            })
+        })
+        .collect();
+    mount_starknet_method(&mock_server, "starknet_getEvents", json!({ "result": { "events": events } }), None).await;
+
+    let url = Url::parse(&mock_server.uri()).unwrap();
+    let mut config =
+        StarknetConfig::new(Network::JsonRpcProvider(url.clone()), *KAKAROT_ADDRESS, *PROXY_ACCOUNT_CLASS_HASH);
+    config.max_logs = 2;
+    let client = KakarotClient::builder()
+        .with_starknet_config(config)
+        .with_provider(JsonRpcClient::new(HttpTransport::new(url)))
+        .build()
+        .unwrap();
+    (mock_server, client)
+}
+
+#[tokio::test]
+async fn test_too_many_logs_in_a_single_block_asks_to_narrow_the_filter() {
+    // Given
+    let filter = LogFilter {
+        from_block: Some(BlockNumberOrTag::Number(10)),
+        to_block: Some(BlockNumberOrTag::Number(11)),
+        ..Default::default()
+    };
+    let (_spread_server, spread) = events_client(&[10, 10, 11]).await;
+    let (_crowded_server, crowded) = events_client(&[10, 10, 10]).await;
+
+    // When
+    let spread = spread.get_logs(filter.clone()).await;
+    let crowded = crowded.get_logs(filter).await;
+
+    // Then
+    assert!(matches!(spread, Err(EthApiError::TooManyLogs { limit: 2, from_block: 10, to_block: 10 })));
+    assert!(matches!(crowded, Err(EthApiError::TooManyLogsInBlock { limit: 2, block_number: 10 })));
+}
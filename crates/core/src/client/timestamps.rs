@@ -0,0 +1,102 @@
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::RwLock;
+
+use super::stats::{CacheCounters, CacheStats};
+
+/// Number of block timestamps kept in cache.
+pub const DEFAULT_BLOCK_TIMESTAMPS_CACHE_CAPACITY: usize = 4096;
+
+/// Cache of the reported timestamps by block number, evicting the oldest entries first.
+/// Timestamps of accepted blocks never change, and the blocks probed by the searches of close
+/// timestamps are mostly the same ones.
+#[derive(Debug)]
+pub struct BlockTimestampsCache {
+    capacity: usize,
+    state: RwLock<(HashMap<u64, u64>, VecDeque<u64>)>,
+    counters: CacheCounters,
+}
+
+impl Default for BlockTimestampsCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_BLOCK_TIMESTAMPS_CACHE_CAPACITY)
+    }
+}
+
+impl BlockTimestampsCache {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, state: RwLock::default(), counters: CacheCounters::default() }
+    }
+
+    pub fn get(&self, block_number: u64) -> Option<u64> {
+        self.counters
+            .record(self.state.read().expect("block timestamps cache lock poisoned").0.get(&block_number).copied())
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.counters.stats(self.state.read().expect("block timestamps cache lock poisoned").0.len())
+    }
+
+    pub fn insert(&self, block_number: u64, timestamp: u64) {
+        let mut state = self.state.write().expect("block timestamps cache lock poisoned");
+        let (timestamps, order) = &mut *state;
+        if timestamps.insert(block_number, timestamp).is_none() {
+            order.push_back(block_number);
+        }
+        while order.len() > self.capacity {
+            if let Some(evicted) = order.pop_front() {
+                timestamps.remove(&evicted);
+            }
+        }
+    }
+}
+
+/// Returns the last block up to `head` whose timestamp is at or before `timestamp`, or `None` if
+/// the genesis block is after it. Block timestamps being non decreasing, the block is found by
+/// binary search, fetching the timestamps of about `log2(head)` blocks.
+pub async fn search_block_by_timestamp<E, F, Fut>(
+    timestamp: u64,
+    head: u64,
+    mut block_timestamp: F,
+) -> Result<Option<u64>, E>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Result<u64, E>>,
+{
+    if block_timestamp(0).await? > timestamp {
+        return Ok(None);
+    }
+
+    // Block `low` is at or before the timestamp, the blocks after `high` are after it
+    let (mut low, mut high) = (0, head);
+    while low < high {
+        let middle = low + (high - low + 1) / 2;
+        if block_timestamp(middle).await? <= timestamp {
+            low = middle;
+        } else {
+            high = middle - 1;
+        }
+    }
+    Ok(Some(low))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_search_block_by_timestamp() {
+        // Given
+        let timestamps = [10, 20, 20, 30, 45];
+        let search = |timestamp| {
+            search_block_by_timestamp(timestamp, 4, |number| async move { Ok::<_, ()>(timestamps[number as usize]) })
+        };
+
+        // Then
+        assert_eq!(Ok(None), search(5).await);
+        assert_eq!(Ok(Some(0)), search(10).await);
+        assert_eq!(Ok(Some(2)), search(20).await);
+        assert_eq!(Ok(Some(2)), search(25).await);
+        assert_eq!(Ok(Some(4)), search(100).await);
+    }
+}
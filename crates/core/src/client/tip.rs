@@ -0,0 +1,78 @@
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use tokio::sync::Mutex;
+
+/// Default time the latest block number is served from cache.
+pub const DEFAULT_BLOCK_NUMBER_TTL: Duration = Duration::from_millis(250);
+
+/// Cache of the latest block number, polled several times per second by wallets and indexers.
+///
+/// Concurrent requests are deduplicated: while the block number is fetched, the other requests
+/// wait for it instead of fetching it again.
+#[derive(Debug)]
+pub struct BlockNumberCache {
+    ttl: Duration,
+    latest: Mutex<Option<(u64, Instant)>>,
+}
+
+impl Default for BlockNumberCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_BLOCK_NUMBER_TTL)
+    }
+}
+
+impl BlockNumberCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, latest: Mutex::new(None) }
+    }
+
+    /// Returns the cached block number if it is fresh, otherwise fetches it. Failed fetches are
+    /// not cached.
+    pub async fn get_or_fetch<F, Fut, E>(&self, fetch: F) -> Result<u64, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<u64, E>>,
+    {
+        let mut latest = self.latest.lock().await;
+        if let Some((block_number, fetched_at)) = *latest {
+            if fetched_at.elapsed() < self.ttl {
+                return Ok(block_number);
+            }
+        }
+        let block_number = fetch().await?;
+        *latest = Some((block_number, Instant::now()));
+        Ok(block_number)
+    }
+
+    /// Forgets the cached block number, e.g. after relaying a transaction so that its sender
+    /// reads the block including it.
+    pub async fn invalidate(&self) {
+        *self.latest.lock().await = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_block_number_cache_single_flight() {
+        // Given
+        let cache = BlockNumberCache::new(Duration::from_secs(60));
+        let fetches = AtomicU64::new(0);
+        let fetch = || async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Ok::<_, ()>(fetches.fetch_add(1, Ordering::SeqCst) + 100)
+        };
+
+        // When
+        let (first, second) = tokio::join!(cache.get_or_fetch(fetch), cache.get_or_fetch(fetch));
+
+        // Then
+        assert_eq!((Ok(100), Ok(100)), (first, second));
+        assert_eq!(1, fetches.load(Ordering::SeqCst));
+    }
+}
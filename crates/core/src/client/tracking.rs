@@ -0,0 +1,735 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reth_primitives::{Address, H256};
+use serde::{Deserialize, Serialize};
+use starknet::core::types::TransactionStatus as StarknetTransactionStatus;
+
+use super::store::{KakarotStore, TRANSACTIONS_NAMESPACE};
+
+/// Maximum number of transactions kept by the tracker before the oldest ones are evicted.
+pub const DEFAULT_TRACKER_CAPACITY: usize = 10_000;
+/// Default time the transactions relayed for a sender are reflected in its pending nonce.
+pub const DEFAULT_READ_YOUR_WRITES_WINDOW: Duration = Duration::from_secs(60);
+/// Default time a relayed transaction has to be accepted on Starknet before being dropped.
+pub const DEFAULT_TRANSACTION_TTL: Duration = Duration::from_secs(600);
+/// Minimum fee increase, in percent, of a transaction replacing a pending one, as in Geth.
+pub const REPLACEMENT_PRICE_BUMP_PERCENT: u128 = 10;
+
+/// The lifecycle status of a transaction relayed to Starknet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RelayStatus {
+    /// The raw transaction was received by the RPC.
+    Received,
+    /// The Starknet invoke transaction was submitted to the Starknet provider.
+    SentToStarknet,
+    /// The transaction was accepted on L2.
+    AcceptedOnL2,
+    /// The transaction was accepted on L1.
+    AcceptedOnL1,
+    /// The transaction was rejected, either by the RPC, the Starknet provider or the sequencer.
+    Rejected,
+    /// The transaction was superseded by a transaction with the same sender and nonce and higher
+    /// fees before being accepted.
+    Replaced,
+    /// The Starknet invoke transaction was not accepted within the transaction TTL. Its nonce is
+    /// freed, but Starknet may still include it later.
+    Dropped,
+}
+
+impl RelayStatus {
+    /// Returns true if the status cannot change anymore.
+    pub fn is_final(&self) -> bool {
+        matches!(self, RelayStatus::AcceptedOnL1 | RelayStatus::Rejected | RelayStatus::Replaced)
+    }
+
+    /// Returns true if a transaction with the same sender and nonce can still replace it.
+    pub fn is_replaceable(&self) -> bool {
+        matches!(self, RelayStatus::Received | RelayStatus::SentToStarknet)
+    }
+
+    /// Returns true if the same transaction can be submitted again, restarting its lifecycle.
+    pub fn is_resubmittable(&self) -> bool {
+        matches!(self, RelayStatus::Rejected | RelayStatus::Dropped)
+    }
+}
+
+impl From<StarknetTransactionStatus> for RelayStatus {
+    fn from(status: StarknetTransactionStatus) -> Self {
+        match status {
+            StarknetTransactionStatus::Pending => RelayStatus::SentToStarknet,
+            StarknetTransactionStatus::AcceptedOnL2 => RelayStatus::AcceptedOnL2,
+            StarknetTransactionStatus::AcceptedOnL1 => RelayStatus::AcceptedOnL1,
+            StarknetTransactionStatus::Rejected => RelayStatus::Rejected,
+        }
+    }
+}
+
+/// A status change of a tracked transaction, timestamped in milliseconds since the unix epoch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LifecycleEvent {
+    pub status: RelayStatus,
+    pub timestamp: u64,
+}
+
+/// Fees offered by a transaction, in WEI per gas. Legacy transactions offer their gas price for
+/// both.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionFees {
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+}
+
+impl TransactionFees {
+    /// Returns true if both fees are bumped by at least [`REPLACEMENT_PRICE_BUMP_PERCENT`].
+    pub fn can_replace(&self, pending: &TransactionFees) -> bool {
+        let bumped = |fee: u128| fee.saturating_mul(100 + REPLACEMENT_PRICE_BUMP_PERCENT) / 100;
+        self.max_fee_per_gas >= bumped(pending.max_fee_per_gas)
+            && self.max_priority_fee_per_gas >= bumped(pending.max_priority_fee_per_gas)
+    }
+}
+
+/// Error raised when a transaction does not pay enough to replace a pending one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("replacement transaction underpriced")]
+pub struct ReplacementUnderpriced;
+
+/// Error raised when a transaction is submitted again while being relayed or once included, with
+/// the hash returned by its first submission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("already known")]
+pub struct AlreadyKnown(pub H256);
+
+/// Error raised when a received transaction is not relayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum ReceiveError {
+    #[error(transparent)]
+    AlreadyKnown(#[from] AlreadyKnown),
+    #[error(transparent)]
+    ReplacementUnderpriced(#[from] ReplacementUnderpriced),
+}
+
+/// A transaction relayed by the RPC along with its lifecycle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackedTransaction {
+    /// Hash of the signed Ethereum transaction.
+    pub eth_hash: H256,
+    /// Hash of the Starknet invoke transaction, once submitted.
+    pub starknet_hash: Option<H256>,
+    pub sender: Address,
+    pub nonce: u64,
+    pub fees: TransactionFees,
+    pub status: RelayStatus,
+    /// Hash of the transaction replacing this one, if any.
+    pub replaced_by: Option<H256>,
+    /// Reason of the rejection, if any.
+    pub error: Option<String>,
+    pub events: Vec<LifecycleEvent>,
+    /// Sent through `eth_sendPrivateRawTransaction`, withheld from the pending responses.
+    #[serde(default)]
+    pub private: bool,
+}
+
+impl TrackedTransaction {
+    /// Returns true if the transaction is private and not included in a block yet.
+    pub fn is_withheld(&self) -> bool {
+        self.private && !matches!(self.status, RelayStatus::AcceptedOnL2 | RelayStatus::AcceptedOnL1)
+    }
+}
+
+/// Counters of the tracked transactions per status.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayStats {
+    pub received: u64,
+    pub sent_to_starknet: u64,
+    pub accepted_on_l2: u64,
+    pub accepted_on_l1: u64,
+    pub rejected: u64,
+    pub replaced: u64,
+    #[serde(default)]
+    pub dropped: u64,
+    /// Number of transactions currently tracked.
+    pub tracked: u64,
+}
+
+#[derive(Default)]
+struct TrackerState {
+    transactions: HashMap<H256, TrackedTransaction>,
+    /// Maps Starknet transaction hashes to Ethereum transaction hashes.
+    starknet_hashes: HashMap<H256, H256>,
+    /// Maps senders and nonces to the hash of the latest transaction received for them.
+    sender_nonces: HashMap<(Address, u64), H256>,
+    /// Insertion order, used to evict the oldest transactions.
+    order: VecDeque<H256>,
+    stats: RelayStats,
+}
+
+/// In memory store recording the lifecycle of the transactions relayed to Starknet, optionally
+/// written through to a persistent store so that it survives restarts.
+pub struct TransactionTracker {
+    capacity: usize,
+    /// Time a transaction has to be accepted on Starknet before being dropped.
+    ttl: Duration,
+    state: RwLock<TrackerState>,
+    store: Option<Arc<dyn KakarotStore>>,
+}
+
+impl Default for TransactionTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_TRACKER_CAPACITY)
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or_default()
+}
+
+impl TransactionTracker {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, ttl: DEFAULT_TRANSACTION_TTL, state: RwLock::new(TrackerState::default()), store: None }
+    }
+
+    /// Sets the time a transaction has to be accepted on Starknet before being dropped.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Creates a tracker persisting the tracked transactions to a store, restoring the ones
+    /// recorded before a restart. Counters are rebuilt from the restored transactions.
+    pub fn with_store(capacity: usize, store: Arc<dyn KakarotStore>) -> Self {
+        let mut restored: Vec<TrackedTransaction> = match store.entries(TRANSACTIONS_NAMESPACE) {
+            Ok(entries) => entries.into_iter().filter_map(|(_, value)| serde_json::from_slice(&value).ok()).collect(),
+            Err(err) => {
+                tracing::warn!("Failed to restore the tracked transactions: {err}");
+                vec![]
+            }
+        };
+        restored.sort_by_key(|tracked| tracked.events.first().map(|event| event.timestamp));
+
+        let mut state = TrackerState::default();
+        for tracked in restored {
+            for event in &tracked.events {
+                state.stats.count(event.status);
+            }
+            if let Some(starknet_hash) = tracked.starknet_hash {
+                state.starknet_hashes.insert(starknet_hash, tracked.eth_hash);
+            }
+            state.sender_nonces.insert((tracked.sender, tracked.nonce), tracked.eth_hash);
+            state.order.push_back(tracked.eth_hash);
+            state.transactions.insert(tracked.eth_hash, tracked);
+        }
+        state.stats.tracked = state.transactions.len() as u64;
+
+        Self { capacity, ttl: DEFAULT_TRANSACTION_TTL, state: RwLock::new(state), store: Some(store) }
+    }
+
+    /// Returns the hash of the pending transaction a new transaction with the given sender, nonce
+    /// and fees replaces, if any. Fails if the new transaction does not bump the fees of the
+    /// pending one enough.
+    fn replacement_of(
+        state: &TrackerState,
+        eth_hash: H256,
+        sender: Address,
+        nonce: u64,
+        fees: &TransactionFees,
+    ) -> Result<Option<H256>, ReplacementUnderpriced> {
+        let pending = state
+            .sender_nonces
+            .get(&(sender, nonce))
+            .filter(|pending_hash| **pending_hash != eth_hash)
+            .and_then(|pending_hash| state.transactions.get(pending_hash))
+            .filter(|pending| pending.status.is_replaceable());
+
+        match pending {
+            Some(pending) if fees.can_replace(&pending.fees) => Ok(Some(pending.eth_hash)),
+            Some(_) => Err(ReplacementUnderpriced),
+            None => Ok(None),
+        }
+    }
+
+    /// Records a newly received transaction, returning the hash of the pending transaction with
+    /// the same sender and nonce it replaces, if any. A rejected or dropped transaction submitted
+    /// again is received again, keeping its past events, so that its new submission is tracked.
+    ///
+    /// Fails if the transaction was already submitted and neither rejected nor dropped since, so
+    /// that it is not relayed twice, with the Starknet transaction hash returned by the first
+    /// submission, or the Ethereum hash if it was not submitted to Starknet yet. Fails as well if
+    /// it does not bump the fees of the pending transaction it replaces enough. The checks and the
+    /// record are made under the same lock, so that of two concurrent submissions of a
+    /// transaction, or of two replacements of a pending one, only one is relayed.
+    pub fn received(
+        &self,
+        eth_hash: H256,
+        sender: Address,
+        nonce: u64,
+        fees: TransactionFees,
+    ) -> Result<Option<H256>, ReceiveError> {
+        let mut state = self.state.write().expect("transaction tracker lock poisoned");
+        if let Some(tracked) = state.transactions.get(&eth_hash).filter(|tracked| !tracked.status.is_resubmittable()) {
+            return Err(AlreadyKnown(tracked.starknet_hash.unwrap_or(eth_hash)).into());
+        }
+        let replaced = Self::replacement_of(&state, eth_hash, sender, nonce, &fees)?;
+
+        if let Some(tracked) = state.transactions.get_mut(&eth_hash) {
+            // The Starknet hash of a dropped submission stays mapped, Starknet may still include it
+            let rejected_starknet_hash =
+                tracked.starknet_hash.take().filter(|_| tracked.status == RelayStatus::Rejected);
+            tracked.fees = fees;
+            tracked.status = RelayStatus::Received;
+            tracked.replaced_by = None;
+            tracked.error = None;
+            tracked.private = false;
+            tracked.events.push(LifecycleEvent { status: RelayStatus::Received, timestamp: now_millis() });
+            if let Some(starknet_hash) = rejected_starknet_hash {
+                state.starknet_hashes.remove(&starknet_hash);
+            }
+            state.sender_nonces.insert((sender, nonce), eth_hash);
+            state.stats.received += 1;
+            self.persist(&state, eth_hash);
+            return Ok(replaced);
+        }
+
+        let tracked = TrackedTransaction {
+            eth_hash,
+            starknet_hash: None,
+            sender,
+            nonce,
+            fees,
+            status: RelayStatus::Received,
+            replaced_by: None,
+            error: None,
+            events: vec![LifecycleEvent { status: RelayStatus::Received, timestamp: now_millis() }],
+            private: false,
+        };
+        state.transactions.insert(eth_hash, tracked);
+        state.sender_nonces.insert((sender, nonce), eth_hash);
+        state.order.push_back(eth_hash);
+        state.stats.received += 1;
+        self.persist(&state, eth_hash);
+
+        while state.order.len() > self.capacity {
+            if let Some(evicted) = state.order.pop_front() {
+                let Some(evicted) = state.transactions.remove(&evicted) else { continue };
+                self.forget(&evicted.eth_hash);
+                if let Some(starknet_hash) = evicted.starknet_hash {
+                    state.starknet_hashes.remove(&starknet_hash);
+                }
+                let key = (evicted.sender, evicted.nonce);
+                if state.sender_nonces.get(&key) == Some(&evicted.eth_hash) {
+                    state.sender_nonces.remove(&key);
+                }
+            }
+        }
+        state.stats.tracked = state.transactions.len() as u64;
+        Ok(replaced)
+    }
+
+    /// Marks a received transaction as private.
+    pub fn set_private(&self, eth_hash: H256) {
+        let mut state = self.state.write().expect("transaction tracker lock poisoned");
+        if let Some(tracked) = state.transactions.get_mut(&eth_hash) {
+            tracked.private = true;
+        }
+        self.persist(&state, eth_hash);
+    }
+
+    /// Records the submission of the Starknet invoke transaction.
+    pub fn sent(&self, eth_hash: H256, starknet_hash: H256) {
+        let mut state = self.state.write().expect("transaction tracker lock poisoned");
+        match state.transactions.get_mut(&eth_hash) {
+            Some(tracked) if !tracked.status.is_final() => tracked.starknet_hash = Some(starknet_hash),
+            _ => return,
+        }
+        state.starknet_hashes.insert(starknet_hash, eth_hash);
+        Self::transition(&mut state, eth_hash, RelayStatus::SentToStarknet, None);
+        self.persist(&state, eth_hash);
+    }
+
+    /// Records the rejection of a transaction.
+    pub fn rejected(&self, eth_hash: H256, error: String) {
+        let mut state = self.state.write().expect("transaction tracker lock poisoned");
+        Self::transition(&mut state, eth_hash, RelayStatus::Rejected, Some(error));
+        self.persist(&state, eth_hash);
+    }
+
+    /// Records the replacement of a pending transaction.
+    pub fn replaced(&self, eth_hash: H256, replaced_by: H256) {
+        let mut state = self.state.write().expect("transaction tracker lock poisoned");
+        if let Some(tracked) = state.transactions.get_mut(&eth_hash).filter(|tracked| !tracked.status.is_final()) {
+            tracked.replaced_by = Some(replaced_by);
+        }
+        Self::transition(&mut state, eth_hash, RelayStatus::Replaced, None);
+        self.persist(&state, eth_hash);
+    }
+
+    /// Records the status reported by Starknet for a Starknet transaction hash. Returns true if the
+    /// status of the transaction changed.
+    pub fn update_from_starknet(&self, starknet_hash: H256, status: StarknetTransactionStatus) -> bool {
+        let mut state = self.state.write().expect("transaction tracker lock poisoned");
+        let Some(eth_hash) = state.starknet_hashes.get(&starknet_hash).copied() else { return false };
+        let changed = Self::transition(&mut state, eth_hash, status.into(), None);
+        if changed {
+            self.persist(&state, eth_hash);
+        }
+        changed
+    }
+
+    /// Drops the transactions received more than the TTL ago and not accepted on Starknet yet,
+    /// freeing their nonce so that the sender can submit another transaction with it. Returns the
+    /// hashes of the dropped transactions.
+    pub fn expire(&self) -> Vec<H256> {
+        let deadline = now_millis().saturating_sub(self.ttl.as_millis() as u64);
+        let is_expired = |tracked: &TrackedTransaction| {
+            tracked.status.is_replaceable()
+                && tracked.events.first().map_or(false, |received| received.timestamp <= deadline)
+        };
+        if !self.state.read().expect("transaction tracker lock poisoned").transactions.values().any(is_expired) {
+            return vec![];
+        }
+
+        let mut state = self.state.write().expect("transaction tracker lock poisoned");
+        let expired: Vec<TrackedTransaction> = state.transactions.values().filter(|t| is_expired(t)).cloned().collect();
+        for tracked in &expired {
+            let error = format!("not accepted on Starknet within {}s", self.ttl.as_secs());
+            Self::transition(&mut state, tracked.eth_hash, RelayStatus::Dropped, Some(error));
+            let key = (tracked.sender, tracked.nonce);
+            if state.sender_nonces.get(&key) == Some(&tracked.eth_hash) {
+                state.sender_nonces.remove(&key);
+            }
+            self.persist(&state, tracked.eth_hash);
+        }
+        expired.into_iter().map(|tracked| tracked.eth_hash).collect()
+    }
+
+    /// Returns the tracked transaction, looked up by Ethereum or Starknet transaction hash.
+    pub fn get(&self, hash: &H256) -> Option<TrackedTransaction> {
+        let state = self.state.read().expect("transaction tracker lock poisoned");
+        let eth_hash = state.starknet_hashes.get(hash).unwrap_or(hash);
+        state.transactions.get(eth_hash).cloned()
+    }
+
+    /// Returns true if the transaction, looked up by Ethereum or Starknet transaction hash, is
+    /// private and not included in a block yet.
+    pub fn is_withheld(&self, hash: &H256) -> bool {
+        self.get(hash).map_or(false, |tracked| tracked.is_withheld())
+    }
+
+    /// Returns the nonce following the latest transaction of `sender` received in the last
+    /// `window` and not rejected, replaced, dropped or private, if any, so that a sender reads its
+    /// own writes before the Starknet provider includes them in its pending state.
+    pub fn next_pending_nonce(&self, sender: Address, window: Duration) -> Option<u64> {
+        let since = now_millis().saturating_sub(window.as_millis() as u64);
+        let state = self.state.read().expect("transaction tracker lock poisoned");
+        state
+            .transactions
+            .values()
+            .filter(|tracked| tracked.sender == sender && !tracked.private)
+            .filter(|tracked| {
+                !matches!(tracked.status, RelayStatus::Rejected | RelayStatus::Replaced | RelayStatus::Dropped)
+            })
+            .filter(|tracked| tracked.events.first().map_or(false, |received| received.timestamp >= since))
+            .map(|tracked| tracked.nonce + 1)
+            .max()
+    }
+
+    /// Returns the relay counters.
+    pub fn stats(&self) -> RelayStats {
+        self.state.read().expect("transaction tracker lock poisoned").stats.clone()
+    }
+
+    /// Moves a transaction to a new status, returning false if the transition is not allowed.
+    fn transition(state: &mut TrackerState, eth_hash: H256, status: RelayStatus, error: Option<String>) -> bool {
+        let Some(tracked) = state.transactions.get_mut(&eth_hash) else { return false };
+        if tracked.status == status || tracked.status.is_final() {
+            return false;
+        }
+        // A dropped transaction can only be reported as included late by Starknet
+        if tracked.status == RelayStatus::Dropped
+            && !matches!(status, RelayStatus::AcceptedOnL2 | RelayStatus::AcceptedOnL1)
+        {
+            return false;
+        }
+
+        tracked.status = status;
+        tracked.error = error;
+        tracked.events.push(LifecycleEvent { status, timestamp: now_millis() });
+
+        state.stats.count(status);
+        true
+    }
+
+    /// Writes a tracked transaction to the store, if any. Storage failures are logged and do not
+    /// fail the relay, the tracker being best effort.
+    fn persist(&self, state: &TrackerState, eth_hash: H256) {
+        let (Some(store), Some(tracked)) = (&self.store, state.transactions.get(&eth_hash)) else { return };
+        let result = serde_json::to_vec(tracked).map_err(|err| err.to_string()).and_then(|value| {
+            store.put(TRANSACTIONS_NAMESPACE, eth_hash.as_bytes(), &value).map_err(|err| err.to_string())
+        });
+        if let Err(err) = result {
+            tracing::warn!("Failed to persist tracked transaction {eth_hash:#x}: {err}");
+        }
+    }
+
+    /// Removes an evicted transaction from the store, if any.
+    fn forget(&self, eth_hash: &H256) {
+        if let Some(Err(err)) =
+            self.store.as_ref().map(|store| store.delete(TRANSACTIONS_NAMESPACE, eth_hash.as_bytes()))
+        {
+            tracing::warn!("Failed to delete tracked transaction {eth_hash:#x}: {err}");
+        }
+    }
+}
+
+impl RelayStats {
+    fn count(&mut self, status: RelayStatus) {
+        match status {
+            RelayStatus::Received => self.received += 1,
+            RelayStatus::SentToStarknet => self.sent_to_starknet += 1,
+            RelayStatus::AcceptedOnL2 => self.accepted_on_l2 += 1,
+            RelayStatus::AcceptedOnL1 => self.accepted_on_l1 += 1,
+            RelayStatus::Rejected => self.rejected += 1,
+            RelayStatus::Replaced => self.replaced += 1,
+            RelayStatus::Dropped => self.dropped += 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::store::InMemoryStore;
+
+    #[test]
+    fn test_tracker_records_lifecycle() {
+        // Given
+        let tracker = TransactionTracker::default();
+        let eth_hash = H256::from_low_u64_be(1);
+        let starknet_hash = H256::from_low_u64_be(2);
+
+        // When
+        tracker.received(eth_hash, Address::zero(), 0, TransactionFees::default()).unwrap();
+        tracker.sent(eth_hash, starknet_hash);
+        tracker.update_from_starknet(starknet_hash, StarknetTransactionStatus::AcceptedOnL2);
+
+        // Then
+        let tracked = tracker.get(&starknet_hash).unwrap();
+        assert_eq!(eth_hash, tracked.eth_hash);
+        assert_eq!(RelayStatus::AcceptedOnL2, tracked.status);
+        assert_eq!(
+            vec![RelayStatus::Received, RelayStatus::SentToStarknet, RelayStatus::AcceptedOnL2],
+            tracked.events.iter().map(|event| event.status).collect::<Vec<_>>()
+        );
+        let stats = tracker.stats();
+        assert_eq!(1, stats.received);
+        assert_eq!(1, stats.sent_to_starknet);
+        assert_eq!(1, stats.accepted_on_l2);
+        assert_eq!(1, stats.tracked);
+    }
+
+    #[test]
+    fn test_tracker_ignores_transitions_after_final_status() {
+        // Given
+        let tracker = TransactionTracker::default();
+        let eth_hash = H256::from_low_u64_be(1);
+        tracker.received(eth_hash, Address::zero(), 0, TransactionFees::default()).unwrap();
+
+        // When
+        tracker.rejected(eth_hash, "invalid nonce".into());
+        tracker.sent(eth_hash, H256::from_low_u64_be(2));
+
+        // Then
+        let tracked = tracker.get(&eth_hash).unwrap();
+        assert_eq!(RelayStatus::Rejected, tracked.status);
+        assert_eq!(Some("invalid nonce".to_string()), tracked.error);
+    }
+
+    #[test]
+    fn test_tracker_evicts_oldest_transactions() {
+        // Given
+        let tracker = TransactionTracker::new(2);
+
+        // When
+        for i in 0..3 {
+            tracker.received(H256::from_low_u64_be(i), Address::zero(), i, TransactionFees::default()).unwrap();
+        }
+
+        // Then
+        assert!(tracker.get(&H256::from_low_u64_be(0)).is_none());
+        assert!(tracker.get(&H256::from_low_u64_be(2)).is_some());
+        assert_eq!(2, tracker.stats().tracked);
+    }
+
+    #[test]
+    fn test_tracker_replacement() {
+        // Given
+        let tracker = TransactionTracker::default();
+        let pending_hash = H256::from_low_u64_be(1);
+        let fees = TransactionFees { max_fee_per_gas: 100, max_priority_fee_per_gas: 10 };
+        tracker.received(pending_hash, Address::zero(), 0, fees).unwrap();
+
+        // When
+        let underpriced = TransactionFees { max_fee_per_gas: 200, max_priority_fee_per_gas: 10 };
+        let bumped = TransactionFees { max_fee_per_gas: 110, max_priority_fee_per_gas: 11 };
+        let other_hash = H256::from_low_u64_be(2);
+
+        // Then
+        assert_eq!(Err(ReplacementUnderpriced.into()), tracker.received(other_hash, Address::zero(), 0, underpriced));
+        assert!(tracker.get(&other_hash).is_none());
+        assert_eq!(Ok(None), tracker.received(H256::from_low_u64_be(3), Address::zero(), 1, bumped));
+        assert_eq!(Ok(Some(pending_hash)), tracker.received(other_hash, Address::zero(), 0, bumped));
+        // The replacement is now the pending transaction of the nonce
+        assert_eq!(
+            Err(ReplacementUnderpriced.into()),
+            tracker.received(H256::from_low_u64_be(4), Address::zero(), 0, bumped)
+        );
+
+        tracker.replaced(pending_hash, other_hash);
+        let replaced = tracker.get(&pending_hash).unwrap();
+        assert_eq!(RelayStatus::Replaced, replaced.status);
+        assert_eq!(Some(other_hash), replaced.replaced_by);
+        assert_eq!(1, tracker.stats().replaced);
+    }
+
+    #[test]
+    fn test_tracker_detects_duplicate_submissions() {
+        // Given
+        let tracker = TransactionTracker::default();
+        let (eth_hash, starknet_hash) = (H256::from_low_u64_be(1), H256::from_low_u64_be(2));
+        let rejected_hash = H256::from_low_u64_be(3);
+        tracker.received(eth_hash, Address::zero(), 0, TransactionFees::default()).unwrap();
+        tracker.received(rejected_hash, Address::zero(), 1, TransactionFees::default()).unwrap();
+        tracker.rejected(rejected_hash, "rejected".into());
+
+        // Then
+        let fees = TransactionFees::default();
+        assert_eq!(Err(AlreadyKnown(eth_hash).into()), tracker.received(eth_hash, Address::zero(), 0, fees));
+        tracker.sent(eth_hash, starknet_hash);
+        assert_eq!(Err(AlreadyKnown(starknet_hash).into()), tracker.received(eth_hash, Address::zero(), 0, fees));
+        assert_eq!(Ok(None), tracker.received(rejected_hash, Address::zero(), 1, fees));
+        assert_eq!(Ok(None), tracker.received(H256::from_low_u64_be(4), Address::zero(), 2, fees));
+        assert_eq!(4, tracker.stats().received);
+    }
+
+    #[test]
+    fn test_tracker_tracks_resubmissions_of_rejected_transactions() {
+        // Given
+        let tracker = TransactionTracker::default();
+        let (eth_hash, starknet_hash) = (H256::from_low_u64_be(1), H256::from_low_u64_be(2));
+        let resubmitted_starknet_hash = H256::from_low_u64_be(3);
+        tracker.received(eth_hash, Address::zero(), 0, TransactionFees::default()).unwrap();
+        tracker.sent(eth_hash, starknet_hash);
+        tracker.rejected(eth_hash, "insufficient max fee".into());
+
+        // When
+        tracker.received(eth_hash, Address::zero(), 0, TransactionFees::default()).unwrap();
+        let received = tracker.get(&eth_hash).unwrap();
+        tracker.sent(eth_hash, resubmitted_starknet_hash);
+        tracker.update_from_starknet(resubmitted_starknet_hash, StarknetTransactionStatus::AcceptedOnL2);
+
+        // Then
+        assert_eq!(RelayStatus::Received, received.status);
+        assert_eq!((None, None), (received.starknet_hash, received.error));
+        let tracked = tracker.get(&resubmitted_starknet_hash).unwrap();
+        assert_eq!(RelayStatus::AcceptedOnL2, tracked.status);
+        assert_eq!(
+            vec![
+                RelayStatus::Received,
+                RelayStatus::SentToStarknet,
+                RelayStatus::Rejected,
+                RelayStatus::Received,
+                RelayStatus::SentToStarknet,
+                RelayStatus::AcceptedOnL2
+            ],
+            tracked.events.iter().map(|event| event.status).collect::<Vec<_>>()
+        );
+        assert!(tracker.get(&starknet_hash).is_none());
+        assert_eq!(
+            Err(AlreadyKnown(resubmitted_starknet_hash).into()),
+            tracker.received(eth_hash, Address::zero(), 0, TransactionFees::default())
+        );
+        assert_eq!((2, 1), (tracker.stats().received, tracker.stats().tracked));
+    }
+
+    #[test]
+    fn test_tracker_next_pending_nonce() {
+        // Given
+        let tracker = TransactionTracker::default();
+        let (sender, other) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2));
+        tracker.received(H256::from_low_u64_be(1), sender, 4, TransactionFees::default()).unwrap();
+        tracker.received(H256::from_low_u64_be(2), sender, 5, TransactionFees::default()).unwrap();
+        tracker.rejected(H256::from_low_u64_be(2), "rejected".into());
+
+        // Then
+        assert_eq!(Some(5), tracker.next_pending_nonce(sender, DEFAULT_READ_YOUR_WRITES_WINDOW));
+        assert_eq!(None, tracker.next_pending_nonce(other, DEFAULT_READ_YOUR_WRITES_WINDOW));
+    }
+
+    #[test]
+    fn test_tracker_drops_transactions_not_accepted_within_ttl() {
+        // Given
+        let tracker = TransactionTracker::default().with_ttl(Duration::ZERO);
+        let sender = Address::from_low_u64_be(1);
+        let (eth_hash, starknet_hash) = (H256::from_low_u64_be(1), H256::from_low_u64_be(2));
+        tracker.received(eth_hash, sender, 4, TransactionFees::default()).unwrap();
+        tracker.sent(eth_hash, starknet_hash);
+
+        // When
+        let dropped = tracker.expire();
+
+        // Then
+        assert_eq!(vec![eth_hash], dropped);
+        let tracked = tracker.get(&starknet_hash).unwrap();
+        assert_eq!(RelayStatus::Dropped, tracked.status);
+        assert_eq!(Some("not accepted on Starknet within 0s".to_string()), tracked.error);
+        assert_eq!(1, tracker.stats().dropped);
+        assert_eq!(None, tracker.next_pending_nonce(sender, DEFAULT_READ_YOUR_WRITES_WINDOW));
+        assert!(tracker.expire().is_empty());
+        assert_eq!(Ok(None), tracker.received(H256::from_low_u64_be(3), sender, 4, TransactionFees::default()));
+
+        // A late inclusion is still recorded
+        tracker.update_from_starknet(starknet_hash, StarknetTransactionStatus::AcceptedOnL2);
+        assert_eq!(RelayStatus::AcceptedOnL2, tracker.get(&eth_hash).unwrap().status);
+    }
+
+    #[test]
+    fn test_tracker_withholds_private_transactions_until_included() {
+        // Given
+        let tracker = TransactionTracker::default();
+        let sender = Address::from_low_u64_be(1);
+        let (eth_hash, starknet_hash) = (H256::from_low_u64_be(1), H256::from_low_u64_be(2));
+        tracker.received(eth_hash, sender, 0, TransactionFees::default()).unwrap();
+        tracker.set_private(eth_hash);
+        tracker.sent(eth_hash, starknet_hash);
+
+        // Then
+        assert!(tracker.is_withheld(&starknet_hash));
+        assert_eq!(None, tracker.next_pending_nonce(sender, DEFAULT_READ_YOUR_WRITES_WINDOW));
+        tracker.update_from_starknet(starknet_hash, StarknetTransactionStatus::AcceptedOnL2);
+        assert!(!tracker.is_withheld(&starknet_hash));
+    }
+
+    #[test]
+    fn test_tracker_restores_from_store() {
+        // Given
+        let store: Arc<dyn KakarotStore> = Arc::new(InMemoryStore::default());
+        let tracker = TransactionTracker::with_store(DEFAULT_TRACKER_CAPACITY, store.clone());
+        let eth_hash = H256::from_low_u64_be(1);
+        let starknet_hash = H256::from_low_u64_be(2);
+        tracker.received(eth_hash, Address::zero(), 0, TransactionFees::default()).unwrap();
+        tracker.sent(eth_hash, starknet_hash);
+
+        // When
+        let restored = TransactionTracker::with_store(DEFAULT_TRACKER_CAPACITY, store);
+
+        // Then
+        assert_eq!(tracker.get(&eth_hash), restored.get(&starknet_hash));
+        assert_eq!(tracker.stats(), restored.stats());
+    }
+}
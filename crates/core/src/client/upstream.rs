@@ -0,0 +1,378 @@
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures::future::{select_ok, FutureExt};
+use rand::Rng;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use starknet::providers::jsonrpc::{
+    HttpTransport, HttpTransportError, JsonRpcError, JsonRpcMethod, JsonRpcResponse, JsonRpcTransport,
+};
+use url::Url;
+
+use super::coalesce::{CoalescingCounters, Flight, RequestCoalescer};
+use super::config::StarknetConfig;
+use super::errors::ConfigError;
+
+/// Default delay after which a hedged request is also sent to the second best endpoint.
+pub const DEFAULT_HEDGE_DELAY: Duration = Duration::from_millis(100);
+/// Weight of the last request in the moving averages of an endpoint.
+const SMOOTHING: f64 = 0.2;
+/// Latency added to the score of an endpoint failing every request.
+const ERROR_PENALTY_MS: f64 = 10_000.;
+/// Share of the requests sent to a random endpoint, so that the scores of the other endpoints
+/// stay up to date.
+const EXPLORATION_RATE: f64 = 0.05;
+/// Upper bounds in milliseconds of the buckets of the latency histograms of the endpoints.
+pub const LATENCY_BUCKETS_MS: [u64; 8] = [10, 25, 50, 100, 250, 500, 1000, 2500];
+
+/// Classes of Starknet reads which can be hedged. Transactions are never hedged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MethodClass {
+    /// Chain tip: `starknet_blockNumber` and `starknet_blockHashAndNumber`.
+    Tip,
+    /// Chain information: `starknet_chainId` and `starknet_syncing`.
+    Chain,
+    /// Contract state: nonces, storage, class hashes and calls.
+    State,
+    /// Blocks, their transactions and receipts.
+    Blocks,
+}
+
+impl FromStr for MethodClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tip" => Ok(Self::Tip),
+            "chain" => Ok(Self::Chain),
+            "state" => Ok(Self::State),
+            "blocks" => Ok(Self::Blocks),
+            _ => Err(format!("invalid method class {s}, expected tip, chain, state or blocks")),
+        }
+    }
+}
+
+impl MethodClass {
+    /// Returns the class of a Starknet method, `None` for the methods which are never hedged.
+    pub fn of(method: JsonRpcMethod) -> Option<Self> {
+        match method {
+            JsonRpcMethod::BlockNumber | JsonRpcMethod::BlockHashAndNumber => Some(Self::Tip),
+            JsonRpcMethod::ChainId | JsonRpcMethod::Syncing => Some(Self::Chain),
+            JsonRpcMethod::GetNonce
+            | JsonRpcMethod::GetStorageAt
+            | JsonRpcMethod::GetClassHashAt
+            | JsonRpcMethod::Call => Some(Self::State),
+            JsonRpcMethod::GetBlockWithTxHashes
+            | JsonRpcMethod::GetBlockWithTxs
+            | JsonRpcMethod::GetBlockTransactionCount
+            | JsonRpcMethod::GetTransactionByHash
+            | JsonRpcMethod::GetTransactionByBlockIdAndIndex
+            | JsonRpcMethod::GetTransactionReceipt => Some(Self::Blocks),
+            _ => None,
+        }
+    }
+}
+
+/// Starknet JSON-RPC endpoints used besides the one of the network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpstreamConfig {
+    pub backup_urls: Vec<Url>,
+    pub hedge_delay: Duration,
+    /// Classes of reads hedged, the chain tip and chain information by default.
+    pub hedged_methods: Vec<MethodClass>,
+    /// Whether identical concurrent reads are sent once.
+    pub coalesce_requests: bool,
+}
+
+impl Default for UpstreamConfig {
+    fn default() -> Self {
+        Self {
+            backup_urls: Vec::new(),
+            hedge_delay: DEFAULT_HEDGE_DELAY,
+            hedged_methods: vec![MethodClass::Tip, MethodClass::Chain],
+            coalesce_requests: true,
+        }
+    }
+}
+
+/// Latency and error rate of a Starknet endpoint, as moving averages over its last requests.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpstreamStats {
+    pub url: String,
+    pub latency_ms: f64,
+    pub error_rate: f64,
+    pub requests: u64,
+    pub errors: u64,
+    /// Number of successful requests by latency, the bucket `i` counting the latencies up to
+    /// `LATENCY_BUCKETS_MS[i]` and the last bucket the slower ones.
+    #[serde(default)]
+    pub latency_histogram: [u64; LATENCY_BUCKETS_MS.len() + 1],
+}
+
+impl UpstreamStats {
+    /// Score of the endpoint, lower is better. Endpoints never used score 0 so that they are
+    /// tried first.
+    pub fn score(&self) -> f64 {
+        self.latency_ms + self.error_rate * ERROR_PENALTY_MS
+    }
+}
+
+/// Scores of the endpoints of an [`UpstreamTransport`], shared with the admin methods.
+#[derive(Debug)]
+pub struct UpstreamScores {
+    stats: RwLock<Vec<UpstreamStats>>,
+}
+
+impl UpstreamScores {
+    pub fn new(urls: &[Url]) -> Self {
+        let stats = urls.iter().map(|url| UpstreamStats { url: url.to_string(), ..Default::default() }).collect();
+        Self { stats: RwLock::new(stats) }
+    }
+
+    pub fn stats(&self) -> Vec<UpstreamStats> {
+        self.stats.read().expect("upstream scores lock poisoned").clone()
+    }
+
+    /// Records the latency of a successful request to an endpoint, or its failure.
+    pub fn record(&self, index: usize, latency: Option<Duration>) {
+        let mut stats = self.stats.write().expect("upstream scores lock poisoned");
+        let Some(stats) = stats.get_mut(index) else { return };
+        let first = stats.requests == 0;
+        stats.requests += 1;
+        let error = match latency {
+            Some(latency) => {
+                let latency_ms = latency.as_secs_f64() * 1000.;
+                let bucket = LATENCY_BUCKETS_MS.partition_point(|bound| latency.as_millis() > u128::from(*bound));
+                stats.latency_histogram[bucket] += 1;
+                stats.latency_ms =
+                    if first { latency_ms } else { SMOOTHING * latency_ms + (1. - SMOOTHING) * stats.latency_ms };
+                0.
+            }
+            None => {
+                stats.errors += 1;
+                1.
+            }
+        };
+        stats.error_rate = if first { error } else { SMOOTHING * error + (1. - SMOOTHING) * stats.error_rate };
+    }
+
+    /// Returns the indexes of the endpoints from the best to the worst, a random endpoint being
+    /// promoted first from time to time.
+    pub fn ranking(&self) -> Vec<usize> {
+        let stats = self.stats.read().expect("upstream scores lock poisoned");
+        let mut ranking: Vec<usize> = (0..stats.len()).collect();
+        ranking.sort_by(|a, b| stats[*a].score().total_cmp(&stats[*b].score()));
+
+        let mut rng = rand::thread_rng();
+        if ranking.len() > 1 && rng.gen_bool(EXPLORATION_RATE) {
+            let explored = ranking.remove(rng.gen_range(1..ranking.len()));
+            ranking.insert(0, explored);
+        }
+        ranking
+    }
+}
+
+/// Returns true for the requests that can be sent again to another endpoint when they fail.
+/// Transactions are never sent twice.
+fn is_retriable(method: JsonRpcMethod) -> bool {
+    !matches!(
+        method,
+        JsonRpcMethod::AddInvokeTransaction
+            | JsonRpcMethod::AddDeclareTransaction
+            | JsonRpcMethod::AddDeployAccountTransaction
+    )
+}
+
+/// Response of a coalesced read, shared by the identical reads.
+type SharedResponse = Arc<JsonRpcResponse<Value>>;
+
+/// Converts a shared response to the type expected by a read.
+fn typed_response<R: DeserializeOwned>(
+    response: &JsonRpcResponse<Value>,
+) -> Result<JsonRpcResponse<R>, HttpTransportError> {
+    Ok(match response {
+        JsonRpcResponse::Success { id, result } => {
+            JsonRpcResponse::Success { id: *id, result: R::deserialize(result).map_err(HttpTransportError::Json)? }
+        }
+        JsonRpcResponse::Error { id, error } => {
+            JsonRpcResponse::Error { id: *id, error: JsonRpcError { code: error.code, message: error.message.clone() } }
+        }
+    })
+}
+
+/// JSON-RPC transport spreading the requests over several Starknet endpoints, routing each
+/// request to the endpoint with the best latency and error rate.
+///
+/// Reads failing on an endpoint are retried on the next ones. The reads of the hedged classes,
+/// e.g. `starknet_blockNumber`, are hedged: when the best endpoint did not answer after the hedge
+/// delay, the request is also sent to the second best endpoint and the first answer wins.
+/// Identical concurrent reads, same method and parameters, are sent once and share the response.
+pub struct UpstreamTransport {
+    endpoints: Vec<HttpTransport>,
+    scores: Arc<UpstreamScores>,
+    hedge_delay: Duration,
+    hedged_methods: Vec<MethodClass>,
+    /// Coalescer of the identical reads, `None` if disabled.
+    coalescer: Option<RequestCoalescer<SharedResponse>>,
+}
+
+impl UpstreamTransport {
+    /// # Panics
+    ///
+    /// Will panic if `urls` is empty.
+    pub fn new(urls: Vec<Url>, hedge_delay: Duration, hedged_methods: Vec<MethodClass>) -> Self {
+        assert!(!urls.is_empty(), "an upstream transport needs at least one endpoint");
+        let scores = Arc::new(UpstreamScores::new(&urls));
+        Self {
+            endpoints: urls.into_iter().map(HttpTransport::new).collect(),
+            scores,
+            hedge_delay,
+            hedged_methods,
+            coalescer: Some(RequestCoalescer::default()),
+        }
+    }
+
+    /// Sends identical concurrent reads separately.
+    pub fn without_coalescing(mut self) -> Self {
+        self.coalescer = None;
+        self
+    }
+
+    /// Returns the transport of the provider URL of the network and of the backup URLs.
+    pub fn from_config(config: &StarknetConfig) -> Result<Self, ConfigError> {
+        let mut urls = vec![config.network.provider_url()?];
+        urls.extend(config.upstream_config.backup_urls.iter().cloned());
+        let upstream_config = &config.upstream_config;
+        let transport = Self::new(urls, upstream_config.hedge_delay, upstream_config.hedged_methods.clone());
+        Ok(if upstream_config.coalesce_requests { transport } else { transport.without_coalescing() })
+    }
+
+    pub fn scores(&self) -> Arc<UpstreamScores> {
+        self.scores.clone()
+    }
+
+    /// Returns the counters of the coalesced reads, `None` if coalescing is disabled.
+    pub fn coalescing_counters(&self) -> Option<Arc<CoalescingCounters>> {
+        self.coalescer.as_ref().map(RequestCoalescer::counters)
+    }
+
+    fn is_hedged(&self, method: JsonRpcMethod) -> bool {
+        MethodClass::of(method).map_or(false, |class| self.hedged_methods.contains(&class))
+    }
+
+    async fn send_to<R: DeserializeOwned>(
+        &self,
+        index: usize,
+        method: JsonRpcMethod,
+        params: &Value,
+    ) -> Result<JsonRpcResponse<R>, HttpTransportError> {
+        let started_at = Instant::now();
+        let response = self.endpoints[index].send_request(method, params).await;
+        self.scores.record(index, response.as_ref().ok().map(|_| started_at.elapsed()));
+        response
+    }
+
+    /// Sends a request to the best endpoint, hedging it or retrying it on the next endpoints
+    /// depending on its method.
+    async fn route<R: DeserializeOwned>(
+        &self,
+        method: JsonRpcMethod,
+        params: &Value,
+    ) -> Result<JsonRpcResponse<R>, HttpTransportError> {
+        let ranking = self.scores.ranking();
+
+        if self.is_hedged(method) && ranking.len() > 1 {
+            let primary = self.send_to(ranking[0], method, params).boxed();
+            let hedge = async {
+                tokio::time::sleep(self.hedge_delay).await;
+                self.send_to(ranking[1], method, params).await
+            }
+            .boxed();
+            return select_ok([primary, hedge]).await.map(|(response, _)| response);
+        }
+
+        let endpoints = if is_retriable(method) { &ranking[..] } else { &ranking[..1] };
+        let mut last_error = None;
+        for index in endpoints {
+            match self.send_to(*index, method, params).await {
+                Ok(response) => return Ok(response),
+                Err(err) => {
+                    tracing::warn!("Starknet endpoint {index} failed: {err}");
+                    last_error = Some(err);
+                }
+            }
+        }
+        Err(last_error.expect("at least one endpoint was tried"))
+    }
+}
+
+#[async_trait]
+impl JsonRpcTransport for UpstreamTransport {
+    type Error = HttpTransportError;
+
+    async fn send_request<P, R>(&self, method: JsonRpcMethod, params: P) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send,
+        R: DeserializeOwned,
+    {
+        let params = serde_json::to_value(params).map_err(HttpTransportError::Json)?;
+        let coalescer = match &self.coalescer {
+            Some(coalescer) if is_retriable(method) => coalescer,
+            _ => return self.route(method, &params).await,
+        };
+
+        let key = serde_json::to_string(&(method, &params)).map_err(HttpTransportError::Json)?;
+        match coalescer.join(key) {
+            Flight::Follower(mut receiver) => match receiver.recv().await {
+                Ok(response) => typed_response(&response),
+                // The identical read failed, it is sent again
+                Err(_) => self.route(method, &params).await,
+            },
+            Flight::Leader(leader) => {
+                let response: SharedResponse = Arc::new(self.route(method, &params).await?);
+                leader.complete(response.clone());
+                typed_response(&response)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upstream_scores() {
+        // Given
+        let urls: Vec<Url> = ["http://a:5050", "http://b:5050"].iter().map(|url| url.parse().unwrap()).collect();
+        let scores = UpstreamScores::new(&urls);
+
+        // When
+        scores.record(0, Some(Duration::from_millis(50)));
+        scores.record(1, Some(Duration::from_millis(10)));
+        scores.record(1, None);
+
+        // Then
+        let stats = scores.stats();
+        assert_eq!(50., stats[0].latency_ms);
+        assert_eq!((2, 1), (stats[1].requests, stats[1].errors));
+        assert_eq!([0, 0, 1, 0, 0, 0, 0, 0, 0], stats[0].latency_histogram);
+        assert_eq!([1, 0, 0, 0, 0, 0, 0, 0, 0], stats[1].latency_histogram);
+        assert!(stats[1].score() > stats[0].score());
+        assert!((0..100).filter(|_| scores.ranking() == vec![0, 1]).count() > 50);
+    }
+
+    #[test]
+    fn test_method_classes() {
+        assert_eq!(Ok(MethodClass::Tip), "tip".parse());
+        assert!("writes".parse::<MethodClass>().is_err());
+        assert_eq!(Some(MethodClass::Tip), MethodClass::of(JsonRpcMethod::BlockNumber));
+        assert_eq!(Some(MethodClass::State), MethodClass::of(JsonRpcMethod::Call));
+        assert_eq!(None, MethodClass::of(JsonRpcMethod::AddInvokeTransaction));
+    }
+}
@@ -52,7 +52,13 @@ impl<P: Provider + Send + Sync> ContractAccount<P> {
         let request = FunctionCall { contract_address: self.address, entry_point_selector: STORAGE, calldata };
 
         // Make the function call to get the Starknet contract address
-        let result = starknet_provider.call(request, block_id).await?;
+        let result = match starknet_provider.call(request, block_id).await {
+            Ok(result) => result,
+            // The contract account is not deployed yet at this block, so its storage is empty. A
+            // contract error is a failure of the read and is returned as is.
+            Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => return Ok(U256::from(0)),
+            Err(err) => return Err(EthApiError::from(err)),
+        };
         if result.len() != 2 {
             return Err(DataDecodingError::InvalidReturnArrayLength {
                 entrypoint: "storage".into(),
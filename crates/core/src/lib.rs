@@ -1,5 +1,8 @@
 #![feature(more_qualified_paths)]
+#[cfg(feature = "client")]
 pub mod client;
+#[cfg(feature = "client")]
 pub mod contracts;
+#[cfg(any(test, feature = "testing"))]
 pub mod mock;
 pub mod models;
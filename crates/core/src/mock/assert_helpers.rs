@@ -1,14 +1,16 @@
 use std::str::FromStr;
 
-use reth_primitives::{Bloom, Bytes, H160, H256, U128, U256};
+use reth_primitives::{Bloom, Bytes, TransactionSigned, EMPTY_OMMER_ROOT, H160, H256, U128, U256, U64};
 use reth_rpc_types::{Block, BlockTransactions, Rich, Signature, Transaction};
 use serde::{Deserialize, Serialize};
 use starknet::core::types::{FieldElement, InvokeTransaction, Transaction as StarknetTransaction};
 
 use crate::client::constants::gas::BASE_FEE_PER_GAS;
 use crate::client::constants::{CHAIN_ID, DIFFICULTY, GAS_LIMIT, GAS_USED, MIX_HASH, NONCE, SIZE, TOTAL_DIFFICULTY};
+use crate::models::call::Calls;
 use crate::models::felt::Felt252Wrapper;
 use crate::models::signature::StarknetSignature;
+use crate::models::transaction::StarknetTransaction as StarknetTransactionWrapper;
 
 #[derive(Serialize, Deserialize, Debug)]
 struct StarknetBlockTest {
@@ -75,7 +77,7 @@ pub fn assert_block_header(block: &Rich<Block>, starknet_res: String, hydrated:
     let starknet_parent_hash = FieldElement::from_str(starknet_data.parent_hash.as_str()).unwrap();
     let parent_hash = H256::from_slice(&starknet_parent_hash.to_bytes_be());
     assert_eq!(block.header.parent_hash, parent_hash);
-    assert_eq!(block.header.uncles_hash, parent_hash);
+    assert_eq!(block.header.uncles_hash, EMPTY_OMMER_ROOT);
 
     let starknet_sequencer = FieldElement::from_str(starknet_data.sequencer_address.as_str()).unwrap();
     let sequencer = H160::from_slice(&starknet_sequencer.to_bytes_be()[12..32]);
@@ -114,8 +116,17 @@ pub fn assert_block_header(block: &Rich<Block>, starknet_res: String, hydrated:
 
 pub fn assert_transaction(ether_tx: Transaction, starknet_tx: StarknetTransaction) {
     assert_eq!(ether_tx.chain_id, Some(CHAIN_ID.into()));
-    assert_eq!(ether_tx.access_list, None);
-    assert_eq!(ether_tx.transaction_type, None);
+    // The access list and the type are the ones of the Ethereum transaction in the calldata
+    let signed_tx = StarknetTransactionWrapper::from(starknet_tx.clone())
+        .calldata()
+        .ok()
+        .and_then(|calldata| Calls::try_from(calldata).ok())
+        .and_then(|calls| TransactionSigned::try_from(&calls).ok());
+    assert_eq!(
+        ether_tx.access_list,
+        signed_tx.as_ref().and_then(|tx| tx.access_list()).map(|access_list| access_list.0.clone())
+    );
+    assert_eq!(ether_tx.transaction_type, signed_tx.map(|tx| U64::from(tx.tx_type() as u8)));
 
     assert_eq!(ether_tx.to, None);
     assert_eq!(ether_tx.value, U256::from(100));
@@ -77,6 +77,8 @@ pub async fn setup_wiremock() -> String {
 
     mock_block_with_txs_hashes().mount(&mock_server).await;
 
+    mock_block_with_txs_hashes_not_found().mount(&mock_server).await;
+
     mock_block_with_txs_latest().mount(&mock_server).await;
 
     mock_block_with_txs_hashes_latest().mount(&mock_server).await;
@@ -144,6 +146,20 @@ fn mock_block_with_txs_hashes() -> Mock {
         ))
 }
 
+/// Hash of a block unknown to the mocked Starknet provider.
+pub const UNKNOWN_BLOCK_HASH: &str = "0x0000000000000000000000000000000000000000000000000000000000000bad";
+
+fn mock_block_with_txs_hashes_not_found() -> Mock {
+    let block_id = BlockId::Hash(H256::from_str(UNKNOWN_BLOCK_HASH).unwrap().into());
+    let starknet_block_id: StarknetBlockId = EthBlockId::new(block_id).try_into().unwrap();
+    Mock::given(method("POST"))
+        .and(body_json(StarknetRpcBaseData::block_with_tx_hashes([&starknet_block_id])))
+        .respond_with(response_template_with_status(StatusCode::OK).set_body_raw(
+            include_str!("fixtures/responses/blocks/starknet_getBlockWithTxHashes_notFound.json"),
+            "application/json",
+        ))
+}
+
 fn mock_block_with_txs_latest() -> Mock {
     let latest_block = StarknetBlockId::Tag(BlockTag::Latest);
     Mock::given(method("POST")).and(body_json(StarknetRpcBaseData::block_with_txs([&latest_block]))).respond_with(
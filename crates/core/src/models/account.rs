@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use starknet::core::types::FieldElement;
+
+/// Type of the Kakarot account deployed at an Ethereum address. Kakarot EOAs are Starknet
+/// contracts too, only their class tells them apart from contract accounts. Precompiles are
+/// executed by Kakarot itself, without a Starknet contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountType {
+    Eoa,
+    Contract,
+    Undeployed,
+    Precompile,
+}
+
+/// Deployment state of the Starknet contract of an Ethereum address at a block. Ethereum has no
+/// notion of deployment, so the reads of undeployed accounts return the zero or empty values of an
+/// account never used: no code, no storage, a zero nonce and an empty call result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountState {
+    /// No contract at the Starknet address of the account, or Kakarot itself not deployed yet.
+    Undeployed,
+    Deployed {
+        starknet_address: FieldElement,
+        class_hash: FieldElement,
+    },
+}
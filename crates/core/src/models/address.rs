@@ -0,0 +1,117 @@
+use std::ops::Deref;
+
+use reth_primitives::{keccak256, Address};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+use thiserror::Error;
+
+/// Address of a request rejected when parsed.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AddressError {
+    #[error("invalid address {0}: expected 0x followed by 40 hexadecimal characters")]
+    InvalidFormat(String),
+    #[error("invalid address {address}: bad EIP-55 checksum, expected {expected}")]
+    InvalidChecksum { address: String, expected: String },
+}
+
+/// Returns the EIP-55 checksummed encoding of an address.
+pub fn to_checksum_address(address: &Address) -> String {
+    let lowercase = hex::encode(address.as_bytes());
+    let hash = keccak256(lowercase.as_bytes());
+    let checksummed: String = lowercase
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = if i % 2 == 0 { hash[i / 2] >> 4 } else { hash[i / 2] & 0xf };
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+    format!("0x{checksummed}")
+}
+
+/// Parses an address of a request: `0x` followed by 40 hexadecimal characters, either all
+/// lowercase, all uppercase or checksummed following EIP-55. Mixed case addresses with a bad
+/// checksum are rejected, as they are most likely mistyped.
+pub fn parse_address(address: &str) -> Result<Address, AddressError> {
+    let digits = address.strip_prefix("0x").or_else(|| address.strip_prefix("0X"));
+    let Some(digits) = digits.filter(|digits| digits.len() == 40 && digits.chars().all(|c| c.is_ascii_hexdigit()))
+    else {
+        return Err(AddressError::InvalidFormat(address.into()));
+    };
+    let parsed = Address::from_slice(&hex::decode(digits).map_err(|_| AddressError::InvalidFormat(address.into()))?);
+
+    let is_mixed_case =
+        digits.chars().any(|c| c.is_ascii_lowercase()) && digits.chars().any(|c| c.is_ascii_uppercase());
+    let expected = to_checksum_address(&parsed);
+    if is_mixed_case && expected[2..] != *digits {
+        return Err(AddressError::InvalidChecksum { address: address.into(), expected });
+    }
+    Ok(parsed)
+}
+
+/// Address parameter of a method, parsed with [`parse_address`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AddressParam(Address);
+
+impl<'de> Deserialize<'de> for AddressParam {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let address = String::deserialize(deserializer)?;
+        parse_address(&address).map(Self).map_err(D::Error::custom)
+    }
+}
+
+impl Deref for AddressParam {
+    type Target = Address;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Address> for AddressParam {
+    fn from(address: Address) -> Self {
+        Self(address)
+    }
+}
+
+impl From<AddressParam> for Address {
+    fn from(address: AddressParam) -> Self {
+        address.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_address() {
+        // Given
+        let checksummed = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        let address = Address::from_str(checksummed).unwrap();
+
+        // Then
+        assert_eq!(checksummed, to_checksum_address(&address));
+        assert_eq!(Ok(address), parse_address(checksummed));
+        assert_eq!(Ok(address), parse_address(&checksummed.to_lowercase()));
+        assert_eq!(Ok(address), parse_address(&format!("0x{}", checksummed[2..].to_uppercase())));
+        assert!(matches!(
+            parse_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD"),
+            Err(AddressError::InvalidChecksum { .. })
+        ));
+        assert!(matches!(
+            parse_address("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beae"),
+            Err(AddressError::InvalidFormat(_))
+        ));
+        assert!(matches!(
+            parse_address("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaeg"),
+            Err(AddressError::InvalidFormat(_))
+        ));
+    }
+}
@@ -1,5 +1,6 @@
 use async_trait::async_trait;
-use reth_primitives::{BlockId as EthereumBlockId, BlockNumberOrTag, Bloom, Bytes, H256, H64, U256};
+use reth_primitives::proofs::EMPTY_ROOT;
+use reth_primitives::{BlockId as EthereumBlockId, BlockNumberOrTag, Bloom, Bytes, EMPTY_OMMER_ROOT, H256, H64, U256};
 use reth_rpc_types::{Block, BlockTransactions, Header, RichBlock};
 use starknet::core::types::{
     BlockId as StarknetBlockId, BlockTag, FieldElement, MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs,
@@ -11,9 +12,7 @@ use super::convertible::ConvertibleStarknetBlock;
 use super::felt::Felt252Wrapper;
 use super::ConversionError;
 use crate::client::api::KakarotEthApi;
-use crate::client::constants::{
-    DIFFICULTY, EARLIEST_BLOCK_NUMBER, GAS_LIMIT, GAS_USED, MIX_HASH, NONCE, SIZE, TOTAL_DIFFICULTY,
-};
+use crate::client::constants::{DIFFICULTY, EARLIEST_BLOCK_NUMBER, GAS_USED, MIX_HASH, NONCE, SIZE, TOTAL_DIFFICULTY};
 
 pub struct EthBlockId(EthereumBlockId);
 
@@ -126,8 +125,7 @@ impl BlockWithTxs {
 #[async_trait]
 impl ConvertibleStarknetBlock for BlockWithTxHashes {
     async fn to_eth_block<P: Provider + Send + Sync>(&self, client: &dyn KakarotEthApi<P>) -> RichBlock {
-        // TODO: Fetch real data
-        let gas_limit = *GAS_LIMIT;
+        let gas_limit = client.block_gas_limit();
 
         // TODO: Fetch real data
         let gas_used = *GAS_USED;
@@ -152,10 +150,11 @@ impl ConvertibleStarknetBlock for BlockWithTxHashes {
 
         let parent_hash = H256::from_slice(&self.parent_hash().to_bytes_be());
         let sequencer = Felt252Wrapper::from(self.sequencer_address()).troncate_to_ethereum_address();
-        let timestamp = U256::from(self.timestamp());
+        let timestamp = client.block_timestamp(self.timestamp(), self.block_number()).await;
 
         let hash = self.block_hash().as_ref().map(|hash| H256::from_slice(&hash.to_bytes_be()));
         let number = self.block_number().map(U256::from);
+        let (transactions_root, receipts_root) = block_roots(client, hash).await;
 
         // TODO: Add filter to tx_hashes
         let transactions = BlockTransactions::Hashes(
@@ -166,14 +165,15 @@ impl ConvertibleStarknetBlock for BlockWithTxHashes {
             // PendingBlockWithTxHashes doesn't have a block hash
             hash,
             parent_hash,
-            uncles_hash: parent_hash,
+            // Kakarot blocks have no uncles
+            uncles_hash: EMPTY_OMMER_ROOT,
             miner: sequencer,
             // PendingBlockWithTxHashes doesn't have a state root
             state_root: H256::zero(),
             // PendingBlockWithTxHashes doesn't have a transactions root
-            transactions_root: H256::zero(),
+            transactions_root,
             // PendingBlockWithTxHashes doesn't have a receipts root
-            receipts_root: H256::zero(),
+            receipts_root,
             // PendingBlockWithTxHashes doesn't have a block number
             number,
             gas_used,
@@ -185,7 +185,8 @@ impl ConvertibleStarknetBlock for BlockWithTxHashes {
             nonce,
             base_fee_per_gas: Some(base_fee_per_gas),
             mix_hash,
-            withdrawals_root: Some(H256::zero()),
+            // Root of the empty withdrawals list, so that the header is a valid Shanghai header
+            withdrawals_root: Some(EMPTY_ROOT),
         };
         let block = Block {
             header,
@@ -202,8 +203,7 @@ impl ConvertibleStarknetBlock for BlockWithTxHashes {
 #[async_trait]
 impl ConvertibleStarknetBlock for BlockWithTxs {
     async fn to_eth_block<P: Provider + Send + Sync>(&self, client: &dyn KakarotEthApi<P>) -> RichBlock {
-        // TODO: Fetch real data
-        let gas_limit = *GAS_LIMIT;
+        let gas_limit = client.block_gas_limit();
 
         // TODO: Fetch real data
         let gas_used = *GAS_USED;
@@ -230,24 +230,26 @@ impl ConvertibleStarknetBlock for BlockWithTxs {
 
         let sequencer = Felt252Wrapper::from(self.sequencer_address()).troncate_to_ethereum_address();
 
-        let timestamp = U256::from(self.timestamp());
+        let timestamp = client.block_timestamp(self.timestamp(), self.block_number()).await;
 
         let hash = self.block_hash().as_ref().map(|hash| H256::from_slice(&hash.to_bytes_be()));
         let number = self.block_number().map(U256::from);
+        let (transactions_root, receipts_root) = block_roots(client, hash).await;
 
         let transactions = client.filter_starknet_into_eth_txs(self.transactions().into(), hash, number).await;
         let header = Header {
             // PendingBlockWithTxs doesn't have a block hash
             hash,
             parent_hash,
-            uncles_hash: parent_hash,
+            // Kakarot blocks have no uncles
+            uncles_hash: EMPTY_OMMER_ROOT,
             miner: sequencer,
             // PendingBlockWithTxs doesn't have a state root
             state_root: H256::zero(),
             // PendingBlockWithTxs doesn't have a transactions root
-            transactions_root: H256::zero(),
+            transactions_root,
             // PendingBlockWithTxs doesn't have a receipts root
-            receipts_root: H256::zero(),
+            receipts_root,
             // PendingBlockWithTxs doesn't have a block number
             number,
             gas_used,
@@ -259,7 +261,8 @@ impl ConvertibleStarknetBlock for BlockWithTxs {
             nonce,
             base_fee_per_gas: Some(base_fee_per_gas),
             mix_hash,
-            withdrawals_root: Some(H256::zero()),
+            // Root of the empty withdrawals list, so that the header is a valid Shanghai header
+            withdrawals_root: Some(EMPTY_ROOT),
         };
         let block = Block {
             header,
@@ -273,9 +276,21 @@ impl ConvertibleStarknetBlock for BlockWithTxs {
     }
 }
 
+/// Returns the transactions and receipts roots of a block, zero if the block is pending or the
+/// roots are not computed.
+async fn block_roots<P: Provider + Send + Sync>(client: &dyn KakarotEthApi<P>, hash: Option<H256>) -> (H256, H256) {
+    let roots = match hash {
+        Some(hash) => client.block_roots(hash).await,
+        None => None,
+    };
+    roots.map_or((H256::zero(), H256::zero()), |roots| (roots.transactions_root, roots.receipts_root))
+}
+
 #[cfg(test)]
 mod tests {
 
+    use reth_rlp::{Decodable, Encodable};
+
     use super::*;
     use crate::client::tests::init_mock_client;
     use crate::mock::constants::{
@@ -324,4 +339,62 @@ mod tests {
             serde_json::from_str(include_str!("test_data/conversion/eth/block_with_txs.json")).unwrap();
         assert_eq!(expected, eth_block_with_txs);
     }
+
+    /// Converts an RPC header to the consensus header, failing on the fields out of its bounds.
+    fn consensus_header(header: &Header) -> reth_primitives::Header {
+        reth_primitives::Header {
+            parent_hash: header.parent_hash,
+            ommers_hash: header.uncles_hash,
+            beneficiary: header.miner,
+            state_root: header.state_root,
+            transactions_root: header.transactions_root,
+            receipts_root: header.receipts_root,
+            withdrawals_root: header.withdrawals_root,
+            logs_bloom: header.logs_bloom,
+            difficulty: header.difficulty,
+            number: header.number.unwrap_or_default().try_into().unwrap(),
+            gas_limit: header.gas_limit.try_into().unwrap(),
+            gas_used: header.gas_used.try_into().unwrap(),
+            timestamp: header.timestamp.try_into().unwrap(),
+            mix_hash: header.mix_hash,
+            nonce: header.nonce.unwrap_or_default().to_low_u64_be(),
+            base_fee_per_gas: header.base_fee_per_gas.map(|base_fee| base_fee.try_into().unwrap()),
+            extra_data: header.extra_data.clone(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_to_eth_block_headers_rlp_round_trip() {
+        // Given
+        let block_with_tx_hashes: MaybePendingBlockWithTxHashes =
+            serde_json::from_str(include_str!("test_data/conversion/starknet/block_with_tx_hashes.json")).unwrap();
+        let block_with_txs: MaybePendingBlockWithTxs =
+            serde_json::from_str(include_str!("test_data/conversion/starknet/block_with_txs.json")).unwrap();
+        let fixtures = fixtures(vec![
+            AvailableFixtures::GetClassHashAt(ABDEL_STARKNET_ADDRESS_HEX.into(), PROXY_ACCOUNT_CLASS_HASH_HEX.into()),
+            AvailableFixtures::GetClassHashAt(OTHER_ADDRESS_HEX.into(), OTHER_PROXY_ACCOUNT_CLASS_HASH_HEX.into()),
+            AvailableFixtures::GetEvmAddress,
+        ]);
+        let client = init_mock_client(Some(fixtures));
+
+        // When
+        let blocks = vec![
+            BlockWithTxHashes::new(block_with_tx_hashes).to_eth_block(&client).await.inner,
+            BlockWithTxs::new(block_with_txs).to_eth_block(&client).await.inner,
+        ];
+
+        // Then
+        for block in blocks {
+            let header = consensus_header(&block.header);
+            let mut encoded = vec![];
+            header.encode(&mut encoded);
+            assert_eq!(header, reth_primitives::Header::decode(&mut encoded.as_slice()).unwrap());
+            // The synthesized fields are consistent with the empty uncles and withdrawals
+            assert!(block.uncles.is_empty());
+            assert_eq!(EMPTY_OMMER_ROOT, header.ommers_hash);
+            assert_eq!(Some(vec![]), block.withdrawals);
+            assert_eq!(Some(EMPTY_ROOT), header.withdrawals_root);
+            assert!(header.gas_used <= header.gas_limit);
+        }
+    }
 }
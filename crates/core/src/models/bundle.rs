@@ -0,0 +1,30 @@
+use reth_primitives::{Address, Bytes, H256, U256, U64};
+use serde::{Deserialize, Serialize};
+
+/// Result of a transaction of a simulated bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleTransactionResult {
+    pub tx_hash: H256,
+    pub from_address: Address,
+    /// Called address, `None` for a deployment.
+    pub to_address: Option<Address>,
+    /// Starknet gas consumed by the transaction, the unit of `eth_estimateGas`.
+    pub gas_used: U256,
+    /// Data returned by the transaction.
+    pub return_data: Bytes,
+    /// Revert reason reported by the Starknet provider if the transaction reverted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Results of an ordered bundle of transactions simulated on top of a block, each transaction
+/// seeing the state changes of the previous ones.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallBundle {
+    /// Block the bundle was simulated on top of.
+    pub state_block_number: U64,
+    pub total_gas_used: U256,
+    pub results: Vec<BundleTransactionResult>,
+}
@@ -124,7 +124,8 @@ impl Calls {
 mod tests {
     use std::str::FromStr;
 
-    use reth_primitives::{Address, U256};
+    use reth_primitives::{keccak256, Address, U256};
+    use reth_rlp::Encodable;
     use serde::Deserialize;
 
     use super::*;
@@ -215,6 +216,26 @@ mod tests {
         TryInto::<TransactionSigned>::try_into(&calls).unwrap();
     }
 
+    #[test]
+    fn test_calls_transaction_rlp_round_trip() {
+        // Given
+        let raw: Vec<FieldElement> = serde_json::from_str(include_str!("test_data/call/kakarot_call.json")).unwrap();
+        let calls: Calls = raw.try_into().unwrap();
+        let transaction = TransactionSigned::try_from(&calls).unwrap();
+
+        // When
+        let mut encoded = vec![];
+        transaction.encode(&mut encoded);
+        let decoded = TransactionSigned::decode(&mut encoded.as_slice()).unwrap();
+
+        // Then
+        assert_eq!(transaction, decoded);
+        let mut enveloped = vec![];
+        transaction.encode_enveloped(&mut enveloped);
+        assert_eq!(transaction.hash(), keccak256(&enveloped));
+        assert!(transaction.recover_signer().is_some());
+    }
+
     #[test]
     fn test_calls_get_to() {
         // Given
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+use crate::client::config::OperatingMode;
+
+/// Features served by the RPC in its operating mode, advertised through `kakarot_capabilities`.
+/// Logs are always read from the Starknet events of the queried range, there is no log index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Capabilities {
+    pub mode: OperatingMode,
+    /// Number of past blocks whose state and logs are served, `None` if the whole history is.
+    pub history_blocks: Option<u64>,
+    /// Relayed transactions are tracked, see `kakarot_getTransactionStatus`.
+    pub transaction_index: bool,
+}
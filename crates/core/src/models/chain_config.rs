@@ -0,0 +1,47 @@
+use reth_primitives::{U128, U64};
+use serde::{Deserialize, Serialize};
+use starknet::core::types::FieldElement;
+
+use crate::client::config::OperatingMode;
+
+/// ERC-20 token the fees are paid in, whose balances are reported as ETH balances.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeToken {
+    pub address: FieldElement,
+    pub decimals: u8,
+}
+
+/// Constants used to translate the Starknet fees to Ethereum gas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasConstants {
+    /// Base fee per gas reported in the blocks and returned by `eth_gasPrice`.
+    pub base_fee_per_gas: U64,
+    pub max_priority_fee_per_gas: U128,
+    /// Minimum gas returned by `eth_estimateGas`, so that wallets accept the transactions.
+    pub minimum_gas: U64,
+    /// Effective gas price reported in the receipts whose fee is unknown.
+    pub effective_gas_price: U128,
+}
+
+/// Chain configuration the RPC operates under, returned by `kakarot_getChainConfig` so that
+/// client SDKs can configure themselves and bug reports include the exact deployment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainConfig {
+    pub chain_id: U64,
+    /// Chain id of the Starknet network, `None` if the provider could not be reached.
+    pub starknet_chain_id: Option<FieldElement>,
+    pub kakarot_address: FieldElement,
+    pub proxy_account_class_hash: FieldElement,
+    pub kakarot_class_hash: Option<FieldElement>,
+    pub mode: OperatingMode,
+    pub fee_token: FeeToken,
+    pub gas: GasConstants,
+    pub client_version: String,
+    /// Block the Starknet network was forked at, `None` unless the provider is a fork.
+    pub fork_block: Option<U64>,
+    /// Namespaces of the methods served, e.g. `eth` or `kakarot`, filled by the RPC server.
+    pub namespaces: Vec<String>,
+}
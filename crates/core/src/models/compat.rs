@@ -0,0 +1,410 @@
+use std::ops::Deref;
+use std::str::FromStr;
+
+use serde::de::Error as _;
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+/// Fields holding byte arrays, serialized as `0x` when empty.
+const BYTES_FIELDS: [&str; 3] = ["input", "data", "extraData"];
+/// EIP-4844 header fields, always `null` as Kakarot does not support blob transactions.
+const BLOB_HEADER_FIELDS: [&str; 2] = ["blobGasUsed", "excessBlobGas"];
+/// Fields holding quantities, or arrays of quantities such as the `baseFeePerGas` of
+/// `eth_feeHistory`. The `nonce` of a header is 8 bytes of data and is left as is.
+const QUANTITY_FIELDS: [&str; 27] = [
+    "baseFeePerGas",
+    "blockNumber",
+    "chainId",
+    "cumulativeGasUsed",
+    "difficulty",
+    "effectiveGasPrice",
+    "gas",
+    "gasLimit",
+    "gasPrice",
+    "gasUsed",
+    "logIndex",
+    "maxFeePerGas",
+    "maxPriorityFeePerGas",
+    "nonce",
+    "number",
+    "oldestBlock",
+    "r",
+    "reward",
+    "s",
+    "size",
+    "status",
+    "timestamp",
+    "totalDifficulty",
+    "transactionIndex",
+    "type",
+    "v",
+    "value",
+];
+
+/// Returns the canonical encoding of a quantity, as Geth: `0x` followed by the lowercase digits
+/// without leading zeros, and `0x0` for zero. Returns `None` if `quantity` is not hexadecimal.
+pub fn canonical_quantity(quantity: &str) -> Option<String> {
+    let digits = quantity.strip_prefix("0x").or_else(|| quantity.strip_prefix("0X"))?;
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    match digits.trim_start_matches('0') {
+        "" => Some("0x0".into()),
+        digits => Some(format!("0x{}", digits.to_ascii_lowercase())),
+    }
+}
+
+/// Rewrites the quantities of a field in their canonical encoding, leaving other values as is.
+fn canonicalize_quantities(value: &mut Value) {
+    match value {
+        Value::String(string) => {
+            if let Some(quantity) = canonical_quantity(string) {
+                *string = quantity;
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(canonicalize_quantities),
+        _ => {}
+    }
+}
+
+/// Encoding of empty byte fields such as `input` or `data`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EmptyBytes {
+    /// `0x`, as Geth.
+    #[default]
+    Hex,
+    /// `null`.
+    Null,
+}
+
+/// Encoding of zero quantities.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ZeroQuantity {
+    /// `0x0`, as Geth.
+    #[default]
+    Compact,
+    /// `0x00`.
+    Padded,
+}
+
+impl FromStr for EmptyBytes {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hex" => Ok(Self::Hex),
+            "null" => Ok(Self::Null),
+            _ => Err(format!("Invalid empty bytes encoding: {s}, expected hex or null")),
+        }
+    }
+}
+
+impl FromStr for ZeroQuantity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "compact" => Ok(Self::Compact),
+            "padded" => Ok(Self::Padded),
+            _ => Err(format!("Invalid zero quantity encoding: {s}, expected compact or padded")),
+        }
+    }
+}
+
+/// Switches for the small differences between the requests and responses of Ethereum clients that
+/// some tools depend on. The default matches Geth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResponseCompat {
+    pub empty_bytes: EmptyBytes,
+    pub zero_quantity: ZeroQuantity,
+    /// Adds `yParity` to typed transactions, or removes it when false.
+    pub include_y_parity: bool,
+    /// Accepts the boolean parameters sent as the strings `"true"` and `"false"`, as some Hardhat
+    /// plugins do, which Geth rejects.
+    pub string_booleans: bool,
+}
+
+impl Default for ResponseCompat {
+    fn default() -> Self {
+        Self::geth()
+    }
+}
+
+impl ResponseCompat {
+    pub fn geth() -> Self {
+        Self {
+            empty_bytes: EmptyBytes::Hex,
+            zero_quantity: ZeroQuantity::Compact,
+            include_y_parity: true,
+            string_booleans: false,
+        }
+    }
+
+    pub fn erigon() -> Self {
+        Self { include_y_parity: false, ..Self::geth() }
+    }
+
+    /// Geth responses, accepting the requests of the Hardhat network plugins.
+    pub fn hardhat() -> Self {
+        Self { string_booleans: true, ..Self::geth() }
+    }
+
+    /// Returns the preset of a client, `geth`, `erigon` or `hardhat`.
+    pub fn preset(client: &str) -> Option<Self> {
+        match client {
+            "geth" => Some(Self::geth()),
+            "erigon" => Some(Self::erigon()),
+            "hardhat" => Some(Self::hardhat()),
+            _ => None,
+        }
+    }
+
+    /// Applies the switches to a serialized response. Quantities are first rewritten in their
+    /// canonical encoding, whatever the type they were serialized from, so that the responses
+    /// can be compared as strings with the ones of Geth.
+    pub fn apply(&self, value: &mut Value) {
+        match value {
+            Value::Object(object) => {
+                let is_header = object.contains_key("parentHash");
+                object
+                    .iter_mut()
+                    .filter(|(key, _)| QUANTITY_FIELDS.contains(&key.as_str()) && !(is_header && *key == "nonce"))
+                    .for_each(|(_, value)| canonicalize_quantities(value));
+
+                for field in BYTES_FIELDS {
+                    match (object.get_mut(field), self.empty_bytes) {
+                        (Some(value), EmptyBytes::Null) if value == "0x" => *value = Value::Null,
+                        (Some(value), EmptyBytes::Hex) if value.is_null() => *value = "0x".into(),
+                        _ => {}
+                    }
+                }
+
+                let is_typed_transaction =
+                    object.contains_key("v") && object.get("type").map_or(false, |kind| kind != "0x0");
+                if self.include_y_parity && is_typed_transaction {
+                    let y_parity = object["v"].clone();
+                    object.entry("yParity").or_insert(y_parity);
+                } else if !self.include_y_parity {
+                    object.remove("yParity");
+                }
+
+                // Clients reading the EIP-4844 fields expect them in every header
+                if object.contains_key("parentHash") && object.contains_key("stateRoot") {
+                    for field in BLOB_HEADER_FIELDS {
+                        object.entry(field).or_insert(Value::Null);
+                    }
+                }
+
+                object
+                    .iter_mut()
+                    .filter(|(key, _)| !BYTES_FIELDS.contains(&key.as_str()))
+                    .for_each(|(_, value)| self.apply(value));
+            }
+            Value::Array(values) => values.iter_mut().for_each(|value| self.apply(value)),
+            Value::String(string) if self.zero_quantity == ZeroQuantity::Padded && string == "0x0" => {
+                *string = "0x00".into();
+            }
+            _ => {}
+        }
+    }
+
+    /// Wraps a response so that it is serialized with the switches applied.
+    pub fn wrap<T: Serialize>(&self, response: T) -> CompatResponse<T> {
+        CompatResponse { response, compat: *self }
+    }
+}
+
+/// Boolean parameter, such as the `full` flag of `eth_getBlockByNumber`, which may be sent as a
+/// string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoolParam {
+    value: bool,
+    quoted: bool,
+}
+
+impl From<bool> for BoolParam {
+    fn from(value: bool) -> Self {
+        Self { value, quoted: false }
+    }
+}
+
+impl<'de> Deserialize<'de> for BoolParam {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Param {
+            Bool(bool),
+            String(String),
+        }
+
+        match Param::deserialize(deserializer)? {
+            Param::Bool(value) => Ok(value.into()),
+            Param::String(value) => match value.as_str() {
+                "true" => Ok(Self { value: true, quoted: true }),
+                "false" => Ok(Self { value: false, quoted: true }),
+                _ => Err(D::Error::custom(format!("invalid boolean: {value}, expected true or false"))),
+            },
+        }
+    }
+}
+
+impl BoolParam {
+    /// Returns the value of the parameter, an error if it was sent as a string without the
+    /// `string_booleans` switch.
+    pub fn resolve(self, compat: &ResponseCompat) -> Result<bool, String> {
+        if self.quoted && !compat.string_booleans {
+            return Err(format!("invalid boolean: \"{}\", expected true or false", self.value));
+        }
+        Ok(self.value)
+    }
+}
+
+/// A response serialized with the [`ResponseCompat`] switches applied. Fields keep the order of
+/// the response type, followed by the fields added by the switches, so that identical responses
+/// are serialized to identical strings.
+#[derive(Debug, Clone)]
+pub struct CompatResponse<T> {
+    response: T,
+    compat: ResponseCompat,
+}
+
+impl<T> CompatResponse<T> {
+    pub fn into_inner(self) -> T {
+        self.response
+    }
+}
+
+impl<T> Deref for CompatResponse<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.response
+    }
+}
+
+impl<T: Serialize> Serialize for CompatResponse<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut value = serde_json::to_value(&self.response).map_err(S::Error::custom)?;
+        self.compat.apply(&mut value);
+        value.serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_response_compat_apply() {
+        // Given
+        let response = json!({
+            "transactions": [{ "type": "0x2", "v": "0x1", "input": "0x", "nonce": "0x0" }],
+            "extraData": "0x",
+        });
+
+        // When
+        let geth = serde_json::to_value(ResponseCompat::geth().wrap(&response)).unwrap();
+        let custom = ResponseCompat {
+            empty_bytes: EmptyBytes::Null,
+            zero_quantity: ZeroQuantity::Padded,
+            include_y_parity: false,
+            string_booleans: false,
+        };
+        let custom = serde_json::to_value(custom.wrap(&geth)).unwrap();
+
+        // Then
+        assert_eq!(
+            json!({
+                "transactions": [{ "type": "0x2", "v": "0x1", "yParity": "0x1", "input": "0x", "nonce": "0x0" }],
+                "extraData": "0x",
+            }),
+            geth
+        );
+        assert_eq!(
+            json!({
+                "transactions": [{ "type": "0x2", "v": "0x1", "input": null, "nonce": "0x00" }],
+                "extraData": null,
+            }),
+            custom
+        );
+    }
+
+    #[test]
+    fn test_response_compat_blob_header_fields() {
+        // Given
+        let header = json!({ "parentHash": "0x0", "stateRoot": "0x0" });
+
+        // When
+        let header = serde_json::to_value(ResponseCompat::default().wrap(&header)).unwrap();
+
+        // Then
+        assert_eq!(Value::Null, header["blobGasUsed"]);
+        assert_eq!(Value::Null, header["excessBlobGas"]);
+        assert!(header.as_object().unwrap().contains_key("excessBlobGas"));
+    }
+
+    #[test]
+    fn test_canonical_quantity() {
+        // Then
+        assert_eq!(Some("0x0".to_string()), canonical_quantity("0x0000"));
+        assert_eq!(Some("0x7a120".to_string()), canonical_quantity("0x07A120"));
+        assert_eq!(None, canonical_quantity("0x"));
+        assert_eq!(None, canonical_quantity("12"));
+        assert_eq!(None, canonical_quantity("0xzz"));
+    }
+
+    #[test]
+    fn test_response_compat_geth_conformance() {
+        // Given
+        let block: Value = serde_json::from_str(include_str!("test_data/compat/block.json")).unwrap();
+        let geth: Value = serde_json::from_str(include_str!("test_data/compat/block_geth.json")).unwrap();
+
+        // When
+        let block = serde_json::to_string(&ResponseCompat::geth().wrap(&block)).unwrap();
+
+        // Then
+        // Compared as strings, fields in the same order, as the tools checking responses do
+        assert_eq!(geth.to_string(), block);
+    }
+
+    #[test]
+    fn test_response_compat_fee_history_quantities() {
+        // Given
+        let fee_history = json!({
+            "baseFeePerGas": ["0x00", "0x03B9ACA00"],
+            "gasUsedRatio": [0.5],
+            "oldestBlock": "0x0F",
+            "reward": [["0x00"]],
+        });
+
+        // When
+        let fee_history = serde_json::to_value(ResponseCompat::geth().wrap(&fee_history)).unwrap();
+
+        // Then
+        assert_eq!(
+            json!({
+                "baseFeePerGas": ["0x0", "0x3b9aca00"],
+                "gasUsedRatio": [0.5],
+                "oldestBlock": "0xf",
+                "reward": [["0x0"]],
+            }),
+            fee_history
+        );
+    }
+
+    #[test]
+    fn test_bool_param_resolve() {
+        // Given
+        let quoted: BoolParam = serde_json::from_value(json!("false")).unwrap();
+        let unquoted: BoolParam = serde_json::from_value(json!(true)).unwrap();
+
+        // Then
+        assert!(quoted.resolve(&ResponseCompat::geth()).is_err());
+        assert_eq!(Ok(false), quoted.resolve(&ResponseCompat::hardhat()));
+        assert_eq!(Ok(true), unquoted.resolve(&ResponseCompat::geth()));
+        assert!(serde_json::from_value::<BoolParam>(json!("yes")).is_err());
+    }
+}
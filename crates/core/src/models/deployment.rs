@@ -0,0 +1,81 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// The outcome of a single check run against the Kakarot deployment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeploymentCheck {
+    /// Name of the check.
+    pub name: String,
+    /// Whether the check passed.
+    pub passed: bool,
+    /// Details about the outcome, including how to fix a failing check.
+    pub message: String,
+}
+
+impl DeploymentCheck {
+    pub fn passed(name: &str, message: impl Into<String>) -> Self {
+        Self { name: name.into(), passed: true, message: message.into() }
+    }
+
+    pub fn failed(name: &str, message: impl Into<String>) -> Self {
+        Self { name: name.into(), passed: false, message: message.into() }
+    }
+}
+
+/// Report of the self-check of the Kakarot deployment the RPC is configured against.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeploymentReport {
+    pub checks: Vec<DeploymentCheck>,
+}
+
+impl DeploymentReport {
+    /// Returns true if all the checks passed.
+    pub fn is_valid(&self) -> bool {
+        self.checks.iter().all(|check| check.passed)
+    }
+
+    /// Returns the checks that failed.
+    pub fn failures(&self) -> impl Iterator<Item = &DeploymentCheck> {
+        self.checks.iter().filter(|check| !check.passed)
+    }
+}
+
+impl fmt::Display for DeploymentReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            let status = if check.passed { "ok" } else { "FAILED" };
+            writeln!(f, "[{status}] {}: {}", check.name, check.message)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deployment_report_is_valid() {
+        // Given
+        let report =
+            DeploymentReport { checks: vec![DeploymentCheck::passed("a", "ok"), DeploymentCheck::passed("b", "ok")] };
+
+        // Then
+        assert!(report.is_valid());
+        assert_eq!(0, report.failures().count());
+    }
+
+    #[test]
+    fn test_deployment_report_lists_failures() {
+        // Given
+        let report = DeploymentReport {
+            checks: vec![DeploymentCheck::passed("a", "ok"), DeploymentCheck::failed("b", "missing")],
+        };
+
+        // Then
+        assert!(!report.is_valid());
+        assert_eq!(vec!["b"], report.failures().map(|check| check.name.as_str()).collect::<Vec<_>>());
+        assert_eq!("[ok] a: ok\n[FAILED] b: missing\n", report.to_string());
+    }
+}
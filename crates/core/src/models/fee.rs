@@ -0,0 +1,89 @@
+use reth_primitives::U256;
+use serde::{Deserialize, Serialize};
+
+/// Estimated Starknet fee of relaying an Ethereum transaction. The relayed invokes pay their fees
+/// in ETH, so amounts are denominated in WEI, and converted to FRI, the smallest unit of STRK, when
+/// the provider reports the gas price in both.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StarknetFeeEstimate {
+    /// Starknet gas consumed by the transaction.
+    pub gas_consumed: U256,
+    /// Starknet gas price, in WEI.
+    pub gas_price: U256,
+    /// Estimated fee of the transaction, in WEI.
+    pub overall_fee: U256,
+    /// Max fee the relayer sets when resubmitting the transaction, in WEI.
+    pub max_fee: U256,
+    /// Starknet gas price, in FRI.
+    pub gas_price_fri: Option<U256>,
+    /// Estimated fee of the transaction, in FRI.
+    pub overall_fee_fri: Option<U256>,
+    /// Max fee the relayer sets when resubmitting the transaction, in FRI.
+    pub max_fee_fri: Option<U256>,
+}
+
+impl StarknetFeeEstimate {
+    /// Converts the WEI amounts to FRI at the rate of the gas prices of a block, which reports the
+    /// price of the same gas in both units.
+    pub fn with_fri_rate(mut self, price_in_wei: U256, price_in_fri: U256) -> Self {
+        if price_in_wei == U256::ZERO {
+            return self;
+        }
+        let to_fri = |wei: U256| wei.saturating_mul(price_in_fri) / price_in_wei;
+        self.gas_price_fri = Some(to_fri(self.gas_price));
+        self.overall_fee_fri = Some(to_fri(self.overall_fee));
+        self.max_fee_fri = Some(to_fri(self.max_fee));
+        self
+    }
+}
+
+/// Translates the fee paid for a Starknet transaction into the gas used by the Ethereum
+/// transaction at `gas_price`, rounded up so that `gas_used * gas_price` covers the fee. Starknet
+/// has no priority fee, the price of the gas is the base fee reported by `eth_feeHistory`.
+pub fn starknet_fee_to_gas(actual_fee: U256, gas_price: U256) -> U256 {
+    if gas_price == U256::ZERO {
+        return U256::ZERO;
+    }
+    actual_fee.div_ceil(gas_price)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_starknet_fee_estimate_in_fri() {
+        // Given
+        let fee_estimate = StarknetFeeEstimate {
+            gas_consumed: U256::from(100),
+            gas_price: U256::from(10),
+            overall_fee: U256::from(1_000),
+            max_fee: U256::from(1_500),
+            gas_price_fri: None,
+            overall_fee_fri: None,
+            max_fee_fri: None,
+        };
+
+        // When
+        let in_fri = fee_estimate.clone().with_fri_rate(U256::from(10), U256::from(25));
+        let without_rate = fee_estimate.clone().with_fri_rate(U256::ZERO, U256::from(25));
+
+        // Then
+        assert_eq!(Some(U256::from(25)), in_fri.gas_price_fri);
+        assert_eq!(Some(U256::from(2_500)), in_fri.overall_fee_fri);
+        assert_eq!(Some(U256::from(3_750)), in_fri.max_fee_fri);
+        assert_eq!(fee_estimate, without_rate);
+        let json = serde_json::to_value(&in_fri).unwrap();
+        assert_eq!("0x3e8", json["overallFee"]);
+        assert_eq!("0x9c4", json["overallFeeFri"]);
+        assert!(serde_json::to_value(&fee_estimate).unwrap()["overallFeeFri"].is_null());
+    }
+
+    #[test]
+    fn test_starknet_fee_to_gas() {
+        assert_eq!(U256::from(21_000), starknet_fee_to_gas(U256::from(21_000), U256::from(1)));
+        assert_eq!(U256::from(3), starknet_fee_to_gas(U256::from(25), U256::from(10)));
+        assert_eq!(U256::ZERO, starknet_fee_to_gas(U256::from(25), U256::ZERO));
+    }
+}
@@ -0,0 +1,175 @@
+use reth_primitives::{Address, BlockNumberOrTag, H256};
+use reth_rpc_types::Log;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::address::AddressParam;
+
+/// Maximum number of topic positions of a log filter, the number of topics of a log.
+pub const MAX_TOPICS: usize = 4;
+
+/// A single value or an array of values, as accepted by the `address` and `topics` fields of a
+/// log filter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ValueOrArray<T> {
+    Value(T),
+    Array(Vec<T>),
+}
+
+impl<T: PartialEq> ValueOrArray<T> {
+    /// Returns true if `value` is the value or one of the values of the array.
+    pub fn contains(&self, value: &T) -> bool {
+        match self {
+            Self::Value(expected) => expected == value,
+            Self::Array(expected) => expected.contains(value),
+        }
+    }
+}
+
+/// Deserializes the addresses of a filter, validated as the address parameters.
+fn deserialize_addresses<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<ValueOrArray<Address>>, D::Error> {
+    Ok(Option::<ValueOrArray<AddressParam>>::deserialize(deserializer)?.map(|addresses| match addresses {
+        ValueOrArray::Value(address) => ValueOrArray::Value(address.into()),
+        ValueOrArray::Array(addresses) => ValueOrArray::Array(addresses.into_iter().map(Into::into).collect()),
+    }))
+}
+
+/// Deserializes the topics of a filter as Geth does: a `null` position, an empty array and an array
+/// containing `null` match any topic, and at most `MAX_TOPICS` positions are accepted.
+fn deserialize_topics<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Vec<Option<ValueOrArray<H256>>>>, D::Error> {
+    let Some(topics) = Option::<Vec<Option<ValueOrArray<Option<H256>>>>>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    if topics.len() > MAX_TOPICS {
+        return Err(serde::de::Error::custom("exceed max topics"));
+    }
+    Ok(Some(
+        topics
+            .into_iter()
+            .map(|topic| match topic? {
+                ValueOrArray::Value(topic) => topic.map(ValueOrArray::Value),
+                ValueOrArray::Array(topics) if topics.is_empty() => None,
+                ValueOrArray::Array(topics) => topics.into_iter().collect::<Option<_>>().map(ValueOrArray::Array),
+            })
+            .collect(),
+    ))
+}
+
+/// Filter of `eth_getLogs`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogFilter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_block: Option<BlockNumberOrTag>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_block: Option<BlockNumberOrTag>,
+    /// Restricts the logs to a single block, exclusive with `from_block` and `to_block`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<H256>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "deserialize_addresses")]
+    pub address: Option<ValueOrArray<Address>>,
+    /// Topics by position, `None` matching any topic and an array matching any of its topics.
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "deserialize_topics")]
+    pub topics: Option<Vec<Option<ValueOrArray<H256>>>>,
+}
+
+impl LogFilter {
+    /// Returns true if the log matches the address and topics of the filter, with the semantics of
+    /// Geth: a log with fewer topics than the positions of the filter never matches, even if the
+    /// extra positions are wildcards.
+    pub fn matches(&self, log: &Log) -> bool {
+        if let Some(address) = &self.address {
+            if !address.contains(&log.address) {
+                return false;
+            }
+        }
+
+        let topics = self.topics.as_deref().unwrap_or_default();
+        if topics.len() > log.topics.len() {
+            return false;
+        }
+        topics.iter().zip(&log.topics).all(|(expected, topic)| match expected {
+            None => true,
+            Some(ValueOrArray::Array(expected)) if expected.is_empty() => true,
+            Some(expected) => expected.contains(topic),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_log_filter_matches() {
+        // Given
+        let log: Log = serde_json::from_str(include_str!("test_data/conversion/eth/event_log3.json")).unwrap();
+        let other_topic = H256::from_low_u64_be(1);
+
+        // When
+        let any = LogFilter::default();
+        let by_address = LogFilter { address: Some(ValueOrArray::Value(log.address)), ..Default::default() };
+        let by_other_address =
+            LogFilter { address: Some(ValueOrArray::Value(Address::from_low_u64_be(1))), ..Default::default() };
+        let by_second_topic = LogFilter {
+            topics: Some(vec![None, Some(ValueOrArray::Array(vec![other_topic, log.topics[1]]))]),
+            ..Default::default()
+        };
+        let by_other_topic =
+            LogFilter { topics: Some(vec![Some(ValueOrArray::Value(other_topic))]), ..Default::default() };
+
+        // Then
+        assert!(any.matches(&log));
+        assert!(by_address.matches(&log));
+        assert!(!by_other_address.matches(&log));
+        assert!(by_second_topic.matches(&log));
+        assert!(!by_other_topic.matches(&log));
+    }
+
+    /// Cases of the topic filters of go-ethereum's `TestFilters` and
+    /// `TestUnmarshalJSONNewFilterArgs`, matched against logs with 0 to 4 topics.
+    #[test]
+    fn test_log_filter_topics_geth_parity() {
+        // Given
+        let (a, b, c, d) =
+            (H256::repeat_byte(0xa), H256::repeat_byte(0xb), H256::repeat_byte(0xc), H256::repeat_byte(0xd));
+        let fixture: Log = serde_json::from_str(include_str!("test_data/conversion/eth/event_log3.json")).unwrap();
+        let logs: Vec<Log> = [vec![], vec![a], vec![a, b], vec![b, c, d], vec![a, b, c, d]]
+            .into_iter()
+            .map(|topics| Log { topics, ..fixture.clone() })
+            .collect();
+        let cases = [
+            (json!(null), vec![0, 1, 2, 3, 4]),
+            (json!([]), vec![0, 1, 2, 3, 4]),
+            (json!([null]), vec![1, 2, 3, 4]),
+            (json!([a]), vec![1, 2, 4]),
+            (json!([[a, b]]), vec![1, 2, 3, 4]),
+            (json!([null, b]), vec![2, 4]),
+            (json!([[a, null]]), vec![1, 2, 3, 4]),
+            (json!([[]]), vec![1, 2, 3, 4]),
+            (json!([a, null, null]), vec![4]),
+            (json!([[a, b], [b, c], null, d]), vec![4]),
+            (json!([b, [c], [d]]), vec![3]),
+            (json!([H256::repeat_byte(0xe)]), vec![]),
+            (json!([null, null, null, null]), vec![4]),
+        ];
+
+        for (topics, expected) in cases {
+            // When
+            let filter: LogFilter = serde_json::from_value(json!({ "topics": topics })).unwrap();
+            let matched: Vec<usize> = (0..logs.len()).filter(|i| filter.matches(&logs[*i])).collect();
+
+            // Then
+            assert_eq!(expected, matched, "topics {topics}");
+        }
+        let too_many = serde_json::from_value::<LogFilter>(json!({ "topics": [a, b, c, d, a] }));
+        assert!(too_many.unwrap_err().to_string().contains("exceed max topics"));
+        assert!(serde_json::from_value::<LogFilter>(json!({ "topics": ["0x12"] })).is_err());
+    }
+}
@@ -0,0 +1,139 @@
+use reth_primitives::{keccak256, H256};
+use serde::{Deserialize, Serialize};
+use starknet::core::types::{FieldElement, MsgToL1, TransactionStatus};
+
+/// Direction of a message between L1 and Starknet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MessageDirection {
+    L1ToL2,
+    L2ToL1,
+}
+
+/// Status of a message between L1 and Starknet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MessageStatus {
+    /// L2 → L1 message of a transaction not accepted on L1 yet.
+    Pending,
+    /// L2 → L1 message accepted on L1, which its recipient can consume. Whether it was already
+    /// consumed is only known to the Starknet core contract on L1.
+    Consumable,
+    /// L1 → L2 message consumed by its L1 handler transaction.
+    Consumed,
+    /// Message of a rejected transaction.
+    Rejected,
+}
+
+impl MessageStatus {
+    /// Returns the status of the L2 → L1 messages sent by a transaction.
+    pub fn of_l2_to_l1(status: TransactionStatus) -> Self {
+        match status {
+            TransactionStatus::Pending | TransactionStatus::AcceptedOnL2 => Self::Pending,
+            TransactionStatus::AcceptedOnL1 => Self::Consumable,
+            TransactionStatus::Rejected => Self::Rejected,
+        }
+    }
+
+    /// Returns the status of the L1 → L2 message consumed by an L1 handler transaction.
+    pub fn of_l1_to_l2(status: TransactionStatus) -> Self {
+        match status {
+            TransactionStatus::Rejected => Self::Rejected,
+            _ => Self::Consumed,
+        }
+    }
+}
+
+/// A message between L1 and Starknet, identified by its hash in the Starknet core contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1Message {
+    pub direction: MessageDirection,
+    pub hash: H256,
+    pub from_address: FieldElement,
+    pub to_address: FieldElement,
+    pub payload: Vec<FieldElement>,
+    /// Nonce of an L1 → L2 message.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+    pub status: MessageStatus,
+}
+
+impl L1Message {
+    /// Returns the L2 → L1 messages sent by `from_address` in a transaction.
+    pub fn l2_to_l1(from_address: FieldElement, messages_sent: &[MsgToL1], status: MessageStatus) -> Vec<Self> {
+        messages_sent
+            .iter()
+            .map(|message| Self {
+                direction: MessageDirection::L2ToL1,
+                hash: l2_to_l1_message_hash(from_address, message.to_address, &message.payload),
+                from_address,
+                to_address: message.to_address,
+                payload: message.payload.clone(),
+                nonce: None,
+                status,
+            })
+            .collect()
+    }
+}
+
+/// Hashes the words of a message as the Starknet core contract, each word padded to 32 bytes.
+fn message_hash(words: impl IntoIterator<Item = FieldElement>) -> H256 {
+    let preimage: Vec<u8> = words.into_iter().flat_map(|word| word.to_bytes_be()).collect();
+    keccak256(preimage)
+}
+
+/// Computes the hash of an L2 → L1 message, as consumed on L1:
+/// `keccak256(from_address, to_address, payload.length, payload)`.
+pub fn l2_to_l1_message_hash(from_address: FieldElement, to_address: FieldElement, payload: &[FieldElement]) -> H256 {
+    message_hash(
+        [from_address, to_address, FieldElement::from(payload.len() as u64)].into_iter().chain(payload.iter().copied()),
+    )
+}
+
+/// Computes the hash of an L1 → L2 message, as sent on L1:
+/// `keccak256(from_address, to_address, nonce, selector, payload.length, payload)`.
+pub fn l1_to_l2_message_hash(
+    from_address: FieldElement,
+    to_address: FieldElement,
+    nonce: u64,
+    selector: FieldElement,
+    payload: &[FieldElement],
+) -> H256 {
+    message_hash(
+        [from_address, to_address, FieldElement::from(nonce), selector, FieldElement::from(payload.len() as u64)]
+            .into_iter()
+            .chain(payload.iter().copied()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_hashes() {
+        // Given
+        let word = |value: u8| {
+            let mut bytes = [0u8; 32];
+            bytes[31] = value;
+            bytes
+        };
+
+        // When
+        let l2_to_l1 = l2_to_l1_message_hash(FieldElement::ONE, FieldElement::TWO, &[FieldElement::THREE]);
+        let l1_to_l2 = l1_to_l2_message_hash(FieldElement::ONE, FieldElement::TWO, 4, FieldElement::THREE, &[]);
+
+        // Then
+        assert_eq!(keccak256([word(1), word(2), word(1), word(3)].concat()), l2_to_l1);
+        assert_eq!(keccak256([word(1), word(2), word(4), word(3), word(0)].concat()), l1_to_l2);
+    }
+
+    #[test]
+    fn test_message_status() {
+        assert_eq!(MessageStatus::Pending, MessageStatus::of_l2_to_l1(TransactionStatus::AcceptedOnL2));
+        assert_eq!(MessageStatus::Consumable, MessageStatus::of_l2_to_l1(TransactionStatus::AcceptedOnL1));
+        assert_eq!(MessageStatus::Consumed, MessageStatus::of_l1_to_l2(TransactionStatus::AcceptedOnL2));
+        assert_eq!(MessageStatus::Rejected, MessageStatus::of_l1_to_l2(TransactionStatus::Rejected));
+    }
+}
@@ -1,19 +1,48 @@
+pub mod account;
+pub mod address;
 pub mod balance;
+#[cfg(feature = "client")]
 pub mod block;
+pub mod bundle;
 pub mod call;
+#[cfg(feature = "client")]
+pub mod capabilities;
+#[cfg(feature = "client")]
+pub mod chain_config;
+pub mod compat;
+#[cfg(feature = "client")]
 pub mod convertible;
+pub mod deployment;
+#[cfg(feature = "client")]
 pub mod event;
+pub mod fee;
 pub mod felt;
+pub mod filter;
+pub mod message;
+pub mod precompile;
 pub mod signature;
+#[cfg(feature = "client")]
+pub mod state_diff;
+pub mod storage;
 #[cfg(test)]
 pub mod tests;
+#[cfg(feature = "client")]
 pub mod transaction;
 
+use reth_rlp::DecodeError;
 use ruint::FromUintError;
 use starknet::core::types::FromByteArrayError;
 use thiserror::Error;
 
-use crate::client::helpers::DataDecodingError;
+#[derive(Debug, Error)]
+pub enum DataDecodingError {
+    #[error("failed to decode signature {0}")]
+    SignatureDecodingError(String),
+    #[error("failed to decode transaction")]
+    TransactionDecodingError(#[from] DecodeError),
+    #[error("{entrypoint} returned invalid array length, expected {expected}, got {actual}")]
+    InvalidReturnArrayLength { entrypoint: String, expected: usize, actual: usize },
+}
 
 #[derive(Debug, Error)]
 /// Conversion error
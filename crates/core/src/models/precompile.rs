@@ -0,0 +1,56 @@
+use reth_primitives::Address;
+use serde::{Deserialize, Serialize};
+
+/// Ethereum precompiles, executed by Kakarot in Cairo at the addresses of the Ethereum ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Precompile {
+    EcRecover = 1,
+    Sha256 = 2,
+    Ripemd160 = 3,
+    Identity = 4,
+    ModExp = 5,
+    EcAdd = 6,
+    EcMul = 7,
+    EcPairing = 8,
+    Blake2f = 9,
+}
+
+impl Precompile {
+    /// Returns the precompile deployed at an address, if any.
+    pub fn from_address(address: Address) -> Option<Self> {
+        if address.0[..19].iter().any(|byte| *byte != 0) {
+            return None;
+        }
+        match address.0[19] {
+            1 => Some(Self::EcRecover),
+            2 => Some(Self::Sha256),
+            3 => Some(Self::Ripemd160),
+            4 => Some(Self::Identity),
+            5 => Some(Self::ModExp),
+            6 => Some(Self::EcAdd),
+            7 => Some(Self::EcMul),
+            8 => Some(Self::EcPairing),
+            9 => Some(Self::Blake2f),
+            _ => None,
+        }
+    }
+
+    pub fn address(&self) -> Address {
+        Address::from_low_u64_be(*self as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_precompile_from_address() {
+        assert_eq!(Some(Precompile::EcRecover), Precompile::from_address(Address::from_low_u64_be(1)));
+        assert_eq!(Some(Precompile::Blake2f), Precompile::from_address(Precompile::Blake2f.address()));
+        assert_eq!(None, Precompile::from_address(Address::zero()));
+        assert_eq!(None, Precompile::from_address(Address::from_low_u64_be(10)));
+        assert_eq!(None, Precompile::from_address(Address::from_low_u64_be(0x101)));
+    }
+}
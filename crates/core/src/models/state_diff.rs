@@ -0,0 +1,109 @@
+use std::collections::BTreeMap;
+
+use reth_primitives::{Address, Bytes, H256, U256};
+use serde::{Deserialize, Serialize};
+use starknet::core::types::{FieldElement, StorageEntry};
+
+use super::felt::Felt252Wrapper;
+use crate::client::devnet::{split_u256, storage_var_address, BYTECODE_CHUNK_SIZE};
+
+/// Changes of a Kakarot account in a block, returned by `kakarot_getStateDiff`. The values are the
+/// ones after the block, the fields not changed being omitted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStateDiff {
+    pub address: Address,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// Changed slots among the slots requested, with their values.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<H256, H256>,
+    /// The other storage writes of the account on Starknet, by Starknet storage key, e.g. the
+    /// writes of slots not requested.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub starknet_storage: BTreeMap<H256, H256>,
+}
+
+/// Storage writes of a Kakarot account in a block, sorted out by what they change.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountStorageWrites {
+    /// Slots among the slots requested whose value changed.
+    pub slots: Vec<U256>,
+    /// Whether the bytecode of the account changed.
+    pub code: bool,
+    /// The other writes, by Starknet storage key.
+    pub other: BTreeMap<H256, H256>,
+}
+
+/// Sorts out the Starknet storage writes of a Kakarot account. A slot is stored as a `Uint256`
+/// under the hash of the `storage_` variable and the slot, which cannot be reversed: only the
+/// `slots` given are recognized.
+pub fn sort_storage_writes(entries: &[StorageEntry], slots: &[U256]) -> AccountStorageWrites {
+    let mut writes: BTreeMap<FieldElement, FieldElement> =
+        entries.iter().map(|entry| (entry.key, entry.value)).collect();
+
+    let mut changed_slots = Vec::new();
+    for slot in slots {
+        let (low, high) = split_u256(*slot);
+        let key = storage_var_address("storage_", &[low, high]);
+        // The high part is stored right after the low part
+        let low_written = writes.remove(&key).is_some();
+        let high_written = writes.remove(&(key + FieldElement::ONE)).is_some();
+        if low_written || high_written {
+            changed_slots.push(*slot);
+        }
+    }
+
+    let bytecode_len = writes.remove(&storage_var_address("bytecode_len_", &[]));
+    if let Some(bytecode_len) = bytecode_len.and_then(|len| usize::try_from(u64::try_from(len).ok()?).ok()) {
+        for index in 0..(bytecode_len + BYTECODE_CHUNK_SIZE - 1) / BYTECODE_CHUNK_SIZE {
+            writes.remove(&storage_var_address("bytecode_", &[FieldElement::from(index)]));
+        }
+    }
+
+    let other = writes
+        .into_iter()
+        .map(|(key, value)| (Felt252Wrapper::from(key).into(), Felt252Wrapper::from(value).into()))
+        .collect();
+    AccountStorageWrites { slots: changed_slots, code: bytecode_len.is_some(), other }
+}
+
+/// Returns whether a native token storage write changes the balance of a Starknet account, the
+/// balances being `Uint256` values of the `ERC20_balances` variable.
+pub fn is_balance_write(entry: &StorageEntry, starknet_address: FieldElement) -> bool {
+    let key = storage_var_address("ERC20_balances", &[starknet_address]);
+    entry.key == key || entry.key == key + FieldElement::ONE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_storage_writes() {
+        // Given
+        let (low, high) = split_u256(U256::from(1));
+        let slot_key = storage_var_address("storage_", &[low, high]);
+        let entry = |key: FieldElement, value: u64| StorageEntry { key, value: FieldElement::from(value) };
+        let entries = vec![
+            entry(slot_key, 42),
+            entry(storage_var_address("bytecode_len_", &[]), 17),
+            entry(storage_var_address("bytecode_", &[FieldElement::ZERO]), 1),
+            entry(storage_var_address("bytecode_", &[FieldElement::ONE]), 2),
+            entry(FieldElement::from(7u8), 3),
+        ];
+
+        // When
+        let writes = sort_storage_writes(&entries, &[U256::from(1), U256::from(2)]);
+
+        // Then
+        assert_eq!(vec![U256::from(1)], writes.slots);
+        assert!(writes.code);
+        assert_eq!(BTreeMap::from([(H256::from_low_u64_be(7), H256::from_low_u64_be(3))]), writes.other);
+        assert!(!sort_storage_writes(&entries[..1], &[]).code);
+    }
+}
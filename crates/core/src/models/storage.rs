@@ -0,0 +1,63 @@
+use reth_primitives::{keccak256, U256};
+use serde::{Deserialize, Serialize};
+
+/// A step of the path from a base storage slot to the slot of a Solidity mapping or dynamic array
+/// element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum StorageKey {
+    /// Key of a mapping, left padded to 32 bytes (e.g. an address or an integer).
+    Mapping(U256),
+    /// Index of a dynamic array element.
+    ArrayIndex(U256),
+}
+
+/// Storage slot computed from a base slot and a path of keys, along with its value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageSlot {
+    pub slot: U256,
+    pub value: U256,
+}
+
+/// Computes the storage slot reached from `base_slot` by following `keys`, using the Solidity
+/// storage layout: `keccak256(key . slot)` for mappings and `keccak256(slot) + index` for dynamic
+/// arrays.
+pub fn compute_storage_slot(base_slot: U256, keys: &[StorageKey]) -> U256 {
+    keys.iter().fold(base_slot, |slot, key| match key {
+        StorageKey::Mapping(key) => {
+            let mut preimage = [0u8; 64];
+            preimage[..32].copy_from_slice(&key.to_be_bytes::<32>());
+            preimage[32..].copy_from_slice(&slot.to_be_bytes::<32>());
+            U256::from_be_bytes(keccak256(preimage).0)
+        }
+        StorageKey::ArrayIndex(index) => {
+            U256::from_be_bytes(keccak256(slot.to_be_bytes::<32>()).0).wrapping_add(*index)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_compute_storage_slot() {
+        // Given
+        let mapping_slot =
+            U256::from_str("0xad3228b676f7d3cd4284a5443f17f1962b36e491b30a40b2405849e597ba5fb5").unwrap();
+        let array_slot = U256::from_str("0x290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563").unwrap();
+
+        // When
+        let base_slot = compute_storage_slot(U256::from(3), &[]);
+        let mapping_element_slot = compute_storage_slot(U256::ZERO, &[StorageKey::Mapping(U256::ZERO)]);
+        let array_element_slot = compute_storage_slot(U256::ZERO, &[StorageKey::ArrayIndex(U256::from(2))]);
+
+        // Then
+        assert_eq!(U256::from(3), base_slot);
+        assert_eq!(mapping_slot, mapping_element_slot);
+        assert_eq!(array_slot + U256::from(2), array_element_slot);
+    }
+}
@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use reth_primitives::{TransactionSigned, H256, U256};
+use reth_primitives::{TransactionSigned, H256, U256, U64};
 use reth_rpc_types::{Signature, Transaction as EthTransaction};
 use starknet::core::types::{BlockId as StarknetBlockId, BlockTag, FieldElement, InvokeTransaction, Transaction};
 use starknet::providers::Provider;
@@ -92,6 +92,10 @@ impl ConvertibleStarknetTransaction for StarknetTransaction {
         let input = tx.input().to_owned();
         let signature = tx.signature;
         let to = tx.to();
+        // Kakarot receives the whole signed transaction, access list included, the converted
+        // transaction exposes it as the user signed it
+        let access_list = tx.access_list().map(|access_list| access_list.0.clone());
+        let transaction_type = Some(U64::from(tx.tx_type() as u8));
 
         let v = if signature.odd_y_parity { 1 } else { 0 } + 35 + 2 * CHAIN_ID;
         let signature = Some(Signature { r: signature.r, s: signature.s, v: U256::from_limbs_slice(&[v]) });
@@ -112,15 +116,15 @@ impl ConvertibleStarknetTransaction for StarknetTransaction {
             input,
             signature,
             chain_id: Some(CHAIN_ID.into()),
-            access_list: None,      // TODO fetch the access list
-            transaction_type: None, // TODO fetch the transaction type
+            access_list,
+            transaction_type,
         })
     }
 }
 
 impl StarknetTransaction {
     /// Checks if the transaction is a Kakarot transaction.
-    async fn is_kakarot_tx<P: Provider + Send + Sync>(
+    pub(crate) async fn is_kakarot_tx<P: Provider + Send + Sync>(
         &self,
         client: &dyn KakarotEthApi<P>,
     ) -> Result<bool, EthApiError<P::Error>> {
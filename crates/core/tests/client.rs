@@ -5,10 +5,11 @@ mod tests {
     use ctor::ctor;
     use ethers::types::Address as EthersAddress;
     use kakarot_rpc_core::client::api::KakarotEthApi;
-    use kakarot_rpc_core::client::config::{Network, StarknetConfig};
+    use kakarot_rpc_core::client::config::Network;
     use kakarot_rpc_core::client::KakarotClient;
+    use kakarot_rpc_core::models::account::AccountType;
     use kakarot_rpc_core::models::felt::Felt252Wrapper;
-    use reth_primitives::{Address, BlockId, BlockNumberOrTag, U256};
+    use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, U256};
     use starknet::core::types::FieldElement;
     use starknet::providers::jsonrpc::HttpTransport;
     use starknet::providers::JsonRpcClient;
@@ -16,7 +17,7 @@ mod tests {
 
     use crate::utils::constants::EOA_WALLET;
     use crate::utils::deploy_helpers::{
-        construct_kakarot_test_sequencer, create_raw_ethereum_tx, deploy_kakarot_system,
+        construct_kakarot_test_sequencer, create_raw_ethereum_tx, create_raw_value_transfer_tx, deploy_kakarot_system,
     };
 
     #[ctor]
@@ -34,14 +35,13 @@ mod tests {
         let deployed_kakarot =
             deploy_kakarot_system(&starknet_test_sequencer, EOA_WALLET.clone(), expected_funded_amount).await;
 
-        let kakarot_client = KakarotClient::new(
-            StarknetConfig::new(
-                Network::JsonRpcProvider(starknet_test_sequencer.url()),
-                deployed_kakarot.kakarot,
-                deployed_kakarot.kakarot_proxy,
-            ),
-            JsonRpcClient::new(HttpTransport::new(starknet_test_sequencer.url())),
-        );
+        let kakarot_client = KakarotClient::builder()
+            .with_network(Network::JsonRpcProvider(starknet_test_sequencer.url()))
+            .with_kakarot_address(deployed_kakarot.kakarot)
+            .with_proxy_account_class_hash(deployed_kakarot.kakarot_proxy)
+            .with_provider(JsonRpcClient::new(HttpTransport::new(starknet_test_sequencer.url())))
+            .build()
+            .unwrap();
 
         // Zero address shouldn't throw 'ContractNotFound', but return zero
         assert_eq!(
@@ -50,6 +50,43 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_undeployed_account_reads() {
+        // Given
+        let starknet_test_sequencer = construct_kakarot_test_sequencer().await;
+
+        let expected_funded_amount = FieldElement::from_dec_str("1000000000000000000").unwrap();
+
+        let deployed_kakarot =
+            deploy_kakarot_system(&starknet_test_sequencer, EOA_WALLET.clone(), expected_funded_amount).await;
+
+        let kakarot_client = KakarotClient::builder()
+            .with_network(Network::JsonRpcProvider(starknet_test_sequencer.url()))
+            .with_kakarot_address(deployed_kakarot.kakarot)
+            .with_proxy_account_class_hash(deployed_kakarot.kakarot_proxy)
+            .with_provider(JsonRpcClient::new(HttpTransport::new(starknet_test_sequencer.url())))
+            .build()
+            .unwrap();
+        let undeployed = Address::from_low_u64_be(0xdead_beef);
+        let latest = BlockId::from(BlockNumberOrTag::Latest);
+
+        // When
+        let account_type = kakarot_client.account_type(undeployed, latest).await.unwrap();
+        let nonce = kakarot_client.nonce(undeployed, latest).await.unwrap();
+        let balance = kakarot_client.balance(undeployed, latest).await.unwrap();
+        let code = kakarot_client.get_code(undeployed, latest).await.unwrap();
+        let storage = kakarot_client.storage_at(undeployed, U256::from(1), latest).await.unwrap();
+        let call = kakarot_client.call(undeployed, Bytes::from(vec![0xde, 0xad]), latest).await.unwrap();
+
+        // Then
+        assert_eq!(AccountType::Undeployed, account_type);
+        assert_eq!(U256::from(0), nonce);
+        assert_eq!(U256::from(0), balance);
+        assert!(code.is_empty());
+        assert_eq!(U256::from(0), storage);
+        assert!(call.is_empty());
+    }
+
     #[tokio::test]
     async fn test_counter() {
         let starknet_test_sequencer = construct_kakarot_test_sequencer().await;
@@ -69,14 +106,13 @@ mod tests {
             .await
             .unwrap();
 
-        let kakarot_client = KakarotClient::new(
-            StarknetConfig::new(
-                Network::JsonRpcProvider(starknet_test_sequencer.url()),
-                deployed_kakarot.kakarot,
-                deployed_kakarot.kakarot_proxy,
-            ),
-            JsonRpcClient::new(HttpTransport::new(starknet_test_sequencer.url())),
-        );
+        let kakarot_client = KakarotClient::builder()
+            .with_network(Network::JsonRpcProvider(starknet_test_sequencer.url()))
+            .with_kakarot_address(deployed_kakarot.kakarot)
+            .with_proxy_account_class_hash(deployed_kakarot.kakarot_proxy)
+            .with_provider(JsonRpcClient::new(HttpTransport::new(starknet_test_sequencer.url())))
+            .build()
+            .unwrap();
 
         let deployed_balance = kakarot_client
             .balance(deployed_kakarot.eoa_eth_address, BlockId::Number(reth_primitives::BlockNumberOrTag::Latest))
@@ -166,14 +202,13 @@ mod tests {
             address.try_into().unwrap()
         };
 
-        let kakarot_client = KakarotClient::new(
-            StarknetConfig::new(
-                Network::JsonRpcProvider(starknet_test_sequencer.url()),
-                deployed_kakarot.kakarot,
-                deployed_kakarot.kakarot_proxy,
-            ),
-            JsonRpcClient::new(HttpTransport::new(starknet_test_sequencer.url())),
-        );
+        let kakarot_client = KakarotClient::builder()
+            .with_network(Network::JsonRpcProvider(starknet_test_sequencer.url()))
+            .with_kakarot_address(deployed_kakarot.kakarot)
+            .with_proxy_account_class_hash(deployed_kakarot.kakarot_proxy)
+            .with_provider(JsonRpcClient::new(HttpTransport::new(starknet_test_sequencer.url())))
+            .build()
+            .unwrap();
 
         kakarot_client
             .get_code(plain_opcodes_eth_address, BlockId::Number(reth_primitives::BlockNumberOrTag::Latest))
@@ -200,14 +235,13 @@ mod tests {
             .await
             .unwrap();
 
-        let kakarot_client = KakarotClient::new(
-            StarknetConfig::new(
-                Network::JsonRpcProvider(starknet_test_sequencer.url()),
-                deployed_kakarot.kakarot,
-                deployed_kakarot.kakarot_proxy,
-            ),
-            JsonRpcClient::new(HttpTransport::new(starknet_test_sequencer.url())),
-        );
+        let kakarot_client = KakarotClient::builder()
+            .with_network(Network::JsonRpcProvider(starknet_test_sequencer.url()))
+            .with_kakarot_address(deployed_kakarot.kakarot)
+            .with_proxy_account_class_hash(deployed_kakarot.kakarot_proxy)
+            .with_provider(JsonRpcClient::new(HttpTransport::new(starknet_test_sequencer.url())))
+            .build()
+            .unwrap();
 
         let counter_eth_address = {
             let address: Felt252Wrapper = (*deployed_addresses.first().unwrap()).into();
@@ -238,4 +272,146 @@ mod tests {
         // Then
         assert_eq!(count, U256::from(1));
     }
+
+    #[tokio::test]
+    async fn test_historical_state_queries() {
+        // Given
+        let starknet_test_sequencer = construct_kakarot_test_sequencer().await;
+
+        let amount_funded = FieldElement::from_dec_str("10000000000000000000").unwrap();
+
+        let deployed_kakarot = deploy_kakarot_system(&starknet_test_sequencer, EOA_WALLET.clone(), amount_funded).await;
+
+        let (counter_abi, deployed_addresses) = deployed_kakarot
+            .deploy_evm_contract(
+                starknet_test_sequencer.url(),
+                "Counter",
+                // no constructor is conveyed as a tuple
+                (),
+            )
+            .await
+            .unwrap();
+
+        let kakarot_client = KakarotClient::builder()
+            .with_network(Network::JsonRpcProvider(starknet_test_sequencer.url()))
+            .with_kakarot_address(deployed_kakarot.kakarot)
+            .with_proxy_account_class_hash(deployed_kakarot.kakarot_proxy)
+            .with_provider(JsonRpcClient::new(HttpTransport::new(starknet_test_sequencer.url())))
+            .build()
+            .unwrap();
+
+        let counter_eth_address = {
+            let address: Felt252Wrapper = (*deployed_addresses.first().unwrap()).into();
+            address.try_into().unwrap()
+        };
+        let block_before_inc = kakarot_client.block_number().await.unwrap().as_u64();
+
+        let nonce = kakarot_client
+            .nonce(deployed_kakarot.eoa_eth_address, BlockId::Number(BlockNumberOrTag::Latest))
+            .await
+            .unwrap();
+        let inc_selector = counter_abi.function("inc").unwrap().short_signature();
+        let inc_tx = create_raw_ethereum_tx(
+            inc_selector,
+            deployed_kakarot.eoa_private_key,
+            counter_eth_address,
+            vec![],
+            nonce.try_into().unwrap(),
+        );
+        let inc_res = kakarot_client.send_transaction(inc_tx).await.unwrap();
+        kakarot_client.transaction_receipt(inc_res).await.expect("increment transaction failed");
+
+        // When
+        let count_before_inc = kakarot_client
+            .storage_at(counter_eth_address, U256::from(0), BlockId::Number(BlockNumberOrTag::Number(block_before_inc)))
+            .await
+            .unwrap();
+        let count_after_inc = kakarot_client
+            .storage_at(counter_eth_address, U256::from(0), BlockId::Number(BlockNumberOrTag::Latest))
+            .await
+            .unwrap();
+        let nonce_after_inc = kakarot_client
+            .nonce(deployed_kakarot.eoa_eth_address, BlockId::Number(BlockNumberOrTag::Latest))
+            .await
+            .unwrap();
+        let nonce_before_inc = kakarot_client
+            .nonce(deployed_kakarot.eoa_eth_address, BlockId::Number(BlockNumberOrTag::Number(block_before_inc)))
+            .await
+            .unwrap();
+        // The genesis block predates the deployment of Kakarot
+        let code_at_genesis =
+            kakarot_client.get_code(counter_eth_address, BlockId::Number(BlockNumberOrTag::Earliest)).await.unwrap();
+        let eoa_code = kakarot_client
+            .get_code(deployed_kakarot.eoa_eth_address, BlockId::Number(BlockNumberOrTag::Latest))
+            .await
+            .unwrap();
+        let eoa_type = kakarot_client
+            .account_type(deployed_kakarot.eoa_eth_address, BlockId::Number(BlockNumberOrTag::Latest))
+            .await
+            .unwrap();
+        let counter_type =
+            kakarot_client.account_type(counter_eth_address, BlockId::Number(BlockNumberOrTag::Latest)).await.unwrap();
+        let balance_at_genesis = kakarot_client
+            .balance(deployed_kakarot.eoa_eth_address, BlockId::Number(BlockNumberOrTag::Earliest))
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(count_before_inc, U256::from(0));
+        assert_eq!(count_after_inc, U256::from(1));
+        assert_eq!(nonce_after_inc, nonce_before_inc + U256::from(1));
+        assert!(code_at_genesis.is_empty());
+        assert!(eoa_code.is_empty());
+        assert_eq!(AccountType::Eoa, eoa_type);
+        assert_eq!(AccountType::Contract, counter_type);
+        assert_eq!(balance_at_genesis, U256::from(0));
+    }
+
+    #[tokio::test]
+    async fn test_balance_at_historical_blocks() {
+        // Given
+        let starknet_test_sequencer = construct_kakarot_test_sequencer().await;
+
+        let amount_funded = FieldElement::from_dec_str("10000000000000000000").unwrap();
+
+        let deployed_kakarot = deploy_kakarot_system(&starknet_test_sequencer, EOA_WALLET.clone(), amount_funded).await;
+
+        let kakarot_client = KakarotClient::builder()
+            .with_network(Network::JsonRpcProvider(starknet_test_sequencer.url()))
+            .with_kakarot_address(deployed_kakarot.kakarot)
+            .with_proxy_account_class_hash(deployed_kakarot.kakarot_proxy)
+            .with_provider(JsonRpcClient::new(HttpTransport::new(starknet_test_sequencer.url())))
+            .build()
+            .unwrap();
+
+        let recipient = Address::from_low_u64_be(0xdead_beef);
+        let value = 1_000_000_000_000_000u128;
+        let block_before_transfer =
+            BlockId::Number(BlockNumberOrTag::Number(kakarot_client.block_number().await.unwrap().as_u64()));
+
+        let nonce = kakarot_client
+            .nonce(deployed_kakarot.eoa_eth_address, BlockId::Number(BlockNumberOrTag::Latest))
+            .await
+            .unwrap();
+        let transfer_tx =
+            create_raw_value_transfer_tx(deployed_kakarot.eoa_private_key, recipient, value, nonce.try_into().unwrap());
+        let transfer_hash = kakarot_client.send_transaction(transfer_tx).await.unwrap();
+        kakarot_client.transaction_receipt(transfer_hash).await.expect("transfer transaction failed");
+        let block_after_transfer =
+            BlockId::Number(BlockNumberOrTag::Number(kakarot_client.block_number().await.unwrap().as_u64()));
+
+        // When
+        let sender_balance_before =
+            kakarot_client.balance(deployed_kakarot.eoa_eth_address, block_before_transfer).await.unwrap();
+        let sender_balance_after =
+            kakarot_client.balance(deployed_kakarot.eoa_eth_address, block_after_transfer).await.unwrap();
+        let recipient_balance_before = kakarot_client.balance(recipient, block_before_transfer).await.unwrap();
+        let recipient_balance_after = kakarot_client.balance(recipient, block_after_transfer).await.unwrap();
+
+        // Then
+        assert_eq!(recipient_balance_before, U256::from(0));
+        assert_eq!(recipient_balance_after, U256::from(value));
+        // The sender also pays the Starknet fee of the transaction
+        assert!(sender_balance_before >= sender_balance_after + U256::from(value));
+    }
 }
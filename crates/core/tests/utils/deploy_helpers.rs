@@ -180,6 +180,20 @@ pub fn create_raw_ethereum_tx(
 
     // Create a transaction object
     let transaction = to_kakarot_transaction(nonce, TransactionKind::Call(to), data.into());
+    sign_raw_ethereum_tx(transaction, eoa_secret_key)
+}
+
+/// Constructs and signs a raw Ethereum transaction transferring `value` wei to `to`.
+pub fn create_raw_value_transfer_tx(eoa_secret_key: H256, to: Address, value: u128, nonce: u64) -> Bytes {
+    let mut transaction = to_kakarot_transaction(nonce, TransactionKind::Call(to), Bytes::default());
+    if let Transaction::Eip1559(tx) = &mut transaction {
+        tx.value = value;
+    }
+    sign_raw_ethereum_tx(transaction, eoa_secret_key)
+}
+
+/// Signs a transaction with the provided EOA secret and encodes it as a raw transaction.
+fn sign_raw_ethereum_tx(transaction: Transaction, eoa_secret_key: H256) -> Bytes {
     let signature =
         sign_message(eoa_secret_key, transaction.signature_hash()).expect("Signing of ethereum transaction failed.");
 
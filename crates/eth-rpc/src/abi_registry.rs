@@ -0,0 +1,323 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use ethers::abi::{Abi, Event, RawLog};
+use eyre::{eyre, Result};
+use futures::future::join_all;
+use kakarot_rpc_core::client::constants::CHAIN_ID;
+use reqwest::Client;
+use reth_primitives::{Address, H256};
+use reth_rpc_types::Log;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::Semaphore;
+
+/// Time allowed to the Sourcify lookups of a call, after which the logs of the contracts not
+/// fetched yet are returned undecoded.
+pub const DEFAULT_SOURCIFY_TIMEOUT: Duration = Duration::from_secs(5);
+/// Number of Sourcify lookups in flight at once, over all calls.
+pub const SOURCIFY_CONCURRENCY: usize = 8;
+/// Time during which a contract whose ABI could not be fetched is not looked up again.
+pub const DEFAULT_SOURCIFY_NEGATIVE_TTL: Duration = Duration::from_secs(600);
+
+/// A decoded event parameter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedParam {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub indexed: bool,
+    pub value: String,
+}
+
+/// The event a log was decoded into.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedEvent {
+    pub name: String,
+    pub signature: String,
+    pub params: Vec<DecodedParam>,
+}
+
+/// A log along with its decoded event, if its ABI is known.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedLog {
+    #[serde(flatten)]
+    pub log: Log,
+    pub event: Option<DecodedEvent>,
+}
+
+/// Registry of contract ABIs used to decode logs.
+///
+/// ABIs are loaded from a directory of JSON files, either raw ABIs or compiler artifacts with an
+/// `abi` field. Files named after a contract address (e.g.
+/// `0x5FbDB2315678afecb367f032d93F642f64180aa3.json`) only decode the logs of this contract, the
+/// others decode the logs of any contract. When a Sourcify server is configured, the ABIs of
+/// unknown contracts are fetched from it.
+#[derive(Debug, Default)]
+pub struct AbiRegistry {
+    events_by_address: RwLock<HashMap<Address, Vec<Event>>>,
+    events: RwLock<Vec<Event>>,
+    sourcify: Option<Sourcify>,
+}
+
+/// Sourcify server the ABIs of unknown contracts are fetched from.
+#[derive(Debug)]
+struct Sourcify {
+    url: String,
+    http: Client,
+    permits: Semaphore,
+    timeout: Duration,
+    negative_ttl: Duration,
+    /// Contracts whose lookup is in flight or failed, with the time of the lookup.
+    lookups: RwLock<HashMap<Address, Instant>>,
+}
+
+impl AbiRegistry {
+    pub fn new(sourcify_url: Option<String>) -> Self {
+        let sourcify = sourcify_url.map(|url| Sourcify {
+            url,
+            http: Client::new(),
+            permits: Semaphore::new(SOURCIFY_CONCURRENCY),
+            timeout: DEFAULT_SOURCIFY_TIMEOUT,
+            negative_ttl: DEFAULT_SOURCIFY_NEGATIVE_TTL,
+            lookups: RwLock::default(),
+        });
+        Self { sourcify, ..Default::default() }
+    }
+
+    /// Sets the time allowed to the Sourcify lookups of a call and the time a failed lookup is
+    /// not retried.
+    pub fn with_sourcify_timeouts(mut self, timeout: Duration, negative_ttl: Duration) -> Self {
+        if let Some(sourcify) = &mut self.sourcify {
+            sourcify.timeout = timeout;
+            sourcify.negative_ttl = negative_ttl;
+        }
+        self
+    }
+
+    /// Loads the ABIs of all the JSON files of a directory.
+    pub fn load_dir(&self, dir: &Path) -> Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().map_or(true, |extension| extension != "json") {
+                continue;
+            }
+            let content = std::fs::read_to_string(&path)?;
+            let abi = parse_abi(&serde_json::from_str(&content)?)
+                .map_err(|err| eyre!("Invalid ABI file {}: {err}", path.display()))?;
+            let address = path.file_stem().and_then(|stem| stem.to_str()).and_then(|stem| stem.parse().ok());
+            self.register(address, &abi);
+        }
+        Ok(())
+    }
+
+    /// Registers the events of an ABI, for a single contract if `address` is set.
+    pub fn register(&self, address: Option<Address>, abi: &Abi) {
+        let events = abi.events().filter(|event| !event.anonymous).cloned();
+        match address {
+            Some(address) => self
+                .events_by_address
+                .write()
+                .expect("ABI registry lock poisoned")
+                .entry(address)
+                .or_default()
+                .extend(events),
+            None => self.events.write().expect("ABI registry lock poisoned").extend(events),
+        }
+    }
+
+    /// Decodes logs, fetching first the ABIs of their unknown contracts from Sourcify. The
+    /// contracts are looked up concurrently, the logs of those not fetched within the timeout
+    /// being returned undecoded.
+    pub async fn decode_logs(&self, logs: Vec<Log>) -> Vec<DecodedLog> {
+        if let Some(sourcify) = &self.sourcify {
+            let addresses: BTreeSet<Address> = logs.iter().map(|log| log.address).collect();
+            let lookups = join_all(addresses.into_iter().map(|address| self.lookup_sourcify(sourcify, address)));
+            if tokio::time::timeout(sourcify.timeout, lookups).await.is_err() {
+                tracing::debug!("Sourcify lookups timed out after {:?}", sourcify.timeout);
+            }
+        }
+
+        logs.into_iter().map(|log| self.decode(log)).collect()
+    }
+
+    /// Decodes a log with the first registered event matching its signature.
+    pub fn decode(&self, log: Log) -> DecodedLog {
+        let event = log.topics.first().and_then(|signature| {
+            let events_by_address = self.events_by_address.read().expect("ABI registry lock poisoned");
+            let events = self.events.read().expect("ABI registry lock poisoned");
+            events_by_address
+                .get(&log.address)
+                .into_iter()
+                .flatten()
+                .chain(events.iter())
+                .filter(|event| event.signature().0 == signature.0)
+                .find_map(|event| decode_event(event, &log))
+        });
+
+        DecodedLog { log, event }
+    }
+
+    /// Fetches the ABI of a contract from Sourcify, unless it is known, being fetched or failed
+    /// to be fetched within the negative TTL. A lookup cancelled by the timeout counts as failed.
+    async fn lookup_sourcify(&self, sourcify: &Sourcify, address: Address) {
+        if self.events_by_address.read().expect("ABI registry lock poisoned").contains_key(&address) {
+            return;
+        }
+        {
+            let mut lookups = sourcify.lookups.write().expect("ABI registry lock poisoned");
+            let now = Instant::now();
+            match lookups.get(&address) {
+                Some(looked_up_at) if now.duration_since(*looked_up_at) < sourcify.negative_ttl => return,
+                _ => lookups.insert(address, now),
+            };
+        }
+
+        let Ok(_permit) = sourcify.permits.acquire().await else { return };
+        match fetch_sourcify_abi(&sourcify.http, &sourcify.url, address).await {
+            Ok(abi) => {
+                self.register(Some(address), &abi);
+                sourcify.lookups.write().expect("ABI registry lock poisoned").remove(&address);
+            }
+            Err(err) => tracing::debug!("no Sourcify ABI for {address:?}: {err}"),
+        }
+    }
+}
+
+/// Parses a raw ABI or the `abi` field of a compiler artifact.
+fn parse_abi(value: &Value) -> Result<Abi> {
+    let abi = match value.get("abi") {
+        Some(abi) => abi.clone(),
+        None => value.clone(),
+    };
+    Ok(serde_json::from_value(abi)?)
+}
+
+async fn fetch_sourcify_abi(http: &Client, sourcify_url: &str, address: Address) -> Result<Abi> {
+    let url = format!("{}/files/any/{CHAIN_ID}/{address:?}", sourcify_url.trim_end_matches('/'));
+    let response: Value = http.get(url).send().await?.error_for_status()?.json().await?;
+    let metadata = response["files"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|file| file["name"] == "metadata.json")
+        .and_then(|file| file["content"].as_str())
+        .ok_or_else(|| eyre!("missing metadata.json"))?;
+    let metadata: Value = serde_json::from_str(metadata)?;
+    parse_abi(&metadata["output"])
+}
+
+fn decode_event(event: &Event, log: &Log) -> Option<DecodedEvent> {
+    let raw_log = RawLog {
+        topics: log.topics.iter().map(|topic| ethers::types::H256(topic.0)).collect(),
+        data: log.data.to_vec(),
+    };
+    let parsed = event.parse_log(raw_log).ok()?;
+    let params = event
+        .inputs
+        .iter()
+        .zip(parsed.params)
+        .map(|(input, param)| DecodedParam {
+            name: param.name,
+            kind: input.kind.to_string(),
+            indexed: input.indexed,
+            value: param.value.to_string(),
+        })
+        .collect();
+
+    Some(DecodedEvent { name: event.name.clone(), signature: format!("{:?}", H256(event.signature().0)), params })
+}
+
+#[cfg(test)]
+mod tests {
+    use reth_primitives::Bytes;
+    use serde_json::json;
+    use wiremock::matchers::path;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    const TRANSFER_ABI: &str = r#"[{"anonymous":false,"name":"Transfer","type":"event","inputs":[
+        {"indexed":true,"name":"from","type":"address"},
+        {"indexed":true,"name":"to","type":"address"},
+        {"indexed":false,"name":"value","type":"uint256"}]}]"#;
+
+    #[test]
+    fn test_decode_transfer_log() {
+        // Given
+        let registry = AbiRegistry::default();
+        registry.register(None, &serde_json::from_str(TRANSFER_ABI).unwrap());
+        let log = transfer_log(Address::zero());
+
+        // When
+        let decoded = registry.decode(log).event.unwrap();
+
+        // Then
+        assert_eq!("Transfer", decoded.name);
+        assert_eq!(3, decoded.params.len());
+        assert_eq!("value", decoded.params[2].name);
+        assert_eq!("uint256", decoded.params[2].kind);
+        assert_eq!("a", decoded.params[2].value);
+    }
+
+    fn transfer_log(address: Address) -> Log {
+        Log {
+            address,
+            topics: vec![
+                "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef".parse().unwrap(),
+                H256::from_low_u64_be(1),
+                H256::from_low_u64_be(2),
+            ],
+            data: Bytes::from(H256::from_low_u64_be(10).0.to_vec()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sourcify_lookups_are_bounded_and_negatively_cached() {
+        // Given
+        let (verified, unverified, slow) =
+            (Address::from_low_u64_be(1), Address::from_low_u64_be(2), Address::from_low_u64_be(3));
+        let metadata = json!({ "output": { "abi": serde_json::from_str::<Value>(TRANSFER_ABI).unwrap() } });
+        let files = json!({ "files": [{ "name": "metadata.json", "content": metadata.to_string() }] });
+        let mock_server = MockServer::start().await;
+        let files_path = |address: Address| path(format!("/files/any/{CHAIN_ID}/{address:?}"));
+        Mock::given(files_path(verified))
+            .respond_with(ResponseTemplate::new(200).set_body_json(files.clone()))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(files_path(unverified))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(files_path(slow))
+            .respond_with(ResponseTemplate::new(200).set_body_json(files).set_delay(Duration::from_secs(5)))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let registry = AbiRegistry::new(Some(mock_server.uri()))
+            .with_sourcify_timeouts(Duration::from_millis(500), Duration::from_secs(60));
+        let logs = vec![transfer_log(verified), transfer_log(unverified), transfer_log(slow)];
+
+        // When
+        let started_at = Instant::now();
+        let first = registry.decode_logs(logs.clone()).await;
+        let elapsed = started_at.elapsed();
+        let second = registry.decode_logs(logs).await;
+
+        // Then
+        assert!(elapsed < Duration::from_secs(2));
+        for decoded in [first, second] {
+            assert_eq!("Transfer", decoded[0].event.as_ref().unwrap().name);
+            assert_eq!(None, decoded[1].event);
+            assert_eq!(None, decoded[2].event);
+        }
+    }
+}
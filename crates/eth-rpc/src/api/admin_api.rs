@@ -0,0 +1,70 @@
+use jsonrpsee::core::RpcResult as Result;
+use jsonrpsee::proc_macros::rpc;
+use kakarot_rpc_core::client::audit::AuditEntry;
+use kakarot_rpc_core::client::breaker::BreakerStats;
+use kakarot_rpc_core::client::calls::CallCacheStats;
+use kakarot_rpc_core::client::coalesce::CoalescingStats;
+use kakarot_rpc_core::client::lanes::LaneStats;
+use kakarot_rpc_core::client::upstream::UpstreamStats;
+use kakarot_rpc_core::models::deployment::DeploymentReport;
+
+use crate::filters::FilterStats;
+use crate::stats::RpcStats;
+use crate::subscription::SubscriptionStats;
+
+#[rpc(server, namespace = "admin")]
+#[async_trait]
+pub trait AdminApi {
+    /// Runs the self-check of the Kakarot deployment the RPC is configured against.
+    #[method(name = "validateDeployment")]
+    async fn validate_deployment(&self) -> Result<DeploymentReport>;
+
+    /// Returns the counters of the websocket subscription notifications, including the ones
+    /// dropped for slow subscribers.
+    #[method(name = "subscriptionStats")]
+    async fn subscription_stats(&self) -> Result<SubscriptionStats>;
+
+    /// Returns the number of filters installed by kind, and the number of filters expired for
+    /// being idle or rejected for exceeding `KAKAROT_MAX_FILTERS`.
+    #[method(name = "filterStats")]
+    async fn filter_stats(&self) -> Result<FilterStats>;
+
+    /// Returns the queue depths of the heavy read and transaction submission lanes.
+    #[method(name = "laneStats")]
+    async fn lane_stats(&self) -> Result<LaneStats>;
+
+    /// Returns the latency and error rate of the Starknet endpoints the requests are routed to.
+    #[method(name = "upstreamStats")]
+    async fn upstream_stats(&self) -> Result<Vec<UpstreamStats>>;
+
+    /// Returns the number of Starknet reads sent and of identical concurrent reads served by them.
+    #[method(name = "coalescingStats")]
+    async fn coalescing_stats(&self) -> Result<CoalescingStats>;
+
+    /// Returns the state of the circuit breaker shedding the expensive requests, e.g.
+    /// `eth_getLogs`, while the Starknet provider is unhealthy.
+    #[method(name = "breakerStats")]
+    async fn breaker_stats(&self) -> Result<BreakerStats>;
+
+    /// Returns the number of `eth_call` results cached and the hits and misses of the cache.
+    #[method(name = "callCacheStats")]
+    async fn call_cache_stats(&self) -> Result<CallCacheStats>;
+
+    /// Returns a snapshot of the cache hit rates, index sizes, in-flight requests and Starknet
+    /// endpoint latencies, also written to the logs as on `SIGUSR1`.
+    #[method(name = "stats")]
+    async fn stats(&self) -> Result<RpcStats>;
+
+    /// Returns the entries of the audit log of the relayed transactions starting at the sequence
+    /// number `from_sequence`, at most 1000 at once, or null if the audit log is disabled.
+    #[method(name = "auditLog")]
+    async fn audit_log(&self, from_sequence: Option<u64>, limit: Option<usize>) -> Result<Option<Vec<AuditEntry>>>;
+
+    /// Returns the level filter of the logs, e.g. `info,kakarot_rpc_core=debug`.
+    #[method(name = "logLevel")]
+    async fn log_level(&self) -> Result<String>;
+
+    /// Replaces the level filter of the logs until the RPC restarts and returns the new filter.
+    #[method(name = "setLogLevel")]
+    async fn set_log_level(&self, filter: String) -> Result<String>;
+}
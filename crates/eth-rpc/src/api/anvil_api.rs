@@ -0,0 +1,29 @@
+use jsonrpsee::core::RpcResult as Result;
+use jsonrpsee::proc_macros::rpc;
+use kakarot_rpc_core::models::address::AddressParam;
+use reth_primitives::{Bytes, H256, U256};
+
+/// State manipulation methods of Anvil, writing the storage of the devnet in dev mode.
+#[rpc(server, namespace = "anvil")]
+#[async_trait]
+pub trait AnvilApi {
+    /// Sets the ETH balance of an account.
+    #[method(name = "setBalance")]
+    async fn set_balance(&self, address: AddressParam, balance: U256) -> Result<()>;
+
+    /// Replaces the bytecode of a deployed contract account.
+    #[method(name = "setCode")]
+    async fn set_code(&self, address: AddressParam, code: Bytes) -> Result<()>;
+
+    /// Sets a storage slot of a deployed contract account.
+    #[method(name = "setStorageAt")]
+    async fn set_storage_at(&self, address: AddressParam, slot: U256, value: H256) -> Result<bool>;
+
+    /// Lets `eth_sendTransaction` send unsigned transactions on behalf of an account.
+    #[method(name = "impersonateAccount")]
+    async fn impersonate_account(&self, address: AddressParam) -> Result<()>;
+
+    /// Stops impersonating an account.
+    #[method(name = "stopImpersonatingAccount")]
+    async fn stop_impersonating_account(&self, address: AddressParam) -> Result<()>;
+}
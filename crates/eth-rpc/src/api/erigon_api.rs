@@ -0,0 +1,17 @@
+use jsonrpsee::core::RpcResult as Result;
+use jsonrpsee::proc_macros::rpc;
+use kakarot_rpc_core::models::compat::CompatResponse;
+use reth_primitives::{BlockNumberOrTag, H256};
+use reth_rpc_types::{Header, TransactionReceipt};
+
+#[rpc(server, namespace = "erigon")]
+#[async_trait]
+pub trait ErigonApi {
+    /// Returns the header of a block by number, which block explorers such as Blockscout probe.
+    #[method(name = "getHeaderByNumber")]
+    async fn header_by_number(&self, number: BlockNumberOrTag) -> Result<Option<CompatResponse<Header>>>;
+
+    /// Returns the receipts of the transactions of a block by hash.
+    #[method(name = "getBlockReceiptsByBlockHash")]
+    async fn block_receipts_by_block_hash(&self, hash: H256) -> Result<CompatResponse<Vec<TransactionReceipt>>>;
+}
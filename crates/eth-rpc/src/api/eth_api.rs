@@ -1,11 +1,17 @@
 use jsonrpsee::core::RpcResult as Result;
 use jsonrpsee::proc_macros::rpc;
+use kakarot_rpc_core::models::address::AddressParam;
+use kakarot_rpc_core::models::compat::{BoolParam, CompatResponse};
+use kakarot_rpc_core::models::filter::LogFilter;
 use reth_primitives::rpc::transaction::eip2930::AccessListWithGasUsed;
 use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, H256, H64, U128, U256, U64};
 use reth_rpc_types::{
-    CallRequest, EIP1186AccountProofResponse, FeeHistory, Index, RichBlock, SyncStatus, Transaction as EthTransaction,
+    CallRequest, EIP1186AccountProofResponse, FeeHistory, Index, Log, RichBlock, Transaction as EthTransaction,
     TransactionReceipt, TransactionRequest, Work,
 };
+use serde_json::Value;
+
+use crate::filters::FilterChanges;
 
 #[rpc(server, namespace = "eth")]
 #[async_trait]
@@ -13,15 +19,17 @@ pub trait EthApi {
     #[method(name = "blockNumber")]
     async fn block_number(&self) -> Result<U64>;
 
-    /// Returns an object with data about the sync status or false.
+    /// Returns an object with data about the sync status or false. A stale sequencer is reported
+    /// as syncing, with a `stale` flag.
     #[method(name = "syncing")]
-    async fn syncing(&self) -> Result<SyncStatus>;
+    async fn syncing(&self) -> Result<Value>;
 
     /// Returns the client coinbase address.
     #[method(name = "coinbase")]
     async fn author(&self) -> Result<Address>;
 
-    /// Returns a list of addresses owned by client.
+    /// Returns the accounts `eth_sendTransaction` sends transactions for: the impersonated accounts
+    /// in dev mode.
     #[method(name = "accounts")]
     async fn accounts(&self) -> Result<Vec<Address>>;
 
@@ -31,11 +39,15 @@ pub trait EthApi {
 
     /// Returns information about a block by hash.
     #[method(name = "getBlockByHash")]
-    async fn block_by_hash(&self, hash: H256, full: bool) -> Result<Option<RichBlock>>;
+    async fn block_by_hash(&self, hash: H256, full: BoolParam) -> Result<Option<CompatResponse<RichBlock>>>;
 
     /// Returns information about a block by number.
     #[method(name = "getBlockByNumber")]
-    async fn block_by_number(&self, number: BlockNumberOrTag, full: bool) -> Result<Option<RichBlock>>;
+    async fn block_by_number(
+        &self,
+        number: BlockNumberOrTag,
+        full: BoolParam,
+    ) -> Result<Option<CompatResponse<RichBlock>>>;
 
     /// Returns the number of transactions in a block from a block matching the given block hash.
     #[method(name = "getBlockTransactionCountByHash")]
@@ -67,11 +79,23 @@ pub trait EthApi {
 
     /// Returns the information about a transaction requested by transaction hash.
     #[method(name = "getTransactionByHash")]
-    async fn transaction_by_hash(&self, hash: H256) -> Result<Option<EthTransaction>>;
+    async fn transaction_by_hash(&self, hash: H256) -> Result<Option<CompatResponse<EthTransaction>>>;
+
+    /// Returns the included transaction of a sender with the given nonce.
+    #[method(name = "getTransactionBySenderAndNonce")]
+    async fn transaction_by_sender_and_nonce(
+        &self,
+        sender: AddressParam,
+        nonce: U256,
+    ) -> Result<Option<CompatResponse<EthTransaction>>>;
 
     /// Returns information about a transaction by block hash and transaction index position.
     #[method(name = "getTransactionByBlockHashAndIndex")]
-    async fn transaction_by_block_hash_and_index(&self, hash: H256, index: Index) -> Result<Option<EthTransaction>>;
+    async fn transaction_by_block_hash_and_index(
+        &self,
+        hash: H256,
+        index: Index,
+    ) -> Result<Option<CompatResponse<EthTransaction>>>;
 
     /// Returns information about a transaction by block number and transaction index position.
     #[method(name = "getTransactionByBlockNumberAndIndex")]
@@ -79,27 +103,27 @@ pub trait EthApi {
         &self,
         number: BlockNumberOrTag,
         index: Index,
-    ) -> Result<Option<EthTransaction>>;
+    ) -> Result<Option<CompatResponse<EthTransaction>>>;
 
     /// Returns the receipt of a transaction by transaction hash.
     #[method(name = "getTransactionReceipt")]
-    async fn transaction_receipt(&self, hash: H256) -> Result<Option<TransactionReceipt>>;
+    async fn transaction_receipt(&self, hash: H256) -> Result<Option<CompatResponse<TransactionReceipt>>>;
 
     /// Returns the balance of the account of given address.
     #[method(name = "getBalance")]
-    async fn balance(&self, address: Address, block_number: Option<BlockId>) -> Result<U256>;
+    async fn balance(&self, address: AddressParam, block_number: Option<BlockId>) -> Result<U256>;
 
     /// Returns the value from a storage position at a given address
     #[method(name = "getStorageAt")]
-    async fn storage_at(&self, address: Address, index: U256, block_id: Option<BlockId>) -> Result<U256>;
+    async fn storage_at(&self, address: AddressParam, index: U256, block_id: Option<BlockId>) -> Result<U256>;
 
     /// Returns the number of transactions sent from an address at given block number.
     #[method(name = "getTransactionCount")]
-    async fn transaction_count(&self, address: Address, block_id: Option<BlockId>) -> Result<U256>;
+    async fn transaction_count(&self, address: AddressParam, block_id: Option<BlockId>) -> Result<U256>;
 
     /// Returns code at a given address at given block number.
     #[method(name = "getCode")]
-    async fn get_code(&self, address: Address, block_id: Option<BlockId>) -> Result<Bytes>;
+    async fn get_code(&self, address: AddressParam, block_id: Option<BlockId>) -> Result<Bytes>;
 
     /// Executes a new message call immediately without creating a transaction on the block chain.
     #[method(name = "call")]
@@ -148,7 +172,7 @@ pub trait EthApi {
         block_count: U256,
         newest_block: BlockNumberOrTag,
         reward_percentiles: Option<Vec<f64>>,
-    ) -> Result<FeeHistory>;
+    ) -> Result<CompatResponse<FeeHistory>>;
 
     /// Returns the current maxPriorityFeePerGas per gas in wei.
     #[method(name = "maxPriorityFeePerGas")]
@@ -184,10 +208,15 @@ pub trait EthApi {
     #[method(name = "sendRawTransaction")]
     async fn send_raw_transaction(&self, bytes: Bytes) -> Result<H256>;
 
+    /// Sends signed transaction which is withheld from the pending responses of the RPC until
+    /// included in a block, returning its hash.
+    #[method(name = "sendPrivateRawTransaction")]
+    async fn send_private_raw_transaction(&self, bytes: Bytes) -> Result<H256>;
+
     /// Returns an Ethereum specific signature with: sign(keccak256("\x19Ethereum Signed Message:\n"
     /// + len(message) + message))).
     #[method(name = "sign")]
-    async fn sign(&self, address: Address, message: Bytes) -> Result<Bytes>;
+    async fn sign(&self, address: AddressParam, message: Bytes) -> Result<Bytes>;
 
     /// Signs a transaction that can be submitted to the network at a later time using with
     /// `sendRawTransaction.`
@@ -196,15 +225,45 @@ pub trait EthApi {
 
     /// Signs data via [EIP-712](https://github.com/ethereum/EIPs/blob/master/EIPS/eip-712.md).
     #[method(name = "signTypedData")]
-    async fn sign_typed_data(&self, address: Address, data: serde_json::Value) -> Result<Bytes>;
+    async fn sign_typed_data(&self, address: AddressParam, data: serde_json::Value) -> Result<Bytes>;
 
     /// Returns the account and storage values of the specified account including the Merkle-proof.
     /// This call can be used to verify that the data you are pulling from is not tampered with.
     #[method(name = "getProof")]
     async fn get_proof(
         &self,
-        address: Address,
+        address: AddressParam,
         keys: Vec<H256>,
         block_id: Option<BlockId>,
     ) -> Result<EIP1186AccountProofResponse>;
+
+    /// Returns the logs matching the given filter.
+    #[method(name = "getLogs")]
+    async fn get_logs(&self, filter: LogFilter) -> Result<CompatResponse<Vec<Log>>>;
+
+    /// Installs a filter of the logs of the new blocks matching the given filter, polled with
+    /// `eth_getFilterChanges`.
+    #[method(name = "newFilter")]
+    async fn new_filter(&self, filter: LogFilter) -> Result<U256>;
+
+    /// Installs a filter of the hashes of the new blocks.
+    #[method(name = "newBlockFilter")]
+    async fn new_block_filter(&self) -> Result<U256>;
+
+    /// Installs a filter of the hashes of the new pending transactions.
+    #[method(name = "newPendingTransactionFilter")]
+    async fn new_pending_transaction_filter(&self) -> Result<U256>;
+
+    /// Returns the changes of a filter since its last poll. Filters not polled for
+    /// `KAKAROT_FILTER_IDLE_TIMEOUT_SECS` are uninstalled.
+    #[method(name = "getFilterChanges")]
+    async fn get_filter_changes(&self, id: U256) -> Result<FilterChanges>;
+
+    /// Returns the logs matching a filter installed by `eth_newFilter`, over its block range.
+    #[method(name = "getFilterLogs")]
+    async fn get_filter_logs(&self, id: U256) -> Result<Vec<Log>>;
+
+    /// Uninstalls a filter, returns false if it was not installed.
+    #[method(name = "uninstallFilter")]
+    async fn uninstall_filter(&self, id: U256) -> Result<bool>;
 }
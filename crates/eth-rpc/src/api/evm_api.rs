@@ -0,0 +1,29 @@
+use jsonrpsee::core::RpcResult as Result;
+use jsonrpsee::proc_macros::rpc;
+use reth_primitives::U64;
+
+/// Dev methods of Hardhat and Ganache, proxied to the devnet in dev mode.
+#[rpc(server, namespace = "evm")]
+#[async_trait]
+pub trait EvmApi {
+    /// Mines a block, with the given timestamp if set.
+    #[method(name = "mine")]
+    async fn mine(&self, timestamp: Option<U64>) -> Result<String>;
+
+    /// Sets the timestamp of the next block.
+    #[method(name = "setNextBlockTimestamp")]
+    async fn set_next_block_timestamp(&self, timestamp: U64) -> Result<()>;
+
+    /// Moves the timestamp of the next block forward by the given number of seconds.
+    #[method(name = "increaseTime")]
+    async fn increase_time(&self, seconds: U64) -> Result<U64>;
+
+    /// Takes a snapshot of the devnet state and returns its id.
+    #[method(name = "snapshot")]
+    async fn snapshot(&self) -> Result<U64>;
+
+    /// Reverts the devnet state to a snapshot. The snapshot and the ones taken after it can no
+    /// longer be reverted to. Returns false if the snapshot is unknown.
+    #[method(name = "revert")]
+    async fn revert(&self, id: U64) -> Result<bool>;
+}
@@ -0,0 +1,12 @@
+use jsonrpsee::core::RpcResult as Result;
+use jsonrpsee::proc_macros::rpc;
+use kakarot_rpc_core::client::liveness::LivenessStatus;
+
+#[rpc(server, namespace = "system")]
+#[async_trait]
+pub trait HealthApi {
+    /// Returns the liveness of the Starknet sequencer, failing if it is stale. Also served as
+    /// `GET /health`, with a 500 status code when unhealthy, for load balancers.
+    #[method(name = "health")]
+    async fn health(&self) -> Result<LivenessStatus>;
+}
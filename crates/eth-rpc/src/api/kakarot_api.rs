@@ -0,0 +1,82 @@
+use jsonrpsee::core::RpcResult as Result;
+use jsonrpsee::proc_macros::rpc;
+use kakarot_rpc_core::client::tracking::{RelayStats, TrackedTransaction};
+use kakarot_rpc_core::models::account::AccountType;
+use kakarot_rpc_core::models::address::AddressParam;
+use kakarot_rpc_core::models::bundle::CallBundle;
+use kakarot_rpc_core::models::capabilities::Capabilities;
+use kakarot_rpc_core::models::chain_config::ChainConfig;
+use kakarot_rpc_core::models::fee::StarknetFeeEstimate;
+use kakarot_rpc_core::models::filter::LogFilter;
+use kakarot_rpc_core::models::message::L1Message;
+use kakarot_rpc_core::models::state_diff::AccountStateDiff;
+use kakarot_rpc_core::models::storage::{StorageKey, StorageSlot};
+use reth_primitives::{BlockId, Bytes, H256, U256, U64};
+use reth_rpc_types::RichBlock;
+
+use crate::abi_registry::DecodedLog;
+
+#[rpc(server, namespace = "kakarot")]
+#[async_trait]
+pub trait KakarotApi {
+    /// Returns the operating mode of the RPC and the features it serves.
+    #[method(name = "capabilities")]
+    async fn capabilities(&self) -> Result<Capabilities>;
+
+    /// Returns the chain configuration the RPC operates under: chain ids, Kakarot deployment, fee
+    /// token, gas translation constants and namespaces served.
+    #[method(name = "getChainConfig")]
+    async fn chain_config(&self) -> Result<ChainConfig>;
+
+    /// Returns the counters of the transactions relayed to Starknet by the RPC.
+    #[method(name = "getRelayStats")]
+    async fn relay_stats(&self) -> Result<RelayStats>;
+
+    /// Returns the lifecycle of a relayed transaction given its Ethereum or Starknet hash.
+    #[method(name = "getTransactionStatus")]
+    async fn transaction_status(&self, hash: H256) -> Result<Option<TrackedTransaction>>;
+
+    /// Returns the hashes and statuses of the messages between L1 and Starknet of a transaction,
+    /// e.g. the messages sent to L1 by the bridge precompiles.
+    #[method(name = "getL1MessageStatus")]
+    async fn l1_message_status(&self, hash: H256) -> Result<Option<Vec<L1Message>>>;
+
+    /// Returns the estimated Starknet fee the relayer would pay to send a raw Ethereum
+    /// transaction.
+    #[method(name = "estimateStarknetFee")]
+    async fn estimate_starknet_fee(&self, bytes: Bytes) -> Result<StarknetFeeEstimate>;
+
+    /// Simulates an ordered bundle of raw Ethereum transactions on top of a block, each
+    /// transaction seeing the state changes of the previous ones.
+    #[method(name = "callBundle")]
+    async fn call_bundle(&self, transactions: Vec<Bytes>, block_id: Option<BlockId>) -> Result<CallBundle>;
+
+    /// Returns the last block produced at or before a unix timestamp, `null` if the timestamp is
+    /// before the genesis block, with its transactions if `hydrated`.
+    #[method(name = "getBlockByTimestamp")]
+    async fn block_by_timestamp(&self, timestamp: U64, hydrated: bool) -> Result<Option<RichBlock>>;
+
+    /// Returns the storage slot reached from a base slot by following mapping keys and array
+    /// indexes, using the Solidity storage layout, along with its value at the given block.
+    #[method(name = "getStorageSlot")]
+    async fn storage_slot(
+        &self,
+        address: AddressParam,
+        base_slot: U256,
+        keys: Vec<StorageKey>,
+        block_id: Option<BlockId>,
+    ) -> Result<StorageSlot>;
+
+    /// Returns the type of the account at an address: `eoa`, `contract` or `undeployed`.
+    #[method(name = "getAccountType")]
+    async fn account_type(&self, address: AddressParam, block_id: Option<BlockId>) -> Result<AccountType>;
+
+    /// Returns the changes of the Kakarot accounts in a block, translated from its Starknet state
+    /// diff: nonces, balances, bytecodes and the values of the given storage slots.
+    #[method(name = "getStateDiff")]
+    async fn state_diff(&self, block_id: BlockId, slots: Option<Vec<U256>>) -> Result<Vec<AccountStateDiff>>;
+
+    /// Returns the logs matching a filter, decoded with the ABIs known to the RPC.
+    #[method(name = "getDecodedLogs")]
+    async fn decoded_logs(&self, filter: LogFilter) -> Result<Vec<DecodedLog>>;
+}
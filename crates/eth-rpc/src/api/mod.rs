@@ -1,4 +1,12 @@
+pub mod admin_api;
 pub mod alchemy_api;
+pub mod anvil_api;
+pub mod erigon_api;
 pub mod eth_api;
+pub mod evm_api;
+pub mod health_api;
+pub mod kakarot_api;
 pub mod net_api;
+pub mod ots_api;
+pub mod pubsub_api;
 pub mod web3_api;
@@ -1,15 +1,14 @@
 use jsonrpsee::core::RpcResult as Result;
 use jsonrpsee::proc_macros::rpc;
-use reth_primitives::U64;
 use reth_rpc_types::PeerCount;
 
 // TODO: Define and implement of methods of Net API
 #[rpc(server, namespace = "net")]
 #[async_trait]
 pub trait NetApi {
-    /// Returns the protocol version encoded as a string.
+    /// Returns the network id, the chain id, as a decimal string.
     #[method(name = "version")]
-    fn version(&self) -> Result<U64>;
+    fn version(&self) -> Result<String>;
 
     /// Returns number of peers connected to node.
     #[method(name = "peerCount")]
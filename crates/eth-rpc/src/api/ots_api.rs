@@ -0,0 +1,18 @@
+use jsonrpsee::core::RpcResult as Result;
+use jsonrpsee::proc_macros::rpc;
+use kakarot_rpc_core::client::creations::ContractCreator;
+use kakarot_rpc_core::models::address::AddressParam;
+use reth_primitives::{H256, U256};
+
+#[rpc(server, namespace = "ots")]
+#[async_trait]
+pub trait OtsApi {
+    /// Returns the transaction which deployed a contract and its sender, `null` if the contract
+    /// creation is not indexed.
+    #[method(name = "getContractCreator")]
+    async fn get_contract_creator(&self, address: AddressParam) -> Result<Option<ContractCreator>>;
+
+    /// Returns the hash of the included transaction of a sender with the given nonce.
+    #[method(name = "getTransactionBySenderAndNonce")]
+    async fn get_transaction_by_sender_and_nonce(&self, sender: AddressParam, nonce: U256) -> Result<Option<H256>>;
+}
@@ -0,0 +1,33 @@
+use jsonrpsee::core::SubscriptionResult;
+use jsonrpsee::proc_macros::rpc;
+use reth_rpc_types::pubsub::{Params, SubscriptionKind};
+use serde_json::Value;
+
+use crate::subscription::PendingTransactionFilter;
+
+#[rpc(server, namespace = "eth")]
+#[async_trait]
+pub trait EthPubSubApi {
+    /// Creates a websocket subscription of the given kind. Notifications are queued per
+    /// subscription and dropped, or the subscription closed, if the client does not keep up.
+    /// A `newHeads` subscription with a `fromBlock` parameter, e.g. the block following the last
+    /// one received before a disconnection, is first notified of the blocks since `fromBlock`.
+    /// A `logs` subscription is notified of the new logs matching the address and topics of its
+    /// filter only.
+    #[subscription(name = "subscribe" => "subscription", unsubscribe = "unsubscribe", item = Value)]
+    async fn subscribe(&self, kind: SubscriptionKind, params: Option<Params>) -> SubscriptionResult;
+}
+
+#[rpc(server, namespace = "kakarot")]
+#[async_trait]
+pub trait KakarotPubSubApi {
+    /// Creates a websocket subscription to the full pending transactions, optionally filtered by
+    /// recipient and function selector. Transactions are notified once when they are first seen in
+    /// the pending block of the Starknet provider, the transactions relayed by the RPC included.
+    #[subscription(
+        name = "pendingTransactionsStream" => "pendingTransaction",
+        unsubscribe = "unsubscribePendingTransactionsStream",
+        item = Value
+    )]
+    async fn pending_transactions_stream(&self, filter: Option<PendingTransactionFilter>) -> SubscriptionResult;
+}
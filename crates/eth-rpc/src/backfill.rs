@@ -0,0 +1,71 @@
+use std::ops::Range;
+
+use eyre::{eyre, Result};
+use kakarot_rpc_core::client::api::KakarotEthApi;
+use starknet::providers::Provider;
+
+use crate::export::parse_range;
+
+/// Number of blocks whose deployments are indexed at once, reporting the progress in between.
+const BACKFILL_CHUNK_BLOCKS: u64 = 1000;
+
+/// Arguments of `kakarot-rpc index backfill-creations`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackfillArgs {
+    /// Scanned blocks, `A..B` excluding block `B`.
+    pub range: Range<u64>,
+}
+
+impl BackfillArgs {
+    /// Parses `--range <A..B>`.
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let mut range = None;
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| eyre!("Missing value of {arg}"));
+            match arg.as_str() {
+                "--range" => range = Some(parse_range(value()?)?),
+                _ => return Err(eyre!("Unexpected backfill argument: {arg}")),
+            }
+        }
+
+        let range = range.ok_or_else(|| eyre!("Missing --range argument, e.g. --range 0..1000"))?;
+        Ok(Self { range })
+    }
+}
+
+/// Indexes the contracts deployed in the blocks of the range, for the blocks produced before the
+/// RPC indexed the creations. Returns the number of contracts indexed.
+pub async fn backfill_contract_creations<P: Provider + Send + Sync + 'static>(
+    kakarot_client: &dyn KakarotEthApi<P>,
+    args: &BackfillArgs,
+) -> Result<u64> {
+    let mut indexed = 0;
+    let mut from_block = args.range.start;
+    while from_block < args.range.end {
+        let to_block = (from_block + BACKFILL_CHUNK_BLOCKS).min(args.range.end) - 1;
+        indexed += kakarot_client
+            .index_contract_creations(from_block, to_block)
+            .await
+            .map_err(|err| eyre!("Failed to index the creations of blocks {from_block} to {to_block}: {err}"))?;
+        tracing::info!("Indexed the contract creations up to block {to_block}, {indexed} contracts");
+        from_block = to_block + 1;
+    }
+    Ok(indexed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backfill_args_parse() {
+        // When
+        let args: Vec<String> = vec!["--range".into(), "0..1000".into()];
+
+        // Then
+        assert_eq!(BackfillArgs { range: 0..1000 }, BackfillArgs::parse(&args).unwrap());
+        assert!(BackfillArgs::parse(&[]).is_err());
+    }
+}
@@ -0,0 +1,115 @@
+use std::future::Future;
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use hyper::{Body, Request, Response};
+use tower::{Layer, Service};
+
+use crate::params::BoxError;
+
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+const X_REAL_IP: &str = "x-real-ip";
+
+tokio::task_local! {
+    /// Address of the client of the HTTP request being served.
+    static CLIENT_ADDRESS: Option<IpAddr>;
+}
+
+/// Returns the address of the client calling the current RPC method, `None` if unknown. Only the
+/// methods called over HTTP behind a proxy setting `X-Forwarded-For` or `X-Real-IP` know it, the
+/// socket address of the connections not being exposed to the middlewares.
+pub fn client_address() -> Option<IpAddr> {
+    CLIENT_ADDRESS.try_with(|client_address| *client_address).ok().flatten()
+}
+
+/// Returns the client address set by a proxy: the first address of `X-Forwarded-For`, the one
+/// of the client the request originates from, or else `X-Real-IP`.
+fn forwarded_address(request: &Request<Body>) -> Option<IpAddr> {
+    let forwarded_for = request
+        .headers()
+        .get(X_FORWARDED_FOR)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next());
+    let real_ip = || request.headers().get(X_REAL_IP).and_then(|value| value.to_str().ok());
+    forwarded_for.or_else(real_ip).and_then(|address| address.trim().parse().ok())
+}
+
+/// Middleware exposing the address of the client of an HTTP request to the RPC methods it calls,
+/// through [`client_address`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClientAddressLayer;
+
+impl<S> Layer<S> for ClientAddressLayer {
+    type Service = ClientAddressService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ClientAddressService { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ClientAddressService<S> {
+    inner: S,
+}
+
+impl<S> Service<Request<Body>> for ClientAddressService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Error: Into<BoxError> + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + 'static>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // The service polled ready is the one called, its clone taking its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        // The HTTP calls are served within the future of the request, websocket connections are
+        // served by a task of their own where the address is unknown
+        let client_address = forwarded_address(&request);
+        Box::pin(CLIENT_ADDRESS.scope(client_address, async move { inner.call(request).await.map_err(Into::into) }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forwarded_address() {
+        let request = |headers: &[(&str, &str)]| {
+            let mut builder = Request::builder();
+            for (name, value) in headers {
+                builder = builder.header(*name, *value);
+            }
+            builder.body(Body::empty()).unwrap()
+        };
+        let client: IpAddr = "203.0.113.7".parse().unwrap();
+
+        assert_eq!(Some(client), forwarded_address(&request(&[("x-forwarded-for", "203.0.113.7, 10.0.0.1")])));
+        assert_eq!(Some(client), forwarded_address(&request(&[("x-real-ip", "203.0.113.7")])));
+        assert_eq!(None, forwarded_address(&request(&[("x-forwarded-for", "unknown")])));
+        assert_eq!(None, forwarded_address(&request(&[])));
+    }
+
+    #[tokio::test]
+    async fn test_client_address_is_scoped_to_the_request() {
+        // Given
+        let client: IpAddr = "203.0.113.7".parse().unwrap();
+
+        // When
+        let scoped = CLIENT_ADDRESS.scope(Some(client), async { client_address() }).await;
+
+        // Then
+        assert_eq!(Some(client), scoped);
+        assert_eq!(None, client_address());
+    }
+}
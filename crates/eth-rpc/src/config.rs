@@ -1,17 +1,354 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
 use eyre::{eyre, Result};
+use kakarot_rpc_core::models::compat::ResponseCompat;
+use reth_primitives::Address;
+
+use crate::params::ParamsMode;
+use crate::rpc::KakarotRpcModule;
+
+/// Preset configuration of a kind of deployment, selected with `--profile`.
+///
+/// A profile only provides default values of environment variables: variables set by the
+/// operator take precedence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Profile {
+    /// Local development against a devnet: all namespaces, dev methods and permissive checks.
+    Dev,
+    /// Block explorers and indexers: archive mode, trie roots and large request budgets.
+    Explorer,
+    /// Public gateways: minimal namespaces, strict checks and small request budgets.
+    Gateway,
+}
+
+impl FromStr for Profile {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "dev" => Ok(Self::Dev),
+            "explorer" => Ok(Self::Explorer),
+            "gateway" => Ok(Self::Gateway),
+            _ => Err(eyre!("Invalid profile: {s}, expected dev, explorer or gateway")),
+        }
+    }
+}
+
+impl Profile {
+    /// Returns the environment variables set by the profile.
+    pub fn env_defaults(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Dev => &[("KAKAROT_DEV_MODE", "true"), ("KAKAROT_CHAIN_ID_POLICY", "permissive")],
+            Self::Explorer => &[
+                ("KAKAROT_MODE", "archive"),
+                ("KAKAROT_COMPUTE_TRIE_ROOTS", "true"),
+                ("KAKAROT_RPC_DISABLED_MODULES", "evm,anvil"),
+                ("KAKAROT_MAX_UPSTREAM_CALLS", "10000"),
+                ("KAKAROT_MAX_UPSTREAM_RESPONSE_BYTES", "536870912"),
+            ],
+            Self::Gateway => &[
+                ("KAKAROT_MODE", "full"),
+                ("KAKAROT_CHAIN_ID_POLICY", "strict"),
+                ("KAKAROT_REJECT_STALE_WRITES", "true"),
+                ("KAKAROT_RPC_DISABLED_MODULES", "admin,evm,anvil,alchemy"),
+                ("KAKAROT_READ_LANE_CONCURRENCY", "32"),
+                ("KAKAROT_WRITE_LANE_CONCURRENCY", "8"),
+                ("KAKAROT_MAX_READ_LANE_QUEUE", "256"),
+                ("KAKAROT_MAX_WRITE_LANE_QUEUE", "64"),
+                ("KAKAROT_MAX_UPSTREAM_CALLS", "100"),
+                ("KAKAROT_MAX_UPSTREAM_RESPONSE_BYTES", "8388608"),
+                ("KAKAROT_SLOW_CONSUMER_POLICY", "disconnect"),
+            ],
+        }
+    }
+
+    /// Sets the environment variables of the profile which are not set yet. Must be called before
+    /// reading the configuration from the environment.
+    pub fn apply(&self) {
+        for (name, value) in self.env_defaults() {
+            if std::env::var_os(name).is_none() {
+                std::env::set_var(name, value);
+            }
+        }
+    }
+}
+
+/// What to do with a subscriber whose notification queue is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SlowConsumerPolicy {
+    /// Drop the notification for this subscriber and keep the subscription open.
+    #[default]
+    Drop,
+    /// Close the subscription. The client has to resubscribe.
+    Disconnect,
+}
+
+impl FromStr for SlowConsumerPolicy {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "drop" => Ok(Self::Drop),
+            "disconnect" => Ok(Self::Disconnect),
+            _ => Err(eyre!("Invalid slow consumer policy: {s}, expected drop or disconnect")),
+        }
+    }
+}
+
+/// Configuration of the websocket subscriptions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriptionConfig {
+    /// Maximum number of pending notifications per subscription.
+    pub queue_capacity: usize,
+    /// Policy applied when the queue of a subscription is full.
+    pub slow_consumer_policy: SlowConsumerPolicy,
+    /// Interval at which the Starknet provider is polled for new notifications.
+    pub poll_interval: Duration,
+    /// Maximum number of past blocks a resumed subscription is notified of.
+    pub resume_blocks: u64,
+    /// Webhooks notified of the activity of watched addresses, by the same poller.
+    pub webhooks: WebhookConfig,
+    /// Filters polled with `eth_getFilterChanges`.
+    pub filters: FilterConfig,
+}
+
+/// Configuration of the webhook notifications.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WebhookConfig {
+    /// URLs the notifications are posted to.
+    pub urls: Vec<String>,
+    /// Addresses whose transactions and logs are notified.
+    pub watched_addresses: Vec<Address>,
+}
+
+impl WebhookConfig {
+    pub fn is_enabled(&self) -> bool {
+        !self.urls.is_empty() && !self.watched_addresses.is_empty()
+    }
+}
+
+/// Configuration of the filters installed by `eth_newFilter` and the like.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FilterConfig {
+    /// Time after which a filter not polled is uninstalled.
+    pub idle_timeout: Duration,
+    /// Maximum number of filters installed at once.
+    pub max_filters: usize,
+    /// Maximum number of filters installed at once by a client, identified by its address.
+    pub max_filters_per_client: usize,
+}
+
+impl Default for FilterConfig {
+    fn default() -> Self {
+        // Geth uninstalls the filters not polled for 5 minutes
+        Self { idle_timeout: Duration::from_secs(5 * 60), max_filters: 10_000, max_filters_per_client: 1_000 }
+    }
+}
+
+impl Default for SubscriptionConfig {
+    fn default() -> Self {
+        Self {
+            queue_capacity: 128,
+            slow_consumer_policy: SlowConsumerPolicy::Drop,
+            poll_interval: Duration::from_secs(1),
+            resume_blocks: 128,
+            webhooks: WebhookConfig::default(),
+            filters: FilterConfig::default(),
+        }
+    }
+}
+
+impl SubscriptionConfig {
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self::default();
+        if let Ok(queue_capacity) = std::env::var("KAKAROT_SUBSCRIPTION_QUEUE_CAPACITY") {
+            config.queue_capacity = queue_capacity
+                .parse()
+                .map_err(|_| eyre!("KAKAROT_SUBSCRIPTION_QUEUE_CAPACITY should be a positive integer"))?;
+        }
+        if let Ok(slow_consumer_policy) = std::env::var("KAKAROT_SLOW_CONSUMER_POLICY") {
+            config.slow_consumer_policy = slow_consumer_policy.parse()?;
+        }
+        if let Ok(poll_interval) = std::env::var("KAKAROT_SUBSCRIPTION_POLL_INTERVAL_MS") {
+            let poll_interval = poll_interval
+                .parse()
+                .map_err(|_| eyre!("KAKAROT_SUBSCRIPTION_POLL_INTERVAL_MS should be a positive integer"))?;
+            config.poll_interval = Duration::from_millis(poll_interval);
+        }
+        if let Ok(resume_blocks) = std::env::var("KAKAROT_SUBSCRIPTION_RESUME_BLOCKS") {
+            config.resume_blocks = resume_blocks
+                .parse()
+                .map_err(|_| eyre!("KAKAROT_SUBSCRIPTION_RESUME_BLOCKS should be a positive integer"))?;
+        }
+        config.webhooks.urls = comma_separated_env_var("KAKAROT_WEBHOOK_URLS");
+        config.webhooks.watched_addresses = comma_separated_env_var("KAKAROT_WEBHOOK_ADDRESSES")
+            .into_iter()
+            .map(|address| {
+                address.parse().map_err(|_| eyre!("Invalid address in KAKAROT_WEBHOOK_ADDRESSES: {address}"))
+            })
+            .collect::<Result<_>>()?;
+        if let Ok(idle_timeout) = std::env::var("KAKAROT_FILTER_IDLE_TIMEOUT_SECS") {
+            let idle_timeout = idle_timeout
+                .parse()
+                .map_err(|_| eyre!("KAKAROT_FILTER_IDLE_TIMEOUT_SECS should be a positive integer"))?;
+            config.filters.idle_timeout = Duration::from_secs(idle_timeout);
+        }
+        if let Ok(max_filters) = std::env::var("KAKAROT_MAX_FILTERS") {
+            config.filters.max_filters =
+                max_filters.parse().map_err(|_| eyre!("KAKAROT_MAX_FILTERS should be a positive integer"))?;
+        }
+        if let Ok(max_filters_per_client) = std::env::var("KAKAROT_MAX_FILTERS_PER_CLIENT") {
+            config.filters.max_filters_per_client = max_filters_per_client
+                .parse()
+                .map_err(|_| eyre!("KAKAROT_MAX_FILTERS_PER_CLIENT should be a positive integer"))?;
+        }
+        Ok(config)
+    }
+}
+
+/// Default minimum size in bytes of the HTTP responses compressed.
+pub const DEFAULT_COMPRESSION_MIN_BYTES: u16 = 1024;
+/// Default interval at which the TLS certificate files are checked for a renewal.
+pub const DEFAULT_TLS_RELOAD_INTERVAL: Duration = Duration::from_secs(60);
+
+/// TLS termination of the built-in server, so that small deployments need no reverse proxy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsConfig {
+    /// PEM certificate chain of the server.
+    pub cert_path: PathBuf,
+    /// PEM private key of the server.
+    pub key_path: PathBuf,
+    /// PEM certificate authorities the clients' certificates must be signed by, if client
+    /// authentication is required.
+    pub client_ca_path: Option<PathBuf>,
+    /// Interval at which the certificate files are checked for a renewal, zero disabling the
+    /// reload.
+    pub reload_interval: Duration,
+}
+
+impl TlsConfig {
+    /// Reads the TLS configuration, `None` if `KAKAROT_TLS_CERT_PATH` is not set.
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(cert_path) = std::env::var("KAKAROT_TLS_CERT_PATH") else { return Ok(None) };
+        let key_path = std::env::var("KAKAROT_TLS_KEY_PATH")
+            .map_err(|_| eyre!("KAKAROT_TLS_KEY_PATH should be set with KAKAROT_TLS_CERT_PATH"))?;
+        let client_ca_path = std::env::var("KAKAROT_TLS_CLIENT_CA_PATH").ok().map(PathBuf::from);
+        let reload_interval = match std::env::var("KAKAROT_TLS_RELOAD_INTERVAL_SECS") {
+            Ok(reload_interval) => Duration::from_secs(
+                reload_interval
+                    .parse()
+                    .map_err(|_| eyre!("KAKAROT_TLS_RELOAD_INTERVAL_SECS should be a positive integer"))?,
+            ),
+            Err(_) => DEFAULT_TLS_RELOAD_INTERVAL,
+        };
+        Ok(Some(Self { cert_path: cert_path.into(), key_path: key_path.into(), client_ca_path, reload_interval }))
+    }
+}
 
 pub struct RPCConfig {
     pub socket_addr: String,
+    pub subscription_config: SubscriptionConfig,
+    /// Modules not served by the RPC.
+    pub disabled_modules: Vec<KakarotRpcModule>,
+    /// Methods not served by the RPC.
+    pub disabled_methods: Vec<String>,
+    /// Directory of the contract ABIs used to decode logs.
+    pub abi_dir: Option<PathBuf>,
+    /// Sourcify server queried for the ABIs of unknown contracts.
+    pub sourcify_url: Option<String>,
+    /// Switches for the response differences between Ethereum clients.
+    pub response_compat: ResponseCompat,
+    /// Minimum size in bytes of the HTTP responses compressed.
+    pub compression_min_bytes: u16,
+    /// How the parameters of the HTTP requests are checked.
+    pub params_mode: ParamsMode,
+    /// TLS termination of the server, plain HTTP if `None`.
+    pub tls_config: Option<TlsConfig>,
 }
 
 impl RPCConfig {
     pub fn new(socket_addr: String) -> RPCConfig {
-        RPCConfig { socket_addr }
+        RPCConfig {
+            socket_addr,
+            subscription_config: SubscriptionConfig::default(),
+            disabled_modules: Vec::new(),
+            disabled_methods: Vec::new(),
+            abi_dir: None,
+            sourcify_url: None,
+            response_compat: ResponseCompat::default(),
+            compression_min_bytes: DEFAULT_COMPRESSION_MIN_BYTES,
+            params_mode: ParamsMode::default(),
+            tls_config: None,
+        }
     }
 
     pub fn from_env() -> Result<Self> {
         let socket_addr = std::env::var("KAKAROT_HTTP_RPC_ADDRESS")
             .map_err(|_| eyre!("Missing mandatory environment variable: KAKAROT_HTTP_RPC_ADDRESS"))?;
-        Ok(RPCConfig::new(socket_addr))
+        let subscription_config = SubscriptionConfig::from_env()?;
+        let disabled_modules = comma_separated_env_var("KAKAROT_RPC_DISABLED_MODULES")
+            .into_iter()
+            .map(|module| module.parse())
+            .collect::<Result<_>>()?;
+        let disabled_methods = comma_separated_env_var("KAKAROT_RPC_DISABLED_METHODS");
+        let abi_dir = std::env::var("KAKAROT_ABI_DIR").ok().map(PathBuf::from);
+        let sourcify_url = std::env::var("KAKAROT_SOURCIFY_URL").ok();
+        let response_compat = response_compat_from_env()?;
+        let compression_min_bytes = match std::env::var("KAKAROT_COMPRESSION_MIN_BYTES") {
+            Ok(min_bytes) => min_bytes
+                .parse()
+                .map_err(|_| eyre!("KAKAROT_COMPRESSION_MIN_BYTES should be an integer below 65536"))?,
+            Err(_) => DEFAULT_COMPRESSION_MIN_BYTES,
+        };
+        let params_mode = match std::env::var("KAKAROT_PARAMS_MODE") {
+            Ok(params_mode) => params_mode.parse()?,
+            Err(_) => ParamsMode::default(),
+        };
+        let tls_config = TlsConfig::from_env()?;
+        Ok(RPCConfig {
+            socket_addr,
+            subscription_config,
+            disabled_modules,
+            disabled_methods,
+            abi_dir,
+            sourcify_url,
+            response_compat,
+            compression_min_bytes,
+            params_mode,
+            tls_config,
+        })
     }
 }
+
+/// Reads the response compatibility preset, `geth` by default, and its overrides.
+fn response_compat_from_env() -> Result<ResponseCompat> {
+    let mut response_compat = match std::env::var("KAKAROT_RESPONSE_COMPAT") {
+        Ok(client) => ResponseCompat::preset(&client)
+            .ok_or_else(|| eyre!("KAKAROT_RESPONSE_COMPAT should be geth, erigon or hardhat, got {client}"))?,
+        Err(_) => ResponseCompat::default(),
+    };
+    if let Ok(empty_bytes) = std::env::var("KAKAROT_EMPTY_BYTES") {
+        response_compat.empty_bytes = empty_bytes.parse().map_err(|err: String| eyre!(err))?;
+    }
+    if let Ok(zero_quantity) = std::env::var("KAKAROT_ZERO_QUANTITY") {
+        response_compat.zero_quantity = zero_quantity.parse().map_err(|err: String| eyre!(err))?;
+    }
+    if let Ok(include_y_parity) = std::env::var("KAKAROT_Y_PARITY") {
+        response_compat.include_y_parity =
+            include_y_parity.parse().map_err(|_| eyre!("KAKAROT_Y_PARITY should be true or false"))?;
+    }
+    if let Ok(string_booleans) = std::env::var("KAKAROT_STRING_BOOLEANS") {
+        response_compat.string_booleans =
+            string_booleans.parse().map_err(|_| eyre!("KAKAROT_STRING_BOOLEANS should be true or false"))?;
+    }
+    Ok(response_compat)
+}
+
+/// Reads a comma separated list from an optional environment variable.
+fn comma_separated_env_var(name: &str) -> Vec<String> {
+    std::env::var(name)
+        .map(|value| value.split(',').map(str::trim).filter(|item| !item.is_empty()).map(String::from).collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,179 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use eyre::{eyre, Result};
+use kakarot_rpc_core::client::api::KakarotEthApi;
+use reth_rpc_types::BlockTransactions;
+use serde::Serialize;
+use starknet::core::types::BlockId as StarknetBlockId;
+use starknet::providers::Provider;
+
+/// Format of the exported files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One JSON object per line, as returned by the RPC.
+    Ndjson,
+}
+
+impl FromStr for ExportFormat {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ndjson" => Ok(Self::Ndjson),
+            // Declined, see docs/backlog.md
+            "parquet" => Err(eyre!("Parquet export is not supported, use --format ndjson")),
+            _ => Err(eyre!("Invalid export format: {s}, expected ndjson")),
+        }
+    }
+}
+
+/// Arguments of `kakarot-rpc index export`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportArgs {
+    pub format: ExportFormat,
+    /// Exported blocks, `A..B` excluding block `B`.
+    pub range: Range<u64>,
+    /// Directory the files are written to.
+    pub output: PathBuf,
+}
+
+impl ExportArgs {
+    /// Parses `--format <format> --range <A..B> [--output <dir>]`.
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let mut format = ExportFormat::Ndjson;
+        let mut range = None;
+        let mut output = PathBuf::from(".");
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| eyre!("Missing value of {arg}"));
+            match arg.as_str() {
+                "--format" => format = value()?.parse()?,
+                "--range" => range = Some(parse_range(value()?)?),
+                "--output" => output = value()?.into(),
+                _ => return Err(eyre!("Unexpected export argument: {arg}")),
+            }
+        }
+
+        let range = range.ok_or_else(|| eyre!("Missing --range argument, e.g. --range 100..200"))?;
+        Ok(Self { format, range, output })
+    }
+}
+
+pub(crate) fn parse_range(range: &str) -> Result<Range<u64>> {
+    let invalid = || eyre!("Invalid block range: {range}, expected A..B");
+    let (start, end) = range.split_once("..").ok_or_else(invalid)?;
+    let (start, end): (u64, u64) = (start.parse().map_err(|_| invalid())?, end.parse().map_err(|_| invalid())?);
+    if start >= end {
+        return Err(invalid());
+    }
+    Ok(start..end)
+}
+
+/// Number of rows written per file.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ExportSummary {
+    pub blocks: u64,
+    pub transactions: u64,
+    pub receipts: u64,
+    pub logs: u64,
+}
+
+struct NdjsonWriter(BufWriter<File>);
+
+impl NdjsonWriter {
+    fn create(args: &ExportArgs, name: &str) -> Result<Self> {
+        let path = args.output.join(format!("{name}.ndjson"));
+        Ok(Self(BufWriter::new(File::create(&path).map_err(|err| eyre!("Failed to create {path:?}: {err}"))?)))
+    }
+
+    fn write<T: Serialize>(&mut self, row: &T) -> Result<()> {
+        serde_json::to_writer(&mut self.0, row)?;
+        self.0.write_all(b"\n")?;
+        Ok(())
+    }
+}
+
+/// Exports the blocks of the range, along with their transactions, receipts and logs, to one
+/// file each in the output directory. Blocks are written with the hashes of their transactions.
+pub async fn export<P: Provider + Send + Sync + 'static>(
+    kakarot_client: &dyn KakarotEthApi<P>,
+    args: &ExportArgs,
+) -> Result<ExportSummary> {
+    std::fs::create_dir_all(&args.output)?;
+    let mut blocks = NdjsonWriter::create(args, "blocks")?;
+    let mut transactions = NdjsonWriter::create(args, "transactions")?;
+    let mut receipts = NdjsonWriter::create(args, "receipts")?;
+    let mut logs = NdjsonWriter::create(args, "logs")?;
+    let mut summary = ExportSummary::default();
+
+    for block_number in args.range.clone() {
+        let mut block = kakarot_client
+            .get_eth_block_from_starknet_block(StarknetBlockId::Number(block_number), true)
+            .await
+            .map_err(|err| eyre!("Failed to fetch block {block_number}: {err}"))?;
+
+        let block_transactions =
+            match std::mem::replace(&mut block.inner.transactions, BlockTransactions::Hashes(vec![])) {
+                BlockTransactions::Full(block_transactions) => block_transactions,
+                _ => vec![],
+            };
+        block.inner.transactions =
+            BlockTransactions::Hashes(block_transactions.iter().map(|transaction| transaction.hash).collect());
+        blocks.write(&block)?;
+        summary.blocks += 1;
+
+        for transaction in block_transactions {
+            let receipt = kakarot_client
+                .transaction_receipt(transaction.hash)
+                .await
+                .map_err(|err| eyre!("Failed to fetch the receipt of {:#x}: {err}", transaction.hash))?;
+            transactions.write(&transaction)?;
+            summary.transactions += 1;
+
+            if let Some(receipt) = receipt {
+                for log in &receipt.logs {
+                    logs.write(log)?;
+                    summary.logs += 1;
+                }
+                receipts.write(&receipt)?;
+                summary.receipts += 1;
+            }
+        }
+    }
+
+    for mut writer in [blocks, transactions, receipts, logs] {
+        writer.0.flush()?;
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &str) -> Vec<String> {
+        args.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_export_args_parse() {
+        // When
+        let parsed = ExportArgs::parse(&args("--format ndjson --range 10..20 --output /tmp/export")).unwrap();
+
+        // Then
+        assert_eq!(ExportArgs { format: ExportFormat::Ndjson, range: 10..20, output: "/tmp/export".into() }, parsed);
+        assert_eq!(PathBuf::from("."), ExportArgs::parse(&args("--range 0..1")).unwrap().output);
+        assert_eq!(
+            "Parquet export is not supported, use --format ndjson",
+            ExportArgs::parse(&args("--format parquet --range 0..1")).unwrap_err().to_string()
+        );
+        assert!(ExportArgs::parse(&args("--format ndjson")).is_err());
+        assert!(ExportArgs::parse(&args("--range 20..10")).is_err());
+        assert!(ExportArgs::parse(&args("--range 10")).is_err());
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use kakarot_rpc_core::models::filter::LogFilter;
+use reth_primitives::{H256, U256};
+use reth_rpc_types::Log;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::config::FilterConfig;
+
+/// State of an installed filter: what its next changes start after.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterState {
+    /// Filter of `eth_newFilter`, whose next changes are the matching logs from `next_block`.
+    Logs { filter: LogFilter, next_block: u64 },
+    /// Filter of `eth_newBlockFilter`, whose next changes are the hashes of the blocks from
+    /// `next_block`.
+    Blocks { next_block: u64 },
+    /// Filter of `eth_newPendingTransactionFilter`, whose next changes are the hashes of the
+    /// pending transactions not `seen` yet.
+    PendingTransactions { seen: HashSet<H256> },
+}
+
+/// State of a filter when polled, along with its generation, the number of times its state was
+/// advanced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolledFilter {
+    pub state: FilterState,
+    pub generation: u64,
+}
+
+/// Changes of a filter since its last poll, returned by `eth_getFilterChanges`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FilterChanges {
+    Logs(Vec<Log>),
+    Hashes(Vec<H256>),
+}
+
+impl FilterChanges {
+    /// Returns no changes, of the same kind.
+    pub fn none(&self) -> Self {
+        match self {
+            Self::Logs(_) => Self::Logs(Vec::new()),
+            Self::Hashes(_) => Self::Hashes(Vec::new()),
+        }
+    }
+}
+
+/// Counters of the installed filters, returned by `admin_filterStats`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterStats {
+    pub installed: usize,
+    pub logs: usize,
+    pub blocks: usize,
+    pub pending_transactions: usize,
+    /// Number of filters uninstalled for not being polled within the idle timeout.
+    pub expired: u64,
+    /// Number of filters rejected for exceeding the maximum number of filters, in total or of a
+    /// client.
+    pub rejected: u64,
+}
+
+/// Filter rejected for exceeding the maximum number of filters installed, in total or by its
+/// client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("too many filters installed, uninstall unused filters with eth_uninstallFilter")]
+pub struct TooManyFilters;
+
+struct InstalledFilter {
+    state: FilterState,
+    /// Address of the client which installed the filter, if known.
+    client: Option<IpAddr>,
+    generation: u64,
+    last_polled: Instant,
+}
+
+/// Filters installed by `eth_newFilter`, `eth_newBlockFilter` and
+/// `eth_newPendingTransactionFilter`, polled with `eth_getFilterChanges`.
+///
+/// Clients such as block explorers install filters and may never uninstall them: a garbage
+/// collection task uninstalls the filters not polled within the idle timeout, and the number of
+/// filters is capped, in total and by client so that a single client cannot exhaust the total cap.
+/// The filters whose client address is unknown are only subject to the total cap.
+pub struct FilterManager {
+    config: FilterConfig,
+    filters: Mutex<HashMap<u64, InstalledFilter>>,
+    next_id: AtomicU64,
+    expired: AtomicU64,
+    rejected: AtomicU64,
+    gc_started: AtomicBool,
+}
+
+impl Default for FilterManager {
+    fn default() -> Self {
+        Self::new(FilterConfig::default())
+    }
+}
+
+impl FilterManager {
+    pub fn new(config: FilterConfig) -> Self {
+        Self {
+            config,
+            filters: Mutex::default(),
+            next_id: AtomicU64::new(1),
+            expired: AtomicU64::new(0),
+            rejected: AtomicU64::new(0),
+            gc_started: AtomicBool::new(false),
+        }
+    }
+
+    /// Installs a filter of `client` at `now` and returns its id.
+    pub fn install(
+        self: &Arc<Self>,
+        state: FilterState,
+        client: Option<IpAddr>,
+        now: Instant,
+    ) -> Result<U256, TooManyFilters> {
+        self.ensure_gc();
+        if self.is_full(&self.filters.lock().expect("filters lock poisoned"), client) {
+            // The garbage collection may not have run since the last filters went idle
+            self.expire(now);
+        }
+
+        let mut filters = self.filters.lock().expect("filters lock poisoned");
+        if self.is_full(&filters, client) {
+            self.rejected.fetch_add(1, Ordering::Relaxed);
+            return Err(TooManyFilters);
+        }
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        filters.insert(id, InstalledFilter { state, client, generation: 0, last_polled: now });
+        Ok(U256::from(id))
+    }
+
+    /// Returns true if no filter can be installed by `client`, the total or its own number of
+    /// filters being reached.
+    fn is_full(&self, filters: &HashMap<u64, InstalledFilter>, client: Option<IpAddr>) -> bool {
+        if filters.len() >= self.config.max_filters {
+            return true;
+        }
+        client.map_or(false, |client| {
+            filters.values().filter(|filter| filter.client == Some(client)).count()
+                >= self.config.max_filters_per_client
+        })
+    }
+
+    /// Returns the state of a filter polled at `now`, `None` if it is not installed.
+    pub fn poll(&self, id: U256, now: Instant) -> Option<PolledFilter> {
+        let id = u64::try_from(id).ok()?;
+        let mut filters = self.filters.lock().expect("filters lock poisoned");
+        let filter = filters.get_mut(&id)?;
+        filter.last_polled = now;
+        Some(PolledFilter { state: filter.state.clone(), generation: filter.generation })
+    }
+
+    /// Advances the state of a filter polled at `generation` once its changes are fetched. Returns
+    /// false if the filter was uninstalled or advanced by a concurrent poll meanwhile, the state
+    /// being left as is so that it never moves backwards: the concurrent poll returned the changes.
+    pub fn update(&self, id: U256, generation: u64, state: FilterState) -> bool {
+        let Ok(id) = u64::try_from(id) else { return false };
+        let mut filters = self.filters.lock().expect("filters lock poisoned");
+        let Some(filter) = filters.get_mut(&id).filter(|filter| filter.generation == generation) else {
+            return false;
+        };
+        filter.state = state;
+        filter.generation += 1;
+        true
+    }
+
+    /// Uninstalls a filter, returns false if it was not installed.
+    pub fn uninstall(&self, id: U256) -> bool {
+        let Ok(id) = u64::try_from(id) else { return false };
+        self.filters.lock().expect("filters lock poisoned").remove(&id).is_some()
+    }
+
+    /// Uninstalls the filters not polled within the idle timeout before `now`, returns their
+    /// number.
+    pub fn expire(&self, now: Instant) -> usize {
+        let mut filters = self.filters.lock().expect("filters lock poisoned");
+        let installed = filters.len();
+        filters.retain(|_, filter| now.saturating_duration_since(filter.last_polled) < self.config.idle_timeout);
+        let expired = installed - filters.len();
+        self.expired.fetch_add(expired as u64, Ordering::Relaxed);
+        expired
+    }
+
+    pub fn stats(&self) -> FilterStats {
+        let filters = self.filters.lock().expect("filters lock poisoned");
+        let count = |kind: fn(&FilterState) -> bool| filters.values().filter(|filter| kind(&filter.state)).count();
+        FilterStats {
+            installed: filters.len(),
+            logs: count(|state| matches!(state, FilterState::Logs { .. })),
+            blocks: count(|state| matches!(state, FilterState::Blocks { .. })),
+            pending_transactions: count(|state| matches!(state, FilterState::PendingTransactions { .. })),
+            expired: self.expired.load(Ordering::Relaxed),
+            rejected: self.rejected.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Starts the garbage collection of the idle filters, on the first filter installed. The task
+    /// stops once the manager is dropped.
+    fn ensure_gc(self: &Arc<Self>) {
+        if self.gc_started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let manager = Arc::downgrade(self);
+        let interval = (self.config.idle_timeout / 4).max(Duration::from_secs(1));
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let Some(manager) = manager.upgrade() else { break };
+                let expired = manager.expire(Instant::now());
+                if expired > 0 {
+                    tracing::debug!("Uninstalled {expired} idle filters");
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_filter_manager_expires_idle_filters() {
+        // Given
+        let config = FilterConfig { idle_timeout: Duration::from_secs(60), max_filters: 2, max_filters_per_client: 2 };
+        let manager = Arc::new(FilterManager::new(config));
+        let now = Instant::now();
+        let blocks = manager.install(FilterState::Blocks { next_block: 1 }, None, now).unwrap();
+        let pending = FilterState::PendingTransactions { seen: HashSet::new() };
+        let polled = manager.install(pending.clone(), None, now).unwrap();
+
+        // When
+        let rejected = manager.install(FilterState::Blocks { next_block: 1 }, None, now + Duration::from_secs(30));
+        manager.poll(polled, now + Duration::from_secs(30));
+        let installed = manager.install(FilterState::Blocks { next_block: 2 }, None, now + Duration::from_secs(70));
+
+        // Then
+        assert_eq!(Err(TooManyFilters), rejected);
+        assert!(installed.is_ok());
+        assert_eq!(None, manager.poll(blocks, now + Duration::from_secs(70)));
+        assert_eq!(Some(pending), manager.poll(polled, now + Duration::from_secs(70)).map(|polled| polled.state));
+        let stats = manager.stats();
+        assert_eq!((2, 1, 1), (stats.installed, stats.blocks, stats.pending_transactions));
+        assert_eq!((1, 1), (stats.expired, stats.rejected));
+        assert!(manager.uninstall(polled) && !manager.uninstall(polled));
+    }
+
+    #[tokio::test]
+    async fn test_filter_manager_advances_a_filter_polled_concurrently_once() {
+        // Given
+        let manager = Arc::new(FilterManager::default());
+        let now = Instant::now();
+        let id = manager.install(FilterState::Blocks { next_block: 1 }, None, now).unwrap();
+        let first = manager.poll(id, now).unwrap();
+        let second = manager.poll(id, now).unwrap();
+
+        // When
+        let first_updated = manager.update(id, first.generation, FilterState::Blocks { next_block: 3 });
+        let second_updated = manager.update(id, second.generation, FilterState::Blocks { next_block: 2 });
+
+        // Then
+        assert!(first_updated && !second_updated);
+        let polled = manager.poll(id, now).unwrap();
+        assert_eq!(PolledFilter { state: FilterState::Blocks { next_block: 3 }, generation: 1 }, polled);
+        assert!(manager.uninstall(id) && !manager.update(id, polled.generation, FilterState::Blocks { next_block: 4 }));
+    }
+
+    #[tokio::test]
+    async fn test_filter_manager_caps_the_filters_of_each_client() {
+        // Given
+        let config = FilterConfig { idle_timeout: Duration::from_secs(60), max_filters: 4, max_filters_per_client: 2 };
+        let manager = Arc::new(FilterManager::new(config));
+        let now = Instant::now();
+        let (greedy, other): (IpAddr, IpAddr) = ("203.0.113.7".parse().unwrap(), "203.0.113.8".parse().unwrap());
+        let install = |client| manager.install(FilterState::Blocks { next_block: 1 }, client, now);
+        let first = install(Some(greedy)).unwrap();
+        install(Some(greedy)).unwrap();
+
+        // When
+        let rejected = install(Some(greedy));
+        let others = [install(Some(other)), install(None)];
+        manager.uninstall(first);
+        let reinstalled = install(Some(greedy));
+
+        // Then
+        assert_eq!(Err(TooManyFilters), rejected);
+        assert!(others.iter().all(Result::is_ok));
+        assert!(reinstalled.is_ok());
+        assert_eq!((4, 1), (manager.stats().installed, manager.stats().rejected));
+    }
+}
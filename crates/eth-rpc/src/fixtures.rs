@@ -0,0 +1,132 @@
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use eyre::{eyre, Result};
+use kakarot_rpc_core::client::api::KakarotEthApi;
+use reth_rpc_types::BlockTransactions;
+use serde::Serialize;
+use starknet::core::types::{BlockId as StarknetBlockId, FieldElement};
+use starknet::providers::Provider;
+use url::Url;
+
+use crate::export::parse_range;
+
+/// Arguments of `kakarot-rpc fixtures dump`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixturesArgs {
+    /// Starknet JSON-RPC provider the fixtures are fetched from, `STARKNET_NETWORK` if `None`.
+    pub url: Option<Url>,
+    /// Dumped blocks, `A..B` excluding block `B`.
+    pub range: Range<u64>,
+    /// Directory the fixtures are written to.
+    pub output: PathBuf,
+}
+
+impl FixturesArgs {
+    /// Parses `[--url <url>] --range <A..B> [--output <dir>]`.
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let mut url = None;
+        let mut range = None;
+        let mut output = PathBuf::from("fixtures");
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| eyre!("Missing value of {arg}"));
+            match arg.as_str() {
+                "--url" => url = Some(Url::parse(value()?)?),
+                "--range" => range = Some(parse_range(value()?)?),
+                "--output" => output = value()?.into(),
+                _ => return Err(eyre!("Unexpected fixtures argument: {arg}")),
+            }
+        }
+
+        let range = range.ok_or_else(|| eyre!("Missing --range argument, e.g. --range 100..101"))?;
+        Ok(Self { url, range, output })
+    }
+}
+
+/// Number of fixtures written per kind.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FixturesSummary {
+    pub blocks: u64,
+    pub receipts: u64,
+}
+
+fn write_fixture<T: Serialize>(path: &Path, fixture: &T) -> Result<()> {
+    let fixture = serde_json::to_string_pretty(fixture)?;
+    std::fs::write(path, fixture + "\n").map_err(|err| eyre!("Failed to write {path:?}: {err}"))
+}
+
+/// Dumps the Starknet blocks of the range and their receipts, along with their conversion by the
+/// client, as JSON fixtures: `starknet/<name>.json` and `eth/<name>.json`, the layout of the
+/// conversion test data of the core crate.
+pub async fn dump_fixtures<P: Provider + Send + Sync + 'static>(
+    kakarot_client: &dyn KakarotEthApi<P>,
+    args: &FixturesArgs,
+) -> Result<FixturesSummary> {
+    let (starknet_dir, eth_dir) = (args.output.join("starknet"), args.output.join("eth"));
+    std::fs::create_dir_all(&starknet_dir)?;
+    std::fs::create_dir_all(&eth_dir)?;
+    let mut summary = FixturesSummary::default();
+
+    for block_number in args.range.clone() {
+        let block_id = StarknetBlockId::Number(block_number);
+        let starknet_block = kakarot_client
+            .starknet_provider()
+            .get_block_with_txs(block_id)
+            .await
+            .map_err(|err| eyre!("Failed to fetch Starknet block {block_number}: {err}"))?;
+        let block = kakarot_client
+            .get_eth_block_from_starknet_block(block_id, true)
+            .await
+            .map_err(|err| eyre!("Failed to convert block {block_number}: {err}"))?;
+        let name = format!("block_{block_number}_with_txs.json");
+        write_fixture(&starknet_dir.join(&name), &starknet_block)?;
+        write_fixture(&eth_dir.join(&name), &block)?;
+        summary.blocks += 1;
+
+        // Only the Kakarot transactions are converted, their hash is the Starknet one
+        let BlockTransactions::Full(transactions) = &block.inner.transactions else { continue };
+        for transaction in transactions {
+            let hash = FieldElement::from_bytes_be(&transaction.hash.0)
+                .map_err(|_| eyre!("Invalid transaction hash {:#x}", transaction.hash))?;
+            let starknet_receipt = kakarot_client
+                .starknet_provider()
+                .get_transaction_receipt(hash)
+                .await
+                .map_err(|err| eyre!("Failed to fetch the Starknet receipt of {hash:#x}: {err}"))?;
+            let receipt = kakarot_client
+                .transaction_receipt(transaction.hash)
+                .await
+                .map_err(|err| eyre!("Failed to convert the receipt of {hash:#x}: {err}"))?;
+            let name = format!("receipt_{hash:#x}.json");
+            write_fixture(&starknet_dir.join(&name), &starknet_receipt)?;
+            write_fixture(&eth_dir.join(&name), &receipt)?;
+            summary.receipts += 1;
+        }
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &str) -> Vec<String> {
+        args.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_fixtures_args_parse() {
+        // When
+        let parsed = FixturesArgs::parse(&args("--url http://localhost:5050 --range 10..12 --output /tmp/f")).unwrap();
+
+        // Then
+        let url = Some(Url::parse("http://localhost:5050").unwrap());
+        assert_eq!(FixturesArgs { url, range: 10..12, output: "/tmp/f".into() }, parsed);
+        assert_eq!(PathBuf::from("fixtures"), FixturesArgs::parse(&args("--range 0..1")).unwrap().output);
+        assert!(FixturesArgs::parse(&args("--url not-a-url --range 0..1")).is_err());
+        assert!(FixturesArgs::parse(&args("--output /tmp/f")).is_err());
+    }
+}
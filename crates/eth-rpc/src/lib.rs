@@ -2,17 +2,38 @@
 // //! It is an adapter layer to interact with Kakarot ZK-EVM.
 use std::net::{AddrParseError, SocketAddr};
 
-use config::RPCConfig;
+use config::{RPCConfig, TlsConfig};
+pub mod abi_registry;
 pub mod api;
+pub mod backfill;
+pub mod client_address;
 pub mod config;
+pub mod export;
+pub mod filters;
+pub mod fixtures;
+pub mod logging;
+pub mod params;
+pub mod retry_after;
 pub mod rpc;
+pub mod server;
 pub mod servers;
+pub mod stats;
+pub mod subscription;
+pub mod tls;
+pub mod webhook;
 
+use client_address::ClientAddressLayer;
 use eyre::Result;
+use jsonrpsee::server::middleware::proxy_get_request::ProxyGetRequestLayer;
 use jsonrpsee::server::{ServerBuilder, ServerHandle};
 use jsonrpsee::RpcModule;
+use params::{ParamsLayer, ParamsMode};
+use retry_after::RetryAfterLayer;
 use thiserror::Error;
+use tls::{serve_tls, TlsError};
 use tower::ServiceBuilder;
+use tower_http::compression::predicate::SizeAbove;
+use tower_http::compression::CompressionLayer;
 use tower_http::cors::{Any, CorsLayer};
 
 #[derive(Error, Debug)]
@@ -21,26 +42,67 @@ pub enum RpcError {
     JsonRpcServerError(#[from] jsonrpsee::core::Error),
     #[error(transparent)]
     ParseError(#[from] AddrParseError),
+    #[error(transparent)]
+    TlsError(#[from] TlsError),
 }
 
 /// # Errors
 ///
-/// Will return `Err` if an error occurs when running the `ServerBuilder` start fails.
+/// Will return `Err` if an error occurs when running the `ServerBuilder` start fails, or if the
+/// TLS certificate cannot be read.
 pub async fn run_server(
     kakarot_rpc_module: RpcModule<()>,
     rpc_config: RPCConfig,
 ) -> Result<(SocketAddr, ServerHandle), RpcError> {
-    let RPCConfig { socket_addr } = rpc_config;
+    let RPCConfig { socket_addr, compression_min_bytes, params_mode, tls_config, .. } = rpc_config;
+
+    start_server(kakarot_rpc_module, socket_addr.parse::<SocketAddr>()?, compression_min_bytes, params_mode, tls_config)
+        .await
+}
 
+/// Starts serving an RPC module on the given address, behind the CORS, compression, health check,
+/// retry hint, parameters and client address middlewares. Responses of at least
+/// `compression_min_bytes` bytes are compressed with gzip or brotli when the client accepts it.
+///
+/// With TLS, the RPC server listens on a local port and TLS is terminated on the given address,
+/// the decrypted connections being forwarded to the RPC server.
+pub(crate) async fn start_server(
+    kakarot_rpc_module: RpcModule<()>,
+    socket_addr: SocketAddr,
+    compression_min_bytes: u16,
+    params_mode: ParamsMode,
+    tls_config: Option<TlsConfig>,
+) -> Result<(SocketAddr, ServerHandle), RpcError> {
     let cors = CorsLayer::new().allow_methods(Any).allow_origin(Any).allow_headers(Any);
 
-    let service = ServiceBuilder::new().layer(cors);
+    // Full blocks and logs compress well, small responses are not worth the CPU
+    let compression = CompressionLayer::new().compress_when(SizeAbove::new(compression_min_bytes));
+
+    // Serves `system_health` as `GET /health` for load balancers
+    let health = ProxyGetRequestLayer::new("/health", "system_health")?;
+
+    // Checks or coerces the parameters of the HTTP requests before they are parsed
+    let params = ParamsLayer::new(params_mode);
+
+    // Answers the requests shed by the request lanes with a 503 status and a `Retry-After` header,
+    // and exposes the address of the client to the methods, e.g. to cap its filters
+    let service = ServiceBuilder::new()
+        .layer(cors)
+        .layer(compression)
+        .layer(health)
+        .layer(RetryAfterLayer)
+        .layer(params)
+        .layer(ClientAddressLayer);
 
-    let server = ServerBuilder::default().set_middleware(service).build(socket_addr.parse::<SocketAddr>()?).await?;
+    let server_addr = if tls_config.is_some() { SocketAddr::from(([127, 0, 0, 1], 0)) } else { socket_addr };
+    let server = ServerBuilder::default().set_middleware(service).build(server_addr).await?;
 
     let addr = server.local_addr()?;
 
     let handle = server.start(kakarot_rpc_module)?;
 
-    Ok((addr, handle))
+    match tls_config {
+        Some(tls_config) => Ok((serve_tls(tls_config, socket_addr, addr).await?, handle)),
+        None => Ok((addr, handle)),
+    }
 }
@@ -0,0 +1,188 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use eyre::{eyre, Result};
+use thiserror::Error;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Layer, Registry};
+
+/// Default level filter, when `RUST_LOG` is not set.
+pub const DEFAULT_LOG_FILTER: &str = "info";
+
+/// Handle changing the level filter of the installed subscriber, set by [`init`].
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Format of the log lines.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Single line with the span context, the `tracing_subscriber` default.
+    #[default]
+    Full,
+    /// Multi line, for local development.
+    Pretty,
+    /// Single line without the span context.
+    Compact,
+    /// One JSON object per line, for log aggregators.
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "full" => Ok(Self::Full),
+            "pretty" => Ok(Self::Pretty),
+            "compact" => Ok(Self::Compact),
+            "json" => Ok(Self::Json),
+            _ => Err(eyre!("Invalid log format: {s}, expected full, pretty, compact or json")),
+        }
+    }
+}
+
+/// How often the log file is rotated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogRotation {
+    Hourly,
+    #[default]
+    Daily,
+    Never,
+}
+
+impl FromStr for LogRotation {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "hourly" => Ok(Self::Hourly),
+            "daily" => Ok(Self::Daily),
+            "never" => Ok(Self::Never),
+            _ => Err(eyre!("Invalid log rotation: {s}, expected hourly, daily or never")),
+        }
+    }
+}
+
+impl From<LogRotation> for Rotation {
+    fn from(rotation: LogRotation) -> Self {
+        match rotation {
+            LogRotation::Hourly => Rotation::HOURLY,
+            LogRotation::Daily => Rotation::DAILY,
+            LogRotation::Never => Rotation::NEVER,
+        }
+    }
+}
+
+/// Configuration of the logs of the RPC.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoggingConfig {
+    pub format: LogFormat,
+    /// Level filter with per module directives, e.g. `info,kakarot_rpc_core=debug`.
+    pub filter: String,
+    /// File the logs are written to instead of stdout, suffixed with the date of the rotation.
+    pub file: Option<PathBuf>,
+    pub rotation: LogRotation,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            format: LogFormat::default(),
+            filter: DEFAULT_LOG_FILTER.into(),
+            file: None,
+            rotation: LogRotation::default(),
+        }
+    }
+}
+
+impl LoggingConfig {
+    /// Reads `KAKAROT_LOG_FORMAT`, `RUST_LOG`, `KAKAROT_LOG_FILE` and `KAKAROT_LOG_ROTATION`.
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self::default();
+        if let Ok(format) = std::env::var("KAKAROT_LOG_FORMAT") {
+            config.format = format.parse()?;
+        }
+        if let Ok(filter) = std::env::var("RUST_LOG") {
+            config.filter = filter;
+        }
+        config.file = std::env::var_os("KAKAROT_LOG_FILE").map(PathBuf::from);
+        if let Ok(rotation) = std::env::var("KAKAROT_LOG_ROTATION") {
+            config.rotation = rotation.parse()?;
+        }
+        Ok(config)
+    }
+}
+
+/// Installs the global subscriber of the logs. The returned guard flushes the log file when
+/// dropped and must be kept until the RPC exits.
+pub fn init(config: &LoggingConfig) -> Result<Option<WorkerGuard>> {
+    let (filter, handle) = reload::Layer::new(EnvFilter::try_new(&config.filter)?);
+
+    let (writer, guard) = match &config.file {
+        Some(path) => {
+            let file_name = path.file_name().ok_or_else(|| eyre!("Invalid log file: {}", path.display()))?;
+            let directory = path.parent().map(PathBuf::from).unwrap_or_default();
+            let appender = RollingFileAppender::new(config.rotation.into(), directory, file_name);
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            (BoxMakeWriter::new(writer), Some(guard))
+        }
+        None => (BoxMakeWriter::new(std::io::stdout), None),
+    };
+
+    // Colors are only meant for terminals
+    let layer = fmt::layer().with_writer(writer).with_ansi(config.file.is_none());
+    let layer = match config.format {
+        LogFormat::Full => layer.boxed(),
+        LogFormat::Pretty => layer.pretty().boxed(),
+        LogFormat::Compact => layer.compact().boxed(),
+        LogFormat::Json => layer.json().boxed(),
+    };
+
+    tracing_subscriber::registry().with(filter).with(layer).try_init()?;
+    // `try_init` fails if a subscriber is already installed, so the handle is set once
+    let _ = FILTER_HANDLE.set(handle);
+    Ok(guard)
+}
+
+/// Error changing the level filter at runtime.
+#[derive(Debug, Error)]
+pub enum LogFilterError {
+    #[error("the logs are not set up by the RPC")]
+    Uninitialized,
+    #[error("invalid log filter: {0}")]
+    InvalidFilter(#[from] tracing_subscriber::filter::ParseError),
+    #[error("failed to reload the log filter: {0}")]
+    Reload(#[from] reload::Error),
+}
+
+/// Returns the current level filter.
+pub fn log_filter() -> Result<String, LogFilterError> {
+    let handle = FILTER_HANDLE.get().ok_or(LogFilterError::Uninitialized)?;
+    Ok(handle.with_current(ToString::to_string)?)
+}
+
+/// Replaces the level filter, e.g. to debug a module of a running RPC without restarting it.
+pub fn set_log_filter(filter: &str) -> Result<(), LogFilterError> {
+    let handle = FILTER_HANDLE.get().ok_or(LogFilterError::Uninitialized)?;
+    handle.reload(EnvFilter::try_new(filter)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_logging_config_values() {
+        // Then
+        assert_eq!(LogFormat::Json, "json".parse().unwrap());
+        assert_eq!(LogRotation::Hourly, "hourly".parse().unwrap());
+        assert!("yaml".parse::<LogFormat>().is_err());
+        assert!("weekly".parse::<LogRotation>().is_err());
+        assert!(matches!(set_log_filter("debug"), Err(LogFilterError::Uninitialized)));
+    }
+}
@@ -1,20 +1,37 @@
 use std::sync::Arc;
 
 use dotenv::dotenv;
-use eyre::Result;
-use kakarot_rpc::config::RPCConfig;
+use eyre::{eyre, Result};
+use jsonrpsee::RpcModule;
+use kakarot_rpc::abi_registry::AbiRegistry;
+use kakarot_rpc::backfill::{backfill_contract_creations, BackfillArgs};
+use kakarot_rpc::config::{Profile, RPCConfig};
+use kakarot_rpc::export::{export, ExportArgs};
+use kakarot_rpc::fixtures::{dump_fixtures, FixturesArgs};
+use kakarot_rpc::logging::{self, LoggingConfig};
 use kakarot_rpc::rpc::KakarotRpcModuleBuilder;
 use kakarot_rpc::run_server;
+use kakarot_rpc_core::client::api::KakarotStarknetApi;
 use kakarot_rpc_core::client::config::{
     JsonRpcClientBuilder, Network, SequencerGatewayProviderBuilder, StarknetConfig,
 };
+use kakarot_rpc_core::client::manifest::DeploymentManifest;
+use kakarot_rpc_core::client::schema::{self, CURRENT_SCHEMA_VERSION};
+use kakarot_rpc_core::client::store::StoreConfig;
+use kakarot_rpc_core::client::upstream::UpstreamTransport;
 use kakarot_rpc_core::client::KakarotClient;
-use starknet::providers::jsonrpc::HttpTransport;
-use starknet::providers::{JsonRpcClient, SequencerGatewayProvider};
-use tracing_subscriber::util::SubscriberInitExt;
+use kakarot_rpc_core::models::compat::ResponseCompat;
+use starknet::providers::{JsonRpcClient, Provider, SequencerGatewayProvider};
+
+/// Command run instead of serving the RPC.
+enum Command {
+    Export(ExportArgs),
+    DumpFixtures(FixturesArgs),
+    BackfillCreations(BackfillArgs),
+}
 
 enum StarknetProvider {
-    JsonRpcClient(JsonRpcClient<HttpTransport>),
+    JsonRpcClient(JsonRpcClient<UpstreamTransport>),
     SequencerGatewayProvider(SequencerGatewayProvider),
 }
 
@@ -23,39 +40,143 @@ async fn main() -> Result<()> {
     dotenv().ok();
     // Environment variables are safe to use after this
 
-    let filter = tracing_subscriber::EnvFilter::try_from_default_env()?;
-    tracing_subscriber::FmtSubscriber::builder().with_env_filter(filter).finish().try_init()?;
+    // Flushes the log file on exit
+    let _log_guard = logging::init(&LoggingConfig::from_env()?)?;
 
-    let starknet_config = StarknetConfig::from_env()?;
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
 
-    let rpc_config = RPCConfig::from_env()?;
+    // `--profile <profile>` presets the configuration, before it is read from the environment
+    if let Some(position) = args.iter().position(|arg| arg == "--profile") {
+        let profile: Profile = args.get(position + 1).ok_or_else(|| eyre!("Missing value of --profile"))?.parse()?;
+        profile.apply();
+        args.drain(position..=position + 1);
+    }
 
-    let starknet_provider: StarknetProvider = match &starknet_config.network {
-        Network::Madara | Network::Katana | Network::Sharingan => {
-            StarknetProvider::JsonRpcClient(JsonRpcClientBuilder::with_http(&starknet_config).unwrap().build())
+    // `--compat <client>` selects the compatibility preset, overriding KAKAROT_RESPONSE_COMPAT
+    if let Some(position) = args.iter().position(|arg| arg == "--compat") {
+        let client = args.get(position + 1).ok_or_else(|| eyre!("Missing value of --compat"))?;
+        if ResponseCompat::preset(client).is_none() {
+            return Err(eyre!("Invalid compatibility preset: {client}, expected geth, erigon or hardhat"));
+        }
+        std::env::set_var("KAKAROT_RESPONSE_COMPAT", client);
+        args.drain(position..=position + 1);
+    }
+
+    // `kakarot-rpc index status` and `kakarot-rpc index verify` only read the store
+    if let [index, command] = args.as_slice() {
+        if index == "index" && (command == "status" || command == "verify") {
+            return inspect_store(command == "verify");
+        }
+    }
+
+    // `kakarot-rpc index export ...` exports blocks, `kakarot-rpc index backfill-creations ...`
+    // indexes past contract creations and `kakarot-rpc fixtures dump ...` dumps test fixtures
+    // instead of serving the RPC
+    let command = match args.as_slice() {
+        [] => None,
+        [index, command, export_args @ ..] if index == "index" && command == "export" => {
+            Some(Command::Export(ExportArgs::parse(export_args)?))
+        }
+        [index, command, backfill_args @ ..] if index == "index" && command == "backfill-creations" => {
+            Some(Command::BackfillCreations(BackfillArgs::parse(backfill_args)?))
+        }
+        [fixtures, command, fixtures_args @ ..] if fixtures == "fixtures" && command == "dump" => {
+            let fixtures_args = FixturesArgs::parse(fixtures_args)?;
+            if let Some(url) = &fixtures_args.url {
+                std::env::set_var("STARKNET_NETWORK", url.as_str());
+            }
+            Some(Command::DumpFixtures(fixtures_args))
         }
-        Network::JsonRpcProvider(url) => {
-            StarknetProvider::JsonRpcClient(JsonRpcClientBuilder::new(HttpTransport::new(url.clone())).build())
+        _ => {
+            return Err(eyre!(
+                "Unknown command: {}, expected `index export`, `index backfill-creations` or `fixtures dump`",
+                args.join(" ")
+            ));
+        }
+    };
+
+    // Without KAKAROT_ADDRESS, the addresses are discovered from the manifest of the deployment
+    let manifest = DeploymentManifest::discover().await?;
+    let mut starknet_config = StarknetConfig::from_env_with_manifest(manifest.as_ref())?;
+
+    let starknet_provider: StarknetProvider = match &starknet_config.network {
+        Network::Madara | Network::Katana | Network::Sharingan | Network::JsonRpcProvider(_) => {
+            let transport = UpstreamTransport::from_config(&starknet_config)?;
+            starknet_config.upstream_scores = Some(transport.scores());
+            starknet_config.coalescing_counters = transport.coalescing_counters();
+            StarknetProvider::JsonRpcClient(JsonRpcClientBuilder::new(transport).build())
         }
         _ => StarknetProvider::SequencerGatewayProvider(
             SequencerGatewayProviderBuilder::new(&starknet_config.network).build(),
         ),
     };
 
+    if let Some(Command::DumpFixtures(fixtures_args)) = command {
+        let summary = match starknet_provider {
+            StarknetProvider::JsonRpcClient(starknet_provider) => {
+                dump_fixtures(&kakarot_client(starknet_config, starknet_provider)?, &fixtures_args).await
+            }
+            StarknetProvider::SequencerGatewayProvider(starknet_provider) => {
+                dump_fixtures(&kakarot_client(starknet_config, starknet_provider)?, &fixtures_args).await
+            }
+        }?;
+        println!(
+            "Dumped {} blocks and {} receipts to {}",
+            summary.blocks,
+            summary.receipts,
+            fixtures_args.output.display()
+        );
+        return Ok(());
+    }
+
+    if let Some(Command::BackfillCreations(backfill_args)) = command {
+        let indexed = match starknet_provider {
+            StarknetProvider::JsonRpcClient(starknet_provider) => {
+                backfill_contract_creations(&kakarot_client(starknet_config, starknet_provider)?, &backfill_args).await
+            }
+            StarknetProvider::SequencerGatewayProvider(starknet_provider) => {
+                backfill_contract_creations(&kakarot_client(starknet_config, starknet_provider)?, &backfill_args).await
+            }
+        }?;
+        println!("Indexed {indexed} contract creations");
+        return Ok(());
+    }
+
+    if let Some(Command::Export(export_args)) = command {
+        let summary = match starknet_provider {
+            StarknetProvider::JsonRpcClient(starknet_provider) => {
+                export(&kakarot_client(starknet_config, starknet_provider)?, &export_args).await
+            }
+            StarknetProvider::SequencerGatewayProvider(starknet_provider) => {
+                export(&kakarot_client(starknet_config, starknet_provider)?, &export_args).await
+            }
+        }?;
+        println!(
+            "Exported {} blocks, {} transactions, {} receipts and {} logs to {}",
+            summary.blocks,
+            summary.transactions,
+            summary.receipts,
+            summary.logs,
+            export_args.output.display()
+        );
+        return Ok(());
+    }
+
+    let rpc_config = RPCConfig::from_env()?;
+
     let kakarot_rpc_module = match starknet_provider {
         StarknetProvider::JsonRpcClient(starknet_provider) => {
-            let kakarot_client = Arc::new(KakarotClient::new(starknet_config, starknet_provider));
-            KakarotRpcModuleBuilder::new(kakarot_client).rpc_module()
+            build_rpc_module(starknet_config, starknet_provider, &rpc_config).await
         }
         StarknetProvider::SequencerGatewayProvider(starknet_provider) => {
-            let kakarot_client = Arc::new(KakarotClient::new(starknet_config, starknet_provider));
-            KakarotRpcModuleBuilder::new(kakarot_client).rpc_module()
+            build_rpc_module(starknet_config, starknet_provider, &rpc_config).await
         }
     }?;
 
+    let scheme = if rpc_config.tls_config.is_some() { "https" } else { "http" };
     let (server_addr, server_handle) = run_server(kakarot_rpc_module, rpc_config).await?;
 
-    let url = format!("http://{server_addr}");
+    let url = format!("{scheme}://{server_addr}");
 
     println!("RPC Server running on {url}...");
 
@@ -63,3 +184,80 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// Prints the schema version and size of the indexes of the store set by `KAKAROT_STORE`, and
+/// with `verify` the entries which cannot be read by this release. The store is not migrated.
+fn inspect_store(verify: bool) -> Result<()> {
+    let store_config: StoreConfig = std::env::var("KAKAROT_STORE").unwrap_or_else(|_| "memory".into()).parse()?;
+    let Some(store) = store_config.open_unmigrated()? else {
+        println!("The indexes are kept in memory, set KAKAROT_STORE to persist them");
+        return Ok(());
+    };
+
+    let status = schema::status(store.as_ref())?;
+    println!("Schema version: {} (this release: {CURRENT_SCHEMA_VERSION})", status.schema_version);
+    for migration in &status.pending_migrations {
+        println!("Pending migration: {migration}");
+    }
+    for (namespace, entries) in &status.entries {
+        println!("{namespace}: {entries} entries");
+    }
+
+    if verify {
+        let report = schema::verify(store.as_ref())?;
+        for invalid in &report.invalid {
+            println!("Invalid entry: {invalid}");
+        }
+        println!("Checked {} entries, {} invalid", report.checked, report.invalid.len());
+        if !report.invalid.is_empty() {
+            return Err(eyre!("{} invalid entries in the store", report.invalid.len()));
+        }
+    }
+    Ok(())
+}
+
+/// Builds the Kakarot client from the configuration read from the environment.
+fn kakarot_client<P: Provider + Send + Sync + 'static>(
+    starknet_config: StarknetConfig,
+    starknet_provider: P,
+) -> Result<KakarotClient<P>> {
+    Ok(KakarotClient::builder().with_starknet_config(starknet_config).with_provider(starknet_provider).build()?)
+}
+
+/// Builds the Kakarot client and its RPC module, after checking that the configured Kakarot
+/// deployment can be used.
+async fn build_rpc_module<P: Provider + Send + Sync + 'static>(
+    starknet_config: StarknetConfig,
+    starknet_provider: P,
+    rpc_config: &RPCConfig,
+) -> Result<RpcModule<()>> {
+    let kakarot_client = Arc::new(kakarot_client(starknet_config, starknet_provider)?);
+
+    let report = kakarot_client.validate_deployment().await.map_err(|err| eyre!(err.to_string()))?;
+    if !report.is_valid() {
+        return Err(eyre!("Kakarot deployment self-check failed:\n{report}"));
+    }
+    tracing::info!("Kakarot deployment self-check passed");
+
+    match kakarot_client.spec_version().await {
+        Some(spec_version) => tracing::info!("Starknet provider implements JSON-RPC spec {spec_version}"),
+        None => tracing::info!("Starknet JSON-RPC spec version unknown"),
+    }
+
+    let abi_registry = AbiRegistry::new(rpc_config.sourcify_url.clone());
+    if let Some(abi_dir) = &rpc_config.abi_dir {
+        abi_registry.load_dir(abi_dir)?;
+    }
+
+    let rpc_module_builder = KakarotRpcModuleBuilder::new(
+        kakarot_client,
+        rpc_config.subscription_config.clone(),
+        Arc::new(abi_registry),
+        rpc_config.response_compat,
+    )
+    .without_modules(&rpc_config.disabled_modules)
+    .without_methods(rpc_config.disabled_methods.clone());
+    rpc_module_builder.dump_stats_on_signal()?;
+
+    Ok(rpc_module_builder.rpc_module()?)
+}
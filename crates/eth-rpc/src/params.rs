@@ -0,0 +1,463 @@
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::task::{Context, Poll};
+
+use eyre::eyre;
+use hyper::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use hyper::{Body, Method, Request, Response};
+use jsonrpsee::types::error::INVALID_PARAMS_CODE;
+use serde_json::{json, Value};
+use tower::{Layer, Service};
+
+/// How the parameters of the requests are checked, selected with `KAKAROT_PARAMS_MODE`.
+///
+/// Some SDKs send quirky parameters, e.g. decimal block numbers or extra arguments: the default
+/// mode parses them as the types of the methods do, ignoring the extra arguments and the unknown
+/// fields, the strict mode rejects them like Geth and the lenient mode coerces them where the
+/// meaning is unambiguous.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParamsMode {
+    #[default]
+    Default,
+    /// Rejects the extra arguments, the unknown fields of the transaction and filter objects and
+    /// the values of the wrong type, with the messages of Geth.
+    Strict,
+    /// Coerces the numbers sent as quantities or block numbers into hex strings, the block tags
+    /// into lowercase and the booleans sent as strings into booleans.
+    Lenient,
+}
+
+impl FromStr for ParamsMode {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        match s {
+            "default" => Ok(Self::Default),
+            "strict" => Ok(Self::Strict),
+            "lenient" => Ok(Self::Lenient),
+            _ => Err(eyre!("Invalid params mode: {s}, expected default, strict or lenient")),
+        }
+    }
+}
+
+/// Kind of a positional parameter, which determines how it is checked or coerced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Param {
+    Address,
+    Hash,
+    Data,
+    Quantity,
+    Bool,
+    /// A block number or tag.
+    BlockNumber,
+    /// A block number, tag or EIP-1898 object.
+    BlockId,
+    /// A call or transaction object.
+    Transaction,
+    /// A log filter object.
+    Filter,
+    /// A parameter only counted, e.g. the typed data of `eth_signTypedData`.
+    Any,
+}
+
+use Param::*;
+
+/// Positional parameters of the methods checked. The methods of the other namespaces, e.g.
+/// `debug_` or `kakarot_`, are passed as they are.
+const METHODS: &[(&str, &[Param])] = &[
+    ("eth_blockNumber", &[]),
+    ("eth_syncing", &[]),
+    ("eth_coinbase", &[]),
+    ("eth_accounts", &[]),
+    ("eth_chainId", &[]),
+    ("eth_getBlockByHash", &[Hash, Bool]),
+    ("eth_getBlockByNumber", &[BlockNumber, Bool]),
+    ("eth_getBlockTransactionCountByHash", &[Hash]),
+    ("eth_getBlockTransactionCountByNumber", &[BlockNumber]),
+    ("eth_getUncleCountByBlockHash", &[Hash]),
+    ("eth_getUncleCountByBlockNumber", &[BlockNumber]),
+    ("eth_getUncleByBlockHashAndIndex", &[Hash, Quantity]),
+    ("eth_getUncleByBlockNumberAndIndex", &[BlockNumber, Quantity]),
+    ("eth_getTransactionByHash", &[Hash]),
+    ("eth_getTransactionBySenderAndNonce", &[Address, Quantity]),
+    ("eth_getTransactionByBlockHashAndIndex", &[Hash, Quantity]),
+    ("eth_getTransactionByBlockNumberAndIndex", &[BlockNumber, Quantity]),
+    ("eth_getTransactionReceipt", &[Hash]),
+    ("eth_getBalance", &[Address, BlockId]),
+    ("eth_getStorageAt", &[Address, Quantity, BlockId]),
+    ("eth_getTransactionCount", &[Address, BlockId]),
+    ("eth_getCode", &[Address, BlockId]),
+    ("eth_call", &[Transaction, BlockId]),
+    ("eth_createAccessList", &[Transaction, BlockId]),
+    ("eth_estimateGas", &[Transaction, BlockId]),
+    ("eth_gasPrice", &[]),
+    ("eth_feeHistory", &[Quantity, BlockNumber, Any]),
+    ("eth_maxPriorityFeePerGas", &[]),
+    ("eth_mining", &[]),
+    ("eth_hashrate", &[]),
+    ("eth_getWork", &[]),
+    ("eth_submitHashrate", &[Quantity, Hash]),
+    ("eth_submitWork", &[Data, Hash, Hash]),
+    ("eth_sendTransaction", &[Transaction]),
+    ("eth_sendRawTransaction", &[Data]),
+    ("eth_sendPrivateRawTransaction", &[Data]),
+    ("eth_sign", &[Address, Data]),
+    ("eth_signTransaction", &[Transaction]),
+    ("eth_signTypedData", &[Address, Any]),
+    ("eth_getProof", &[Address, Any, BlockId]),
+    ("eth_getLogs", &[Filter]),
+    ("eth_newFilter", &[Filter]),
+    ("eth_newBlockFilter", &[]),
+    ("eth_newPendingTransactionFilter", &[]),
+    ("eth_getFilterChanges", &[Quantity]),
+    ("eth_getFilterLogs", &[Quantity]),
+    ("eth_uninstallFilter", &[Quantity]),
+    ("net_version", &[]),
+    ("net_peerCount", &[]),
+    ("net_listening", &[]),
+    ("web3_clientVersion", &[]),
+    ("web3_sha3", &[Data]),
+];
+
+const BLOCK_TAGS: &[&str] = &["latest", "earliest", "pending", "safe", "finalized"];
+
+/// Fields of the call and transaction objects, as in Geth's `TransactionArgs`.
+const TRANSACTION_FIELDS: &[(&str, Param)] = &[
+    ("from", Address),
+    ("to", Address),
+    ("gas", Quantity),
+    ("gasPrice", Quantity),
+    ("maxFeePerGas", Quantity),
+    ("maxPriorityFeePerGas", Quantity),
+    ("value", Quantity),
+    ("nonce", Quantity),
+    ("data", Data),
+    ("input", Data),
+    ("accessList", Any),
+    ("chainId", Quantity),
+    ("type", Quantity),
+];
+
+const FILTER_FIELDS: &[(&str, Param)] =
+    &[("fromBlock", BlockNumber), ("toBlock", BlockNumber), ("blockHash", Hash), ("address", Any), ("topics", Any)];
+
+const BLOCK_ID_FIELDS: &[(&str, Param)] =
+    &[("blockHash", Hash), ("blockNumber", BlockNumber), ("requireCanonical", Bool)];
+
+fn is_hex(value: &str, len: Option<usize>) -> bool {
+    value.strip_prefix("0x").map_or(false, |digits| {
+        digits.bytes().all(|b| b.is_ascii_hexdigit()) && len.map_or(true, |len| digits.len() == len)
+    })
+}
+
+/// Checks a value in strict mode, returns the reason it is rejected.
+fn check(param: Param, value: &Value) -> Result<(), String> {
+    let expect_string = |kind: &str| value.as_str().ok_or_else(|| format!("cannot unmarshal non-string into {kind}"));
+    match param {
+        Address if !is_hex(expect_string("address")?, Some(40)) => Err("invalid address".into()),
+        Hash if !is_hex(expect_string("hash")?, Some(64)) => Err("invalid hash".into()),
+        Data => {
+            let data = expect_string("hex bytes")?;
+            match data.strip_prefix("0x") {
+                None => Err("hex string without 0x prefix".into()),
+                Some(digits) if digits.len() % 2 == 1 => Err("hex string of odd length".into()),
+                Some(_) if !is_hex(data, None) => Err("invalid hex string".into()),
+                Some(_) => Ok(()),
+            }
+        }
+        Quantity => {
+            let quantity = expect_string("quantity")?;
+            match quantity.strip_prefix("0x") {
+                None => Err("hex string without 0x prefix".into()),
+                Some("") => Err("hex string \"0x\"".into()),
+                Some(digits) if digits.len() > 1 && digits.starts_with('0') => {
+                    Err("hex number with leading zero digits".into())
+                }
+                Some(_) if !is_hex(quantity, None) => Err("invalid hex string".into()),
+                Some(_) => Ok(()),
+            }
+        }
+        Bool if !value.is_boolean() => Err("cannot unmarshal non-bool into bool".into()),
+        BlockNumber => match value.as_str() {
+            Some(tag) if BLOCK_TAGS.contains(&tag) => Ok(()),
+            Some(_) => check(Quantity, value),
+            None => Err("cannot unmarshal non-string into block number".into()),
+        },
+        BlockId if value.is_object() => check_object(value, BLOCK_ID_FIELDS),
+        BlockId => check(BlockNumber, value),
+        Transaction | Filter if !value.is_object() => Err("cannot unmarshal non-object into object".into()),
+        Transaction => check_object(value, TRANSACTION_FIELDS),
+        Filter => check_object(value, FILTER_FIELDS),
+        _ => Ok(()),
+    }
+}
+
+fn check_object(value: &Value, fields: &[(&str, Param)]) -> Result<(), String> {
+    let Some(object) = value.as_object() else { return Ok(()) };
+    for (field, value) in object {
+        let Some((_, param)) = fields.iter().find(|(name, _)| name == field) else {
+            return Err(format!("json: unknown field \"{field}\""));
+        };
+        if !value.is_null() {
+            check(*param, value).map_err(|reason| format!("{field}: {reason}"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Coerces a value in lenient mode, where the meaning is unambiguous.
+fn coerce(param: Param, value: &mut Value) {
+    match (param, &*value) {
+        (Quantity | BlockNumber | BlockId, Value::Number(number)) => {
+            if let Some(number) = number.as_u64() {
+                *value = Value::String(format!("{number:#x}"));
+            }
+        }
+        (BlockNumber | BlockId, Value::String(tag)) if BLOCK_TAGS.contains(&tag.to_lowercase().as_str()) => {
+            *value = Value::String(tag.to_lowercase());
+        }
+        (Bool, Value::String(boolean)) if boolean == "true" || boolean == "false" => {
+            *value = Value::Bool(boolean == "true");
+        }
+        (BlockId, Value::Object(_)) => coerce_object(value, BLOCK_ID_FIELDS),
+        (Transaction, Value::Object(_)) => coerce_object(value, TRANSACTION_FIELDS),
+        (Filter, Value::Object(_)) => coerce_object(value, FILTER_FIELDS),
+        _ => {}
+    }
+}
+
+fn coerce_object(value: &mut Value, fields: &[(&str, Param)]) {
+    let Some(object) = value.as_object_mut() else { return };
+    for (field, param) in fields {
+        if let Some(value) = object.get_mut(*field) {
+            coerce(*param, value);
+        }
+    }
+}
+
+/// Checks or coerces the parameters of a call to `method`, according to the mode. Returns the
+/// error message of Geth if the parameters are rejected.
+pub fn normalize_params(mode: ParamsMode, method: &str, params: &mut Value) -> Result<(), String> {
+    let Some((_, expected)) = METHODS.iter().find(|(name, _)| *name == method) else { return Ok(()) };
+    let Some(params) = params.as_array_mut() else { return Ok(()) };
+    match mode {
+        ParamsMode::Default => Ok(()),
+        ParamsMode::Strict => {
+            if params.len() > expected.len() {
+                return Err(format!("too many arguments, want at most {}", expected.len()));
+            }
+            for (index, (param, value)) in expected.iter().zip(params.iter()).enumerate() {
+                // Optional parameters may be sent as null
+                if !value.is_null() {
+                    check(*param, value).map_err(|reason| format!("invalid argument {index}: {reason}"))?;
+                }
+            }
+            Ok(())
+        }
+        ParamsMode::Lenient => {
+            for (param, value) in expected.iter().zip(params.iter_mut()) {
+                coerce(*param, value);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Normalizes a call of a request, returns its error response if it is rejected.
+fn normalize_call(mode: ParamsMode, call: &mut Value) -> Option<Value> {
+    let object = call.as_object_mut()?;
+    let method = object.get("method").and_then(Value::as_str)?.to_string();
+    let params = object.get_mut("params")?;
+    let message = normalize_params(mode, &method, params).err()?;
+    let id = object.get("id").cloned().unwrap_or(Value::Null);
+    Some(json!({ "jsonrpc": "2.0", "id": id, "error": { "code": INVALID_PARAMS_CODE, "message": message } }))
+}
+
+/// Normalizes the calls of a single or batch request. The rejected calls are removed from the
+/// request, a rejected single request becoming `null`, and their error responses returned.
+fn normalize_request(mode: ParamsMode, request: &mut Value) -> Vec<Value> {
+    match request {
+        Value::Array(calls) => {
+            let mut rejected = Vec::new();
+            calls.retain_mut(|call| match normalize_call(mode, call) {
+                Some(error) => {
+                    rejected.push(error);
+                    false
+                }
+                None => true,
+            });
+            rejected
+        }
+        _ => match normalize_call(mode, request) {
+            Some(error) => {
+                *request = Value::Null;
+                vec![error]
+            }
+            None => Vec::new(),
+        },
+    }
+}
+
+fn json_response(body: &Value) -> Response<Body> {
+    let body = serde_json::to_vec(body).expect("JSON values serialize");
+    let mut response = Response::new(Body::from(body));
+    response.headers_mut().insert(CONTENT_TYPE, "application/json".parse().expect("valid header value"));
+    response
+}
+
+/// Middleware checking or coercing the parameters of the HTTP requests according to the
+/// [`ParamsMode`]. The websocket requests are not upgraded through it and are parsed as in the
+/// default mode.
+#[derive(Debug, Clone, Copy)]
+pub struct ParamsLayer {
+    mode: ParamsMode,
+}
+
+impl ParamsLayer {
+    pub fn new(mode: ParamsMode) -> Self {
+        Self { mode }
+    }
+}
+
+impl<S> Layer<S> for ParamsLayer {
+    type Service = ParamsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ParamsService { inner, mode: self.mode }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParamsService<S> {
+    inner: S,
+    mode: ParamsMode,
+}
+
+pub(crate) type BoxError = Box<dyn Error + Send + Sync + 'static>;
+
+impl<S> Service<Request<Body>> for ParamsService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Error: Into<BoxError> + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + 'static>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // The service polled ready is the one called, its clone taking its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let mode = self.mode;
+
+        Box::pin(async move {
+            if mode == ParamsMode::Default || request.method() != Method::POST {
+                return inner.call(request).await.map_err(Into::into);
+            }
+
+            let (mut parts, body) = request.into_parts();
+            let bytes = hyper::body::to_bytes(body).await?;
+            // Malformed requests are answered by the server
+            let Ok(mut value) = serde_json::from_slice::<Value>(&bytes) else {
+                return inner.call(Request::from_parts(parts, Body::from(bytes))).await.map_err(Into::into);
+            };
+
+            let mut rejected = normalize_request(mode, &mut value);
+            match &value {
+                Value::Null if !rejected.is_empty() => return Ok(json_response(&rejected.remove(0))),
+                Value::Array(calls) if calls.is_empty() && !rejected.is_empty() => {
+                    return Ok(json_response(&Value::Array(rejected)));
+                }
+                _ => {}
+            }
+
+            let body = serde_json::to_vec(&value)?;
+            parts.headers.insert(CONTENT_LENGTH, body.len().into());
+            let response = inner.call(Request::from_parts(parts, Body::from(body))).await.map_err(Into::into)?;
+            if rejected.is_empty() {
+                return Ok(response);
+            }
+
+            // The error responses of the rejected calls join the responses of the batch
+            let (mut parts, body) = response.into_parts();
+            let bytes = hyper::body::to_bytes(body).await?;
+            let Ok(Value::Array(mut responses)) = serde_json::from_slice::<Value>(&bytes) else {
+                return Ok(Response::from_parts(parts, Body::from(bytes)));
+            };
+            responses.extend(rejected);
+            let body = serde_json::to_vec(&responses)?;
+            parts.headers.insert(CONTENT_LENGTH, body.len().into());
+            Ok(Response::from_parts(parts, Body::from(body)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_params() {
+        // Given
+        let address = "0x0000000000000000000000000000000000000001";
+        let strict = |method: &str, mut params: Value| normalize_params(ParamsMode::Strict, method, &mut params);
+        let lenient = |method: &str, mut params: Value| {
+            normalize_params(ParamsMode::Lenient, method, &mut params).map(|_| params)
+        };
+
+        // Then
+        assert_eq!(Ok(()), strict("eth_getBalance", json!([address, "latest"])));
+        assert_eq!(Ok(()), strict("eth_call", json!([{ "to": address, "value": "0x1" }, { "blockNumber": "0x10" }])));
+        assert_eq!(Ok(()), strict("kakarot_anything", json!([1, 2, 3])));
+        assert_eq!(
+            Err("too many arguments, want at most 2".to_string()),
+            strict("eth_getBalance", json!([address, "latest", true]))
+        );
+        assert_eq!(
+            Err("invalid argument 1: cannot unmarshal non-bool into bool".to_string()),
+            strict("eth_getBlockByNumber", json!(["latest", "true"]))
+        );
+        assert_eq!(
+            Err("invalid argument 0: cannot unmarshal non-string into block number".to_string()),
+            strict("eth_getBlockByNumber", json!([16, false]))
+        );
+        assert_eq!(
+            Err("invalid argument 0: json: unknown field \"gasLimit\"".to_string()),
+            strict("eth_estimateGas", json!([{ "to": address, "gasLimit": "0x1" }]))
+        );
+        assert_eq!(
+            Err("invalid argument 0: value: hex number with leading zero digits".to_string()),
+            strict("eth_call", json!([{ "to": address, "value": "0x01" }]))
+        );
+        assert_eq!(Ok(json!(["0x10", true])), lenient("eth_getBlockByNumber", json!([16, "true"])));
+        assert_eq!(Ok(json!([address, "latest"])), lenient("eth_getBalance", json!([address, "Latest"])));
+        assert_eq!(
+            Ok(json!([{ "fromBlock": "0x1", "toBlock": "latest" }])),
+            lenient("eth_getLogs", json!([{ "fromBlock": 1, "toBlock": "LATEST" }]))
+        );
+    }
+
+    #[test]
+    fn test_normalize_batch_request() {
+        // Given
+        let mut request = json!([
+            { "jsonrpc": "2.0", "id": 1, "method": "eth_blockNumber", "params": [] },
+            { "jsonrpc": "2.0", "id": 2, "method": "eth_blockNumber", "params": ["latest"] },
+        ]);
+
+        // When
+        let rejected = normalize_request(ParamsMode::Strict, &mut request);
+
+        // Then
+        assert_eq!(1, request.as_array().unwrap().len());
+        assert_eq!(json!(2), rejected[0]["id"]);
+        assert_eq!(json!(INVALID_PARAMS_CODE), rejected[0]["error"]["code"]);
+    }
+}
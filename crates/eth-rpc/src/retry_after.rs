@@ -0,0 +1,123 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use hyper::body::HttpBody;
+use hyper::header::RETRY_AFTER;
+use hyper::{Body, Request, Response, StatusCode};
+use kakarot_rpc_core::client::errors::EthRpcErrorCode;
+use serde_json::Value;
+use tower::{Layer, Service};
+
+use crate::params::BoxError;
+
+/// Responses larger than this are never a shed request, and are passed without being read.
+const MAX_SHED_RESPONSE_BYTES: u64 = 1024;
+
+/// Returns the retry hint of a call shed because the server is busy, in seconds.
+fn retry_after(response: &Value) -> Option<u64> {
+    let error = response.get("error")?;
+    if error.get("code")?.as_i64()? != EthRpcErrorCode::LimitExceeded as i64 {
+        return None;
+    }
+    error.get("data")?.get("retryAfter")?.as_u64()
+}
+
+/// Returns the retry hint of a response whose calls were all shed, in seconds.
+fn shed_response_retry_after(response: &Value) -> Option<u64> {
+    match response {
+        Value::Array(responses) if !responses.is_empty() => {
+            responses.iter().map(retry_after).collect::<Option<Vec<_>>>()?.into_iter().max()
+        }
+        Value::Array(_) => None,
+        response => retry_after(response),
+    }
+}
+
+/// Middleware answering the HTTP requests shed by the request lanes with a `503 Service
+/// Unavailable` status and a `Retry-After` header, so that load balancers and HTTP clients back
+/// off without parsing the JSON-RPC error. Batches are only answered so if all their calls were
+/// shed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RetryAfterLayer;
+
+impl<S> Layer<S> for RetryAfterLayer {
+    type Service = RetryAfterService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RetryAfterService { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RetryAfterService<S> {
+    inner: S,
+}
+
+impl<S> Service<Request<Body>> for RetryAfterService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Error: Into<BoxError> + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + 'static>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // The service polled ready is the one called, its clone taking its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            let response = inner.call(request).await.map_err(Into::into)?;
+            let small = response.body().size_hint().exact().map_or(false, |size| size <= MAX_SHED_RESPONSE_BYTES);
+            if !small || response.status() != StatusCode::OK {
+                return Ok(response);
+            }
+
+            let (mut parts, body) = response.into_parts();
+            let bytes = hyper::body::to_bytes(body).await?;
+            let retry_after = serde_json::from_slice::<Value>(&bytes).ok().as_ref().and_then(shed_response_retry_after);
+            if let Some(retry_after) = retry_after {
+                parts.status = StatusCode::SERVICE_UNAVAILABLE;
+                parts.headers.insert(RETRY_AFTER, retry_after.into());
+            }
+            Ok(Response::from_parts(parts, Body::from(bytes)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_shed_response_retry_after() {
+        // Given
+        let shed = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "error": { "code": -32005, "message": "server busy", "data": { "retryAfter": 2 } }
+        });
+        let too_many_logs = json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "error": { "code": -32005, "message": "query returned more than 10000 logs" }
+        });
+        let result = json!({ "jsonrpc": "2.0", "id": 3, "result": "0x1" });
+
+        // Then
+        assert_eq!(Some(2), shed_response_retry_after(&shed));
+        assert_eq!(Some(2), shed_response_retry_after(&json!([shed.clone(), shed.clone()])));
+        assert_eq!(None, shed_response_retry_after(&json!([shed, result])));
+        assert_eq!(None, shed_response_retry_after(&too_many_logs));
+        assert_eq!(None, shed_response_retry_after(&json!([])));
+    }
+}
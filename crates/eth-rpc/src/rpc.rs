@@ -1,20 +1,43 @@
 use std::collections::HashMap;
 use std::marker::PhantomData;
-use std::sync::Arc;
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock};
 
 use jsonrpsee::core::Error;
 use jsonrpsee::{Methods, RpcModule};
 use kakarot_rpc_core::client::api::KakarotEthApi;
+use kakarot_rpc_core::models::compat::ResponseCompat;
 use starknet::providers::Provider;
 
+use crate::abi_registry::AbiRegistry;
+use crate::api::admin_api::AdminApiServer;
 use crate::api::alchemy_api::AlchemyApiServer;
+use crate::api::anvil_api::AnvilApiServer;
+use crate::api::erigon_api::ErigonApiServer;
 use crate::api::eth_api::EthApiServer;
+use crate::api::evm_api::EvmApiServer;
+use crate::api::health_api::HealthApiServer;
+use crate::api::kakarot_api::KakarotApiServer;
 use crate::api::net_api::NetApiServer;
+use crate::api::ots_api::OtsApiServer;
+use crate::api::pubsub_api::{EthPubSubApiServer, KakarotPubSubApiServer};
 use crate::api::web3_api::Web3ApiServer;
+use crate::config::SubscriptionConfig;
+use crate::filters::FilterManager;
+use crate::servers::admin_rpc::AdminRpc;
 use crate::servers::alchemy_rpc::AlchemyRpc;
+use crate::servers::anvil_rpc::AnvilRpc;
+use crate::servers::erigon_rpc::ErigonRpc;
 use crate::servers::eth_rpc::KakarotEthRpc;
+use crate::servers::evm_rpc::EvmRpc;
+use crate::servers::health_rpc::HealthRpc;
+use crate::servers::kakarot_rpc::KakarotRpc;
 use crate::servers::net_rpc::NetRpc;
+use crate::servers::ots_rpc::OtsRpc;
+use crate::servers::pubsub_rpc::KakarotPubSubRpc;
 use crate::servers::web3_rpc::Web3Rpc;
+use crate::stats::dump_stats_on_signal;
+use crate::subscription::SubscriptionHub;
 
 /// Represents RPC modules that are supported by reth
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
@@ -23,17 +46,76 @@ pub enum KakarotRpcModule {
     Alchemy,
     Web3,
     Net,
+    Admin,
+    Kakarot,
+    PubSub,
+    Health,
+    Evm,
+    Anvil,
+    Ots,
+    Erigon,
+}
+
+impl FromStr for KakarotRpcModule {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "eth" => Ok(Self::Eth),
+            "alchemy" => Ok(Self::Alchemy),
+            "web3" => Ok(Self::Web3),
+            "net" => Ok(Self::Net),
+            "admin" => Ok(Self::Admin),
+            "kakarot" => Ok(Self::Kakarot),
+            "pubsub" => Ok(Self::PubSub),
+            "health" => Ok(Self::Health),
+            "evm" => Ok(Self::Evm),
+            "anvil" => Ok(Self::Anvil),
+            "ots" => Ok(Self::Ots),
+            "erigon" => Ok(Self::Erigon),
+            _ => Err(eyre::eyre!("Invalid RPC module: {s}")),
+        }
+    }
 }
 
 pub struct KakarotRpcModuleBuilder<P: Provider + Send + Sync + 'static> {
     modules: HashMap<KakarotRpcModule, Methods>,
+    disabled_methods: Vec<String>,
+    /// Namespaces of the methods served, reported by `kakarot_getChainConfig`.
+    namespaces: Arc<OnceLock<Vec<String>>>,
+    /// Sources of the statistics dumped on `SIGUSR1`.
+    stats_sources: (Arc<dyn KakarotEthApi<P>>, Arc<SubscriptionHub>, Arc<FilterManager>),
     _phantom: PhantomData<P>,
 }
 
 impl<P: Provider + Send + Sync + 'static> KakarotRpcModuleBuilder<P> {
-    pub fn new(kakarot_client: Arc<dyn KakarotEthApi<P>>) -> Self {
-        let eth_rpc_module = KakarotEthRpc::new(kakarot_client.clone()).into_rpc();
-        let alchemy_rpc_module = AlchemyRpc::new(kakarot_client).into_rpc();
+    pub fn new(
+        kakarot_client: Arc<dyn KakarotEthApi<P>>,
+        subscription_config: SubscriptionConfig,
+        abi_registry: Arc<AbiRegistry>,
+        response_compat: ResponseCompat,
+    ) -> Self {
+        let filter_manager = Arc::new(FilterManager::new(subscription_config.filters));
+        let subscription_hub = Arc::new(SubscriptionHub::new(subscription_config));
+
+        let eth_rpc_module =
+            KakarotEthRpc::new(kakarot_client.clone(), response_compat, filter_manager.clone()).into_rpc();
+        let alchemy_rpc_module = AlchemyRpc::new(kakarot_client.clone()).into_rpc();
+        let stats_sources = (kakarot_client.clone(), subscription_hub.clone(), filter_manager.clone());
+        let admin_rpc_module =
+            AdminRpc::new(kakarot_client.clone(), subscription_hub.clone(), filter_manager).into_rpc();
+        let pubsub_rpc = KakarotPubSubRpc::new(kakarot_client.clone(), subscription_hub);
+        let mut pubsub_rpc_module = EthPubSubApiServer::into_rpc(pubsub_rpc.clone());
+        pubsub_rpc_module
+            .merge(KakarotPubSubApiServer::into_rpc(pubsub_rpc))
+            .expect("the Ethereum and Kakarot subscriptions have distinct names");
+        let health_rpc_module = HealthRpc::new(kakarot_client.clone()).into_rpc();
+        let evm_rpc_module = EvmRpc::new(kakarot_client.clone()).into_rpc();
+        let anvil_rpc_module = AnvilRpc::new(kakarot_client.clone()).into_rpc();
+        let ots_rpc_module = OtsRpc::new(kakarot_client.clone()).into_rpc();
+        let erigon_rpc_module = ErigonRpc::new(kakarot_client.clone(), response_compat).into_rpc();
+        let namespaces = Arc::new(OnceLock::new());
+        let kakarot_rpc_module = KakarotRpc::new(kakarot_client, abi_registry, namespaces.clone()).into_rpc();
         let web3_rpc_module = Web3Rpc::default().into_rpc();
         let net_rpc_module = NetRpc::default().into_rpc();
 
@@ -43,8 +125,42 @@ impl<P: Provider + Send + Sync + 'static> KakarotRpcModuleBuilder<P> {
         modules.insert(KakarotRpcModule::Alchemy, alchemy_rpc_module.into());
         modules.insert(KakarotRpcModule::Web3, web3_rpc_module.into());
         modules.insert(KakarotRpcModule::Net, net_rpc_module.into());
+        modules.insert(KakarotRpcModule::Admin, admin_rpc_module.into());
+        modules.insert(KakarotRpcModule::Kakarot, kakarot_rpc_module.into());
+        modules.insert(KakarotRpcModule::PubSub, pubsub_rpc_module.into());
+        modules.insert(KakarotRpcModule::Health, health_rpc_module.into());
+        modules.insert(KakarotRpcModule::Evm, evm_rpc_module.into());
+        modules.insert(KakarotRpcModule::Anvil, anvil_rpc_module.into());
+        modules.insert(KakarotRpcModule::Ots, ots_rpc_module.into());
+        modules.insert(KakarotRpcModule::Erigon, erigon_rpc_module.into());
+
+        Self { modules, disabled_methods: Vec::new(), namespaces, stats_sources, _phantom: PhantomData }
+    }
 
-        Self { modules, _phantom: PhantomData }
+    /// Logs the statistics returned by `admin_stats` each time the process receives `SIGUSR1`.
+    /// Left to the caller, so that embedding processes keep their own signal handlers.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the signal handler cannot be installed.
+    pub fn dump_stats_on_signal(&self) -> std::io::Result<()> {
+        let (kakarot_client, subscription_hub, filter_manager) = &self.stats_sources;
+        dump_stats_on_signal(kakarot_client.clone(), subscription_hub.clone(), filter_manager.clone())
+    }
+
+    /// Removes the given modules from the RPC module.
+    pub fn without_modules(mut self, modules: &[KakarotRpcModule]) -> Self {
+        for module in modules {
+            self.modules.remove(module);
+        }
+        self
+    }
+
+    /// Removes the given methods, e.g. `eth_sendRawTransaction`, from the RPC module. Calls to
+    /// removed methods fail with a method not found error.
+    pub fn without_methods(mut self, methods: Vec<String>) -> Self {
+        self.disabled_methods = methods;
+        self
     }
 
     pub fn rpc_module(&self) -> Result<RpcModule<()>, Error> {
@@ -54,6 +170,23 @@ impl<P: Provider + Send + Sync + 'static> KakarotRpcModuleBuilder<P> {
             rpc_module.merge(methods)?;
         }
 
+        let disabled_methods: Vec<&'static str> = rpc_module
+            .method_names()
+            .filter(|name| self.disabled_methods.iter().any(|method| method.as_str() == *name))
+            .collect();
+        for method in disabled_methods {
+            rpc_module.remove_method(method);
+        }
+
+        let mut namespaces: Vec<String> = rpc_module
+            .method_names()
+            .filter_map(|name| name.split_once('_').map(|(namespace, _)| namespace.to_string()))
+            .collect();
+        namespaces.sort();
+        namespaces.dedup();
+        // The served methods are the same for every module built, the first ones are kept
+        let _ = self.namespaces.set(namespaces);
+
         Ok(rpc_module)
     }
 }
@@ -0,0 +1,185 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use jsonrpsee::server::ServerHandle;
+use kakarot_rpc_core::client::api::KakarotEthApi;
+use kakarot_rpc_core::models::compat::ResponseCompat;
+use starknet::providers::Provider;
+
+use crate::abi_registry::AbiRegistry;
+use crate::config::{RPCConfig, SubscriptionConfig, TlsConfig, DEFAULT_COMPRESSION_MIN_BYTES};
+use crate::params::ParamsMode;
+use crate::rpc::{KakarotRpcModule, KakarotRpcModuleBuilder};
+use crate::{start_server, RpcError};
+
+/// A Kakarot RPC server embedded in another process, e.g. a devnet binary.
+///
+/// Example :
+/// ```ignore
+///   let server = KakarotRpcServer::builder().with_client(Arc::new(kakarot_client)).serve(addr).await?;
+///   println!("RPC Server running on http://{}", server.local_addr());
+///   server.stopped().await;
+/// ```
+#[derive(Debug, Clone)]
+pub struct KakarotRpcServer {
+    local_addr: SocketAddr,
+    handle: ServerHandle,
+}
+
+impl KakarotRpcServer {
+    /// Returns a builder of the server, configured as the standalone binary without environment
+    /// variables.
+    pub fn builder() -> KakarotRpcServerBuilder {
+        KakarotRpcServerBuilder::default()
+    }
+
+    /// Returns the address the server listens on, with the port assigned by the system when
+    /// serving on port 0.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Returns the handle of the server, used to stop it.
+    pub fn handle(&self) -> &ServerHandle {
+        &self.handle
+    }
+
+    /// Waits for the server to be stopped.
+    pub async fn stopped(self) {
+        self.handle.stopped().await
+    }
+}
+
+/// Builder of a [`KakarotRpcServer`]. The server is served once a Kakarot client is set with
+/// [`KakarotRpcServerBuilder::with_client`].
+pub struct KakarotRpcServerBuilder<C = ()> {
+    client: C,
+    subscription_config: SubscriptionConfig,
+    abi_registry: Arc<AbiRegistry>,
+    response_compat: ResponseCompat,
+    disabled_modules: Vec<KakarotRpcModule>,
+    disabled_methods: Vec<String>,
+    compression_min_bytes: u16,
+    params_mode: ParamsMode,
+    tls_config: Option<TlsConfig>,
+}
+
+impl Default for KakarotRpcServerBuilder {
+    fn default() -> Self {
+        Self {
+            client: (),
+            subscription_config: SubscriptionConfig::default(),
+            abi_registry: Arc::new(AbiRegistry::new(None)),
+            response_compat: ResponseCompat::default(),
+            disabled_modules: Vec::new(),
+            disabled_methods: Vec::new(),
+            compression_min_bytes: DEFAULT_COMPRESSION_MIN_BYTES,
+            params_mode: ParamsMode::default(),
+            tls_config: None,
+        }
+    }
+}
+
+impl<C> KakarotRpcServerBuilder<C> {
+    /// Sets the Kakarot client serving the requests.
+    pub fn with_client<P: Provider + Send + Sync + 'static>(
+        self,
+        client: Arc<dyn KakarotEthApi<P>>,
+    ) -> KakarotRpcServerBuilder<Arc<dyn KakarotEthApi<P>>> {
+        KakarotRpcServerBuilder {
+            client,
+            subscription_config: self.subscription_config,
+            abi_registry: self.abi_registry,
+            response_compat: self.response_compat,
+            disabled_modules: self.disabled_modules,
+            disabled_methods: self.disabled_methods,
+            compression_min_bytes: self.compression_min_bytes,
+            params_mode: self.params_mode,
+            tls_config: self.tls_config,
+        }
+    }
+
+    /// Applies the RPC configuration of the standalone binary. The socket address of the
+    /// configuration is ignored, the server listening on the address passed to `serve`.
+    pub fn with_rpc_config(mut self, rpc_config: &RPCConfig) -> Self {
+        self.subscription_config = rpc_config.subscription_config.clone();
+        self.response_compat = rpc_config.response_compat;
+        self.disabled_modules = rpc_config.disabled_modules.clone();
+        self.disabled_methods = rpc_config.disabled_methods.clone();
+        self.compression_min_bytes = rpc_config.compression_min_bytes;
+        self.params_mode = rpc_config.params_mode;
+        self.tls_config = rpc_config.tls_config.clone();
+        self
+    }
+
+    pub fn with_subscription_config(mut self, subscription_config: SubscriptionConfig) -> Self {
+        self.subscription_config = subscription_config;
+        self
+    }
+
+    /// Sets the registry of the contract ABIs used to decode logs.
+    pub fn with_abi_registry(mut self, abi_registry: Arc<AbiRegistry>) -> Self {
+        self.abi_registry = abi_registry;
+        self
+    }
+
+    pub fn with_response_compat(mut self, response_compat: ResponseCompat) -> Self {
+        self.response_compat = response_compat;
+        self
+    }
+
+    /// Sets the minimum size in bytes of the HTTP responses compressed, when the client accepts
+    /// gzip or brotli.
+    pub fn with_compression_min_bytes(mut self, compression_min_bytes: u16) -> Self {
+        self.compression_min_bytes = compression_min_bytes;
+        self
+    }
+
+    /// Sets how the parameters of the HTTP requests are checked.
+    pub fn with_params_mode(mut self, params_mode: ParamsMode) -> Self {
+        self.params_mode = params_mode;
+        self
+    }
+
+    /// Terminates TLS on the address passed to `serve`, with HTTP/2 negotiated through ALPN.
+    pub fn with_tls_config(mut self, tls_config: TlsConfig) -> Self {
+        self.tls_config = Some(tls_config);
+        self
+    }
+
+    /// Removes the given modules from the server.
+    pub fn without_modules(mut self, modules: &[KakarotRpcModule]) -> Self {
+        self.disabled_modules.extend_from_slice(modules);
+        self
+    }
+
+    /// Removes the given methods, e.g. `eth_sendRawTransaction`, from the server.
+    pub fn without_methods(mut self, methods: Vec<String>) -> Self {
+        self.disabled_methods.extend(methods);
+        self
+    }
+}
+
+impl<P: Provider + Send + Sync + 'static> KakarotRpcServerBuilder<Arc<dyn KakarotEthApi<P>>> {
+    /// Starts serving the RPC on the given address, with the same middlewares as the standalone
+    /// binary. The deployment self-check of the binary is left to the caller.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the RPC module cannot be built or the server cannot be started.
+    pub async fn serve(self, addr: SocketAddr) -> Result<KakarotRpcServer, RpcError> {
+        let rpc_module = KakarotRpcModuleBuilder::new(
+            self.client,
+            self.subscription_config,
+            self.abi_registry,
+            self.response_compat,
+        )
+        .without_modules(&self.disabled_modules)
+        .without_methods(self.disabled_methods)
+        .rpc_module()?;
+
+        let (local_addr, handle) =
+            start_server(rpc_module, addr, self.compression_min_bytes, self.params_mode, self.tls_config).await?;
+        Ok(KakarotRpcServer { local_addr, handle })
+    }
+}
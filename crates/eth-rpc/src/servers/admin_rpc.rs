@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use jsonrpsee::core::{async_trait, RpcResult as Result};
+use jsonrpsee::types::error::{INTERNAL_ERROR_CODE, INVALID_PARAMS_CODE};
+use kakarot_rpc_core::client::api::KakarotEthApi;
+use kakarot_rpc_core::client::audit::{AuditEntry, MAX_AUDIT_ENTRIES};
+use kakarot_rpc_core::client::breaker::BreakerStats;
+use kakarot_rpc_core::client::calls::CallCacheStats;
+use kakarot_rpc_core::client::coalesce::CoalescingStats;
+use kakarot_rpc_core::client::errors::rpc_err;
+use kakarot_rpc_core::client::lanes::LaneStats;
+use kakarot_rpc_core::client::upstream::UpstreamStats;
+use kakarot_rpc_core::models::deployment::DeploymentReport;
+use starknet::providers::Provider;
+
+use crate::api::admin_api::AdminApiServer;
+use crate::filters::{FilterManager, FilterStats};
+use crate::logging::{log_filter, set_log_filter, LogFilterError};
+use crate::stats::RpcStats;
+use crate::subscription::{SubscriptionHub, SubscriptionStats};
+
+/// The RPC module for the operator facing admin api.
+pub struct AdminRpc<P: Provider + Send + Sync> {
+    pub kakarot_client: Arc<dyn KakarotEthApi<P>>,
+    pub subscription_hub: Arc<SubscriptionHub>,
+    pub filter_manager: Arc<FilterManager>,
+}
+
+impl<P: Provider + Send + Sync> AdminRpc<P> {
+    #[must_use]
+    pub fn new(
+        kakarot_client: Arc<dyn KakarotEthApi<P>>,
+        subscription_hub: Arc<SubscriptionHub>,
+        filter_manager: Arc<FilterManager>,
+    ) -> Self {
+        Self { kakarot_client, subscription_hub, filter_manager }
+    }
+}
+
+#[async_trait]
+impl<P: Provider + Send + Sync + 'static> AdminApiServer for AdminRpc<P> {
+    async fn validate_deployment(&self) -> Result<DeploymentReport> {
+        let report = self.kakarot_client.validate_deployment().await?;
+        Ok(report)
+    }
+
+    async fn subscription_stats(&self) -> Result<SubscriptionStats> {
+        Ok(self.subscription_hub.stats())
+    }
+
+    async fn filter_stats(&self) -> Result<FilterStats> {
+        Ok(self.filter_manager.stats())
+    }
+
+    async fn lane_stats(&self) -> Result<LaneStats> {
+        Ok(self.kakarot_client.lane_stats())
+    }
+
+    async fn upstream_stats(&self) -> Result<Vec<UpstreamStats>> {
+        Ok(self.kakarot_client.upstream_stats())
+    }
+
+    async fn coalescing_stats(&self) -> Result<CoalescingStats> {
+        Ok(self.kakarot_client.coalescing_stats())
+    }
+
+    async fn breaker_stats(&self) -> Result<BreakerStats> {
+        Ok(self.kakarot_client.breaker_stats())
+    }
+
+    async fn call_cache_stats(&self) -> Result<CallCacheStats> {
+        Ok(self.kakarot_client.call_cache_stats())
+    }
+
+    async fn stats(&self) -> Result<RpcStats> {
+        let stats = RpcStats::collect(self.kakarot_client.as_ref(), &self.subscription_hub, &self.filter_manager);
+        stats.log();
+        Ok(stats)
+    }
+
+    async fn audit_log(&self, from_sequence: Option<u64>, limit: Option<usize>) -> Result<Option<Vec<AuditEntry>>> {
+        let limit = limit.map_or(MAX_AUDIT_ENTRIES, |limit| limit.min(MAX_AUDIT_ENTRIES));
+        Ok(self.kakarot_client.audit_log(from_sequence.unwrap_or_default(), limit)?)
+    }
+
+    async fn log_level(&self) -> Result<String> {
+        log_filter().map_err(|err| rpc_err(INTERNAL_ERROR_CODE, err.to_string()))
+    }
+
+    async fn set_log_level(&self, filter: String) -> Result<String> {
+        set_log_filter(&filter).map_err(|err| match err {
+            LogFilterError::InvalidFilter(_) => rpc_err(INVALID_PARAMS_CODE, err.to_string()),
+            _ => rpc_err(INTERNAL_ERROR_CODE, err.to_string()),
+        })?;
+        tracing::info!("Log filter set to {filter}");
+        self.log_level().await
+    }
+}
@@ -2,8 +2,8 @@ use std::sync::Arc;
 
 use jsonrpsee::core::{async_trait, RpcResult as Result};
 use kakarot_rpc_core::client::api::KakarotEthApi;
+use kakarot_rpc_core::models::address::AddressParam;
 use kakarot_rpc_core::models::balance::TokenBalances;
-use reth_primitives::Address;
 use starknet::providers::Provider;
 
 use crate::api::alchemy_api::AlchemyApiServer;
@@ -22,8 +22,15 @@ impl<P: Provider + Send + Sync> AlchemyRpc<P> {
 
 #[async_trait]
 impl<P: Provider + Send + Sync + 'static> AlchemyApiServer for AlchemyRpc<P> {
-    async fn token_balances(&self, address: Address, contract_addresses: Vec<Address>) -> Result<TokenBalances> {
-        let token_balances = self.kakarot_client.token_balances(address, contract_addresses).await?;
+    async fn token_balances(
+        &self,
+        address: AddressParam,
+        contract_addresses: Vec<AddressParam>,
+    ) -> Result<TokenBalances> {
+        let token_balances = self
+            .kakarot_client
+            .token_balances(*address, contract_addresses.into_iter().map(Into::into).collect())
+            .await?;
         Ok(token_balances)
     }
 }
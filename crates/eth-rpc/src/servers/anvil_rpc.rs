@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use jsonrpsee::core::{async_trait, RpcResult as Result};
+use kakarot_rpc_core::client::api::KakarotEthApi;
+use kakarot_rpc_core::models::address::AddressParam;
+use reth_primitives::{Bytes, H256, U256};
+use starknet::providers::Provider;
+
+use crate::api::anvil_api::AnvilApiServer;
+
+/// The RPC module for the state manipulation methods of Anvil.
+pub struct AnvilRpc<P: Provider + Send + Sync> {
+    pub kakarot_client: Arc<dyn KakarotEthApi<P>>,
+}
+
+impl<P: Provider + Send + Sync> AnvilRpc<P> {
+    #[must_use]
+    pub fn new(kakarot_client: Arc<dyn KakarotEthApi<P>>) -> Self {
+        Self { kakarot_client }
+    }
+}
+
+#[async_trait]
+impl<P: Provider + Send + Sync + 'static> AnvilApiServer for AnvilRpc<P> {
+    async fn set_balance(&self, address: AddressParam, balance: U256) -> Result<()> {
+        self.kakarot_client.set_balance(*address, balance).await?;
+        Ok(())
+    }
+
+    async fn set_code(&self, address: AddressParam, code: Bytes) -> Result<()> {
+        self.kakarot_client.set_code(*address, code).await?;
+        Ok(())
+    }
+
+    async fn set_storage_at(&self, address: AddressParam, slot: U256, value: H256) -> Result<bool> {
+        self.kakarot_client.set_storage_at(*address, slot, U256::from_be_bytes(value.0)).await?;
+        Ok(true)
+    }
+
+    async fn impersonate_account(&self, address: AddressParam) -> Result<()> {
+        self.kakarot_client.impersonate_account(*address, true)?;
+        Ok(())
+    }
+
+    async fn stop_impersonating_account(&self, address: AddressParam) -> Result<()> {
+        self.kakarot_client.impersonate_account(*address, false)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use jsonrpsee::core::{async_trait, RpcResult as Result};
+use kakarot_rpc_core::client::api::KakarotEthApi;
+use kakarot_rpc_core::client::errors::EthApiError;
+use kakarot_rpc_core::models::block::EthBlockId;
+use kakarot_rpc_core::models::compat::{CompatResponse, ResponseCompat};
+use reth_primitives::{BlockId, BlockNumberOrTag, H256};
+use reth_rpc_types::{Header, TransactionReceipt};
+use starknet::core::types::BlockId as StarknetBlockId;
+use starknet::providers::Provider;
+
+use crate::api::erigon_api::ErigonApiServer;
+
+/// The RPC module for the Erigon helpers probed by block explorers, backed by the conversions of
+/// the `eth` methods.
+pub struct ErigonRpc<P: Provider + Send + Sync> {
+    pub kakarot_client: Arc<dyn KakarotEthApi<P>>,
+    pub response_compat: ResponseCompat,
+}
+
+impl<P: Provider + Send + Sync> ErigonRpc<P> {
+    #[must_use]
+    pub fn new(kakarot_client: Arc<dyn KakarotEthApi<P>>, response_compat: ResponseCompat) -> Self {
+        Self { kakarot_client, response_compat }
+    }
+}
+
+#[async_trait]
+impl<P: Provider + Send + Sync + 'static> ErigonApiServer for ErigonRpc<P> {
+    async fn header_by_number(&self, number: BlockNumberOrTag) -> Result<Option<CompatResponse<Header>>> {
+        let block_id = EthBlockId::new(BlockId::Number(number));
+        let starknet_block_id: StarknetBlockId = block_id.try_into().map_err(EthApiError::<P::Error>::from)?;
+        let block = self.kakarot_client.get_eth_block_from_starknet_block(starknet_block_id, false).await?;
+        Ok(Some(self.response_compat.wrap(block.inner.header)))
+    }
+
+    async fn block_receipts_by_block_hash(&self, hash: H256) -> Result<CompatResponse<Vec<TransactionReceipt>>> {
+        let receipts = self.kakarot_client.block_receipts(BlockId::Hash(hash.into())).await?;
+        Ok(self.response_compat.wrap(receipts))
+    }
+}
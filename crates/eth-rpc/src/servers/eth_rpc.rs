@@ -1,35 +1,134 @@
 use std::sync::Arc;
+use std::time::Instant;
 
 use jsonrpsee::core::{async_trait, RpcResult as Result};
-use jsonrpsee::types::error::{INTERNAL_ERROR_CODE, METHOD_NOT_FOUND_CODE};
+use jsonrpsee::types::error::{INTERNAL_ERROR_CODE, INVALID_PARAMS_CODE};
+use jsonrpsee::types::ErrorObject;
 use kakarot_rpc_core::client::api::KakarotEthApi;
 use kakarot_rpc_core::client::constants::CHAIN_ID;
-use kakarot_rpc_core::client::errors::{rpc_err, EthApiError};
+use kakarot_rpc_core::client::errors::{rpc_err, EthApiError, EthRpcErrorCode};
+use kakarot_rpc_core::models::address::AddressParam;
 use kakarot_rpc_core::models::block::EthBlockId;
+use kakarot_rpc_core::models::compat::{BoolParam, CompatResponse, ResponseCompat};
+use kakarot_rpc_core::models::filter::LogFilter;
 use reth_primitives::rpc::transaction::eip2930::AccessListWithGasUsed;
 use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, H256, H64, U128, U256, U64};
 use reth_rpc_types::{
-    CallRequest, EIP1186AccountProofResponse, FeeHistory, Index, RichBlock, SyncStatus,
+    BlockTransactions, CallRequest, EIP1186AccountProofResponse, FeeHistory, Index, Log, RichBlock,
     Transaction as EtherTransaction, TransactionReceipt, TransactionRequest, Work,
 };
-use serde_json::Value;
-use starknet::core::types::BlockId as StarknetBlockId;
-use starknet::providers::Provider;
+use serde_json::{json, Value};
+use starknet::core::types::{BlockId as StarknetBlockId, BlockTag, StarknetError};
+use starknet::providers::{Provider, ProviderError};
 
 use crate::api::eth_api::EthApiServer;
+use crate::client_address::client_address;
+use crate::filters::{FilterChanges, FilterManager, FilterState, PolledFilter};
+
+/// Maximum number of blocks a filter returns the changes of per poll, the following blocks being
+/// returned on the next polls.
+const MAX_FILTER_CHANGES_BLOCKS: u64 = 128;
 
 /// The RPC module for the Ethereum protocol required by Kakarot.
 pub struct KakarotEthRpc<P: Provider + Send + Sync> {
     pub kakarot_client: Arc<dyn KakarotEthApi<P>>,
+    pub response_compat: ResponseCompat,
+    pub filter_manager: Arc<FilterManager>,
 }
 
 impl<P: Provider + Send + Sync> KakarotEthRpc<P> {
     #[must_use]
-    pub fn new(kakarot_client: Arc<dyn KakarotEthApi<P>>) -> Self {
-        Self { kakarot_client }
+    pub fn new(
+        kakarot_client: Arc<dyn KakarotEthApi<P>>,
+        response_compat: ResponseCompat,
+        filter_manager: Arc<FilterManager>,
+    ) -> Self {
+        Self { kakarot_client, response_compat, filter_manager }
+    }
+
+    /// Resolves a boolean parameter, accepted as a string only with the `string_booleans` switch.
+    fn resolve_bool(&self, param: BoolParam) -> Result<bool> {
+        param.resolve(&self.response_compat).map_err(|err| rpc_err(INVALID_PARAMS_CODE, err))
+    }
+
+    fn install_filter(&self, state: FilterState) -> Result<U256> {
+        self.filter_manager
+            .install(state, client_address(), Instant::now())
+            .map_err(|err| rpc_err(EthRpcErrorCode::LimitExceeded as i32, err.to_string()))
+    }
+
+    /// Returns the hashes of the transactions of the pending block.
+    async fn pending_transaction_hashes(&self) -> Result<Vec<H256>> {
+        let block = self
+            .kakarot_client
+            .get_eth_block_from_starknet_block(StarknetBlockId::Tag(BlockTag::Pending), false)
+            .await?;
+        let BlockTransactions::Hashes(hashes) = block.inner.transactions else { return Ok(Vec::new()) };
+        Ok(hashes)
+    }
+
+    /// Returns the changes of a filter and its state once they are returned.
+    async fn filter_changes(&self, state: FilterState) -> Result<(FilterChanges, FilterState)> {
+        match state {
+            FilterState::Logs { filter, next_block } => {
+                self.kakarot_client.shed_if_overloaded()?;
+                let head = self.kakarot_client.block_number().await?.as_u64();
+                let to_block = match filter.to_block {
+                    Some(BlockNumberOrTag::Number(to_block)) => head.min(to_block),
+                    _ => head,
+                };
+                let to_block = to_block.min(next_block.saturating_add(MAX_FILTER_CHANGES_BLOCKS - 1));
+                if next_block > to_block {
+                    return Ok((FilterChanges::Logs(Vec::new()), FilterState::Logs { filter, next_block }));
+                }
+                let range_filter = LogFilter {
+                    from_block: Some(BlockNumberOrTag::Number(next_block)),
+                    to_block: Some(BlockNumberOrTag::Number(to_block)),
+                    ..filter.clone()
+                };
+                let logs = self.kakarot_client.get_logs(range_filter).await?;
+                Ok((FilterChanges::Logs(logs), FilterState::Logs { filter, next_block: to_block + 1 }))
+            }
+            FilterState::Blocks { next_block } => {
+                let head = self.kakarot_client.block_number().await?.as_u64();
+                let last_block = head.min(next_block.saturating_add(MAX_FILTER_CHANGES_BLOCKS - 1));
+                let mut hashes = Vec::new();
+                for number in next_block..=last_block {
+                    let block = self
+                        .kakarot_client
+                        .get_eth_block_from_starknet_block(StarknetBlockId::Number(number), false)
+                        .await?;
+                    hashes.extend(block.header.hash);
+                }
+                Ok((FilterChanges::Hashes(hashes), FilterState::Blocks { next_block: next_block.max(last_block + 1) }))
+            }
+            FilterState::PendingTransactions { seen } => {
+                // Transactions stay in the pending block until it is accepted, each one is returned
+                // once
+                let hashes = self.pending_transaction_hashes().await?;
+                let new_hashes = hashes.iter().filter(|hash| !seen.contains(hash)).copied().collect();
+                Ok((
+                    FilterChanges::Hashes(new_hashes),
+                    FilterState::PendingTransactions { seen: hashes.into_iter().collect() },
+                ))
+            }
+        }
     }
 }
 
+fn filter_not_found() -> ErrorObject<'static> {
+    rpc_err(EthRpcErrorCode::InvalidInput as i32, "filter not found")
+}
+
+/// Error of the proof-of-work methods, which some mining tooling still probes: Kakarot blocks are
+/// produced by the Starknet sequencer and are never mined.
+fn pow_unsupported(method: &str) -> ErrorObject<'static> {
+    rpc_err(
+        EthRpcErrorCode::MethodNotSupported as i32,
+        format!("{method} is not supported: Kakarot blocks are produced by the Starknet sequencer, not mined"),
+    )
+}
+
 #[async_trait]
 impl<P: Provider + Send + Sync + 'static> EthApiServer for KakarotEthRpc<P> {
     async fn block_number(&self) -> Result<U64> {
@@ -37,8 +136,26 @@ impl<P: Provider + Send + Sync + 'static> EthApiServer for KakarotEthRpc<P> {
         Ok(block_number)
     }
 
-    async fn syncing(&self) -> Result<SyncStatus> {
+    async fn syncing(&self) -> Result<Value> {
         let status = self.kakarot_client.syncing().await?;
+        let mut status = serde_json::to_value(status).map_err(|err| rpc_err(INTERNAL_ERROR_CODE, err.to_string()))?;
+
+        // A stale sequencer is reported as syncing, with a `stale` hint
+        if let Ok(liveness) = self.kakarot_client.liveness().await {
+            if liveness.stale {
+                if !status.is_object() {
+                    let latest_block_number = U256::from(liveness.latest_block_number);
+                    status = json!({
+                        "startingBlock": latest_block_number,
+                        "currentBlock": latest_block_number,
+                        "highestBlock": latest_block_number,
+                    });
+                }
+                status["stale"] = true.into();
+                status["blockAge"] = json!(U64::from(liveness.block_age));
+            }
+        }
+
         Ok(status)
     }
 
@@ -47,25 +164,37 @@ impl<P: Provider + Send + Sync + 'static> EthApiServer for KakarotEthRpc<P> {
     }
 
     async fn accounts(&self) -> Result<Vec<Address>> {
-        Ok(Vec::new())
+        Ok(self.kakarot_client.impersonated_accounts())
     }
 
     async fn chain_id(&self) -> Result<Option<U64>> {
         Ok(Some(CHAIN_ID.into()))
     }
 
-    async fn block_by_hash(&self, hash: H256, full: bool) -> Result<Option<RichBlock>> {
+    /// Returns the block with the given hash, the hash of the Starknet block, fetched directly by
+    /// hash. Unknown hashes return `null`, as on Ethereum.
+    async fn block_by_hash(&self, hash: H256, full: BoolParam) -> Result<Option<CompatResponse<RichBlock>>> {
+        let full = self.resolve_bool(full)?;
         let block_id = EthBlockId::new(BlockId::Hash(hash.into()));
-        let starknet_block_id: StarknetBlockId = block_id.try_into().map_err(EthApiError::<P::Error>::from)?;
-        let block = self.kakarot_client.get_eth_block_from_starknet_block(starknet_block_id, full).await?;
-        Ok(Some(block))
+        // A hash above the field prime cannot be the hash of a Starknet block
+        let Ok(starknet_block_id) = StarknetBlockId::try_from(block_id) else { return Ok(None) };
+        match self.kakarot_client.get_eth_block_from_starknet_block(starknet_block_id, full).await {
+            Ok(block) => Ok(Some(self.response_compat.wrap(block))),
+            Err(EthApiError::RequestError(ProviderError::StarknetError(StarknetError::BlockNotFound))) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
     }
 
-    async fn block_by_number(&self, number: BlockNumberOrTag, full: bool) -> Result<Option<RichBlock>> {
+    async fn block_by_number(
+        &self,
+        number: BlockNumberOrTag,
+        full: BoolParam,
+    ) -> Result<Option<CompatResponse<RichBlock>>> {
+        let full = self.resolve_bool(full)?;
         let block_id = EthBlockId::new(BlockId::Number(number));
         let starknet_block_id: StarknetBlockId = block_id.try_into().map_err(EthApiError::<P::Error>::from)?;
         let block = self.kakarot_client.get_eth_block_from_starknet_block(starknet_block_id, full).await?;
-        Ok(Some(block))
+        Ok(Some(self.response_compat.wrap(block)))
     }
 
     async fn block_transaction_count_by_hash(&self, hash: H256) -> Result<U64> {
@@ -98,55 +227,71 @@ impl<P: Provider + Send + Sync + 'static> EthApiServer for KakarotEthRpc<P> {
         todo!()
     }
 
-    async fn transaction_by_hash(&self, _hash: H256) -> Result<Option<EtherTransaction>> {
+    async fn transaction_by_hash(&self, _hash: H256) -> Result<Option<CompatResponse<EtherTransaction>>> {
         let ether_tx = self.kakarot_client.transaction_by_hash(_hash).await?;
-        Ok(ether_tx)
+        Ok(ether_tx.map(|tx| self.response_compat.wrap(tx)))
     }
 
-    async fn transaction_by_block_hash_and_index(&self, hash: H256, index: Index) -> Result<Option<EtherTransaction>> {
+    async fn transaction_by_sender_and_nonce(
+        &self,
+        sender: AddressParam,
+        nonce: U256,
+    ) -> Result<Option<CompatResponse<EtherTransaction>>> {
+        let Some(hash) = self.kakarot_client.transaction_by_sender_and_nonce(*sender, nonce).await? else {
+            return Ok(None);
+        };
+        let ether_tx = self.kakarot_client.transaction_by_hash(hash).await?;
+        Ok(ether_tx.map(|tx| self.response_compat.wrap(tx)))
+    }
+
+    async fn transaction_by_block_hash_and_index(
+        &self,
+        hash: H256,
+        index: Index,
+    ) -> Result<Option<CompatResponse<EtherTransaction>>> {
         let block_id = BlockId::Hash(hash.into());
         let tx = self.kakarot_client.transaction_by_block_id_and_index(block_id, index).await?;
-        Ok(Some(tx))
+        Ok(Some(self.response_compat.wrap(tx)))
     }
 
     async fn transaction_by_block_number_and_index(
         &self,
         number: BlockNumberOrTag,
         index: Index,
-    ) -> Result<Option<EtherTransaction>> {
+    ) -> Result<Option<CompatResponse<EtherTransaction>>> {
         let block_id = BlockId::Number(number);
         let tx = self.kakarot_client.transaction_by_block_id_and_index(block_id, index).await?;
-        Ok(Some(tx))
+        Ok(Some(self.response_compat.wrap(tx)))
     }
 
-    async fn transaction_receipt(&self, hash: H256) -> Result<Option<TransactionReceipt>> {
+    async fn transaction_receipt(&self, hash: H256) -> Result<Option<CompatResponse<TransactionReceipt>>> {
         let receipt = self.kakarot_client.transaction_receipt(hash).await?;
-        Ok(receipt)
+        Ok(receipt.map(|receipt| self.response_compat.wrap(receipt)))
     }
 
-    async fn balance(&self, address: Address, block_id: Option<BlockId>) -> Result<U256> {
+    async fn balance(&self, address: AddressParam, block_id: Option<BlockId>) -> Result<U256> {
         let block_id = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
-        let balance = self.kakarot_client.balance(address, block_id).await?;
+        let balance = self.kakarot_client.balance(*address, block_id).await?;
         Ok(balance)
     }
 
-    async fn storage_at(&self, address: Address, index: U256, block_id: Option<BlockId>) -> Result<U256> {
+    async fn storage_at(&self, address: AddressParam, index: U256, block_id: Option<BlockId>) -> Result<U256> {
         let block_id = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
-        let value = self.kakarot_client.storage_at(address, index, block_id).await?;
+        let value = self.kakarot_client.storage_at(*address, index, block_id).await?;
         Ok(value)
     }
 
-    async fn transaction_count(&self, address: Address, block_id: Option<BlockId>) -> Result<U256> {
+    async fn transaction_count(&self, address: AddressParam, block_id: Option<BlockId>) -> Result<U256> {
         let block_id = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
 
-        let transaction_count = self.kakarot_client.nonce(address, block_id).await?;
+        let transaction_count = self.kakarot_client.nonce(*address, block_id).await?;
 
         Ok(transaction_count)
     }
 
-    async fn get_code(&self, address: Address, block_id: Option<BlockId>) -> Result<Bytes> {
+    async fn get_code(&self, address: AddressParam, block_id: Option<BlockId>) -> Result<Bytes> {
         let block_id = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
-        let code = self.kakarot_client.get_code(address, block_id).await?;
+        let code = self.kakarot_client.get_code(*address, block_id).await?;
         Ok(code)
     }
 
@@ -181,8 +326,7 @@ impl<P: Provider + Send + Sync + 'static> EthApiServer for KakarotEthRpc<P> {
     }
 
     async fn gas_price(&self) -> Result<U256> {
-        let gas_price = self.kakarot_client.base_fee_per_gas();
-        Ok(gas_price)
+        Ok(self.kakarot_client.gas_price().await?)
     }
 
     async fn fee_history(
@@ -190,10 +334,10 @@ impl<P: Provider + Send + Sync + 'static> EthApiServer for KakarotEthRpc<P> {
         block_count: U256,
         newest_block: BlockNumberOrTag,
         reward_percentiles: Option<Vec<f64>>,
-    ) -> Result<FeeHistory> {
+    ) -> Result<CompatResponse<FeeHistory>> {
         let fee_history = self.kakarot_client.fee_history(block_count, newest_block, reward_percentiles).await?;
 
-        Ok(fee_history)
+        Ok(self.response_compat.wrap(fee_history))
     }
 
     async fn max_priority_fee_per_gas(&self) -> Result<U128> {
@@ -202,27 +346,41 @@ impl<P: Provider + Send + Sync + 'static> EthApiServer for KakarotEthRpc<P> {
     }
 
     async fn is_mining(&self) -> Result<bool> {
-        Err(rpc_err(METHOD_NOT_FOUND_CODE, "Unsupported method: eth_mining. See available methods at https://github.com/sayajin-labs/kakarot-rpc/blob/main/docs/rpc_api_status.md".to_string()))
+        Err(pow_unsupported("eth_mining"))
     }
 
     async fn hashrate(&self) -> Result<U256> {
-        Err(rpc_err(METHOD_NOT_FOUND_CODE, "Unsupported method: eth_hashrate. See available methods at https://github.com/sayajin-labs/kakarot-rpc/blob/main/docs/rpc_api_status.md".to_string()))
+        Err(pow_unsupported("eth_hashrate"))
     }
 
     async fn get_work(&self) -> Result<Work> {
-        Err(rpc_err(METHOD_NOT_FOUND_CODE, "Unsupported method: eth_getWork. See available methods at https://github.com/sayajin-labs/kakarot-rpc/blob/main/docs/rpc_api_status.md".to_string()))
+        Err(pow_unsupported("eth_getWork"))
     }
 
     async fn submit_hashrate(&self, _hashrate: U256, _id: H256) -> Result<bool> {
-        todo!()
+        Err(pow_unsupported("eth_submitHashrate"))
     }
 
     async fn submit_work(&self, _nonce: H64, _pow_hash: H256, _mix_digest: H256) -> Result<bool> {
-        todo!()
-    }
-
-    async fn send_transaction(&self, _request: TransactionRequest) -> Result<H256> {
-        todo!()
+        Err(pow_unsupported("eth_submitWork"))
+    }
+
+    async fn send_transaction(&self, request: TransactionRequest) -> Result<H256> {
+        // Only transactions of impersonated accounts can be sent without signature, in dev mode
+        let request = CallRequest {
+            from: request.from,
+            to: request.to,
+            gas: request.gas,
+            max_fee_per_gas: request.max_fee_per_gas.or(request.gas_price),
+            max_priority_fee_per_gas: request.max_priority_fee_per_gas,
+            value: request.value,
+            data: request.data,
+            nonce: request.nonce,
+            access_list: request.access_list,
+            ..Default::default()
+        };
+        let transaction_hash = self.kakarot_client.send_impersonated_transaction(request).await?;
+        Ok(transaction_hash)
     }
 
     async fn send_raw_transaction(&self, bytes: Bytes) -> Result<H256> {
@@ -230,7 +388,12 @@ impl<P: Provider + Send + Sync + 'static> EthApiServer for KakarotEthRpc<P> {
         Ok(transaction_hash)
     }
 
-    async fn sign(&self, _address: Address, _message: Bytes) -> Result<Bytes> {
+    async fn send_private_raw_transaction(&self, bytes: Bytes) -> Result<H256> {
+        let transaction_hash = self.kakarot_client.send_private_transaction(bytes).await?;
+        Ok(transaction_hash)
+    }
+
+    async fn sign(&self, _address: AddressParam, _message: Bytes) -> Result<Bytes> {
         todo!()
     }
 
@@ -238,16 +401,71 @@ impl<P: Provider + Send + Sync + 'static> EthApiServer for KakarotEthRpc<P> {
         todo!()
     }
 
-    async fn sign_typed_data(&self, _address: Address, _data: Value) -> Result<Bytes> {
+    async fn sign_typed_data(&self, _address: AddressParam, _data: Value) -> Result<Bytes> {
         todo!()
     }
 
     async fn get_proof(
         &self,
-        _address: Address,
+        _address: AddressParam,
         _keys: Vec<H256>,
         _block_id: Option<BlockId>,
     ) -> Result<EIP1186AccountProofResponse> {
         todo!()
     }
+
+    async fn get_logs(&self, filter: LogFilter) -> Result<CompatResponse<Vec<Log>>> {
+        // Shed while the Starknet provider is unhealthy, the poller of the subscriptions still
+        // fetching the logs of the new blocks
+        self.kakarot_client.shed_if_overloaded()?;
+        let logs = self.kakarot_client.get_logs(filter).await?;
+        Ok(self.response_compat.wrap(logs))
+    }
+
+    async fn new_filter(&self, filter: LogFilter) -> Result<U256> {
+        if filter.block_hash.is_some() {
+            return Err(rpc_err(
+                EthRpcErrorCode::InvalidInput as i32,
+                "blockHash is not supported by eth_newFilter, use eth_getLogs",
+            ));
+        }
+        // As Geth, the changes are the logs of the new blocks, the range of the filter only
+        // bounding them
+        let next_block = self.kakarot_client.block_number().await?.as_u64() + 1;
+        self.install_filter(FilterState::Logs { filter, next_block })
+    }
+
+    async fn new_block_filter(&self) -> Result<U256> {
+        let next_block = self.kakarot_client.block_number().await?.as_u64() + 1;
+        self.install_filter(FilterState::Blocks { next_block })
+    }
+
+    async fn new_pending_transaction_filter(&self) -> Result<U256> {
+        let seen = self.pending_transaction_hashes().await?.into_iter().collect();
+        self.install_filter(FilterState::PendingTransactions { seen })
+    }
+
+    async fn get_filter_changes(&self, id: U256) -> Result<FilterChanges> {
+        let polled = self.filter_manager.poll(id, Instant::now()).ok_or_else(filter_not_found)?;
+        let (changes, state) = self.filter_changes(polled.state).await?;
+        // Of concurrent polls of a filter, only the first to advance it returns the changes
+        if !self.filter_manager.update(id, polled.generation, state) {
+            return Ok(changes.none());
+        }
+        Ok(changes)
+    }
+
+    async fn get_filter_logs(&self, id: U256) -> Result<Vec<Log>> {
+        let Some(PolledFilter { state: FilterState::Logs { filter, .. }, .. }) =
+            self.filter_manager.poll(id, Instant::now())
+        else {
+            return Err(filter_not_found());
+        };
+        self.kakarot_client.shed_if_overloaded()?;
+        Ok(self.kakarot_client.get_logs(filter).await?)
+    }
+
+    async fn uninstall_filter(&self, id: U256) -> Result<bool> {
+        Ok(self.filter_manager.uninstall(id))
+    }
 }
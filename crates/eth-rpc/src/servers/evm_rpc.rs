@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use jsonrpsee::core::{async_trait, RpcResult as Result};
+use kakarot_rpc_core::client::api::KakarotEthApi;
+use kakarot_rpc_core::client::errors::EthApiError;
+use reth_primitives::U64;
+use starknet::providers::Provider;
+
+use crate::api::evm_api::EvmApiServer;
+
+/// The RPC module for the dev methods of Hardhat and Ganache.
+pub struct EvmRpc<P: Provider + Send + Sync> {
+    pub kakarot_client: Arc<dyn KakarotEthApi<P>>,
+}
+
+impl<P: Provider + Send + Sync> EvmRpc<P> {
+    #[must_use]
+    pub fn new(kakarot_client: Arc<dyn KakarotEthApi<P>>) -> Self {
+        Self { kakarot_client }
+    }
+}
+
+#[async_trait]
+impl<P: Provider + Send + Sync + 'static> EvmApiServer for EvmRpc<P> {
+    async fn mine(&self, timestamp: Option<U64>) -> Result<String> {
+        let devnet = self.kakarot_client.devnet()?;
+        if let Some(timestamp) = timestamp {
+            devnet.set_next_block_timestamp(timestamp.as_u64()).await.map_err(EthApiError::<P::Error>::from)?;
+        }
+        devnet.generate_block().await.map_err(EthApiError::<P::Error>::from)?;
+        Ok("0x0".into())
+    }
+
+    async fn set_next_block_timestamp(&self, timestamp: U64) -> Result<()> {
+        let devnet = self.kakarot_client.devnet()?;
+        devnet.set_next_block_timestamp(timestamp.as_u64()).await.map_err(EthApiError::<P::Error>::from)?;
+        Ok(())
+    }
+
+    async fn increase_time(&self, seconds: U64) -> Result<U64> {
+        let devnet = self.kakarot_client.devnet()?;
+        devnet.increase_next_block_timestamp(seconds.as_u64()).await.map_err(EthApiError::<P::Error>::from)?;
+        Ok(seconds)
+    }
+
+    async fn snapshot(&self) -> Result<U64> {
+        let id = self.kakarot_client.snapshot().await?;
+        Ok(id)
+    }
+
+    async fn revert(&self, id: U64) -> Result<bool> {
+        let reverted = self.kakarot_client.revert(id).await?;
+        Ok(reverted)
+    }
+}
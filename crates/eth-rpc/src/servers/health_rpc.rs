@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use jsonrpsee::core::{async_trait, RpcResult as Result};
+use kakarot_rpc_core::client::api::KakarotEthApi;
+use kakarot_rpc_core::client::errors::EthApiError;
+use kakarot_rpc_core::client::liveness::LivenessStatus;
+use starknet::providers::Provider;
+
+use crate::api::health_api::HealthApiServer;
+
+/// The RPC module for the health check of the RPC.
+pub struct HealthRpc<P: Provider + Send + Sync> {
+    pub kakarot_client: Arc<dyn KakarotEthApi<P>>,
+}
+
+impl<P: Provider + Send + Sync> HealthRpc<P> {
+    #[must_use]
+    pub fn new(kakarot_client: Arc<dyn KakarotEthApi<P>>) -> Self {
+        Self { kakarot_client }
+    }
+}
+
+#[async_trait]
+impl<P: Provider + Send + Sync + 'static> HealthApiServer for HealthRpc<P> {
+    async fn health(&self) -> Result<LivenessStatus> {
+        let liveness = self.kakarot_client.liveness().await?;
+        if liveness.stale {
+            return Err(EthApiError::<P::Error>::StaleSequencer { block_age: liveness.block_age }.into());
+        }
+        Ok(liveness)
+    }
+}
@@ -0,0 +1,115 @@
+use std::sync::{Arc, OnceLock};
+
+use jsonrpsee::core::{async_trait, RpcResult as Result};
+use kakarot_rpc_core::client::api::KakarotEthApi;
+use kakarot_rpc_core::client::tracking::{RelayStats, TrackedTransaction};
+use kakarot_rpc_core::models::account::AccountType;
+use kakarot_rpc_core::models::address::AddressParam;
+use kakarot_rpc_core::models::bundle::CallBundle;
+use kakarot_rpc_core::models::capabilities::Capabilities;
+use kakarot_rpc_core::models::chain_config::ChainConfig;
+use kakarot_rpc_core::models::fee::StarknetFeeEstimate;
+use kakarot_rpc_core::models::filter::LogFilter;
+use kakarot_rpc_core::models::message::L1Message;
+use kakarot_rpc_core::models::state_diff::AccountStateDiff;
+use kakarot_rpc_core::models::storage::{compute_storage_slot, StorageKey, StorageSlot};
+use reth_primitives::{BlockId, BlockNumberOrTag, Bytes, H256, U256, U64};
+use reth_rpc_types::RichBlock;
+use starknet::providers::Provider;
+
+use crate::abi_registry::{AbiRegistry, DecodedLog};
+use crate::api::kakarot_api::KakarotApiServer;
+
+/// The RPC module for the Kakarot specific methods.
+pub struct KakarotRpc<P: Provider + Send + Sync> {
+    pub kakarot_client: Arc<dyn KakarotEthApi<P>>,
+    pub abi_registry: Arc<AbiRegistry>,
+    /// Namespaces of the methods served, set once the RPC module is built.
+    pub namespaces: Arc<OnceLock<Vec<String>>>,
+}
+
+impl<P: Provider + Send + Sync> KakarotRpc<P> {
+    #[must_use]
+    pub fn new(
+        kakarot_client: Arc<dyn KakarotEthApi<P>>,
+        abi_registry: Arc<AbiRegistry>,
+        namespaces: Arc<OnceLock<Vec<String>>>,
+    ) -> Self {
+        Self { kakarot_client, abi_registry, namespaces }
+    }
+}
+
+#[async_trait]
+impl<P: Provider + Send + Sync + 'static> KakarotApiServer for KakarotRpc<P> {
+    async fn capabilities(&self) -> Result<Capabilities> {
+        Ok(self.kakarot_client.capabilities())
+    }
+
+    async fn chain_config(&self) -> Result<ChainConfig> {
+        let mut chain_config = self.kakarot_client.chain_config().await;
+        chain_config.namespaces = self.namespaces.get().cloned().unwrap_or_default();
+        Ok(chain_config)
+    }
+
+    async fn relay_stats(&self) -> Result<RelayStats> {
+        Ok(self.kakarot_client.relay_stats())
+    }
+
+    async fn transaction_status(&self, hash: H256) -> Result<Option<TrackedTransaction>> {
+        let status = self.kakarot_client.transaction_status(hash).await?;
+        Ok(status)
+    }
+
+    async fn l1_message_status(&self, hash: H256) -> Result<Option<Vec<L1Message>>> {
+        let messages = self.kakarot_client.l1_messages(hash).await?;
+        Ok(messages)
+    }
+
+    async fn estimate_starknet_fee(&self, bytes: Bytes) -> Result<StarknetFeeEstimate> {
+        let fee_estimate = self.kakarot_client.estimate_starknet_fee(bytes).await?;
+        Ok(fee_estimate)
+    }
+
+    async fn call_bundle(&self, transactions: Vec<Bytes>, block_id: Option<BlockId>) -> Result<CallBundle> {
+        self.kakarot_client.shed_if_overloaded()?;
+        let block_id = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
+        let bundle = self.kakarot_client.call_bundle(transactions, block_id).await?;
+        Ok(bundle)
+    }
+
+    async fn block_by_timestamp(&self, timestamp: U64, hydrated: bool) -> Result<Option<RichBlock>> {
+        let block = self.kakarot_client.block_by_timestamp(timestamp.as_u64(), hydrated).await?;
+        Ok(block)
+    }
+
+    async fn storage_slot(
+        &self,
+        address: AddressParam,
+        base_slot: U256,
+        keys: Vec<StorageKey>,
+        block_id: Option<BlockId>,
+    ) -> Result<StorageSlot> {
+        let block_id = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
+        let slot = compute_storage_slot(base_slot, &keys);
+        let value = self.kakarot_client.storage_at(*address, slot, block_id).await?;
+        Ok(StorageSlot { slot, value })
+    }
+
+    async fn account_type(&self, address: AddressParam, block_id: Option<BlockId>) -> Result<AccountType> {
+        let block_id = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
+        let account_type = self.kakarot_client.account_type(*address, block_id).await?;
+        Ok(account_type)
+    }
+
+    async fn state_diff(&self, block_id: BlockId, slots: Option<Vec<U256>>) -> Result<Vec<AccountStateDiff>> {
+        self.kakarot_client.shed_if_overloaded()?;
+        let state_diff = self.kakarot_client.state_diff(block_id, slots.unwrap_or_default()).await?;
+        Ok(state_diff)
+    }
+
+    async fn decoded_logs(&self, filter: LogFilter) -> Result<Vec<DecodedLog>> {
+        self.kakarot_client.shed_if_overloaded()?;
+        let logs = self.kakarot_client.get_logs(filter).await?;
+        Ok(self.abi_registry.decode_logs(logs).await)
+    }
+}
@@ -1,4 +1,12 @@
+pub mod admin_rpc;
 pub mod alchemy_rpc;
+pub mod anvil_rpc;
+pub mod erigon_rpc;
 pub mod eth_rpc;
+pub mod evm_rpc;
+pub mod health_rpc;
+pub mod kakarot_rpc;
 pub mod net_rpc;
+pub mod ots_rpc;
+pub mod pubsub_rpc;
 pub mod web3_rpc;
@@ -1,5 +1,5 @@
 use jsonrpsee::core::{async_trait, RpcResult as Result};
-use reth_primitives::U64;
+use kakarot_rpc_core::client::constants::CHAIN_ID;
 use reth_rpc_types::PeerCount;
 
 use crate::api::net_api::NetApiServer;
@@ -16,10 +16,9 @@ impl NetRpc {
 
 #[async_trait]
 impl NetApiServer for NetRpc {
-    /// Get the protocol version of the Kakarot Starknet RPC.
-    fn version(&self) -> Result<U64> {
-        let protocol_version = 1_u64;
-        Ok(protocol_version.into())
+    /// Returns the network id, the chain id as wallets such as MetaMask expect it.
+    fn version(&self) -> Result<String> {
+        Ok(CHAIN_ID.to_string())
     }
 
     fn peer_count(&self) -> Result<PeerCount> {
@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use jsonrpsee::core::{async_trait, RpcResult as Result};
+use kakarot_rpc_core::client::api::KakarotEthApi;
+use kakarot_rpc_core::client::creations::ContractCreator;
+use kakarot_rpc_core::models::address::AddressParam;
+use reth_primitives::{H256, U256};
+use starknet::providers::Provider;
+
+use crate::api::ots_api::OtsApiServer;
+
+/// The RPC module for the Otterscan methods backed by the indexes of the RPC.
+pub struct OtsRpc<P: Provider + Send + Sync> {
+    pub kakarot_client: Arc<dyn KakarotEthApi<P>>,
+}
+
+impl<P: Provider + Send + Sync> OtsRpc<P> {
+    #[must_use]
+    pub fn new(kakarot_client: Arc<dyn KakarotEthApi<P>>) -> Self {
+        Self { kakarot_client }
+    }
+}
+
+#[async_trait]
+impl<P: Provider + Send + Sync + 'static> OtsApiServer for OtsRpc<P> {
+    async fn get_contract_creator(&self, address: AddressParam) -> Result<Option<ContractCreator>> {
+        Ok(self.kakarot_client.contract_creator(*address))
+    }
+
+    async fn get_transaction_by_sender_and_nonce(&self, sender: AddressParam, nonce: U256) -> Result<Option<H256>> {
+        let hash = self.kakarot_client.transaction_by_sender_and_nonce(*sender, nonce).await?;
+        Ok(hash)
+    }
+}
@@ -0,0 +1,427 @@
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use jsonrpsee::core::{async_trait, SubscriptionResult};
+use jsonrpsee::server::SubscriptionMessage;
+use jsonrpsee::types::error::INVALID_PARAMS_CODE;
+use jsonrpsee::{PendingSubscriptionSink, SubscriptionSink};
+use kakarot_rpc_core::client::api::KakarotEthApi;
+use kakarot_rpc_core::client::errors::rpc_err;
+use kakarot_rpc_core::models::filter::LogFilter;
+use reth_primitives::{BlockNumberOrTag, H256};
+use reth_rpc_types::pubsub::{Params, SubscriptionKind};
+use reth_rpc_types::{BlockTransactions, FilterBlockOption, SyncStatus};
+use serde_json::{json, Value};
+use starknet::core::types::{BlockId as StarknetBlockId, BlockTag};
+use starknet::providers::Provider;
+use tokio::sync::mpsc::Receiver;
+
+use crate::api::pubsub_api::{EthPubSubApiServer, KakarotPubSubApiServer};
+use crate::subscription::{resume_range, PendingTransactionFilter, SubscriptionCursor, SubscriptionHub};
+use crate::webhook::WebhookNotifier;
+
+/// Maximum number of blocks notified per poll when the poller falls behind the chain head, the
+/// following blocks being notified on the next polls.
+const MAX_NEW_HEADS_CATCH_UP: u64 = 16;
+
+/// The RPC module for the Ethereum websocket subscriptions, and the Kakarot ones. Clones share the
+/// same poller.
+pub struct KakarotPubSubRpc<P: Provider + Send + Sync> {
+    pub kakarot_client: Arc<dyn KakarotEthApi<P>>,
+    pub subscription_hub: Arc<SubscriptionHub>,
+    poller_started: Arc<AtomicBool>,
+}
+
+impl<P: Provider + Send + Sync> Clone for KakarotPubSubRpc<P> {
+    fn clone(&self) -> Self {
+        Self {
+            kakarot_client: self.kakarot_client.clone(),
+            subscription_hub: self.subscription_hub.clone(),
+            poller_started: self.poller_started.clone(),
+        }
+    }
+}
+
+impl<P: Provider + Send + Sync + 'static> KakarotPubSubRpc<P> {
+    #[must_use]
+    pub fn new(kakarot_client: Arc<dyn KakarotEthApi<P>>, subscription_hub: Arc<SubscriptionHub>) -> Self {
+        let pubsub = Self { kakarot_client, subscription_hub, poller_started: Arc::new(AtomicBool::new(false)) };
+        // Webhooks are notified whether or not a websocket client subscribed
+        if pubsub.subscription_hub.config().webhooks.is_enabled() {
+            pubsub.ensure_poller();
+        }
+        pubsub
+    }
+
+    /// Starts polling the Starknet provider for notifications, on the first subscription.
+    fn ensure_poller(&self) {
+        if self.poller_started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let kakarot_client = self.kakarot_client.clone();
+        let subscription_hub = self.subscription_hub.clone();
+        let webhooks = &subscription_hub.config().webhooks;
+        let webhook_notifier = webhooks.is_enabled().then(|| WebhookNotifier::new(webhooks));
+        tokio::spawn(poll_notifications(kakarot_client, subscription_hub, webhook_notifier));
+    }
+
+    /// Returns the past blocks a subscription resumed from `from_block` is notified of.
+    async fn resume_range(&self, from_block: u64) -> Result<RangeInclusive<u64>, String> {
+        let head = self
+            .kakarot_client
+            .block_number()
+            .await
+            .map_err(|err| format!("failed to fetch the block number: {err}"))?
+            .as_u64();
+        resume_range(from_block, head, self.subscription_hub.config().resume_blocks)
+    }
+
+    /// Returns the logs of the `replayed` blocks matching the filter of a resumed `logs`
+    /// subscription, in the order of the chain.
+    async fn replayed_logs(&self, log_filter: &LogFilter, replayed: RangeInclusive<u64>) -> Result<Vec<Value>, String> {
+        if replayed.is_empty() {
+            return Ok(Vec::new());
+        }
+        let replayed_filter = LogFilter {
+            from_block: Some(BlockNumberOrTag::Number(*replayed.start())),
+            to_block: Some(BlockNumberOrTag::Number(*replayed.end())),
+            ..log_filter.clone()
+        };
+        let logs = self
+            .kakarot_client
+            .get_logs(replayed_filter)
+            .await
+            .map_err(|err| format!("failed to replay the logs from block {}: {err}", replayed.start()))?;
+        logs.iter().map(serde_json::to_value).collect::<Result<_, _>>().map_err(|err| err.to_string())
+    }
+}
+
+/// Returns the block a `newHeads` or `logs` subscription resumes from, the `fromBlock` of its
+/// parameters, e.g. `{"fromBlock": "0x2a"}`. The parameters of both kinds are parsed as a log
+/// filter, the only object parameter of `eth_subscribe`.
+fn resume_from_block(params: Option<&Params>) -> Option<u64> {
+    let Some(Params::Logs(filter)) = params else { return None };
+    match filter.block_option {
+        FilterBlockOption::Range { from_block: Some(BlockNumberOrTag::Number(from_block)), .. } => Some(from_block),
+        _ => None,
+    }
+}
+
+/// Returns the filter of a `logs` subscription, matching any log without parameters. The block
+/// range of the filter is not kept, its `fromBlock` being the block the subscription resumes from
+/// and the subscription being notified of the new logs afterwards.
+fn log_filter(params: Option<&Params>) -> Result<LogFilter, String> {
+    let Some(Params::Logs(filter)) = params else { return Ok(LogFilter::default()) };
+    let log_filter: LogFilter = serde_json::to_value(filter)
+        .and_then(serde_json::from_value)
+        .map_err(|err| format!("Invalid logs subscription filter: {err}"))?;
+    Ok(LogFilter { address: log_filter.address, topics: log_filter.topics, ..Default::default() })
+}
+
+#[async_trait]
+impl<P: Provider + Send + Sync + 'static> EthPubSubApiServer for KakarotPubSubRpc<P> {
+    async fn subscribe(
+        &self,
+        pending: PendingSubscriptionSink,
+        kind: SubscriptionKind,
+        params: Option<Params>,
+    ) -> SubscriptionResult {
+        if !matches!(kind, SubscriptionKind::NewHeads | SubscriptionKind::Logs | SubscriptionKind::Syncing) {
+            pending.reject(rpc_err(INVALID_PARAMS_CODE, format!("Unsupported subscription kind: {kind:?}"))).await;
+            return Ok(());
+        }
+
+        let log_filter = match kind {
+            SubscriptionKind::Logs => match log_filter(params.as_ref()) {
+                Ok(log_filter) => Some(log_filter),
+                Err(message) => {
+                    pending.reject(rpc_err(INVALID_PARAMS_CODE, message)).await;
+                    return Ok(());
+                }
+            },
+            _ => None,
+        };
+
+        let replayed = match resume_from_block(params.as_ref()).filter(|_| kind != SubscriptionKind::Syncing) {
+            Some(from_block) => match self.resume_range(from_block).await {
+                Ok(replayed) => Some(replayed),
+                Err(message) => {
+                    pending.reject(rpc_err(INVALID_PARAMS_CODE, message)).await;
+                    return Ok(());
+                }
+            },
+            None => None,
+        };
+
+        // Subscribed before the replay so that the blocks and logs produced meanwhile are queued
+        let receiver = match &log_filter {
+            Some(log_filter) => self.subscription_hub.subscribe_logs(log_filter.clone()),
+            None => self.subscription_hub.subscribe(kind),
+        };
+        // Missed logs are fetched before accepting the subscription so that a failure, e.g. too many
+        // logs, is returned to the client
+        let replayed_logs = match (&log_filter, replayed.clone()) {
+            (Some(log_filter), Some(replayed)) => match self.replayed_logs(log_filter, replayed).await {
+                Ok(replayed_logs) => replayed_logs,
+                Err(message) => {
+                    pending.reject(rpc_err(INVALID_PARAMS_CODE, message)).await;
+                    return Ok(());
+                }
+            },
+            _ => Vec::new(),
+        };
+        let replayed_heads = replayed.filter(|_| kind == SubscriptionKind::NewHeads);
+
+        let sink = pending.accept().await?;
+        self.ensure_poller();
+        let kakarot_client = self.kakarot_client.clone();
+        tokio::spawn(async move {
+            let mut cursor = SubscriptionCursor::default();
+            if let Some(replayed_heads) = replayed_heads {
+                if !replay_new_heads(kakarot_client.as_ref(), &sink, &mut cursor, replayed_heads).await {
+                    return;
+                }
+            }
+            if !replay_logs(&sink, &mut cursor, replayed_logs).await {
+                return;
+            }
+            forward_notifications(sink, receiver, cursor).await;
+        });
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<P: Provider + Send + Sync + 'static> KakarotPubSubApiServer for KakarotPubSubRpc<P> {
+    async fn pending_transactions_stream(
+        &self,
+        pending: PendingSubscriptionSink,
+        filter: Option<PendingTransactionFilter>,
+    ) -> SubscriptionResult {
+        let sink = pending.accept().await?;
+        let receiver = self.subscription_hub.subscribe_pending_transactions(filter.unwrap_or_default());
+        self.ensure_poller();
+        tokio::spawn(forward_notifications(sink, receiver, SubscriptionCursor::default()));
+
+        Ok(())
+    }
+}
+
+/// Notifies a resumed `newHeads` subscription of the blocks it missed. Returns false if the
+/// subscription is closed, including when a block cannot be fetched so that the client
+/// resubscribes instead of missing it.
+async fn replay_new_heads<P: Provider + Send + Sync + 'static>(
+    kakarot_client: &dyn KakarotEthApi<P>,
+    sink: &SubscriptionSink,
+    cursor: &mut SubscriptionCursor,
+    replayed: RangeInclusive<u64>,
+) -> bool {
+    for number in replayed {
+        let block = match kakarot_client.get_eth_block_from_starknet_block(StarknetBlockId::Number(number), false).await
+        {
+            Ok(block) => block,
+            Err(err) => {
+                tracing::warn!("newHeads subscription: failed to replay block {number}: {err}");
+                return false;
+            }
+        };
+        let Ok(header) = serde_json::to_value(&block.header) else { return false };
+        cursor.advance(&header);
+        let Ok(message) = SubscriptionMessage::from_json(&header) else { return false };
+        if sink.send(message).await.is_err() {
+            return false;
+        }
+    }
+    true
+}
+
+/// Notifies a resumed `logs` subscription of the logs it missed. Returns false if the subscription
+/// is closed.
+async fn replay_logs(sink: &SubscriptionSink, cursor: &mut SubscriptionCursor, replayed_logs: Vec<Value>) -> bool {
+    for log in replayed_logs {
+        cursor.advance(&log);
+        let Ok(message) = SubscriptionMessage::from_json(&log) else { return false };
+        if sink.send(message).await.is_err() {
+            return false;
+        }
+    }
+    true
+}
+
+/// Forwards the queued notifications of a subscription to its websocket sink, until the client
+/// unsubscribes or the hub closes the queue of a slow subscriber. Blocks and logs already delivered
+/// to the subscription are not forwarded again.
+async fn forward_notifications(sink: SubscriptionSink, mut receiver: Receiver<Value>, mut cursor: SubscriptionCursor) {
+    loop {
+        tokio::select! {
+            _ = sink.closed() => break,
+            notification = receiver.recv() => {
+                let Some(notification) = notification else { break };
+                if !cursor.advance(&notification) {
+                    continue;
+                }
+                let Ok(message) = SubscriptionMessage::from_json(&notification) else { continue };
+                if sink.send(message).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Polls the Starknet provider and notifies the subscribers of new blocks, of their logs and of the
+/// transitions between syncing and synced, and the webhooks of the activity of the watched
+/// addresses.
+async fn poll_notifications<P: Provider + Send + Sync + 'static>(
+    kakarot_client: Arc<dyn KakarotEthApi<P>>,
+    subscription_hub: Arc<SubscriptionHub>,
+    webhook_notifier: Option<WebhookNotifier>,
+) {
+    let mut interval = tokio::time::interval(subscription_hub.config().poll_interval);
+    let mut last_block_number: Option<u64> = None;
+    let mut last_syncing: Option<bool> = None;
+    let mut pending_transactions: HashSet<H256> = HashSet::new();
+
+    loop {
+        interval.tick().await;
+
+        if subscription_hub.has_subscribers(SubscriptionKind::NewHeads)
+            || subscription_hub.has_subscribers(SubscriptionKind::Logs)
+            || webhook_notifier.is_some()
+        {
+            notify_new_blocks(
+                kakarot_client.as_ref(),
+                &subscription_hub,
+                webhook_notifier.as_ref(),
+                &mut last_block_number,
+            )
+            .await;
+        } else {
+            last_block_number = None;
+        }
+
+        if subscription_hub.has_subscribers(SubscriptionKind::Syncing) {
+            notify_syncing(kakarot_client.as_ref(), &subscription_hub, &mut last_syncing).await;
+        } else {
+            last_syncing = None;
+        }
+
+        if subscription_hub.has_subscribers(SubscriptionKind::NewPendingTransactions) {
+            notify_pending_transactions(kakarot_client.as_ref(), &subscription_hub, &mut pending_transactions).await;
+        } else {
+            pending_transactions.clear();
+        }
+    }
+}
+
+/// Notifies the pending transactions subscribers of the transactions of the pending block not in
+/// `pending_transactions`, the transactions of the previous poll, which is replaced by the
+/// transactions of the pending block.
+async fn notify_pending_transactions<P: Provider + Send + Sync + 'static>(
+    kakarot_client: &dyn KakarotEthApi<P>,
+    subscription_hub: &SubscriptionHub,
+    pending_transactions: &mut HashSet<H256>,
+) {
+    let block =
+        match kakarot_client.get_eth_block_from_starknet_block(StarknetBlockId::Tag(BlockTag::Pending), true).await {
+            Ok(block) => block,
+            Err(err) => {
+                tracing::warn!("pending transactions subscription: failed to fetch the pending block: {err}");
+                return;
+            }
+        };
+    let BlockTransactions::Full(transactions) = &block.inner.transactions else { return };
+
+    // Transactions stay in the pending block until it is accepted, each one is notified once
+    for transaction in transactions.iter().filter(|transaction| !pending_transactions.contains(&transaction.hash)) {
+        subscription_hub.notify_pending_transaction(transaction);
+    }
+    *pending_transactions = transactions.iter().map(|transaction| transaction.hash).collect();
+}
+
+/// Notifies the `newHeads` and `logs` subscribers, and the webhooks if any, of the blocks produced
+/// since `last_block_number`.
+async fn notify_new_blocks<P: Provider + Send + Sync + 'static>(
+    kakarot_client: &dyn KakarotEthApi<P>,
+    subscription_hub: &SubscriptionHub,
+    webhook_notifier: Option<&WebhookNotifier>,
+    last_block_number: &mut Option<u64>,
+) {
+    let block_number = match kakarot_client.block_number().await {
+        Ok(block_number) => block_number.as_u64(),
+        Err(err) => {
+            tracing::warn!("newHeads subscription: failed to fetch the block number: {err}");
+            return;
+        }
+    };
+
+    // A provider behind the last notified block, e.g. a lagging node after a failover, makes the
+    // range empty: the poller waits for it to catch up instead of notifying blocks again
+    let first_block_number = last_block_number.map_or(block_number, |last| last + 1);
+    let last_notified_number = block_number.min(first_block_number.saturating_add(MAX_NEW_HEADS_CATCH_UP - 1));
+
+    for number in first_block_number..=last_notified_number {
+        let hydrated = webhook_notifier.is_some();
+        let block =
+            match kakarot_client.get_eth_block_from_starknet_block(StarknetBlockId::Number(number), hydrated).await {
+                Ok(block) => block,
+                Err(err) => {
+                    tracing::warn!("newHeads subscription: failed to fetch block {number}: {err}");
+                    return;
+                }
+            };
+        match serde_json::to_value(&block.header) {
+            Ok(header) => subscription_hub.notify(SubscriptionKind::NewHeads, &header),
+            Err(err) => tracing::warn!("newHeads subscription: failed to serialize block {number}: {err}"),
+        }
+        if subscription_hub.has_subscribers(SubscriptionKind::Logs) {
+            // Fetched unfiltered once, the filters of the subscribers being applied by the hub
+            let block_filter = LogFilter {
+                from_block: Some(BlockNumberOrTag::Number(number)),
+                to_block: Some(BlockNumberOrTag::Number(number)),
+                ..Default::default()
+            };
+            match kakarot_client.get_logs(block_filter).await {
+                Ok(logs) => logs.iter().for_each(|log| subscription_hub.notify_log(log)),
+                Err(err) => {
+                    tracing::warn!("logs subscription: failed to fetch the logs of block {number}: {err}");
+                    return;
+                }
+            }
+        }
+        if let Some(webhook_notifier) = webhook_notifier {
+            webhook_notifier.notify_block(kakarot_client, &block).await;
+        }
+        *last_block_number = Some(number);
+    }
+}
+
+/// Notifies the `syncing` subscribers when the Starknet node starts or stops syncing, using the
+/// same translation of the sync status as `eth_syncing`.
+async fn notify_syncing<P: Provider + Send + Sync + 'static>(
+    kakarot_client: &dyn KakarotEthApi<P>,
+    subscription_hub: &SubscriptionHub,
+    last_syncing: &mut Option<bool>,
+) {
+    let status = match kakarot_client.syncing().await {
+        Ok(status) => status,
+        Err(err) => {
+            tracing::warn!("syncing subscription: failed to fetch the sync status: {err}");
+            return;
+        }
+    };
+
+    let notification = match &status {
+        SyncStatus::Info(info) => json!({ "syncing": true, "status": info }),
+        SyncStatus::None => json!({ "syncing": false }),
+    };
+    let syncing = matches!(status, SyncStatus::Info(_));
+
+    // The first poll only records the current state, subscribers are notified of transitions.
+    if last_syncing.replace(syncing).map_or(false, |last| last != syncing) {
+        subscription_hub.notify(SubscriptionKind::Syncing, &notification);
+    }
+}
@@ -0,0 +1,172 @@
+use kakarot_rpc_core::client::api::KakarotEthApi;
+use kakarot_rpc_core::client::stats::{CacheStats, ClientStats};
+use serde::{Deserialize, Serialize};
+use starknet::providers::Provider;
+
+use crate::filters::{FilterManager, FilterStats};
+use crate::subscription::{SubscriptionHub, SubscriptionStats};
+
+/// Target of the log events of the statistics dumps, e.g. `RUST_LOG=info,kakarot_rpc::stats=off`
+/// to silence them.
+pub const STATS_TARGET: &str = "kakarot_rpc::stats";
+
+/// Snapshot of the caches, indexes, queues and subscriptions of the RPC, returned by
+/// `admin_stats`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcStats {
+    #[serde(flatten)]
+    pub client: ClientStats,
+    pub subscriptions: SubscriptionStats,
+    pub filters: FilterStats,
+}
+
+impl RpcStats {
+    pub fn collect<P: Provider + Send + Sync>(
+        kakarot_client: &dyn KakarotEthApi<P>,
+        subscription_hub: &SubscriptionHub,
+        filter_manager: &FilterManager,
+    ) -> Self {
+        Self {
+            client: kakarot_client.stats(),
+            subscriptions: subscription_hub.stats(),
+            filters: filter_manager.stats(),
+        }
+    }
+
+    /// Logs the statistics as structured events, one per cache, index, lane and Starknet endpoint,
+    /// so that they can be queried in the log aggregator with `KAKAROT_LOG_FORMAT=json`.
+    pub fn log(&self) {
+        let ClientStats {
+            call_cache,
+            block_roots_cache,
+            block_timestamps_cache,
+            indexes,
+            lanes,
+            relay,
+            upstreams,
+            coalescing,
+        } = &self.client;
+
+        let caches: [(&str, &CacheStats); 3] =
+            [("call", call_cache), ("block_roots", block_roots_cache), ("block_timestamps", block_timestamps_cache)];
+        for (cache, stats) in caches {
+            tracing::info!(
+                target: STATS_TARGET,
+                cache,
+                entries = stats.entries,
+                hits = stats.hits,
+                misses = stats.misses,
+                hit_rate = stats.hit_rate,
+                "cache stats"
+            );
+        }
+        for (index, entries) in indexes {
+            tracing::info!(target: STATS_TARGET, index = index.as_str(), entries, "index stats");
+        }
+        for (lane, depth) in [("read", &lanes.read), ("write", &lanes.write)] {
+            tracing::info!(
+                target: STATS_TARGET,
+                lane,
+                in_flight = depth.in_flight,
+                queued = depth.queued,
+                shed = depth.shed,
+                "lane stats"
+            );
+        }
+        for upstream in upstreams {
+            tracing::info!(
+                target: STATS_TARGET,
+                url = upstream.url.as_str(),
+                requests = upstream.requests,
+                errors = upstream.errors,
+                latency_ms = upstream.latency_ms,
+                latency_histogram = ?upstream.latency_histogram,
+                "upstream stats"
+            );
+        }
+        tracing::info!(
+            target: STATS_TARGET,
+            requests = coalescing.requests,
+            coalesced = coalescing.coalesced,
+            "coalescing stats"
+        );
+        tracing::info!(
+            target: STATS_TARGET,
+            tracked = relay.tracked,
+            received = relay.received,
+            rejected = relay.rejected,
+            dropped = relay.dropped,
+            "relay stats"
+        );
+        tracing::info!(
+            target: STATS_TARGET,
+            active = self.subscriptions.active,
+            dropped = self.subscriptions.dropped,
+            disconnected = self.subscriptions.disconnected,
+            "subscription stats"
+        );
+        tracing::info!(
+            target: STATS_TARGET,
+            installed = self.filters.installed,
+            expired = self.filters.expired,
+            rejected = self.filters.rejected,
+            "filter stats"
+        );
+    }
+}
+
+/// Logs the statistics of the RPC each time the process receives `SIGUSR1`, e.g. with
+/// `kill -USR1 <pid>`.
+///
+/// # Errors
+///
+/// Will return `Err` if the signal handler cannot be installed.
+#[cfg(unix)]
+pub fn dump_stats_on_signal<P: Provider + Send + Sync + 'static>(
+    kakarot_client: std::sync::Arc<dyn KakarotEthApi<P>>,
+    subscription_hub: std::sync::Arc<SubscriptionHub>,
+    filter_manager: std::sync::Arc<FilterManager>,
+) -> std::io::Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut signals = signal(SignalKind::user_defined1())?;
+    tokio::spawn(async move {
+        while signals.recv().await.is_some() {
+            RpcStats::collect(kakarot_client.as_ref(), &subscription_hub, &filter_manager).log();
+        }
+    });
+    Ok(())
+}
+
+/// `SIGUSR1` only exists on unix, the statistics are returned by `admin_stats` on other systems.
+#[cfg(not(unix))]
+pub fn dump_stats_on_signal<P: Provider + Send + Sync + 'static>(
+    _kakarot_client: std::sync::Arc<dyn KakarotEthApi<P>>,
+    _subscription_hub: std::sync::Arc<SubscriptionHub>,
+    _filter_manager: std::sync::Arc<FilterManager>,
+) -> std::io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rpc_stats_serialization() {
+        // Given
+        let mut stats = RpcStats::default();
+        stats.client.indexes.insert("creations".into(), 3);
+        stats.client.call_cache = CacheStats::new(1, 3, 1);
+
+        // When
+        let json = serde_json::to_value(&stats).unwrap();
+
+        // Then
+        assert_eq!(0.75, json["callCache"]["hitRate"]);
+        assert_eq!(3, json["indexes"]["creations"]);
+        assert_eq!(0, json["subscriptions"]["active"]);
+        assert_eq!(stats, serde_json::from_value(json).unwrap());
+    }
+}
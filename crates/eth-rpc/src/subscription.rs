@@ -0,0 +1,444 @@
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+use kakarot_rpc_core::models::filter::LogFilter;
+use reth_primitives::{Address, Bytes};
+use reth_rpc_types::pubsub::SubscriptionKind;
+use reth_rpc_types::{Log, Transaction};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+use crate::config::{SlowConsumerPolicy, SubscriptionConfig};
+
+/// Counters of the notifications sent to the websocket subscribers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionStats {
+    /// Number of open subscriptions.
+    pub active: u64,
+    /// Notifications queued for a subscriber.
+    pub delivered: u64,
+    /// Notifications dropped because the queue of a subscriber was full.
+    pub dropped: u64,
+    /// Subscriptions closed because their queue was full.
+    pub disconnected: u64,
+}
+
+/// Filter of a `kakarot_pendingTransactionsStream` subscription. Empty lists match any
+/// transaction.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PendingTransactionFilter {
+    /// Recipients of the transactions. Deployments, without recipient, do not match a non empty
+    /// list.
+    pub to: Vec<Address>,
+    /// Function selectors the calldata of the transactions starts with.
+    pub selectors: Vec<Bytes>,
+}
+
+impl PendingTransactionFilter {
+    pub fn matches(&self, transaction: &Transaction) -> bool {
+        let to_matches = self.to.is_empty() || transaction.to.map_or(false, |to| self.to.contains(&to));
+        let selector_matches =
+            self.selectors.is_empty() || self.selectors.iter().any(|selector| transaction.input.starts_with(selector));
+        to_matches && selector_matches
+    }
+}
+
+/// Filter of a subscription, applied before queueing its notifications.
+#[derive(Debug)]
+enum SubscriptionFilter {
+    Logs(LogFilter),
+    PendingTransactions(PendingTransactionFilter),
+}
+
+#[derive(Debug)]
+struct Subscriber {
+    kind: SubscriptionKind,
+    filter: Option<SubscriptionFilter>,
+    sender: Sender<Value>,
+}
+
+/// Fans notifications out to the websocket subscribers.
+///
+/// Each subscriber owns a bounded queue, drained by its own task. Logs are filtered per
+/// subscription before being queued, so that a busy chain only fills the queues of the
+/// subscribers interested in its logs. The notifier never waits on a
+/// subscriber: when a queue is full, the notification is dropped or the subscription is closed,
+/// depending on the configured [`SlowConsumerPolicy`]. A closed subscription is not reopened, the
+/// client is expected to call `eth_subscribe` again.
+#[derive(Debug)]
+pub struct SubscriptionHub {
+    config: SubscriptionConfig,
+    subscribers: Mutex<Vec<Subscriber>>,
+    delivered: AtomicU64,
+    dropped: AtomicU64,
+    disconnected: AtomicU64,
+}
+
+impl SubscriptionHub {
+    #[must_use]
+    pub fn new(config: SubscriptionConfig) -> Self {
+        Self {
+            config,
+            subscribers: Mutex::new(Vec::new()),
+            delivered: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            disconnected: AtomicU64::new(0),
+        }
+    }
+
+    pub fn config(&self) -> &SubscriptionConfig {
+        &self.config
+    }
+
+    /// Registers a new subscriber to `kind` and returns the receiving end of its queue.
+    pub fn subscribe(&self, kind: SubscriptionKind) -> Receiver<Value> {
+        self.register(kind, None)
+    }
+
+    /// Registers a new `logs` subscriber, notified of the logs matching `filter` only.
+    pub fn subscribe_logs(&self, filter: LogFilter) -> Receiver<Value> {
+        self.register(SubscriptionKind::Logs, Some(SubscriptionFilter::Logs(filter)))
+    }
+
+    /// Registers a new subscriber to the full pending transactions matching `filter`. These
+    /// subscribers are registered as `newPendingTransactions` ones, which `eth_subscribe` does not
+    /// serve.
+    pub fn subscribe_pending_transactions(&self, filter: PendingTransactionFilter) -> Receiver<Value> {
+        self.register(SubscriptionKind::NewPendingTransactions, Some(SubscriptionFilter::PendingTransactions(filter)))
+    }
+
+    fn register(&self, kind: SubscriptionKind, filter: Option<SubscriptionFilter>) -> Receiver<Value> {
+        let (sender, receiver) = channel(self.config.queue_capacity.max(1));
+        self.subscribers.lock().expect("subscribers lock poisoned").push(Subscriber { kind, filter, sender });
+        receiver
+    }
+
+    /// Returns the subscribers, without those whose subscription was closed by the client, e.g.
+    /// on `eth_unsubscribe` or when its websocket disconnected.
+    fn live_subscribers(&self) -> MutexGuard<'_, Vec<Subscriber>> {
+        let mut subscribers = self.subscribers.lock().expect("subscribers lock poisoned");
+        subscribers.retain(|subscriber| !subscriber.sender.is_closed());
+        subscribers
+    }
+
+    /// Returns true if at least one open subscription to `kind` is registered.
+    pub fn has_subscribers(&self, kind: SubscriptionKind) -> bool {
+        self.live_subscribers().iter().any(|subscriber| subscriber.kind == kind)
+    }
+
+    /// Queues a notification for every subscriber to `kind`, without waiting on slow ones.
+    pub fn notify(&self, kind: SubscriptionKind, notification: &Value) {
+        self.dispatch(kind, notification, |_| true);
+    }
+
+    /// Queues a log for the `logs` subscribers whose filter it matches.
+    pub fn notify_log(&self, log: &Log) {
+        let Ok(notification) = serde_json::to_value(log) else { return };
+        self.dispatch(SubscriptionKind::Logs, &notification, |filter| match filter {
+            Some(SubscriptionFilter::Logs(filter)) => filter.matches(log),
+            _ => true,
+        });
+    }
+
+    /// Queues a pending transaction for the pending transactions subscribers whose filter it
+    /// matches.
+    pub fn notify_pending_transaction(&self, transaction: &Transaction) {
+        let Ok(notification) = serde_json::to_value(transaction) else { return };
+        self.dispatch(SubscriptionKind::NewPendingTransactions, &notification, |filter| match filter {
+            Some(SubscriptionFilter::PendingTransactions(filter)) => filter.matches(transaction),
+            _ => true,
+        });
+    }
+
+    fn dispatch(
+        &self,
+        kind: SubscriptionKind,
+        notification: &Value,
+        matches: impl Fn(Option<&SubscriptionFilter>) -> bool,
+    ) {
+        let policy = self.config.slow_consumer_policy;
+        self.live_subscribers().retain(|Subscriber { kind: subscribed, filter, sender }| {
+            if *subscribed != kind || !matches(filter.as_ref()) {
+                return true;
+            }
+            match sender.try_send(notification.clone()) {
+                Ok(()) => {
+                    self.delivered.fetch_add(1, Ordering::Relaxed);
+                    true
+                }
+                Err(TrySendError::Full(_)) => match policy {
+                    SlowConsumerPolicy::Drop => {
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        tracing::debug!("subscription queue full, notification dropped");
+                        true
+                    }
+                    SlowConsumerPolicy::Disconnect => {
+                        self.disconnected.fetch_add(1, Ordering::Relaxed);
+                        tracing::warn!("subscription queue full, slow subscriber disconnected");
+                        false
+                    }
+                },
+                Err(TrySendError::Closed(_)) => false,
+            }
+        });
+    }
+
+    pub fn stats(&self) -> SubscriptionStats {
+        SubscriptionStats {
+            active: self.live_subscribers().len() as u64,
+            delivered: self.delivered.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            disconnected: self.disconnected.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Position of a subscription in the chain, the number of the last block delivered to it, or the
+/// block number and index of the last log delivered to it.
+///
+/// The poller can notify a block again, e.g. when the Starknet provider it polls falls behind
+/// after switching to another node. The cursor filters these notifications out so that each
+/// subscriber receives every block and log once and in order. The cursor lives as long as the
+/// subscription, a client resubscribing after a disconnection resumes with `fromBlock`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SubscriptionCursor {
+    last_block_number: Option<u64>,
+    last_log: Option<(u64, u64)>,
+}
+
+impl SubscriptionCursor {
+    /// Returns true if the notification should be delivered, and moves the cursor past it.
+    /// Logs are positioned by their block number and log index, blocks by their number, other
+    /// notifications and pending logs are always delivered.
+    pub fn advance(&mut self, notification: &Value) -> bool {
+        if let (Some(block_number), Some(log_index)) =
+            (hex_field(notification, "blockNumber"), hex_field(notification, "logIndex"))
+        {
+            let position = (block_number, log_index);
+            if self.last_log.map_or(false, |last| position <= last) {
+                return false;
+            }
+            self.last_log = Some(position);
+            return true;
+        }
+        let Some(block_number) = hex_field(notification, "number") else { return true };
+        if self.last_block_number.map_or(false, |last| block_number <= last) {
+            return false;
+        }
+        self.last_block_number = Some(block_number);
+        true
+    }
+}
+
+/// Returns the hex quantity `key` of a notification.
+fn hex_field(notification: &Value, key: &str) -> Option<u64> {
+    notification
+        .get(key)
+        .and_then(Value::as_str)
+        .and_then(|value| u64::from_str_radix(value.trim_start_matches("0x"), 16).ok())
+}
+
+/// Returns the blocks a `newHeads` or `logs` subscription resumed from `from_block` is notified of
+/// before the new ones, up to the chain head. Subscriptions can resume from the last
+/// `resume_blocks` blocks only.
+pub fn resume_range(from_block: u64, head: u64, resume_blocks: u64) -> Result<RangeInclusive<u64>, String> {
+    if from_block > head.saturating_add(1) {
+        return Err(format!("fromBlock {from_block} is ahead of the chain head {head}"));
+    }
+    if head.saturating_sub(from_block) >= resume_blocks {
+        return Err(format!(
+            "fromBlock {from_block} is too old, subscriptions resume from the last {resume_blocks} blocks only"
+        ));
+    }
+    Ok(from_block..=head)
+}
+
+#[cfg(test)]
+mod tests {
+    use kakarot_rpc_core::models::filter::ValueOrArray;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_notify_drops_notifications_of_slow_subscribers() {
+        // Given
+        let config = SubscriptionConfig { queue_capacity: 1, ..Default::default() };
+        let hub = SubscriptionHub::new(config);
+        let mut receiver = hub.subscribe(SubscriptionKind::NewHeads);
+
+        // When
+        hub.notify(SubscriptionKind::NewHeads, &json!(1));
+        hub.notify(SubscriptionKind::NewHeads, &json!(2));
+
+        // Then
+        assert_eq!(json!(1), receiver.try_recv().unwrap());
+        assert!(receiver.try_recv().is_err());
+        let stats = hub.stats();
+        assert_eq!((1, 1, 1, 0), (stats.active, stats.delivered, stats.dropped, stats.disconnected));
+    }
+
+    #[test]
+    fn test_notify_disconnects_slow_subscribers() {
+        // Given
+        let config = SubscriptionConfig {
+            queue_capacity: 1,
+            slow_consumer_policy: SlowConsumerPolicy::Disconnect,
+            ..Default::default()
+        };
+        let hub = SubscriptionHub::new(config);
+        let mut draining_receiver = hub.subscribe(SubscriptionKind::NewHeads);
+        let _idle_receiver = hub.subscribe(SubscriptionKind::NewHeads);
+
+        // When
+        hub.notify(SubscriptionKind::NewHeads, &json!(1));
+        draining_receiver.try_recv().unwrap();
+        hub.notify(SubscriptionKind::NewHeads, &json!(2));
+        hub.notify(SubscriptionKind::NewHeads, &json!(3));
+
+        // Then
+        let stats = hub.stats();
+        assert_eq!(0, stats.active);
+        assert_eq!(2, stats.disconnected);
+        assert_eq!(json!(2), draining_receiver.try_recv().unwrap());
+    }
+
+    #[test]
+    fn test_closed_subscriptions_are_not_counted_nor_notified() {
+        // Given
+        let hub = SubscriptionHub::new(SubscriptionConfig::default());
+        let mut open_receiver = hub.subscribe(SubscriptionKind::NewHeads);
+        let closed_receiver = hub.subscribe(SubscriptionKind::NewHeads);
+        let closed_logs_receiver = hub.subscribe_logs(LogFilter::default());
+
+        // When
+        drop(closed_receiver);
+        drop(closed_logs_receiver);
+        let stats = hub.stats();
+        let has_logs_subscribers = hub.has_subscribers(SubscriptionKind::Logs);
+        hub.notify(SubscriptionKind::NewHeads, &json!(1));
+
+        // Then
+        assert_eq!(1, stats.active);
+        assert!(!has_logs_subscribers);
+        assert_eq!(json!(1), open_receiver.try_recv().unwrap());
+        assert_eq!(1, hub.stats().delivered);
+    }
+
+    #[test]
+    fn test_notify_log_applies_subscription_filters() {
+        // Given
+        let hub = SubscriptionHub::new(SubscriptionConfig::default());
+        let (watched, other) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2));
+        let mut watching_receiver =
+            hub.subscribe_logs(LogFilter { address: Some(ValueOrArray::Value(watched)), ..Default::default() });
+        let mut any_receiver = hub.subscribe_logs(LogFilter::default());
+        let log = |address: Address| -> Log {
+            serde_json::from_value(json!({
+                "address": address,
+                "topics": [],
+                "data": "0x",
+                "blockHash": null,
+                "blockNumber": null,
+                "transactionHash": null,
+                "transactionIndex": null,
+                "logIndex": null,
+                "removed": false
+            }))
+            .unwrap()
+        };
+
+        // When
+        hub.notify_log(&log(other));
+        hub.notify_log(&log(watched));
+
+        // Then
+        assert_eq!(json!(watched), watching_receiver.try_recv().unwrap()["address"]);
+        assert!(watching_receiver.try_recv().is_err());
+        assert_eq!(json!(other), any_receiver.try_recv().unwrap()["address"]);
+        assert_eq!(json!(watched), any_receiver.try_recv().unwrap()["address"]);
+        assert_eq!(3, hub.stats().delivered);
+    }
+
+    #[test]
+    fn test_notify_pending_transaction_applies_subscription_filters() {
+        // Given
+        let hub = SubscriptionHub::new(SubscriptionConfig::default());
+        let (token, other) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2));
+        let transfer = Bytes::from(vec![0xa9, 0x05, 0x9c, 0xbb]);
+        let mut transfers_receiver = hub.subscribe_pending_transactions(PendingTransactionFilter {
+            to: vec![token],
+            selectors: vec![transfer.clone()],
+        });
+        let mut any_receiver = hub.subscribe_pending_transactions(PendingTransactionFilter::default());
+        let transaction = |to: Option<Address>, input: Bytes| -> Transaction {
+            serde_json::from_value(json!({
+                "hash": "0x03204b4c0e379c3a5ccb80d08661d5a538e95e2960581c9faf7ebcf8ff5a7d3c",
+                "r": "0x1",
+                "s": "0x1",
+                "v": "0x1",
+                "nonce": "0x0",
+                "from": "0x54b288676b749def5fc10eb17244fe2c87375de1",
+                "to": to,
+                "value": "0x0",
+                "gas": "0x64",
+                "input": input,
+            }))
+            .unwrap()
+        };
+
+        // When
+        hub.notify_pending_transaction(&transaction(None, transfer.clone()));
+        hub.notify_pending_transaction(&transaction(Some(other), transfer.clone()));
+        hub.notify_pending_transaction(&transaction(Some(token), Bytes::from(vec![0xde, 0xad, 0xbe, 0xef])));
+        hub.notify_pending_transaction(&transaction(Some(token), [transfer.to_vec(), vec![0; 64]].concat().into()));
+
+        // Then
+        assert_eq!(json!(token), transfers_receiver.try_recv().unwrap()["to"]);
+        assert!(transfers_receiver.try_recv().is_err());
+        assert_eq!(4, std::iter::from_fn(|| any_receiver.try_recv().ok()).count());
+    }
+
+    #[test]
+    fn test_subscription_cursor_skips_delivered_blocks() {
+        // Given
+        let mut cursor = SubscriptionCursor::default();
+
+        // Then
+        assert!(cursor.advance(&json!({ "number": "0xa" })));
+        assert!(cursor.advance(&json!({ "number": "0xb" })));
+        assert!(!cursor.advance(&json!({ "number": "0xa" })));
+        assert!(!cursor.advance(&json!({ "number": "0xb" })));
+        assert!(cursor.advance(&json!({ "syncing": false })));
+        assert!(cursor.advance(&json!({ "number": "0xc" })));
+    }
+
+    #[test]
+    fn test_subscription_cursor_skips_delivered_logs() {
+        // Given
+        let mut cursor = SubscriptionCursor::default();
+
+        // Then
+        assert!(cursor.advance(&json!({ "blockNumber": "0xa", "logIndex": "0x0" })));
+        assert!(cursor.advance(&json!({ "blockNumber": "0xa", "logIndex": "0x1" })));
+        assert!(cursor.advance(&json!({ "blockNumber": "0xb", "logIndex": "0x0" })));
+        assert!(!cursor.advance(&json!({ "blockNumber": "0xa", "logIndex": "0x1" })));
+        assert!(!cursor.advance(&json!({ "blockNumber": "0xb", "logIndex": "0x0" })));
+        assert!(cursor.advance(&json!({ "blockNumber": null, "logIndex": null })));
+        assert!(cursor.advance(&json!({ "blockNumber": "0xb", "logIndex": "0x1" })));
+    }
+
+    #[test]
+    fn test_resume_range() {
+        assert_eq!(Ok(8..=10), resume_range(8, 10, 4));
+        // Resuming from the next block replays nothing
+        assert!(resume_range(11, 10, 4).unwrap().is_empty());
+        assert!(resume_range(12, 10, 4).is_err());
+        assert!(resume_range(6, 10, 4).is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+use thiserror::Error;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::rustls::server::{AllowAnyAuthenticatedClient, ClientHello, ResolvesServerCert};
+use tokio_rustls::rustls::sign::{any_supported_type, CertifiedKey};
+use tokio_rustls::rustls::{Certificate, PrivateKey, RootCertStore, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+
+use crate::config::TlsConfig;
+
+/// Protocols negotiated with ALPN, HTTP/2 first. Websocket clients negotiate HTTP/1.1.
+const ALPN_PROTOCOLS: [&[u8]; 2] = [b"h2", b"http/1.1"];
+
+#[derive(Debug, Error)]
+pub enum TlsError {
+    #[error("failed to read {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("no certificate found in {0}")]
+    NoCertificate(PathBuf),
+    #[error("no private key found in {0}")]
+    NoPrivateKey(PathBuf),
+    #[error("failed to listen on {0}: {1}")]
+    Bind(SocketAddr, std::io::Error),
+    #[error("invalid TLS configuration: {0}")]
+    Rustls(#[from] tokio_rustls::rustls::Error),
+}
+
+/// Reads the PEM certificate chain of a file.
+fn load_certificates(path: &Path) -> Result<Vec<Certificate>, TlsError> {
+    let file = File::open(path).map_err(|err| TlsError::Io(path.into(), err))?;
+    let certificates =
+        rustls_pemfile::certs(&mut BufReader::new(file)).map_err(|err| TlsError::Io(path.into(), err))?;
+    if certificates.is_empty() {
+        return Err(TlsError::NoCertificate(path.into()));
+    }
+    Ok(certificates.into_iter().map(Certificate).collect())
+}
+
+/// Reads the first PEM private key of a file, in PKCS#8, PKCS#1 or SEC1 format.
+fn load_private_key(path: &Path) -> Result<PrivateKey, TlsError> {
+    let file = File::open(path).map_err(|err| TlsError::Io(path.into(), err))?;
+    let items = rustls_pemfile::read_all(&mut BufReader::new(file)).map_err(|err| TlsError::Io(path.into(), err))?;
+    items
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::ECKey(key) => Some(PrivateKey(key)),
+            _ => None,
+        })
+        .ok_or_else(|| TlsError::NoPrivateKey(path.into()))
+}
+
+/// Reads the certificate and the private key of the server.
+fn load_certified_key(config: &TlsConfig) -> Result<CertifiedKey, TlsError> {
+    let certificates = load_certificates(&config.cert_path)?;
+    let private_key = load_private_key(&config.key_path)?;
+    let signing_key = any_supported_type(&private_key)
+        .map_err(|_| TlsError::Rustls(tokio_rustls::rustls::Error::General("unsupported private key type".into())))?;
+    Ok(CertifiedKey::new(certificates, signing_key))
+}
+
+/// Returns the last modification time of the certificate and of the private key.
+fn modified_at(config: &TlsConfig) -> Option<(SystemTime, SystemTime)> {
+    let modified_at = |path: &Path| std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+    Some((modified_at(&config.cert_path)?, modified_at(&config.key_path)?))
+}
+
+/// Certificate of the server, swapped when the certificate files change so that renewed
+/// certificates are served without a restart. The connections already established keep the
+/// certificate they negotiated.
+struct ReloadingCertResolver {
+    certified_key: RwLock<Arc<CertifiedKey>>,
+}
+
+impl ResolvesServerCert for ReloadingCertResolver {
+    fn resolve(&self, _client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+        Some(self.certified_key.read().expect("certificate lock poisoned").clone())
+    }
+}
+
+/// Checks the certificate files every reload interval, and serves the new certificate once both
+/// files can be read. A renewal written halfway keeps the previous certificate until the next
+/// check.
+async fn reload_certificates(config: TlsConfig, resolver: Arc<ReloadingCertResolver>) {
+    let mut last_modified_at = modified_at(&config);
+    let mut interval = tokio::time::interval(config.reload_interval);
+    loop {
+        interval.tick().await;
+        let modified = modified_at(&config);
+        if modified.is_none() || modified == last_modified_at {
+            continue;
+        }
+        match load_certified_key(&config) {
+            Ok(certified_key) => {
+                *resolver.certified_key.write().expect("certificate lock poisoned") = Arc::new(certified_key);
+                last_modified_at = modified;
+                tracing::info!("Reloaded the TLS certificate from {}", config.cert_path.display());
+            }
+            Err(err) => tracing::warn!("Failed to reload the TLS certificate, keeping the previous one: {err}"),
+        }
+    }
+}
+
+/// Builds the TLS configuration of the server, requiring the clients to present a certificate
+/// signed by the configured certificate authorities if any.
+fn server_config(config: &TlsConfig, resolver: Arc<ReloadingCertResolver>) -> Result<ServerConfig, TlsError> {
+    let builder = ServerConfig::builder().with_safe_defaults();
+    let mut server_config = match &config.client_ca_path {
+        Some(client_ca_path) => {
+            let mut roots = RootCertStore::empty();
+            for certificate in load_certificates(client_ca_path)? {
+                roots.add(&certificate).map_err(|err| {
+                    TlsError::Rustls(tokio_rustls::rustls::Error::General(format!("invalid client CA: {err}")))
+                })?;
+            }
+            builder.with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots).boxed())
+        }
+        None => builder.with_no_client_auth(),
+    }
+    .with_cert_resolver(resolver);
+    server_config.alpn_protocols = ALPN_PROTOCOLS.iter().map(|protocol| protocol.to_vec()).collect();
+    Ok(server_config)
+}
+
+/// Terminates TLS on `socket_addr` and forwards the decrypted connections to the RPC server
+/// listening on `backend_addr`, which serves HTTP/1.1, HTTP/2 and websockets on the same
+/// connections. Returns the address TLS is terminated on.
+///
+/// # Errors
+///
+/// Will return `Err` if the certificate, the private key or the client certificate authorities
+/// cannot be read, or if `socket_addr` cannot be bound.
+pub async fn serve_tls(
+    config: TlsConfig,
+    socket_addr: SocketAddr,
+    backend_addr: SocketAddr,
+) -> Result<SocketAddr, TlsError> {
+    let resolver =
+        Arc::new(ReloadingCertResolver { certified_key: RwLock::new(Arc::new(load_certified_key(&config)?)) });
+    let acceptor = TlsAcceptor::from(Arc::new(server_config(&config, resolver.clone())?));
+    let listener = TcpListener::bind(socket_addr).await.map_err(|err| TlsError::Bind(socket_addr, err))?;
+    let local_addr = listener.local_addr().map_err(|err| TlsError::Bind(socket_addr, err))?;
+
+    if !config.reload_interval.is_zero() {
+        tokio::spawn(reload_certificates(config, resolver));
+    }
+    tokio::spawn(async move {
+        loop {
+            let (stream, peer_addr) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(err) => {
+                    tracing::warn!("Failed to accept a TLS connection: {err}");
+                    continue;
+                }
+            };
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                // Handshake failures are the clients', e.g. scanners or untrusted client certificates
+                let mut tls_stream = match acceptor.accept(stream).await {
+                    Ok(tls_stream) => tls_stream,
+                    Err(err) => {
+                        tracing::debug!("TLS handshake with {peer_addr} failed: {err}");
+                        return;
+                    }
+                };
+                let mut backend = match TcpStream::connect(backend_addr).await {
+                    Ok(backend) => backend,
+                    Err(err) => {
+                        tracing::warn!("Failed to connect to the RPC server: {err}");
+                        return;
+                    }
+                };
+                if let Err(err) = tokio::io::copy_bidirectional(&mut tls_stream, &mut backend).await {
+                    tracing::debug!("TLS connection with {peer_addr} closed: {err}");
+                }
+            });
+        }
+    });
+
+    Ok(local_addr)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_load_certified_key_errors() {
+        // Given
+        let dir = std::env::temp_dir().join(format!("kakarot-rpc-tls-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let empty = dir.join("empty.pem");
+        std::fs::write(&empty, "").unwrap();
+        let config = |cert_path: &Path, key_path: &Path| TlsConfig {
+            cert_path: cert_path.into(),
+            key_path: key_path.into(),
+            client_ca_path: None,
+            reload_interval: Duration::ZERO,
+        };
+
+        // When
+        let missing = load_certified_key(&config(&dir.join("missing.pem"), &empty));
+        let no_certificate = load_certified_key(&config(&empty, &empty));
+
+        // Then
+        assert!(matches!(missing, Err(TlsError::Io(..))));
+        assert!(matches!(no_certificate, Err(TlsError::NoCertificate(path)) if path == empty));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
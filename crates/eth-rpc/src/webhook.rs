@@ -0,0 +1,115 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use kakarot_rpc_core::client::api::KakarotEthApi;
+use reqwest::Client;
+use reth_primitives::Address;
+use reth_rpc_types::{BlockTransactions, Log, RichBlock, Transaction, TransactionReceipt};
+use serde_json::{json, Value};
+use starknet::providers::Provider;
+
+use crate::config::WebhookConfig;
+
+/// Time after which a webhook delivery is abandoned.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Posts the transactions and logs touching the watched addresses to the configured webhooks.
+///
+/// Deliveries are not retried: a webhook that is down misses the notifications of the blocks
+/// produced meanwhile.
+#[derive(Debug)]
+pub struct WebhookNotifier {
+    urls: Vec<String>,
+    watched_addresses: HashSet<Address>,
+    http: Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(config: &WebhookConfig) -> Self {
+        Self {
+            urls: config.urls.clone(),
+            watched_addresses: config.watched_addresses.iter().copied().collect(),
+            http: Client::new(),
+        }
+    }
+
+    fn watches_transaction(&self, from: Address, to: Option<Address>) -> bool {
+        self.watched_addresses.contains(&from) || to.map_or(false, |to| self.watched_addresses.contains(&to))
+    }
+
+    /// Returns the notifications of the logs emitted by the watched addresses.
+    fn log_notifications(&self, logs: &[Log]) -> Vec<Value> {
+        logs.iter()
+            .filter(|log| self.watched_addresses.contains(&log.address))
+            .map(|log| json!({ "event": "log", "log": log }))
+            .collect()
+    }
+
+    /// Returns the notifications of a transaction and its logs touching the watched addresses.
+    pub fn notifications(&self, transaction: &Transaction, receipt: Option<&TransactionReceipt>) -> Vec<Value> {
+        let mut notifications = vec![];
+        if self.watches_transaction(transaction.from, transaction.to) {
+            notifications.push(json!({ "event": "transaction", "transaction": transaction, "receipt": receipt }));
+        }
+        if let Some(receipt) = receipt {
+            notifications.extend(self.log_notifications(&receipt.logs));
+        }
+        notifications
+    }
+
+    /// Notifies the webhooks of the activity of the watched addresses in a hydrated block.
+    pub async fn notify_block<P: Provider + Send + Sync + 'static>(
+        &self,
+        kakarot_client: &dyn KakarotEthApi<P>,
+        block: &RichBlock,
+    ) {
+        let BlockTransactions::Full(transactions) = &block.transactions else { return };
+        for transaction in transactions {
+            let receipt = match kakarot_client.transaction_receipt(transaction.hash).await {
+                Ok(receipt) => receipt,
+                Err(err) => {
+                    tracing::warn!("webhooks: failed to fetch the receipt of {:#x}: {err}", transaction.hash);
+                    None
+                }
+            };
+            for notification in self.notifications(transaction, receipt.as_ref()) {
+                self.post(&notification).await;
+            }
+        }
+    }
+
+    async fn post(&self, notification: &Value) {
+        for url in &self.urls {
+            let response = self.http.post(url).timeout(WEBHOOK_TIMEOUT).json(notification).send().await;
+            match response.and_then(|response| response.error_for_status()) {
+                Ok(_) => tracing::debug!("webhooks: notification delivered to {url}"),
+                Err(err) => tracing::warn!("webhooks: failed to notify {url}: {err}"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webhook_notifier_watches_addresses() {
+        // Given
+        let watched = Address::from_low_u64_be(1);
+        let other = Address::from_low_u64_be(2);
+        let config = WebhookConfig { urls: vec!["http://localhost".into()], watched_addresses: vec![watched] };
+        let notifier = WebhookNotifier::new(&config);
+        let log = |address: Address| Log { address, ..Default::default() };
+
+        // When
+        let notifications = notifier.log_notifications(&[log(watched), log(other)]);
+
+        // Then
+        assert_eq!(1, notifications.len());
+        assert_eq!("log", notifications[0]["event"]);
+        assert!(notifier.watches_transaction(watched, None));
+        assert!(notifier.watches_transaction(other, Some(watched)));
+        assert!(!notifier.watches_transaction(other, Some(other)));
+    }
+}
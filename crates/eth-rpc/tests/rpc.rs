@@ -4,16 +4,26 @@ mod utils;
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
+    use std::sync::Arc;
 
+    use kakarot_rpc::api::erigon_api::ErigonApiServer;
     use kakarot_rpc::api::eth_api::EthApiServer;
+    use kakarot_rpc::rpc::KakarotRpcModule;
+    use kakarot_rpc::server::KakarotRpcServer;
+    use kakarot_rpc::servers::erigon_rpc::ErigonRpc;
+    use kakarot_rpc_core::client::api::{KakarotEthApi, KakarotStarknetApi};
+    use kakarot_rpc_core::client::constants::CHAIN_ID;
+    use kakarot_rpc_core::client::errors::EthRpcErrorCode;
     use kakarot_rpc_core::mock::assert_helpers::{assert_block, assert_block_header, assert_transaction};
-    use reth_primitives::{BlockNumberOrTag, H160, H256, U256, U64};
+    use kakarot_rpc_core::mock::wiremock_utils::UNKNOWN_BLOCK_HASH;
+    use kakarot_rpc_core::models::compat::ResponseCompat;
+    use reth_primitives::{BlockNumberOrTag, H160, H256, H64, U256, U64};
     use reth_rpc_types::Index;
     use serde_json::json;
     use starknet::core::types::{FieldElement, Transaction as StarknetTransaction};
     use starknet::macros::felt;
 
-    use crate::utils::setup_kakarot_eth_rpc;
+    use crate::utils::{setup_kakarot_client, setup_kakarot_eth_rpc};
 
     fn get_test_tx() -> serde_json::Value {
         json!({
@@ -754,7 +764,7 @@ mod tests {
         let hash = H256::from_str("0x0449aa33ad836b65b10fa60082de99e24ac876ee2fd93e723a99190a530af0a9").unwrap();
 
         let hydrated = true;
-        let block = kakarot_rpc.block_by_hash(hash, hydrated).await.unwrap().unwrap();
+        let block = kakarot_rpc.block_by_hash(hash, hydrated.into()).await.unwrap().unwrap().into_inner();
 
         let starknet_res = json!({
             "block_hash": "0x449aa33ad836b65b10fa60082de99e24ac876ee2fd93e723a99190a530af0a9",
@@ -793,7 +803,7 @@ mod tests {
         let kakarot_rpc = setup_kakarot_eth_rpc().await;
         let hash = H256::from_str("0x0197be2810df6b5eedd5d9e468b200d0b845b642b81a44755e19047f08cc8c6e").unwrap();
         let hydrated = false;
-        let block = kakarot_rpc.block_by_hash(hash, hydrated).await.unwrap().unwrap();
+        let block = kakarot_rpc.block_by_hash(hash, hydrated.into()).await.unwrap().unwrap().into_inner();
 
         let starknet_res = json!({
             "block_hash": "0x197be2810df6b5eedd5d9e468b200d0b845b642b81a44755e19047f08cc8c6e",
@@ -825,13 +835,25 @@ mod tests {
         assert_block_header(&block, starknet_res.to_string(), false);
     }
 
+    #[tokio::test]
+    async fn test_get_block_by_hash_unknown_is_null() {
+        let kakarot_rpc = setup_kakarot_eth_rpc().await;
+
+        let unknown = H256::from_str(UNKNOWN_BLOCK_HASH).unwrap();
+        assert!(kakarot_rpc.block_by_hash(unknown, false.into()).await.unwrap().is_none());
+
+        // Above the field prime, not a Starknet block hash
+        let invalid = H256::repeat_byte(0xff);
+        assert!(kakarot_rpc.block_by_hash(invalid, false.into()).await.unwrap().is_none());
+    }
+
     #[tokio::test]
     async fn test_get_block_by_number_hydrated_is_ok() {
         let kakarot_rpc = setup_kakarot_eth_rpc().await;
         let block_number = BlockNumberOrTag::Latest;
         let hydrated = true;
 
-        let block = kakarot_rpc.block_by_number(block_number, hydrated).await.unwrap().unwrap();
+        let block = kakarot_rpc.block_by_number(block_number, hydrated.into()).await.unwrap().unwrap().into_inner();
 
         let starknet_res = json!({
             "block_hash": "0x449aa33ad836b65b10fa60082de99e24ac876ee2fd93e723a99190a530af0a9",
@@ -871,7 +893,7 @@ mod tests {
         let block_number = BlockNumberOrTag::Latest;
         let hydrated = false;
 
-        let block = kakarot_rpc.block_by_number(block_number, hydrated).await.unwrap().unwrap();
+        let block = kakarot_rpc.block_by_number(block_number, hydrated.into()).await.unwrap().unwrap().into_inner();
 
         let starknet_res = json!({
             "block_hash": "0x197be2810df6b5eedd5d9e468b200d0b845b642b81a44755e19047f08cc8c6e",
@@ -903,6 +925,23 @@ mod tests {
         assert_block_header(&block, starknet_res.to_string(), false);
     }
 
+    #[tokio::test]
+    async fn test_erigon_header_by_number_is_block_header() {
+        // Given
+        let kakarot_rpc = setup_kakarot_eth_rpc().await;
+        let kakarot_client: Arc<dyn KakarotEthApi<_>> = setup_kakarot_client().await;
+        let erigon_rpc = ErigonRpc::new(kakarot_client, ResponseCompat::default());
+
+        // When
+        let header = erigon_rpc.header_by_number(BlockNumberOrTag::Latest).await.unwrap().unwrap().into_inner();
+        let block =
+            kakarot_rpc.block_by_number(BlockNumberOrTag::Latest, false.into()).await.unwrap().unwrap().into_inner();
+
+        // Then
+        assert_eq!(block.inner.header, header);
+        assert_eq!(Some(U256::from(19639)), header.number);
+    }
+
     #[tokio::test]
     async fn test_block_transaction_count_by_hash_is_ok() {
         let kakarot_rpc = setup_kakarot_eth_rpc().await;
@@ -926,7 +965,7 @@ mod tests {
         let kakarot_rpc = setup_kakarot_eth_rpc().await;
         let hash = H256::from_str("0x03204b4c0e379c3a5ccb80d08661d5a538e95e2960581c9faf7ebcf8ff5a7d3c").unwrap();
 
-        let transaction_receipt = kakarot_rpc.transaction_receipt(hash).await.unwrap().unwrap();
+        let transaction_receipt = kakarot_rpc.transaction_receipt(hash).await.unwrap().unwrap().into_inner();
 
         assert_eq!(
             transaction_receipt.transaction_hash,
@@ -951,18 +990,27 @@ mod tests {
 
         assert_eq!(transaction_receipt.from, H160::from_str("0x54b288676b749def5fc10eb17244fe2c87375de1").unwrap());
 
+        // The fee paid on Starknet is translated into gas at the base fee reported by eth_feeHistory
+        let fee_history = kakarot_rpc.fee_history(U256::from(1), BlockNumberOrTag::Number(13), None).await.unwrap();
+        let base_fee = *fee_history.base_fee_per_gas.first().unwrap();
+        assert_eq!(base_fee, U256::from(transaction_receipt.effective_gas_price));
+        let actual_fee = U256::from(0x04514f14cba800_u64);
+        assert_eq!(Some(actual_fee.div_ceil(base_fee)), transaction_receipt.gas_used);
+        assert!(Some(transaction_receipt.cumulative_gas_used) >= transaction_receipt.gas_used);
+        let transaction = kakarot_rpc.transaction_by_hash(hash).await.unwrap().unwrap().into_inner();
+        assert_eq!(
+            transaction.transaction_type.map(|ty| ty.to::<u8>()),
+            Some(transaction_receipt.transaction_type.to())
+        );
+
         // TODO
         // assert_eq!(transaction_receipt.logs, None);
         // assert_eq!(transaction_receipt.contract_address, Some(U64::from(1)));
 
         // assert_eq!(transaction_receipt.transaction_index, None);
         // assert_eq!(transaction_receipt.to, None);
-        // assert_eq!(transaction_receipt.cumulative_gas_used, U256::from(1000000));
-        // assert_eq!(transaction_receipt.gas_used, None);
         // assert_eq!(transaction_receipt.logs_bloom, Bloom::default());
         // assert_eq!(transaction_receipt.state_root, None);
-        // assert_eq!(transaction_receipt.effective_gas_price, U128::from(1000000));
-        // assert_eq!(transaction_receipt.transaction_type, U256::from(0));
     }
 
     #[tokio::test]
@@ -974,7 +1022,7 @@ mod tests {
         let index: Index = Index::default();
 
         let transaction =
-            kakarot_rpc.transaction_by_block_number_and_index(block_number, index).await.unwrap().unwrap();
+            kakarot_rpc.transaction_by_block_number_and_index(block_number, index).await.unwrap().unwrap().into_inner();
 
         let starknet_tx = get_test_tx();
         assert_transaction(
@@ -998,7 +1046,8 @@ mod tests {
         // workaround as Index does not implement new()
         let index: Index = Index::default();
 
-        let transaction = kakarot_rpc.transaction_by_block_hash_and_index(hash, index).await.unwrap().unwrap();
+        let transaction =
+            kakarot_rpc.transaction_by_block_hash_and_index(hash, index).await.unwrap().unwrap().into_inner();
 
         let starknet_tx = get_test_tx();
 
@@ -1013,4 +1062,78 @@ mod tests {
         );
         assert_eq!(U256::from(transaction.block_number.unwrap()), U256::from(13));
     }
+
+    #[tokio::test]
+    async fn test_proof_of_work_methods_are_unsupported() {
+        // Given
+        let kakarot_rpc = setup_kakarot_eth_rpc().await;
+
+        // When
+        let get_work = kakarot_rpc.get_work().await.unwrap_err();
+        let submit_work = kakarot_rpc.submit_work(H64::zero(), H256::zero(), H256::zero()).await.unwrap_err();
+
+        // Then
+        assert_eq!(EthRpcErrorCode::MethodNotSupported as i32, get_work.code());
+        assert_eq!(EthRpcErrorCode::MethodNotSupported as i32, submit_work.code());
+        assert!(submit_work.message().starts_with("eth_submitWork is not supported"));
+    }
+
+    #[tokio::test]
+    async fn test_embedded_server_serves_rpc() {
+        // Given
+        let kakarot_client: Arc<dyn KakarotEthApi<_>> = setup_kakarot_client().await;
+        let server = KakarotRpcServer::builder()
+            .with_client(kakarot_client)
+            .serve("127.0.0.1:0".parse().unwrap())
+            .await
+            .unwrap();
+
+        // When
+        let response: serde_json::Value = reqwest::Client::new()
+            .post(format!("http://{}", server.local_addr()))
+            .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": "eth_chainId", "params": [] }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(json!(format!("{CHAIN_ID:#x}")), response["result"]);
+        server.handle().stop().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_chain_config_reports_deployment_and_namespaces() {
+        // Given
+        let kakarot_client: Arc<dyn KakarotEthApi<_>> = setup_kakarot_client().await;
+        let kakarot_address = kakarot_client.kakarot_address();
+        let server = KakarotRpcServer::builder()
+            .with_client(kakarot_client)
+            .without_modules(&[KakarotRpcModule::Admin])
+            .serve("127.0.0.1:0".parse().unwrap())
+            .await
+            .unwrap();
+
+        // When
+        let response: serde_json::Value = reqwest::Client::new()
+            .post(format!("http://{}", server.local_addr()))
+            .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": "kakarot_getChainConfig", "params": [] }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        // Then
+        let chain_config = &response["result"];
+        assert_eq!(json!(format!("{CHAIN_ID:#x}")), chain_config["chainId"]);
+        assert_eq!(json!(format!("{kakarot_address:#x}")), chain_config["kakarotAddress"]);
+        let namespaces = chain_config["namespaces"].as_array().unwrap();
+        assert!(namespaces.contains(&json!("eth")) && namespaces.contains(&json!("kakarot")));
+        assert!(!namespaces.contains(&json!("admin")));
+        server.handle().stop().unwrap();
+    }
 }
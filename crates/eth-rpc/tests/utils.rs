@@ -4,6 +4,7 @@ use kakarot_rpc::servers::eth_rpc::KakarotEthRpc;
 use kakarot_rpc_core::client::config::{JsonRpcClientBuilder, Network, StarknetConfig};
 use kakarot_rpc_core::client::KakarotClient;
 use kakarot_rpc_core::mock::wiremock_utils::setup_wiremock;
+use kakarot_rpc_core::models::compat::ResponseCompat;
 use starknet::core::types::FieldElement;
 use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::JsonRpcClient;
@@ -35,6 +36,11 @@ use url::Url;
 ///   }
 /// ```
 pub async fn setup_kakarot_eth_rpc() -> KakarotEthRpc<JsonRpcClient<HttpTransport>> {
+    KakarotEthRpc::new(setup_kakarot_client().await, ResponseCompat::default(), Arc::default())
+}
+
+/// Run wiremock to fake starknet rpc and return a Kakarot client using it.
+pub async fn setup_kakarot_client() -> Arc<KakarotClient<JsonRpcClient<HttpTransport>>> {
     let provider_url = setup_wiremock().await;
     let kakarot_address =
         FieldElement::from_hex_be("0x566864dbc2ae76c2d12a8a5a334913d0806f85b7a4dccea87467c3ba3616e75").unwrap();
@@ -48,7 +54,5 @@ pub async fn setup_kakarot_eth_rpc() -> KakarotEthRpc<JsonRpcClient<HttpTranspor
     );
     let starknet_provider = JsonRpcClientBuilder::with_http(&config).unwrap().build();
 
-    let kakarot_client = KakarotClient::new(config, starknet_provider);
-
-    KakarotEthRpc::new(Arc::new(kakarot_client))
+    Arc::new(KakarotClient::builder().with_starknet_config(config).with_provider(starknet_provider).build().unwrap())
 }